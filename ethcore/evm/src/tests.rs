@@ -165,6 +165,40 @@ fn test_extcodecopy(factory: super::Factory) {
 	assert_store(&ext, 0, "6005600055000000000000000000000000000000000000000000000000000000");
 }
 
+evm_test!{ignorejit => test_extcodehash: test_extcodehash_jit, test_extcodehash_int}
+fn test_extcodehash(factory: super::Factory) {
+		// 33 - sender
+		// 3f - extcodehash
+		// 60 00 - push 0
+		// 55 - sstore
+		// 73 00..02 - push20 (address without code)
+		// 3f - extcodehash
+		// 60 01 - push 1
+		// 55 - sstore
+
+	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
+	let sender = Address::from_str("cd1722f2947def4cf144679da39c4c32bdc35681").unwrap();
+	let code = "333f6000557300000000000000000000000000000000000000023f600155".from_hex().unwrap();
+	let sender_code = "6005600055".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.address = address.clone();
+	params.sender = sender.clone();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_constantinople();
+	ext.codes.insert(sender, Arc::new(sender_code));
+
+	let gas_left = {
+		let mut vm = factory.create(&params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(74_189));
+	assert_store(&ext, 0, "ffa3059b9f97094288461a40a0a9fb69492fa436dd1cfa108f04ae159f23aafe");
+	assert_store(&ext, 1, "0000000000000000000000000000000000000000000000000000000000000000");
+}
+
 evm_test!{test_log_empty: test_log_empty_jit, test_log_empty_int}
 fn test_log_empty(factory: super::Factory) {
 	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
@@ -659,6 +693,27 @@ fn test_badinstruction_int() {
 	}
 }
 
+#[test]
+fn test_shift_before_constantinople_int() {
+	let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+	let code = "600160011b600055".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_byzantium();
+
+	let err = {
+		let mut vm = factory.create(&params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap_err()
+	};
+
+	match err {
+		vm::Error::BadInstruction { instruction: 0x1b } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+}
+
 evm_test!{test_pop: test_pop_jit, test_pop_int}
 fn test_pop(factory: super::Factory) {
 	let code = "60f060aa50600055".from_hex().unwrap();
@@ -716,7 +771,7 @@ fn test_jumps(factory: super::Factory) {
 		test_finalize(vm.exec(params, &mut ext)).unwrap()
 	};
 
-	assert_eq!(ext.sstore_clears, 1);
+	assert_eq!(ext.sstore_clears, ext.schedule().sstore_refund_gas as i128);
 	assert_store(&ext, 0, "0000000000000000000000000000000000000000000000000000000000000000"); // 5!
 	assert_store(&ext, 1, "0000000000000000000000000000000000000000000000000000000000000078"); // 5!
 	assert_eq!(gas_left, U256::from(54_117));
@@ -787,6 +842,375 @@ fn test_create_in_staticcall(factory: super::Factory) {
 	assert_eq!(ext.calls.len(), 0);
 }
 
+evm_test!{ignorejit => test_create2: test_create2_jit, test_create2_int}
+fn test_create2(factory: super::Factory) {
+	// stores 0xff at memory byte 31 and calls CREATE2 with salt 0, size 1, offset 31, value 0
+	let code = "60ff60005260006001601f6000f5".from_hex().unwrap();
+
+	let address = Address::from(0x155);
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	params.address = address.clone();
+	let mut ext = FakeExt::new_constantinople();
+
+	let gas_left = {
+		let mut vm = factory.create(&params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_set_contains(&ext.calls, &FakeCall {
+		call_type: FakeCallType::Create,
+		gas: U256::from(66_908),
+		sender_address: None,
+		receive_address: None,
+		value: Some(U256::zero()),
+		data: vec![0xff],
+		code_address: None
+	});
+	assert_eq!(gas_left, U256::from(1_062));
+	assert_eq!(ext.calls.len(), 1);
+}
+
+evm_test!{ignorejit => test_create2_not_enabled: test_create2_not_enabled_jit, test_create2_not_enabled_int}
+fn test_create2_not_enabled(factory: super::Factory) {
+	let code = "60ff60005260006001601f6000f5".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_byzantium();
+
+	let err = {
+		let mut vm = factory.create(&params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap_err()
+	};
+
+	match err {
+		vm::Error::BadInstruction { instruction: 0xf5 } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+	assert_eq!(ext.calls.len(), 0);
+}
+
+evm_test!{ignorejit => test_create_p2sh: test_create_p2sh_jit, test_create_p2sh_int}
+fn test_create_p2sh(factory: super::Factory) {
+	// stores 0xff at memory byte 31 and calls legacy EIP-86 CREATE2 (0xfb) with size 1, offset 31, value 0
+	let code = "60ff6000526001601f6000fb".from_hex().unwrap();
+
+	let address = Address::from(0x155);
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	params.address = address.clone();
+	let mut ext = FakeExt::new_byzantium();
+
+	let gas_left = {
+		let mut vm = factory.create(&params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_set_contains(&ext.calls, &FakeCall {
+		call_type: FakeCallType::Create,
+		gas: U256::from(66_917),
+		sender_address: None,
+		receive_address: None,
+		value: Some(U256::zero()),
+		data: vec![0xff],
+		code_address: None
+	});
+	assert_eq!(gas_left, U256::from(1_062));
+	assert_eq!(ext.calls.len(), 1);
+}
+
+evm_test!{ignorejit => test_create_p2sh_not_enabled: test_create_p2sh_not_enabled_jit, test_create_p2sh_not_enabled_int}
+fn test_create_p2sh_not_enabled(factory: super::Factory) {
+	let code = "60ff6000526001601f6000fb".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new();
+
+	let err = {
+		let mut vm = factory.create(&params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap_err()
+	};
+
+	match err {
+		vm::Error::BadInstruction { instruction: 0xfb } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+	assert_eq!(ext.calls.len(), 0);
+}
+
+evm_test!{ignorejit => test_shl: test_shl_jit, test_shl_int}
+fn test_shl(factory: super::Factory) {
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"00",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000002");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"ff",
+		"8000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"0101",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"00",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"8000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"0000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe");
+}
+
+evm_test!{ignorejit => test_shr: test_shr_jit, test_shr_int}
+fn test_shr(factory: super::Factory) {
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"00",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"4000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"ff",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0101",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"00",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"0000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+}
+
+evm_test!{ignorejit => test_sar: test_sar_jit, test_sar_int}
+fn test_sar(factory: super::Factory) {
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"00",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"0000000000000000000000000000000000000000000000000000000000000001",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"c000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"ff",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0100",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0101",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"00",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"0000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"4000000000000000000000000000000000000000000000000000000000000000",
+		"fe",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"f8",
+		"000000000000000000000000000000000000000000000000000000000000007f");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"fe",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+}
+
+fn push_two_pop_one_constantinople_test(factory: &super::Factory, opcode: u8, push1: &str, push2: &str, result: &str) {
+	let mut push1 = push1.from_hex().unwrap();
+	let mut push2 = push2.from_hex().unwrap();
+	assert!(push1.len() <= 32 && push1.len() != 0);
+	assert!(push2.len() <= 32 && push2.len() != 0);
+
+	let mut code = Vec::new();
+	code.push(0x60 + ((push1.len() - 1) as u8));
+	code.append(&mut push1);
+	code.push(0x60 + ((push2.len() - 1) as u8));
+	code.append(&mut push2);
+	code.push(opcode);
+	code.append(&mut vec![0x60, 0x00, 0x55]);
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_constantinople();
+
+	let _ = {
+		let mut vm = factory.create(&params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_store(&ext, 0, result);
+}
+
 fn assert_set_contains<T : Debug + Eq + PartialEq + Hash>(set: &HashSet<T>, val: &T) {
 	let contains = set.contains(val);
 	if !contains {
@@ -16,13 +16,14 @@
 
 //! Cache for data fetched from the network.
 //!
-//! Stores ancient block headers, bodies, receipts, and total difficulties.
+//! Stores ancient block headers, bodies, receipts, total difficulties, and proved storage values.
 //! Furthermore, stores a "gas price corpus" of relative recency, which is a sorted
 //! vector of all gas prices from a recent range of blocks.
 
 use ethcore::encoded;
 use ethcore::header::BlockNumber;
 use ethcore::receipt::Receipt;
+use bytes::Bytes;
 
 use stats::Corpus;
 use time::{SteadyTime, Duration};
@@ -43,6 +44,8 @@ pub struct CacheSizes {
 	pub receipts: usize,
 	/// Maximum size, in bytes, of cached chain score for the block.
 	pub chain_score: usize,
+	/// Maximum size, in bytes, of cached storage values and their proofs.
+	pub storage: usize,
 }
 
 impl Default for CacheSizes {
@@ -54,6 +57,7 @@ impl Default for CacheSizes {
 			bodies: 20 * MB,
 			receipts: 10 * MB,
 			chain_score: 7 * MB,
+			storage: 5 * MB,
 		}
 	}
 }
@@ -69,6 +73,7 @@ pub struct Cache {
 	bodies: MemoryLruCache<H256, encoded::Body>,
 	receipts: MemoryLruCache<H256, Vec<Receipt>>,
 	chain_score: MemoryLruCache<H256, U256>,
+	storage: MemoryLruCache<(H256, H256), (Vec<Bytes>, H256)>,
 	corpus: Option<(Corpus<U256>, SteadyTime)>,
 	corpus_expiration: Duration,
 }
@@ -82,6 +87,7 @@ impl Cache {
 			bodies: MemoryLruCache::new(sizes.bodies),
 			receipts: MemoryLruCache::new(sizes.receipts),
 			chain_score: MemoryLruCache::new(sizes.chain_score),
+			storage: MemoryLruCache::new(sizes.storage),
 			corpus: None,
 			corpus_expiration: corpus_expiration,
 		}
@@ -112,6 +118,11 @@ impl Cache {
 		self.chain_score.get_mut(hash).map(|x| x.clone())
 	}
 
+	/// Query a storage value and its proof by the account's storage root and the storage key.
+	pub fn storage(&mut self, storage_root: &H256, key: &H256) -> Option<(Vec<Bytes>, H256)> {
+		self.storage.get_mut(&(*storage_root, *key)).map(|x| x.clone())
+	}
+
 	/// Cache the given header.
 	pub fn insert_block_header(&mut self, hash: H256, hdr: encoded::Header) {
 		self.headers.insert(hash, hdr);
@@ -137,6 +148,11 @@ impl Cache {
 		self.chain_score.insert(hash, score);
 	}
 
+	/// Cache the given storage value and its proof.
+	pub fn insert_storage(&mut self, storage_root: H256, key: H256, proof_and_value: (Vec<Bytes>, H256)) {
+		self.storage.insert((storage_root, key), proof_and_value);
+	}
+
 	/// Get gas price corpus, if recent enough.
 	pub fn gas_price_corpus(&self) -> Option<Corpus<U256>> {
 		let now = SteadyTime::now();
@@ -168,6 +184,7 @@ impl HeapSizeOf for Cache {
 			+ self.bodies.current_size()
 			+ self.receipts.current_size()
 			+ self.chain_score.current_size()
+			+ self.storage.current_size()
 			// TODO: + corpus
 	}
 }
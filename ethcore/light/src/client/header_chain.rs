@@ -500,6 +500,8 @@ impl HeaderChain {
 			BlockId::Latest | BlockId::Pending => {
 				Some(self.best_block.read().hash)
 			}
+			// finality isn't tracked by the light client.
+			BlockId::Finalized => None,
 		}
 	}
 
@@ -554,6 +556,7 @@ impl HeaderChain {
 
 				load_from_db(hash)
 			}
+			BlockId::Finalized => None,
 		}
 	}
 
@@ -575,7 +578,8 @@ impl HeaderChain {
 				if self.best_block.read().number < num { return None }
 				candidates.get(&num).map(|era| era.candidates[0].total_difficulty)
 			}
-			BlockId::Latest | BlockId::Pending => Some(self.best_block.read().total_difficulty)
+			BlockId::Latest | BlockId::Pending => Some(self.best_block.read().total_difficulty),
+			BlockId::Finalized => None,
 		}
 	}
 
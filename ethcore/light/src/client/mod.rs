@@ -19,7 +19,7 @@
 use std::sync::{Weak, Arc};
 
 use ethcore::block_status::BlockStatus;
-use ethcore::client::{ClientReport, EnvInfo};
+use ethcore::client::{ClientReport, EnvInfo, DatabaseEngine};
 use ethcore::engines::{epoch, EthEngine, EpochChange, EpochTransition, Proof};
 use ethcore::machine::EthereumMachine;
 use ethcore::error::BlockImportError;
@@ -63,6 +63,8 @@ pub struct Config {
 	pub db_compaction: CompactionProfile,
 	/// Should db have WAL enabled?
 	pub db_wal: bool,
+	/// Database backend.
+	pub db_engine: DatabaseEngine,
 	/// Should it do full verification of blocks?
 	pub verify_full: bool,
 	/// Should it check the seal of blocks?
@@ -77,6 +79,7 @@ impl Default for Config {
 			db_cache_size: None,
 			db_compaction: CompactionProfile::default(),
 			db_wal: true,
+			db_engine: DatabaseEngine::default(),
 			verify_full: true,
 			check_seal: true,
 		}
@@ -371,7 +374,9 @@ impl<T: ChainDataFetcher> Client<T> {
 
 	/// Get a report about blocks imported.
 	pub fn report(&self) -> ClientReport {
-		self.report.read().clone()
+		let mut report = self.report.read().clone();
+		report.db_memory_used = self.db.memory_footprint();
+		report
 	}
 
 	/// Get blockchain mem usage in bytes.
@@ -634,4 +639,11 @@ impl<T: ChainDataFetcher> ::ethcore::client::EngineClient for Client<T> {
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header> {
 		Client::block_header(self, id)
 	}
+
+	// engine state is recomputed from headers on the light client.
+	fn engine_data(&self, _hash: H256) -> Option<Vec<u8>> {
+		None
+	}
+
+	fn insert_engine_data(&self, _hash: H256, _data: Vec<u8>) {}
 }
@@ -26,7 +26,7 @@ use ethcore::service::ClientIoMessage;
 use ethcore::spec::Spec;
 use io::{IoContext, IoError, IoHandler, IoService};
 use kvdb;
-use kvdb_rocksdb::{Database, DatabaseConfig};
+use kvdb_rocksdb::DatabaseConfig;
 
 use cache::Cache;
 use parking_lot::Mutex;
@@ -68,10 +68,7 @@ impl<T: ChainDataFetcher> Service<T> {
 		db_config.compaction = config.db_compaction;
 		db_config.wal = config.db_wal;
 
-		let db = Arc::new(Database::open(
-			&db_config,
-			&path.to_str().expect("DB path could not be converted to string.")
-		).map_err(Error::Database)?);
+		let db = config.db_engine.open(&db_config, path).map_err(Error::Database)?;
 
 		let io_service = IoService::<ClientIoMessage>::start().map_err(Error::Io)?;
 		let client = Arc::new(Client::new(config,
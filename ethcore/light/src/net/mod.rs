@@ -126,13 +126,16 @@ mod timeout {
 }
 
 /// A request id.
-#[cfg(not(test))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct ReqId(usize);
 
 #[cfg(test)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
-pub struct ReqId(pub usize);
+impl ReqId {
+	/// Create a request id with the given number.
+	pub fn new(id: usize) -> Self {
+		ReqId(id)
+	}
+}
 
 impl fmt::Display for ReqId {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -56,6 +56,10 @@ pub enum Request {
 	Body(Body),
 	/// A request for an account.
 	Account(Account),
+	/// A request for an account along with its Merkle proof.
+	AccountProof(AccountProof),
+	/// A request for a storage value along with its Merkle proof.
+	Storage(Storage),
 	/// A request for a contract's code.
 	Code(Code),
 	/// A request for proof of execution.
@@ -141,6 +145,8 @@ impl_single!(TransactionIndex, TransactionIndex, net_request::TransactionIndexRe
 impl_single!(Receipts, BlockReceipts, Vec<Receipt>);
 impl_single!(Body, Body, encoded::Block);
 impl_single!(Account, Account, Option<BasicAccount>);
+impl_single!(AccountProof, AccountProof, (Vec<Bytes>, Option<BasicAccount>));
+impl_single!(Storage, Storage, (Vec<Bytes>, H256));
 impl_single!(Code, Code, Bytes);
 impl_single!(Execution, TransactionProof, super::ExecutionResult);
 impl_single!(Signal, Signal, Vec<u8>);
@@ -251,6 +257,8 @@ pub enum CheckedRequest {
 	Receipts(BlockReceipts, net_request::IncompleteReceiptsRequest),
 	Body(Body, net_request::IncompleteBodyRequest),
 	Account(Account, net_request::IncompleteAccountRequest),
+	AccountProof(AccountProof, net_request::IncompleteAccountRequest),
+	Storage(Storage, net_request::IncompleteStorageRequest),
 	Code(Code, net_request::IncompleteCodeRequest),
 	Execution(TransactionProof, net_request::IncompleteExecutionRequest),
 	Signal(Signal, net_request::IncompleteSignalRequest)
@@ -299,6 +307,21 @@ impl From<Request> for CheckedRequest {
 				};
 				CheckedRequest::Account(req, net_req)
 			}
+			Request::AccountProof(req) => {
+				let net_req = net_request::IncompleteAccountRequest {
+					block_hash: req.header.field(),
+					address_hash: ::hash::keccak(&req.address).into(),
+				};
+				CheckedRequest::AccountProof(req, net_req)
+			}
+			Request::Storage(req) => {
+				let net_req = net_request::IncompleteStorageRequest {
+					block_hash: req.header.field(),
+					address_hash: ::hash::keccak(&req.address).into(),
+					key_hash: ::hash::keccak(&req.key).into(),
+				};
+				CheckedRequest::Storage(req, net_req)
+			}
 			Request::Code(req) => {
 				let net_req = net_request::IncompleteCodeRequest {
 					block_hash: req.header.field(),
@@ -340,6 +363,8 @@ impl CheckedRequest {
 			CheckedRequest::Receipts(_, req) => NetRequest::Receipts(req),
 			CheckedRequest::Body(_, req) => NetRequest::Body(req),
 			CheckedRequest::Account(_, req) => NetRequest::Account(req),
+			CheckedRequest::AccountProof(_, req) => NetRequest::Account(req),
+			CheckedRequest::Storage(_, req) => NetRequest::Storage(req),
 			CheckedRequest::Code(_, req) => NetRequest::Code(req),
 			CheckedRequest::Execution(_, req) => NetRequest::Execution(req),
 			CheckedRequest::Signal(_, req) => NetRequest::Signal(req),
@@ -355,6 +380,8 @@ impl CheckedRequest {
 			CheckedRequest::Receipts(ref x, _) => x.0.needs_header(),
 			CheckedRequest::Body(ref x, _) => x.0.needs_header(),
 			CheckedRequest::Account(ref x, _) => x.header.needs_header(),
+			CheckedRequest::AccountProof(ref x, _) => x.header.needs_header(),
+			CheckedRequest::Storage(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Code(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Execution(ref x, _) => x.header.needs_header(),
 			_ => None,
@@ -369,6 +396,8 @@ impl CheckedRequest {
 			CheckedRequest::Receipts(ref mut x, _) => x.0 = HeaderRef::Stored(header),
 			CheckedRequest::Body(ref mut x, _) => x.0 = HeaderRef::Stored(header),
 			CheckedRequest::Account(ref mut x, _) => x.header = HeaderRef::Stored(header),
+			CheckedRequest::AccountProof(ref mut x, _) => x.header = HeaderRef::Stored(header),
+			CheckedRequest::Storage(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Code(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Execution(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			_ => {},
@@ -448,6 +477,14 @@ impl CheckedRequest {
 						Response::Body(encoded::Block::new(stream.out()))
 					})
 			}
+			CheckedRequest::Storage(ref check, _) => {
+				// empty storage trie -> every key is unset.
+				if check.storage_root == KECCAK_NULL_RLP {
+					return Some(Response::Storage((Vec::new(), H256::new())));
+				}
+
+				cache.lock().storage(&check.storage_root, &check.key).map(Response::Storage)
+			}
 			CheckedRequest::Code(_, ref req) => {
 				if req.code_hash.as_ref().map_or(false, |&h| h == KECCAK_EMPTY) {
 					Some(Response::Code(Vec::new()))
@@ -469,6 +506,8 @@ macro_rules! match_me {
 			CheckedRequest::Receipts($check, $req) => $e,
 			CheckedRequest::Body($check, $req) => $e,
 			CheckedRequest::Account($check, $req) => $e,
+			CheckedRequest::AccountProof($check, $req) => $e,
+			CheckedRequest::Storage($check, $req) => $e,
 			CheckedRequest::Code($check, $req) => $e,
 			CheckedRequest::Execution($check, $req) => $e,
 			CheckedRequest::Signal($check, $req) => $e,
@@ -498,6 +537,8 @@ impl IncompleteRequest for CheckedRequest {
 			CheckedRequest::Receipts(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Body(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Account(_, ref req) => req.check_outputs(f),
+			CheckedRequest::AccountProof(_, ref req) => req.check_outputs(f),
+			CheckedRequest::Storage(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Code(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Execution(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Signal(_, ref req) => req.check_outputs(f),
@@ -520,6 +561,8 @@ impl IncompleteRequest for CheckedRequest {
 			CheckedRequest::Receipts(_, req) => req.complete().map(CompleteRequest::Receipts),
 			CheckedRequest::Body(_, req) => req.complete().map(CompleteRequest::Body),
 			CheckedRequest::Account(_, req) => req.complete().map(CompleteRequest::Account),
+			CheckedRequest::AccountProof(_, req) => req.complete().map(CompleteRequest::Account),
+			CheckedRequest::Storage(_, req) => req.complete().map(CompleteRequest::Storage),
 			CheckedRequest::Code(_, req) => req.complete().map(CompleteRequest::Code),
 			CheckedRequest::Execution(_, req) => req.complete().map(CompleteRequest::Execution),
 			CheckedRequest::Signal(_, req) => req.complete().map(CompleteRequest::Signal),
@@ -571,6 +614,12 @@ impl net_request::CheckedRequest for CheckedRequest {
 			CheckedRequest::Account(ref prover, _) =>
 				expect!((&NetResponse::Account(ref res), _) =>
 					prover.check_response(cache, &res.proof).map(Response::Account)),
+			CheckedRequest::AccountProof(ref prover, _) =>
+				expect!((&NetResponse::Account(ref res), _) =>
+					prover.check_response(cache, &res.proof).map(Response::AccountProof)),
+			CheckedRequest::Storage(ref prover, _) =>
+				expect!((&NetResponse::Storage(ref res), _) =>
+					prover.check_response(cache, &res.proof).map(Response::Storage)),
 			CheckedRequest::Code(ref prover, _) =>
 				expect!((&NetResponse::Code(ref res), &CompleteRequest::Code(ref req)) =>
 					prover.check_response(cache, &req.code_hash, &res.code).map(Response::Code)),
@@ -601,6 +650,12 @@ pub enum Response {
 	/// Response to an Account request.
 	// TODO: `unwrap_or(engine_defaults)`
 	Account(Option<BasicAccount>),
+	/// Response to an account proof request.
+	/// Returns the proof and the proved account.
+	AccountProof((Vec<Bytes>, Option<BasicAccount>)),
+	/// Response to a storage request.
+	/// Returns the proof and the proved storage value.
+	Storage((Vec<Bytes>, H256)),
 	/// Response to a request for code.
 	Code(Vec<u8>),
 	/// Response to a request for proved execution.
@@ -613,14 +668,15 @@ impl net_request::ResponseLike for Response {
 	fn fill_outputs<F>(&self, mut f: F) where F: FnMut(usize, Output) {
 		match *self {
 			Response::HeaderProof((ref hash, _)) => f(0, Output::Hash(*hash)),
-			Response::Account(None) => {
+			Response::Account(None) | Response::AccountProof((_, None)) => {
 				f(0, Output::Hash(KECCAK_EMPTY)); // code hash
 				f(1, Output::Hash(KECCAK_NULL_RLP)); // storage root.
 			}
-			Response::Account(Some(ref acc)) => {
+			Response::Account(Some(ref acc)) | Response::AccountProof((_, Some(ref acc))) => {
 				f(0, Output::Hash(acc.code_hash));
 				f(1, Output::Hash(acc.storage_root));
 			}
+			Response::Storage((_, ref value)) => f(0, Output::Hash(*value)),
 			_ => {}
 		}
 	}
@@ -853,6 +909,52 @@ impl Account {
 	}
 }
 
+/// Request for an account structure along with the proof of its inclusion or exclusion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProof {
+	/// Header for verification.
+	pub header: HeaderRef,
+	/// Address requested.
+	pub address: Address,
+}
+
+impl AccountProof {
+	/// Check a response with an account against the stored header, keeping the proof.
+	pub fn check_response(&self, cache: &Mutex<::cache::Cache>, proof: &[Bytes]) -> Result<(Vec<Bytes>, Option<BasicAccount>), Error> {
+		let account = Account { header: self.header.clone(), address: self.address }.check_response(cache, proof)?;
+		Ok((proof.to_vec(), account))
+	}
+}
+
+/// Request for a storage value of an account along with the proof of its inclusion or exclusion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Storage {
+	/// Header for verification.
+	pub header: HeaderRef,
+	/// Address requested.
+	pub address: Address,
+	/// Storage root of the account at the given header, proved by a prior account request.
+	pub storage_root: H256,
+	/// Storage key requested.
+	pub key: H256,
+}
+
+impl Storage {
+	/// Check a response with a storage value against the account's storage root.
+	pub fn check_response(&self, cache: &Mutex<::cache::Cache>, proof: &[Bytes]) -> Result<(Vec<Bytes>, H256), Error> {
+		let mut db = MemoryDB::new();
+		for node in proof { db.insert(&node[..]); }
+
+		let value = match TrieDB::new(&db, &self.storage_root).and_then(|t| t.get(&keccak(&self.key)))? {
+			Some(val) => UntrustedRlp::new(&val).as_val::<U256>()?.into(),
+			None => H256::new(),
+		};
+
+		cache.lock().insert_storage(self.storage_root, self.key, (proof.to_vec(), value));
+		Ok((proof.to_vec(), value))
+	}
+}
+
 /// Request for account code.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Code {
@@ -941,7 +1043,7 @@ impl Signal {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use ethereum_types::{H256, Address};
+	use ethereum_types::{H256, U256, Address};
 	use memorydb::MemoryDB;
 	use parking_lot::Mutex;
 	use trie::{Trie, TrieMut, SecTrieDB, SecTrieDBMut};
@@ -1089,6 +1191,58 @@ mod tests {
 		assert!(req.check_response(&cache, &proof[..]).is_ok());
 	}
 
+	#[test]
+	fn check_storage_proof() {
+		let mut root = H256::default();
+		let mut db = MemoryDB::new();
+		let key = H256::random();
+		let value = H256::from(0x1234);
+
+		{
+			let mut trie = SecTrieDBMut::new(&mut db, &mut root);
+			for _ in 0..100 {
+				trie.insert(&*H256::random(), &::rlp::encode(&U256::from(1))).unwrap();
+			}
+
+			trie.insert(&*key, &::rlp::encode(&U256::from(&*value))).unwrap();
+		}
+
+		let proof = {
+			let trie = SecTrieDB::new(&db, &root).unwrap();
+			let mut recorder = Recorder::new();
+
+			trie.get_with(&*key, &mut recorder).unwrap().unwrap();
+
+			recorder.drain().into_iter().map(|r| r.data).collect::<Vec<_>>()
+		};
+
+		let req = Storage {
+			header: encoded::Header::new(::rlp::encode(&Header::new()).into_vec()).into(),
+			address: Address::random(),
+			storage_root: root,
+			key: key,
+		};
+
+		let cache = Mutex::new(make_cache());
+		assert_eq!(req.check_response(&cache, &proof[..]).unwrap(), (proof.clone(), value));
+		assert_eq!(cache.lock().storage(&root, &key), Some((proof.clone(), value)));
+
+		let checked = CheckedRequest::from(Request::Storage(req.clone()));
+		match checked.respond_local(&cache) {
+			Some(Response::Storage(res)) => assert_eq!(res, (proof.clone(), value)),
+			_ => panic!("proved storage value is cached"),
+		}
+
+		let empty = CheckedRequest::from(Request::Storage(Storage { storage_root: KECCAK_NULL_RLP, ..req.clone() }));
+		match empty.respond_local(&cache) {
+			Some(Response::Storage(res)) => assert_eq!(res, (Vec::new(), H256::new())),
+			_ => panic!("empty storage trie is answered locally"),
+		}
+
+		let bad_req = Storage { storage_root: H256::random(), ..req };
+		assert!(bad_req.check_response(&cache, &proof[..]).is_err());
+	}
+
 	#[test]
 	fn check_code() {
 		let code = vec![1u8; 256];
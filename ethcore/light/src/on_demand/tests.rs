@@ -22,9 +22,12 @@ use ethcore::header::{Header, Seal};
 use futures::Future;
 use network::{PeerId, NodeId};
 use net::*;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256, Address};
+use memorydb::MemoryDB;
 use parking_lot::Mutex;
 use time::Duration;
+use trie::{Trie, TrieMut, SecTrieDB, SecTrieDBMut};
+use trie::recorder::Recorder;
 use ::request::{self as basic_request, Response};
 
 use std::sync::Arc;
@@ -140,7 +143,7 @@ fn single_request() {
 	let harness = Harness::create();
 
 	let peer_id = 10101;
-	let req_id = ReqId(14426);
+	let req_id = ReqId::new(14426);
 
 	harness.inject_peer(peer_id, Peer {
 		status: dummy_status(),
@@ -201,7 +204,7 @@ fn reassign() {
 	let harness = Harness::create();
 
 	let peer_ids = (10101, 12345);
-	let req_ids = (ReqId(14426), ReqId(555));
+	let req_ids = (ReqId::new(14426), ReqId::new(555));
 
 	harness.inject_peer(peer_ids.0, Peer {
 		status: dummy_status(),
@@ -246,7 +249,7 @@ fn partial_response() {
 	let harness = Harness::create();
 
 	let peer_id = 111;
-	let req_ids = (ReqId(14426), ReqId(555));
+	let req_ids = (ReqId::new(14426), ReqId::new(555));
 
 	harness.inject_peer(peer_id, Peer {
 		status: dummy_status(),
@@ -305,7 +308,7 @@ fn part_bad_part_good() {
 	let harness = Harness::create();
 
 	let peer_id = 111;
-	let req_ids = (ReqId(14426), ReqId(555));
+	let req_ids = (ReqId::new(14426), ReqId::new(555));
 
 	harness.inject_peer(peer_id, Peer {
 		status: dummy_status(),
@@ -373,7 +376,7 @@ fn wrong_kind() {
 	let harness = Harness::create();
 
 	let peer_id = 10101;
-	let req_id = ReqId(14426);
+	let req_id = ReqId::new(14426);
 
 	harness.inject_peer(peer_id, Peer {
 		status: dummy_status(),
@@ -405,7 +408,7 @@ fn back_references() {
 	let harness = Harness::create();
 
 	let peer_id = 10101;
-	let req_id = ReqId(14426);
+	let req_id = ReqId::new(14426);
 
 	harness.inject_peer(peer_id, Peer {
 		status: dummy_status(),
@@ -462,7 +465,7 @@ fn fill_from_cache() {
 	let harness = Harness::create();
 
 	let peer_id = 10101;
-	let req_id = ReqId(14426);
+	let req_id = ReqId::new(14426);
 
 	harness.inject_peer(peer_id, Peer {
 		status: dummy_status(),
@@ -496,3 +499,71 @@ fn fill_from_cache() {
 
 	assert!(recv.wait().is_ok());
 }
+
+#[test]
+fn storage_is_proved_and_cached() {
+	let harness = Harness::create();
+
+	let peer_id = 10101;
+	let req_id = ReqId::new(14426);
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let key = H256::from(5);
+	let value = H256::from(0xabcd);
+
+	let mut db = MemoryDB::new();
+	let mut storage_root = H256::default();
+	{
+		let mut trie = SecTrieDBMut::new(&mut db, &mut storage_root);
+		trie.insert(&*key, &::rlp::encode(&U256::from(&*value))).unwrap();
+	}
+
+	let proof = {
+		let trie = SecTrieDB::new(&db, &storage_root).unwrap();
+		let mut recorder = Recorder::new();
+
+		trie.get_with(&*key, &mut recorder).unwrap().unwrap();
+
+		recorder.drain().into_iter().map(|r| r.data).collect::<Vec<_>>()
+	};
+
+	let mut header = Header::default();
+	header.set_number(1);
+	let header = encoded::Header::new(header.rlp(Seal::With));
+	let storage = || request::Storage {
+		header: header.clone().into(),
+		address: Address::default(),
+		storage_root: storage_root,
+		key: key,
+	};
+
+	let recv = harness.service.request_raw(&Context::NoOp, vec![storage().into()]).unwrap();
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, req_id));
+
+	assert_eq!(harness.service.pending.read().len(), 0);
+
+	harness.service.on_responses(
+		&Context::WithPeer(peer_id),
+		req_id,
+		&[Response::Storage(basic_request::StorageResponse { proof: proof.clone(), value: value })]
+	);
+
+	match recv.wait().unwrap().pop() {
+		Some(super::Response::Storage(res)) => assert_eq!(res, (proof.clone(), value)),
+		_ => panic!("storage request yields storage response"),
+	}
+
+	// answered from the cache without dispatching to a peer.
+	let recv = harness.service.request_raw(&Context::NoOp, vec![storage().into()]).unwrap();
+
+	assert!(harness.service.pending.read().is_empty());
+	match recv.wait().unwrap().pop() {
+		Some(super::Response::Storage(res)) => assert_eq!(res, (proof, value)),
+		_ => panic!("storage request yields storage response"),
+	}
+}
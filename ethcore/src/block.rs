@@ -553,6 +553,15 @@ impl LockedBlock {
 		Ok(SealedBlock { block: s.block, uncle_bytes: s.uncle_bytes })
 	}
 
+	/// Provide a seal generated internally by the engine in order to turn this into a `SealedBlock`.
+	///
+	/// Gives the engine a chance to finish the header through `Engine::on_seal_block`.
+	pub fn seal_internally(self, engine: &EthEngine, seal: Vec<Bytes>) -> Result<SealedBlock, Error> {
+		let mut sealed = self.seal(engine, seal)?;
+		engine.on_seal_block(&mut sealed.block)?;
+		Ok(sealed)
+	}
+
 	/// Provide a valid seal in order to turn this into a `SealedBlock`.
 	/// This does check the validity of `seal` with the engine.
 	/// Returns the `ClosedBlock` back again if the seal is no good.
@@ -32,7 +32,7 @@ use log_entry::{LogEntry, LocalizedLogEntry};
 use receipt::Receipt;
 use blockchain::best_block::{BestBlock, BestAncientBlock};
 use blockchain::block_info::{BlockInfo, BlockLocation, BranchBecomingCanonChainData};
-use blockchain::extras::{BlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions};
+use blockchain::extras::{BlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions, EngineData};
 use types::blockchain_info::BlockChainInfo;
 use types::tree_route::TreeRoute;
 use blockchain::update::ExtrasUpdate;
@@ -688,6 +688,7 @@ impl BlockChain {
 	/// This is used by snapshot restoration and when downloading missing blocks for the chain gap.
 	/// `is_best` forces the best block to be updated to this block.
 	/// `is_ancient` forces the best block of the first block sequence to be updated to this block.
+	/// The first block sequence is complete once an ancient block is also the best block.
 	/// `parent_td` is a parent total diffuculty
 	/// Supply a dummy parent total difficulty when the parent block may not be in the chain.
 	/// Returns true if the block is disconnected.
@@ -734,7 +735,7 @@ impl BlockChain {
 			if is_ancient {
 				let mut best_ancient_block = self.best_ancient_block.write();
 				let ancient_number = best_ancient_block.as_ref().map_or(0, |b| b.number);
-				if self.block_hash(header.number() + 1).is_some() {
+				if is_best || self.block_hash(header.number() + 1).is_some() {
 					batch.delete(db::COL_EXTRA, b"ancient");
 					*best_ancient_block = None;
 				} else if header.number() > ancient_number {
@@ -866,6 +867,16 @@ impl BlockChain {
 		self.db.read(db::COL_EXTRA, &hash)
 	}
 
+	/// Write data the consensus engine stored about a block.
+	pub fn insert_engine_data(&self, batch: &mut DBTransaction, hash: H256, data: Bytes) {
+		batch.write(db::COL_EXTRA, &hash, &EngineData { data: data });
+	}
+
+	/// Get data the consensus engine stored about a block.
+	pub fn get_engine_data(&self, hash: H256) -> Option<Bytes> {
+		self.db.read(db::COL_EXTRA, &hash).map(|engine_data: EngineData| engine_data.data)
+	}
+
 	/// Add a child to a given block. Assumes that the block hash is in
 	/// the chain and the child's parent is this block.
 	///
@@ -1005,6 +1016,14 @@ impl BlockChain {
 		}
 	}
 
+	/// Discard pending insertion updates, used when writing the insertion batch failed.
+	pub fn clear_pending(&self) {
+		*self.pending_best_block.write() = None;
+		self.pending_block_hashes.write().clear();
+		self.pending_block_details.write().clear();
+		self.pending_transaction_addresses.write().clear();
+	}
+
 	/// Apply pending insertion updates
 	pub fn commit(&self) {
 		let mut pending_best_block = self.pending_best_block.write();
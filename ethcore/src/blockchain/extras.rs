@@ -42,6 +42,8 @@ pub enum ExtrasIndex {
 	EpochTransitions = 5,
 	/// Pending epoch transition data index.
 	PendingEpochTransition = 6,
+	/// Consensus engine data index.
+	EngineData = 7,
 }
 
 fn with_index(hash: &H256, i: ExtrasIndex) -> H264 {
@@ -107,6 +109,14 @@ impl Key<::engines::epoch::PendingTransition> for H256 {
 	}
 }
 
+impl Key<EngineData> for H256 {
+	type Target = H264;
+
+	fn key(&self) -> H264 {
+		with_index(self, ExtrasIndex::EngineData)
+	}
+}
+
 /// length of epoch keys.
 pub const EPOCH_KEY_LEN: usize = DB_PREFIX_LEN + 16;
 
@@ -197,6 +207,12 @@ pub struct EpochTransitions {
 	pub candidates: Vec<EpochTransition>,
 }
 
+/// Data stored by the consensus engine about a block, e.g. a snapshot of its state.
+#[derive(Clone, RlpEncodable, RlpDecodable)]
+pub struct EngineData {
+	pub data: Vec<u8>,
+}
+
 #[cfg(test)]
 mod tests {
 	use rlp::*;
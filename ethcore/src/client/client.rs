@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{HashSet, HashMap, BTreeMap, VecDeque};
+use std::collections::{HashSet, HashMap, BTreeMap, BTreeSet, VecDeque};
 use std::str::FromStr;
 use std::sync::{Arc, Weak};
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
@@ -23,7 +23,7 @@ use time::precise_time_ns;
 use itertools::Itertools;
 
 // util
-use hash::keccak;
+use hash::{keccak, KECCAK_NULL_RLP};
 use timer::PerfTimer;
 use bytes::Bytes;
 use journaldb;
@@ -61,10 +61,11 @@ use service::ClientIoMessage;
 use snapshot::{self, io as snapshot_io};
 use spec::Spec;
 use state_db::StateDB;
-use state::{self, State};
+use state::{self, State, StateOverride};
 use trace;
 use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase};
 use trace::FlatTransactionTraces;
+use trace::{DebugTracer, DebugTrace, PrestateAccount};
 use transaction::{self, LocalizedTransaction, UnverifiedTransaction, SignedTransaction, Transaction, PendingTransaction, Action};
 use types::filter::Filter;
 use types::mode::Mode as IpcMode;
@@ -84,6 +85,8 @@ use_contract!(registry, "Registry", "res/contracts/registrar.json");
 const MAX_TX_QUEUE_SIZE: usize = 4096;
 const MAX_QUEUE_SIZE_TO_SLEEP_ON: usize = 2;
 const MIN_HISTORY_SIZE: u64 = 8;
+// Number of recent blocks searched for a finalized block on startup.
+const FINALIZED_BLOCK_LOOKBACK: usize = 64;
 
 /// Report on the status of a client.
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
@@ -96,6 +99,8 @@ pub struct ClientReport {
 	pub gas_processed: U256,
 	/// Memory used by state DB
 	pub state_db_mem: usize,
+	/// Bytes held by the database, if it lives entirely in memory.
+	pub db_memory_used: Option<usize>,
 }
 
 impl ClientReport {
@@ -160,6 +165,8 @@ pub struct Client {
 	notify: RwLock<Vec<Weak<ChainNotify>>>,
 	queue_transactions: AtomicUsize,
 	last_hashes: RwLock<VecDeque<H256>>,
+	/// Latest canonical block known to be final under the consensus rules.
+	finalized_block: RwLock<Option<H256>>,
 	factories: Factories,
 	history: u64,
 	ancient_verifier: Mutex<Option<AncientVerifier>>,
@@ -221,6 +228,7 @@ impl Client {
 		}
 
 		let engine = spec.engine.clone();
+		let finalized_block = latest_finalized_block(&*engine, &chain);
 
 		let block_queue = BlockQueue::new(config.queue.clone(), engine.clone(), message_channel.clone(), config.verifier_type.verifying_seal());
 
@@ -252,6 +260,7 @@ impl Client {
 			notify: RwLock::new(Vec::new()),
 			queue_transactions: AtomicUsize::new(0),
 			last_hashes: RwLock::new(VecDeque::new()),
+			finalized_block: RwLock::new(finalized_block),
 			factories: factories,
 			history: history,
 			ancient_verifier: Mutex::new(None),
@@ -506,6 +515,7 @@ impl Client {
 		}
 
 		let max_blocks_to_import = 4;
+		let mut commit_failed = false;
 		let (imported_blocks, import_results, invalid_blocks, imported, proposed_blocks, duration, is_empty) = {
 			let mut imported_blocks = Vec::with_capacity(max_blocks_to_import);
 			let mut invalid_blocks = HashSet::new();
@@ -532,12 +542,19 @@ impl Client {
 						self.block_queue.mark_as_good(&[header.hash()]);
 						proposed_blocks.push(block.bytes);
 					} else {
-						imported_blocks.push(header.hash());
+						match self.commit_block(closed_block, &header, &block.bytes) {
+							Ok(route) => {
+								imported_blocks.push(header.hash());
+								import_results.push(route);
 
-						let route = self.commit_block(closed_block, &header, &block.bytes);
-						import_results.push(route);
-
-						self.report.write().accrue_block(&block);
+								self.report.write().accrue_block(&block);
+							},
+							Err(e) => {
+								error!(target: "client", "Failed to commit block #{} ({}): {}", header.number(), header.hash(), e);
+								commit_failed = true;
+								break;
+							},
+						}
 					}
 				} else {
 					invalid_blocks.insert(header.hash());
@@ -578,13 +595,20 @@ impl Client {
 		}
 
 		self.db.read().flush().expect("DB flush failed.");
+
+		if commit_failed {
+			warn!(target: "client", "Block import disabled, restart once the database can accept writes again");
+			self.disable();
+		}
 		imported
 	}
 
 	/// Import a block with transaction receipts.
 	/// The block is guaranteed to be the next best blocks in the first block sequence.
+	/// If `is_best` is set the block also becomes the best block, in which case its state must
+	/// already be in the state database.
 	/// Does no sealing or transaction validation.
-	fn import_old_block(&self, block_bytes: Bytes, receipts_bytes: Bytes) -> Result<H256, ::error::Error> {
+	fn import_old_block(&self, block_bytes: Bytes, receipts_bytes: Bytes, is_best: bool) -> Result<H256, ::error::Error> {
 		let block = BlockView::new(&block_bytes);
 		let header = block.header();
 		let receipts = ::rlp::decode_list(&receipts_bytes);
@@ -631,7 +655,11 @@ impl Client {
 
 			// Commit results
 			let mut batch = DBTransaction::new();
-			chain.insert_unordered_block(&mut batch, &block_bytes, receipts, None, false, true);
+			chain.insert_unordered_block(&mut batch, &block_bytes, receipts, None, is_best, true);
+			if is_best {
+				// the state was written without journalling; start the journal from this block.
+				self.state_db.write().journal_under(&mut batch, header.number(), &hash)?;
+			}
 			// Final commit to the DB
 			self.db.read().write_buffered(batch);
 			chain.commit();
@@ -644,7 +672,7 @@ impl Client {
 	// it is for reconstructing the state transition.
 	//
 	// The header passed is from the original block data and is sealed.
-	fn commit_block<B>(&self, block: B, header: &Header, block_data: &[u8]) -> ImportRoute where B: IsBlock + Drain {
+	fn commit_block<B>(&self, block: B, header: &Header, block_data: &[u8]) -> Result<ImportRoute, EthcoreError> where B: IsBlock + Drain {
 		let hash = &header.hash();
 		let number = header.number();
 		let parent = header.parent_hash();
@@ -691,20 +719,40 @@ impl Client {
 		});
 
 		let is_canon = route.enacted.last().map_or(false, |h| h == hash);
+		// Final commit to the DB. A capped database may reject it, caches are only updated once it is accepted
+		{
+			let db = self.db.read();
+			if db.is_capped() {
+				if let Err(e) = db.write(batch) {
+					chain.clear_pending();
+					drop(chain);
+					drop(state);
+					// the block is already in the state journal, reload it from what made it to the database.
+					let mut state_db = self.state_db.write();
+					let cache_size = state_db.cache_size();
+					*state_db = StateDB::new(journaldb::new(db.clone(), self.pruning, ::db::COL_STATE), cache_size);
+					return Err(e.into());
+				}
+			} else {
+				db.write_buffered(batch);
+			}
+		}
 		state.sync_cache(&route.enacted, &route.retracted, is_canon);
-		// Final commit to the DB
-		self.db.read().write_buffered(batch);
 		chain.commit();
 
 		self.check_epoch_end(&header, &chain);
 
+		if is_canon && self.engine.is_finalized(header) {
+			*self.finalized_block.write() = Some(*hash);
+		}
+
 		self.update_last_hashes(&parent, hash);
 
 		if let Err(e) = self.prune_ancient(state, &chain) {
 			warn!("Failed to prune ancient state data: {}", e);
 		}
 
-		route
+		Ok(route)
 	}
 
 	// check for epoch end signal and write pending transition if it occurs.
@@ -975,6 +1023,7 @@ impl Client {
 	pub fn report(&self) -> ClientReport {
 		let mut report = self.report.read().clone();
 		report.state_db_mem = self.state_db.read().mem_used();
+		report.db_memory_used = self.db.read().memory_footprint();
 		report
 	}
 
@@ -1068,20 +1117,21 @@ impl Client {
 		self.history
 	}
 
-	fn block_hash(chain: &BlockChain, miner: &Miner, id: BlockId) -> Option<H256> {
+	fn chain_block_hash(&self, chain: &BlockChain, id: BlockId) -> Option<H256> {
 		match id {
 			BlockId::Hash(hash) => Some(hash),
 			BlockId::Number(number) => chain.block_hash(number),
 			BlockId::Earliest => chain.block_hash(0),
 			BlockId::Latest => Some(chain.best_block_hash()),
-			BlockId::Pending => miner.pending_block_header(chain.best_block_number()).map(|header| header.hash())
+			BlockId::Pending => self.miner.pending_block_header(chain.best_block_number()).map(|header| header.hash()),
+			BlockId::Finalized => self.finalized_block.read().clone(),
 		}
 	}
 
 	fn transaction_address(&self, id: TransactionId) -> Option<TransactionAddress> {
 		match id {
 			TransactionId::Hash(ref hash) => self.chain.read().transaction_address(hash),
-			TransactionId::Location(id, index) => Self::block_hash(&self.chain.read(), &self.miner, id).map(|hash| TransactionAddress {
+			TransactionId::Location(id, index) => self.chain_block_hash(&self.chain.read(), id).map(|hash| TransactionAddress {
 				block_hash: hash,
 				index: index,
 			})
@@ -1173,6 +1223,7 @@ impl Client {
 			BlockId::Earliest => Some(0),
 			BlockId::Latest => Some(self.chain.read().best_block_number()),
 			BlockId::Pending => Some(self.chain.read().best_block_number() + 1),
+			BlockId::Finalized => self.finalized_block.read().and_then(|hash| self.chain.read().block_number(&hash)),
 		}
 	}
 }
@@ -1194,17 +1245,21 @@ impl snapshot::DatabaseRestore for Client {
 		*state_db = StateDB::new(journaldb::new(db.clone(), self.pruning, ::db::COL_STATE), cache_size);
 		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
 		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
+		*self.finalized_block.write() = latest_finalized_block(&*self.engine, &chain);
 		Ok(())
 	}
 }
 
 impl BlockChainClient for Client {
-	fn call(&self, transaction: &SignedTransaction, analytics: CallAnalytics, block: BlockId) -> Result<Executed, CallError> {
+	fn call(&self, transaction: &SignedTransaction, analytics: CallAnalytics, block: BlockId, state_override: Option<&StateOverride>) -> Result<Executed, CallError> {
 		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
 		env_info.gas_limit = U256::max_value();
 
 		// that's just a copy of the state.
 		let mut state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		if let Some(state_override) = state_override {
+			state.apply_overrides(state_override).map_err(ExecutionError::from)?;
+		}
 		let machine = self.engine.machine();
 
 		Self::do_virtual_call(machine, &env_info, &mut state, transaction, analytics)
@@ -1228,7 +1283,7 @@ impl BlockChainClient for Client {
 		Ok(results)
 	}
 
-	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId) -> Result<U256, CallError> {
+	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId, state_override: Option<&StateOverride>) -> Result<U256, CallError> {
 		let (mut upper, max_upper, env_info)  = {
 			let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
 			let init = env_info.gas_limit;
@@ -1238,7 +1293,10 @@ impl BlockChainClient for Client {
 		};
 
 		// that's just a copy of the state.
-		let original_state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		let mut original_state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		if let Some(state_override) = state_override {
+			original_state.apply_overrides(state_override).map_err(ExecutionError::from)?;
+		}
 		let sender = t.sender();
 		let options = || TransactOptions::with_tracing().dont_check_nonce();
 
@@ -1300,6 +1358,82 @@ impl BlockChainClient for Client {
 		Ok(self.replay_block_transactions(block, analytics)?.nth(address.index).expect(PROOF))
 	}
 
+	fn debug_trace(&self, id: TransactionId, tracer: DebugTracer) -> Result<DebugTrace, CallError> {
+		let address = self.transaction_address(id).ok_or(CallError::TransactionNotFound)?;
+		let block = BlockId::Hash(address.block_hash);
+		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
+		let body = self.block_body(block).ok_or(CallError::StatePruned)?;
+		let mut state = self.state_at_beginning(block).ok_or(CallError::StatePruned)?;
+		let machine = self.engine.machine();
+
+		const PROOF: &'static str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
+		let mut txs = body.transactions().into_iter().map(|t| SignedTransaction::new(t).expect(PROOF));
+
+		// bring the state to the one right before the transaction.
+		for t in txs.by_ref().take(address.index) {
+			let x = Self::do_virtual_call(machine, &env_info, &mut state, &t, Default::default())?;
+			env_info.gas_used = env_info.gas_used + x.gas_used;
+		}
+		let t = txs.next().expect("The transaction address contains a valid index within block; qed");
+
+		match tracer {
+			DebugTracer::StructLogger(config) => {
+				let options = TransactOptions::new(trace::NoopTracer, trace::StructLogger::toplevel(config))
+					.dont_check_nonce()
+					.save_output_from_contract();
+				let executed = Executive::new(&mut state, &env_info, machine).transact_virtual(&t, options)?;
+
+				Ok(DebugTrace::StructLogs {
+					gas: executed.gas_used,
+					failed: executed.exception.is_some(),
+					return_value: executed.output,
+					struct_logs: executed.vm_trace.unwrap_or_default(),
+				})
+			},
+			DebugTracer::CallTracer => {
+				let options = TransactOptions::new(trace::CallTracer::default(), trace::NoopVMTracer)
+					.dont_check_nonce()
+					.save_output_from_contract();
+				let executed = Executive::new(&mut state, &env_info, machine).transact_virtual(&t, options)?;
+
+				Ok(DebugTrace::Call(executed.trace.into_iter().next().expect("top-level call or create is always traced; qed")))
+			},
+			DebugTracer::PrestateTracer => {
+				let original = state.clone();
+				let options = TransactOptions::new(trace::PrestateTracer::default(), trace::NoopVMTracer)
+					.dont_check_nonce();
+				let executed = Executive::new(&mut state, &env_info, machine).transact_virtual(&t, options)?;
+				let diff = state.diff_from(original.clone()).map_err(ExecutionError::from)?;
+
+				let touched: BTreeSet<Address> = executed.trace.into_iter()
+					.chain(Some(env_info.author))
+					.collect();
+
+				let mut prestate = BTreeMap::new();
+				for address in touched {
+					if !original.exists(&address).map_err(ExecutionError::from)? {
+						continue;
+					}
+
+					let storage = diff.raw.get(&address)
+						.map(|account_diff| account_diff.storage.iter()
+							.map(|(key, value)| (*key, value.pre().cloned().unwrap_or_default()))
+							.collect())
+						.unwrap_or_default();
+
+					prestate.insert(address, PrestateAccount {
+						balance: original.balance(&address).map_err(ExecutionError::from)?,
+						nonce: original.nonce(&address).map_err(ExecutionError::from)?,
+						code: original.code(&address).map_err(ExecutionError::from)?.map_or_else(Vec::new, |c| (*c).clone()),
+						storage: storage,
+					});
+				}
+
+				Ok(DebugTrace::Prestate(prestate))
+			},
+		}
+	}
+
 	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Box<Iterator<Item = Executed>>, CallError> {
 		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
 		let body = self.block_body(block).ok_or(CallError::StatePruned)?;
@@ -1385,7 +1519,7 @@ impl BlockChainClient for Client {
 			return self.block_header(BlockId::Latest);
 		}
 
-		Self::block_hash(&chain, &self.miner, id).and_then(|hash| chain.block_header_data(&hash))
+		self.chain_block_hash(&chain, id).and_then(|hash| chain.block_header_data(&hash))
 	}
 
 	fn block_number(&self, id: BlockId) -> Option<BlockNumber> {
@@ -1403,7 +1537,7 @@ impl BlockChainClient for Client {
 			return self.block_body(BlockId::Latest);
 		}
 
-		Self::block_hash(&chain, &self.miner, id).and_then(|hash| chain.block_body(&hash))
+		self.chain_block_hash(&chain, id).and_then(|hash| chain.block_body(&hash))
 	}
 
 	fn block(&self, id: BlockId) -> Option<encoded::Block> {
@@ -1417,7 +1551,7 @@ impl BlockChainClient for Client {
 			return self.block(BlockId::Latest);
 		}
 
-		Self::block_hash(&chain, &self.miner, id).and_then(|hash| {
+		self.chain_block_hash(&chain, id).and_then(|hash| {
 			chain.block(&hash)
 		})
 	}
@@ -1428,7 +1562,7 @@ impl BlockChainClient for Client {
 		}
 
 		let chain = self.chain.read();
-		match Self::block_hash(&chain, &self.miner, id) {
+		match self.chain_block_hash(&chain, id) {
 			Some(ref hash) if chain.is_known(hash) => BlockStatus::InChain,
 			Some(hash) => self.block_queue.status(&hash).into(),
 			None => BlockStatus::Unknown
@@ -1447,7 +1581,7 @@ impl BlockChainClient for Client {
 			return Some(latest_difficulty);
 		}
 
-		Self::block_hash(&chain, &self.miner, id).and_then(|hash| chain.block_details(&hash)).map(|d| d.total_difficulty)
+		self.chain_block_hash(&chain, id).and_then(|hash| chain.block_details(&hash)).map(|d| d.total_difficulty)
 	}
 
 	fn nonce(&self, address: &Address, id: BlockId) -> Option<U256> {
@@ -1460,7 +1594,7 @@ impl BlockChainClient for Client {
 
 	fn block_hash(&self, id: BlockId) -> Option<H256> {
 		let chain = self.chain.read();
-		Self::block_hash(&chain, &self.miner, id)
+		self.chain_block_hash(&chain, id)
 	}
 
 	fn code(&self, address: &Address, id: BlockId) -> Option<Option<Bytes>> {
@@ -1649,7 +1783,54 @@ impl BlockChainClient for Client {
 				return Err(BlockImportError::Block(BlockError::UnknownParent(header.parent_hash())));
 			}
 		}
-		self.import_old_block(block_bytes, receipts_bytes).map_err(Into::into)
+		self.import_old_block(block_bytes, receipts_bytes, false).map_err(Into::into)
+	}
+
+	fn state_node_known(&self, account: Option<&H256>, hash: &H256) -> bool {
+		let state_db = self.state_db.read();
+		match account {
+			Some(address_hash) => self.factories.accountdb.readonly(state_db.as_hashdb(), *address_hash).contains(hash),
+			None => state_db.as_hashdb().contains(hash),
+		}
+	}
+
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, accounts: &[H256]) -> Result<(), EthcoreError> {
+		let _import_lock = self.import_lock.lock();
+		let mut state_db = self.state_db.write();
+		for (account, data) in nodes {
+			let hash = keccak(&data);
+			let value = DBValue::from_vec(data);
+			match account {
+				Some(address_hash) => self.factories.accountdb.create(state_db.as_hashdb_mut(), address_hash).emplace(hash, value),
+				None => state_db.as_hashdb_mut().emplace(hash, value),
+			}
+		}
+		for address_hash in accounts {
+			state_db.note_account_hash(address_hash);
+		}
+		let mut batch = DBTransaction::new();
+		state_db.inject(&mut batch)?;
+		self.db.read().write_buffered(batch);
+		self.db.read().flush().expect("DB flush failed.");
+		Ok(())
+	}
+
+	fn import_fast_sync_pivot(&self, block_bytes: Bytes, receipts_bytes: Bytes) -> Result<H256, BlockImportError> {
+		{
+			let header = BlockView::new(&block_bytes).header_view();
+			if self.chain.read().is_known(&header.hash()) {
+				return Err(BlockImportError::Import(ImportError::AlreadyInChain));
+			}
+			let status = self.block_status(BlockId::Hash(header.parent_hash()));
+			if  status == BlockStatus::Unknown || status == BlockStatus::Pending {
+				return Err(BlockImportError::Block(BlockError::UnknownParent(header.parent_hash())));
+			}
+			let state_root = header.state_root();
+			if state_root != KECCAK_NULL_RLP && !self.state_node_known(None, &state_root) {
+				return Err(BlockImportError::Other(format!("State root {} of block {} is not in the database", state_root, header.hash())));
+			}
+		}
+		self.import_old_block(block_bytes, receipts_bytes, true).map_err(Into::into)
 	}
 
 	fn queue_info(&self) -> BlockQueueInfo {
@@ -1666,6 +1847,10 @@ impl BlockChainClient for Client {
 		chain_info
 	}
 
+	fn report(&self) -> ClientReport {
+		Client::report(self)
+	}
+
 	fn additional_params(&self) -> BTreeMap<String, String> {
 		self.engine.additional_params().into_iter().collect()
 	}
@@ -1783,7 +1968,7 @@ impl BlockChainClient for Client {
 	fn call_contract(&self, block_id: BlockId, address: Address, data: Bytes) -> Result<Bytes, String> {
 		let transaction = self.contract_call_tx(block_id, address, data);
 
-		self.call(&transaction, Default::default(), block_id)
+		self.call(&transaction, Default::default(), block_id, None)
 			.map_err(|e| format!("{:?}", e))
 			.map(|executed| {
 				executed.output
@@ -1933,7 +2118,7 @@ impl MiningBlockChainClient for Client {
 			let block_data = block.rlp_bytes();
 			let header = block.header().clone();
 
-			let route = self.commit_block(block, &header, &block_data);
+			let route = self.commit_block(block, &header, &block_data)?;
 			trace!(target: "client", "Imported sealed block #{} ({})", number, h);
 			self.state_db.write().sync_cache(&route.enacted, &route.retracted, false);
 			route
@@ -1988,6 +2173,16 @@ impl super::traits::EngineClient for Client {
 	fn block_header(&self, id: BlockId) -> Option<::encoded::Header> {
 		BlockChainClient::block_header(self, id)
 	}
+
+	fn engine_data(&self, hash: H256) -> Option<Bytes> {
+		self.chain.read().get_engine_data(hash)
+	}
+
+	fn insert_engine_data(&self, hash: H256, data: Bytes) {
+		let mut batch = DBTransaction::new();
+		self.chain.read().insert_engine_data(&mut batch, hash, data);
+		self.db.read().write_buffered(batch);
+	}
 }
 
 impl ProvingBlockChainClient for Client {
@@ -2035,6 +2230,23 @@ impl Drop for Client {
 	}
 }
 
+/// Finds the most recent canonical block the engine considers final,
+/// looking back at most `FINALIZED_BLOCK_LOOKBACK` blocks from the best one.
+fn latest_finalized_block(engine: &EthEngine, chain: &BlockChain) -> Option<H256> {
+	let mut hash = chain.best_block_hash();
+	for _ in 0..FINALIZED_BLOCK_LOOKBACK {
+		let header = chain.block_header(&hash)?;
+		if engine.is_finalized(&header) {
+			return Some(hash);
+		}
+		if header.number() == 0 {
+			break;
+		}
+		hash = *header.parent_hash();
+	}
+	None
+}
+
 /// Returns `LocalizedReceipt` given `LocalizedTransaction`
 /// and a vector of receipts from given block up to transaction index.
 fn transaction_receipt(machine: &::machine::EthereumMachine, mut tx: LocalizedTransaction, mut receipts: Vec<Receipt>) -> LocalizedReceipt {
@@ -15,13 +15,18 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::str::FromStr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::fmt::{Display, Formatter, Error as FmtError};
 
 use mode::Mode as IpcMode;
 use verification::{VerifierType, QueueConfig};
 use journaldb;
-use kvdb_rocksdb::CompactionProfile;
+use kvdb::{self, KeyValueDB};
+use kvdb_memorydb;
+#[cfg(feature = "logdb")]
+use kvdb_logdb;
+use kvdb_rocksdb::{CompactionProfile, Database, DatabaseConfig};
 
 pub use std::time::Duration;
 pub use blockchain::Config as BlockChainConfig;
@@ -69,6 +74,76 @@ impl FromStr for DatabaseCompactionProfile {
 	}
 }
 
+/// Key-value database backend used by the client.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DatabaseEngine {
+	/// RocksDB on disk.
+	RocksDb,
+	/// Pure-Rust log-structured store on disk.
+	#[cfg(feature = "logdb")]
+	LogDb,
+	/// Everything is kept in memory and nothing is written to the database directory.
+	Memory {
+		/// Maximum number of bytes of keys and values, if capped.
+		limit: Option<usize>,
+		/// File loaded on start and written back on shutdown, if any.
+		dump_path: Option<PathBuf>,
+	},
+}
+
+impl Default for DatabaseEngine {
+	fn default() -> Self {
+		DatabaseEngine::RocksDb
+	}
+}
+
+impl DatabaseEngine {
+	/// Whether the database lives entirely in memory.
+	pub fn is_memory(&self) -> bool {
+		match *self {
+			DatabaseEngine::Memory { .. } => true,
+			_ => false,
+		}
+	}
+
+	/// Open a database with the given configuration.
+	/// The path is only used by the on-disk engines; an in-memory database touches the disk only through its dump file.
+	pub fn open(&self, config: &DatabaseConfig, path: &Path) -> kvdb::Result<Arc<KeyValueDB>> {
+		let columns = config.columns.unwrap_or(0);
+		Ok(match *self {
+			DatabaseEngine::RocksDb => Arc::new(Database::open(
+				config,
+				&path.to_str().expect("DB path could not be converted to string.")
+			)?),
+			#[cfg(feature = "logdb")]
+			DatabaseEngine::LogDb => Arc::new(kvdb_logdb::Database::open(
+				&kvdb_logdb::DatabaseConfig { columns: config.columns, wal: config.wal },
+				&path.to_str().expect("DB path could not be converted to string.")
+			)?),
+			DatabaseEngine::Memory { limit, dump_path: Some(ref dump_path) } =>
+				Arc::new(kvdb_memorydb::open(columns, limit, dump_path)?),
+			DatabaseEngine::Memory { limit: Some(limit), dump_path: None } =>
+				Arc::new(kvdb_memorydb::with_limit(columns, limit)),
+			DatabaseEngine::Memory { limit: None, dump_path: None } =>
+				Arc::new(kvdb_memorydb::create(columns)),
+		})
+	}
+}
+
+impl FromStr for DatabaseEngine {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"rocksdb" => Ok(DatabaseEngine::RocksDb),
+			"memory" => Ok(DatabaseEngine::Memory { limit: None, dump_path: None }),
+			#[cfg(feature = "logdb")]
+			"logdb" => Ok(DatabaseEngine::LogDb),
+			_ => Err("Invalid database engine given. Expected rocksdb/memory, or logdb if built with the `logdb` feature.".into()),
+		}
+	}
+}
+
 /// Operating mode for the client.
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum Mode {
@@ -147,6 +222,8 @@ pub struct ClientConfig {
 	pub db_compaction: DatabaseCompactionProfile,
 	/// Should db have WAL enabled?
 	pub db_wal: bool,
+	/// Database backend.
+	pub db_engine: DatabaseEngine,
 	/// Operating mode
 	pub mode: Mode,
 	/// The chain spec name
@@ -167,7 +244,7 @@ pub struct ClientConfig {
 
 #[cfg(test)]
 mod test {
-	use super::{DatabaseCompactionProfile, Mode};
+	use super::{DatabaseCompactionProfile, DatabaseEngine, Mode};
 
 	#[test]
 	fn test_default_compaction_profile() {
@@ -181,6 +258,20 @@ mod test {
 		assert_eq!(DatabaseCompactionProfile::HDD, "hdd".parse().unwrap());
 	}
 
+	#[test]
+	fn test_parsing_database_engine() {
+		assert_eq!(DatabaseEngine::default(), DatabaseEngine::RocksDb);
+		assert_eq!(DatabaseEngine::RocksDb, "rocksdb".parse().unwrap());
+		assert_eq!(DatabaseEngine::Memory { limit: None, dump_path: None }, "memory".parse().unwrap());
+		assert!("leveldb".parse::<DatabaseEngine>().is_err());
+	}
+
+	#[test]
+	#[cfg(feature = "logdb")]
+	fn test_parsing_logdb_engine() {
+		assert_eq!(DatabaseEngine::LogDb, "logdb".parse().unwrap());
+	}
+
 	#[test]
 	fn test_mode_default() {
 		assert_eq!(Mode::default(), Mode::Active);
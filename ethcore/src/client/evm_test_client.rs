@@ -71,6 +71,7 @@ lazy_static! {
 	pub static ref EIP161: spec::Spec = ethereum::new_eip161_test();
 	pub static ref BYZANTIUM: spec::Spec = ethereum::new_byzantium_test();
 	pub static ref BYZANTIUM_TRANSITION: spec::Spec = ethereum::new_transition_test();
+	pub static ref CONSTANTINOPLE: spec::Spec = ethereum::new_constantinople_test();
 }
 
 /// Simplified, single-block EVM test client.
@@ -88,9 +89,9 @@ impl<'a> EvmTestClient<'a> {
 			ForkSpec::EIP150 => Some(&*EIP150),
 			ForkSpec::EIP158 => Some(&*EIP161),
 			ForkSpec::Byzantium => Some(&*BYZANTIUM),
+			ForkSpec::Constantinople => Some(&*CONSTANTINOPLE),
 			ForkSpec::EIP158ToByzantiumAt5 => Some(&BYZANTIUM_TRANSITION),
 			ForkSpec::FrontierToHomesteadAt5 | ForkSpec::HomesteadToDaoAt5 | ForkSpec::HomesteadToEIP150At5 => None,
-			_ => None,
 		}
 	}
 
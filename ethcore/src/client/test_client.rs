@@ -22,7 +22,7 @@ use std::collections::{HashMap, BTreeMap};
 use std::mem;
 use itertools::Itertools;
 use rustc_hex::FromHex;
-use hash::keccak;
+use hash::{keccak, KECCAK_NULL_RLP};
 use ethereum_types::{H256, U256, Address};
 use parking_lot::RwLock;
 use journaldb;
@@ -37,7 +37,7 @@ use blockchain::{TreeRoute, BlockReceipts};
 use client::{
 	BlockChainClient, MiningBlockChainClient, BlockChainInfo, BlockStatus, BlockId,
 	TransactionId, UncleId, TraceId, TraceFilter, LastHashes, CallAnalytics, BlockImportError,
-	ProvingBlockChainClient,
+	ProvingBlockChainClient, ClientReport,
 };
 use db::{NUM_COLUMNS, COL_STATE};
 use header::{Header as BlockHeader, BlockNumber};
@@ -58,8 +58,9 @@ use verification::queue::QueueInfo;
 use block::{OpenBlock, SealedBlock, ClosedBlock};
 use executive::Executed;
 use error::CallError;
-use trace::LocalizedTrace;
+use trace::{LocalizedTrace, DebugTracer, DebugTrace};
 use state_db::StateDB;
+use state::StateOverride;
 use encoded;
 
 /// Test client.
@@ -86,6 +87,8 @@ pub struct TestBlockChainClient {
 	pub code: RwLock<HashMap<Address, Bytes>>,
 	/// Execution result.
 	pub execution_result: RwLock<Option<Result<Executed, CallError>>>,
+	/// Debug trace result.
+	pub debug_trace_result: RwLock<Option<Result<DebugTrace, CallError>>>,
 	/// Transaction receipts.
 	pub receipts: RwLock<HashMap<TransactionId, LocalizedReceipt>>,
 	/// Logs
@@ -108,6 +111,10 @@ pub struct TestBlockChainClient {
 	pub traces: RwLock<Option<Vec<LocalizedTrace>>>,
 	/// Pruning history size to report.
 	pub history: RwLock<Option<u64>>,
+	/// State trie nodes imported by fast sync, keyed by hash.
+	pub state_nodes: RwLock<HashMap<H256, Bytes>>,
+	/// State root of the blocks generated by `add_blocks`.
+	pub state_root: RwLock<H256>,
 }
 
 /// Used for generating test client blocks.
@@ -163,6 +170,7 @@ impl TestBlockChainClient {
 			storage: RwLock::new(HashMap::new()),
 			code: RwLock::new(HashMap::new()),
 			execution_result: RwLock::new(None),
+			debug_trace_result: RwLock::new(None),
 			receipts: RwLock::new(HashMap::new()),
 			logs: RwLock::new(Vec::new()),
 			queue_size: AtomicUsize::new(0),
@@ -174,6 +182,8 @@ impl TestBlockChainClient {
 			first_block: RwLock::new(None),
 			traces: RwLock::new(None),
 			history: RwLock::new(None),
+			state_nodes: RwLock::new(HashMap::new()),
+			state_root: RwLock::new(KECCAK_NULL_RLP),
 		};
 
 		// insert genesis hash.
@@ -194,6 +204,11 @@ impl TestBlockChainClient {
 		*self.execution_result.write() = Some(result);
 	}
 
+	/// Set the debug trace result.
+	pub fn set_debug_trace_result(&self, result: Result<DebugTrace, CallError>) {
+		*self.debug_trace_result.write() = Some(result);
+	}
+
 	/// Set the balance of account `address` to `balance`.
 	pub fn set_balance(&self, address: Address, balance: U256) {
 		self.balances.write().insert(address, balance);
@@ -239,6 +254,7 @@ impl TestBlockChainClient {
 			header.set_number(n as BlockNumber);
 			header.set_gas_limit(U256::from(1_000_000));
 			header.set_extra_data(self.extra_data.clone());
+			header.set_state_root(self.state_root.read().clone());
 			let uncles = match with {
 				EachBlockWith::Uncle | EachBlockWith::UncleAndTransaction => {
 					let mut uncles = RlpStream::new_list(1);
@@ -317,7 +333,8 @@ impl TestBlockChainClient {
 			BlockId::Hash(hash) => Some(hash),
 			BlockId::Number(n) => self.numbers.read().get(&(n as usize)).cloned(),
 			BlockId::Earliest => self.numbers.read().get(&0).cloned(),
-			BlockId::Latest | BlockId::Pending => self.numbers.read().get(&(self.numbers.read().len() - 1)).cloned()
+			BlockId::Latest | BlockId::Pending => self.numbers.read().get(&(self.numbers.read().len() - 1)).cloned(),
+			BlockId::Finalized => None,
 		}
 	}
 
@@ -350,6 +367,11 @@ impl TestBlockChainClient {
 	pub fn set_history(&self, h: Option<u64>) {
 		*self.history.write() = h;
 	}
+
+	/// Set the state root of blocks generated by `add_blocks`.
+	pub fn set_state_root(&self, root: H256) {
+		*self.state_root.write() = root;
+	}
 }
 
 pub fn get_temp_state_db() -> (StateDB, TempDir) {
@@ -407,19 +429,19 @@ impl MiningBlockChainClient for TestBlockChainClient {
 }
 
 impl BlockChainClient for TestBlockChainClient {
-	fn call(&self, _t: &SignedTransaction, _analytics: CallAnalytics, _block: BlockId) -> Result<Executed, CallError> {
+	fn call(&self, _t: &SignedTransaction, _analytics: CallAnalytics, _block: BlockId, _state_override: Option<&StateOverride>) -> Result<Executed, CallError> {
 		self.execution_result.read().clone().unwrap()
 	}
 
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], block: BlockId) -> Result<Vec<Executed>, CallError> {
 		let mut res = Vec::with_capacity(txs.len());
 		for &(ref tx, analytics) in txs {
-			res.push(self.call(tx, analytics, block)?);
+			res.push(self.call(tx, analytics, block, None)?);
 		}
 		Ok(res)
 	}
 
-	fn estimate_gas(&self, _t: &SignedTransaction, _block: BlockId) -> Result<U256, CallError> {
+	fn estimate_gas(&self, _t: &SignedTransaction, _block: BlockId, _state_override: Option<&StateOverride>) -> Result<U256, CallError> {
 		Ok(21000.into())
 	}
 
@@ -427,6 +449,10 @@ impl BlockChainClient for TestBlockChainClient {
 		self.execution_result.read().clone().unwrap()
 	}
 
+	fn debug_trace(&self, _id: TransactionId, _tracer: DebugTracer) -> Result<DebugTrace, CallError> {
+		self.debug_trace_result.read().clone().unwrap()
+	}
+
 	fn replay_block_transactions(&self, _block: BlockId, _analytics: CallAnalytics) -> Result<Box<Iterator<Item = Executed>>, CallError> {
 		Ok(Box::new(self.execution_result.read().clone().unwrap().into_iter()))
 	}
@@ -609,6 +635,9 @@ impl BlockChainClient for TestBlockChainClient {
 
 	// TODO: returns just hashes instead of node state rlp(?)
 	fn state_data(&self, hash: &H256) -> Option<Bytes> {
+		if let Some(data) = self.state_nodes.read().get(hash) {
+			return Some(data.clone());
+		}
 		// starts with 'f' ?
 		if *hash > H256::from("f000000000000000000000000000000000000000000000000000000000000000") {
 			let mut rlp = RlpStream::new();
@@ -629,6 +658,10 @@ impl BlockChainClient for TestBlockChainClient {
 			rlp.append(&receipt);
 			return Some(rlp.out());
 		}
+		// other known blocks have no transactions
+		if self.blocks.read().contains_key(hash) {
+			return Some(::rlp::encode(&BlockReceipts::new(Vec::new())).into_vec());
+		}
 		None
 	}
 
@@ -681,6 +714,26 @@ impl BlockChainClient for TestBlockChainClient {
 		self.import_block(b)
 	}
 
+	fn state_node_known(&self, _account: Option<&H256>, hash: &H256) -> bool {
+		*hash == KECCAK_NULL_RLP || self.state_nodes.read().contains_key(hash)
+	}
+
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, _accounts: &[H256]) -> Result<(), EthcoreError> {
+		let mut state_nodes = self.state_nodes.write();
+		for (_, data) in nodes {
+			state_nodes.insert(keccak(&data), data);
+		}
+		Ok(())
+	}
+
+	fn import_fast_sync_pivot(&self, b: Bytes, _r: Bytes) -> Result<H256, BlockImportError> {
+		let state_root = Rlp::new(&b).val_at::<BlockHeader>(0).state_root().clone();
+		if !self.state_node_known(None, &state_root) {
+			return Err(BlockImportError::Other(format!("State root {} is not in the database", state_root)));
+		}
+		self.import_block(b)
+	}
+
 	fn queue_info(&self) -> QueueInfo {
 		QueueInfo {
 			verified_queue_size: self.queue_size.load(AtomicOrder::Relaxed),
@@ -715,6 +768,10 @@ impl BlockChainClient for TestBlockChainClient {
 		}
 	}
 
+	fn report(&self) -> ClientReport {
+		Default::default()
+	}
+
 	fn filter_traces(&self, _filter: TraceFilter) -> Option<Vec<LocalizedTrace>> {
 		self.traces.read().clone()
 	}
@@ -838,4 +895,10 @@ impl super::traits::EngineClient for TestBlockChainClient {
 	fn block_header(&self, id: BlockId) -> Option<::encoded::Header> {
 		BlockChainClient::block_header(self, id)
 	}
+
+	fn engine_data(&self, _hash: H256) -> Option<Bytes> {
+		None
+	}
+
+	fn insert_engine_data(&self, _hash: H256, _data: Bytes) {}
 }
@@ -19,6 +19,7 @@ use itertools::Itertools;
 
 use block::{OpenBlock, SealedBlock, ClosedBlock};
 use blockchain::TreeRoute;
+use client::ClientReport;
 use encoded;
 use vm::LastHashes;
 use error::{ImportResult, CallError, Error as EthcoreError, BlockImportError};
@@ -29,7 +30,8 @@ use filter::Filter;
 use header::{BlockNumber};
 use log_entry::LocalizedLogEntry;
 use receipt::LocalizedReceipt;
-use trace::LocalizedTrace;
+use state::StateOverride;
+use trace::{LocalizedTrace, DebugTracer, DebugTrace};
 use transaction::{LocalizedTransaction, PendingTransaction, SignedTransaction, ImportResult as TransactionImportResult};
 use verification::queue::QueueInfo as BlockQueueInfo;
 
@@ -163,6 +165,19 @@ pub trait BlockChainClient : Sync + Send {
 	/// Import a block with transaction receipts. Does no sealing and transaction validation.
 	fn import_block_with_receipts(&self, block_bytes: Bytes, receipts_bytes: Bytes) -> Result<H256, BlockImportError>;
 
+	/// Check if a state trie node or contract code is in the state database.
+	/// Storage trie nodes and code are looked up under the address hash of the account they belong to.
+	fn state_node_known(&self, account: Option<&H256>, hash: &H256) -> bool;
+
+	/// Write state trie nodes and contract code fetched from the network directly into the state database.
+	/// Each node is paired with the address hash of the account it belongs to, if any.
+	/// `accounts` lists the address hashes of all accounts found in the nodes.
+	fn import_state_nodes(&self, nodes: Vec<(Option<H256>, Bytes)>, accounts: &[H256]) -> Result<(), EthcoreError>;
+
+	/// Import a block with transaction receipts as the new best block. Does no sealing and transaction validation.
+	/// The state of the block must already be in the state database.
+	fn import_fast_sync_pivot(&self, block_bytes: Bytes, receipts_bytes: Bytes) -> Result<H256, BlockImportError>;
+
 	/// Get block queue information.
 	fn queue_info(&self) -> BlockQueueInfo;
 
@@ -172,6 +187,9 @@ pub trait BlockChainClient : Sync + Send {
 	/// Get blockchain information.
 	fn chain_info(&self) -> BlockChainInfo;
 
+	/// Get a report about blocks imported and memory used.
+	fn report(&self) -> ClientReport;
+
 	/// Get the registrar address, if it exists.
 	fn additional_params(&self) -> BTreeMap<String, String>;
 
@@ -182,18 +200,23 @@ pub trait BlockChainClient : Sync + Send {
 	fn logs(&self, filter: Filter) -> Vec<LocalizedLogEntry>;
 
 	/// Makes a non-persistent transaction call.
-	fn call(&self, tx: &SignedTransaction, analytics: CallAnalytics, block: BlockId) -> Result<Executed, CallError>;
+	/// The state of the accounts may be overridden for the duration of the call.
+	fn call(&self, tx: &SignedTransaction, analytics: CallAnalytics, block: BlockId, state_override: Option<&StateOverride>) -> Result<Executed, CallError>;
 
 	/// Makes multiple non-persistent but dependent transaction calls.
 	/// Returns a vector of successes or a failure if any of the transaction fails.
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], block: BlockId) -> Result<Vec<Executed>, CallError>;
 
 	/// Estimates how much gas will be necessary for a call.
-	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId) -> Result<U256, CallError>;
+	/// The state of the accounts may be overridden for the duration of the estimation.
+	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId, state_override: Option<&StateOverride>) -> Result<U256, CallError>;
 
 	/// Replays a given transaction for inspection.
 	fn replay(&self, t: TransactionId, analytics: CallAnalytics) -> Result<Executed, CallError>;
 
+	/// Replays a given transaction for inspection with one of the debug tracers.
+	fn debug_trace(&self, t: TransactionId, tracer: DebugTracer) -> Result<DebugTrace, CallError>;
+
 	/// Replays all the transactions in a given block for inspection.
 	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Box<Iterator<Item = Executed>>, CallError>;
 
@@ -343,6 +366,12 @@ pub trait EngineClient: Sync + Send {
 
 	/// Get raw block header data by block id.
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header>;
+
+	/// Get data the consensus engine stored about given block earlier.
+	fn engine_data(&self, hash: H256) -> Option<Bytes>;
+
+	/// Persist data the consensus engine keeps about given block, e.g. a snapshot of its state.
+	fn insert_engine_data(&self, hash: H256, data: Bytes);
 }
 
 /// Extended client interface for providing proofs of the state.
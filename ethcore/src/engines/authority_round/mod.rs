@@ -26,7 +26,7 @@ use std::iter::FromIterator;
 use account_provider::AccountProvider;
 use block::*;
 use client::EngineClient;
-use engines::{Engine, Seal, EngineError, ConstructedVerifier};
+use engines::{Engine, Seal, EngineError, ConstructedVerifier, ReportStats};
 use error::{Error, BlockError};
 use ethjson;
 use machine::{AuxiliaryData, Call, EthereumMachine};
@@ -42,6 +42,7 @@ use ethkey::{public_to_address, recover, verify_address, Signature};
 use io::{IoContext, IoHandler, TimerToken, IoService};
 use itertools::{self, Itertools};
 use rlp::{encode, Decodable, DecoderError, Encodable, RlpStream, UntrustedRlp};
+use bytes::Bytes;
 use ethereum_types::{H256, H520, Address, U128, U256};
 use parking_lot::{Mutex, RwLock};
 use unexpected::{Mismatch, OutOfBounds};
@@ -393,6 +394,10 @@ pub struct AuthorityRound {
 	empty_steps_transition: u64,
 	maximum_empty_steps: usize,
 	machine: EthereumMachine,
+	/// Verified headers by step and author, used to detect equivocation.
+	received_step_headers: RwLock<BTreeMap<(usize, Address), Header>>,
+	reported_benign: AtomicUsize,
+	reported_malicious: AtomicUsize,
 }
 
 // header-chain validator.
@@ -624,6 +629,9 @@ impl AuthorityRound {
 				empty_steps_transition: our_params.empty_steps_transition,
 				maximum_empty_steps: our_params.maximum_empty_steps,
 				machine: machine,
+				received_step_headers: RwLock::new(BTreeMap::new()),
+				reported_benign: AtomicUsize::new(0),
+				reported_malicious: AtomicUsize::new(0),
 			});
 
 		// Do not initialize timeouts for tests.
@@ -634,6 +642,52 @@ impl AuthorityRound {
 		Ok(engine)
 	}
 
+	fn report_benign(&self, validator: &Address, set_block: BlockNumber, block: BlockNumber) {
+		self.reported_benign.fetch_add(1, AtomicOrdering::SeqCst);
+		self.validators.report_benign(validator, set_block, block);
+	}
+
+	fn report_malicious(&self, validator: &Address, set_block: BlockNumber, block: BlockNumber, proof: Bytes) {
+		self.reported_malicious.fetch_add(1, AtomicOrdering::SeqCst);
+		self.validators.report_malicious(validator, set_block, block, proof);
+	}
+
+	/// Remember a verified header and report its author if it already sealed
+	/// a different block in the same step.
+	///
+	/// Only headers from the last two rounds of steps are kept.
+	fn check_equivocation(&self, header: &Header, validators: &ValidatorSet, set_number: BlockNumber) -> Result<(), Error> {
+		let step = header_step(header, self.empty_steps_transition)?;
+		let window = 2 * validators.count(header.parent_hash());
+		let oldest_step = self.step.load().saturating_sub(window);
+		if step < oldest_step {
+			return Ok(());
+		}
+
+		let key = (step, *header.author());
+		let sibling = {
+			let mut received = self.received_step_headers.write();
+			*received = received.split_off(&(oldest_step, Address::default()));
+			match received.get(&key) {
+				Some(existing) if existing.hash() != header.hash() => Some(existing.clone()),
+				Some(_) => None,
+				None => {
+					received.insert(key, header.clone());
+					None
+				},
+			}
+		};
+
+		if let Some(sibling) = sibling {
+			warn!(target: "engine", "Validator {} sealed multiple blocks in step {}: {} and {}",
+				header.author(), step, sibling.hash(), header.hash());
+			let proof = ::rlp::encode_list(&[sibling, header.clone()]).into_vec();
+			self.report_malicious(header.author(), set_number, header.number(), proof);
+		}
+
+		Ok(())
+	}
+
 	fn empty_steps(&self, from_step: U256, to_step: U256, parent_hash: H256) -> Vec<EmptyStep> {
 		self.empty_steps.lock().iter().filter(|e| {
 			U256::from(e.step) > from_step &&
@@ -1013,7 +1067,7 @@ impl Engine<EthereumMachine> for AuthorityRound {
 
 		match verify_timestamp(&*self.step, header_step(header, self.empty_steps_transition)?) {
 			Err(BlockError::InvalidSeal) => {
-				self.validators.report_benign(header.author(), set_number, header.number());
+				self.report_benign(header.author(), set_number, header.number());
 				Err(BlockError::InvalidSeal.into())
 			}
 			Err(e) => Err(e.into()),
@@ -1033,7 +1087,7 @@ impl Engine<EthereumMachine> for AuthorityRound {
 			|| (header.number() >= self.validate_step_transition && step <= parent_step) {
 			trace!(target: "engine", "Multiple blocks proposed for step {}.", parent_step);
 
-			self.validators.report_malicious(header.author(), set_number, header.number(), Default::default());
+			self.report_malicious(header.author(), set_number, header.number(), Default::default());
 			Err(EngineError::DoubleVote(header.author().clone()))?;
 		}
 
@@ -1062,7 +1116,7 @@ impl Engine<EthereumMachine> for AuthorityRound {
 			};
 
 			if let err @ Err(_) = validate_empty_steps() {
-				self.validators.report_benign(header.author(), set_number, header.number());
+				self.report_benign(header.author(), set_number, header.number());
 				return err;
 			}
 
@@ -1076,7 +1130,7 @@ impl Engine<EthereumMachine> for AuthorityRound {
 					let skipped_primary = step_proposer(&*self.validators, &parent.hash(), s);
 					// Do not report this signer.
 					if skipped_primary != me {
-						self.validators.report_benign(&skipped_primary, set_number, header.number());
+						self.report_benign(&skipped_primary, set_number, header.number());
 						// Stop reporting once validators start repeating.
 						if !reported.insert(skipped_primary) { break; }
  					}
@@ -1092,8 +1146,8 @@ impl Engine<EthereumMachine> for AuthorityRound {
 		// fetch correct validator set for current epoch, taking into account
 		// finality of previous transitions.
 		let active_set;
-		let validators = if self.immediate_transitions {
-			&*self.validators
+		let (validators, set_number): (&ValidatorSet, BlockNumber) = if self.immediate_transitions {
+			(&*self.validators, header.number())
 		} else {
 			// get correct validator set for epoch.
 			let client = match self.client.read().as_ref().and_then(|weak| weak.upgrade()) {
@@ -1111,7 +1165,7 @@ impl Engine<EthereumMachine> for AuthorityRound {
 			}
 
 			active_set = epoch_manager.validators().clone();
-			&active_set as &_
+			(&active_set as &_, epoch_manager.epoch_transition_number)
 		};
 
 		// verify signature against fixed list, but reports should go to the
@@ -1120,6 +1174,7 @@ impl Engine<EthereumMachine> for AuthorityRound {
 		if res.is_ok() {
 			let header_step = header_step(header, self.empty_steps_transition)?;
 			self.clear_empty_steps(header_step.into());
+			self.check_equivocation(header, validators, set_number)?;
 		}
 		res
 	}
@@ -1287,6 +1342,13 @@ impl Engine<EthereumMachine> for AuthorityRound {
 		}
 	}
 
+	fn report_stats(&self) -> ReportStats {
+		ReportStats {
+			benign: self.reported_benign.load(AtomicOrdering::SeqCst),
+			malicious: self.reported_malicious.load(AtomicOrdering::SeqCst),
+		}
+	}
+
 	fn register_client(&self, client: Weak<EngineClient>) {
 		*self.client.write() = Some(client.clone());
 		self.validators.register_client(client);
@@ -1322,7 +1384,7 @@ mod tests {
 	use account_provider::AccountProvider;
 	use spec::Spec;
 	use transaction::{Action, Transaction};
-	use engines::{Seal, Engine, EngineError, EthEngine};
+	use engines::{Seal, Engine, EngineError, EthEngine, ReportStats};
 	use engines::validator_set::TestSet;
 	use error::Error;
 	use super::{AuthorityRoundParams, AuthorityRound, EmptyStep, SealedEmptyStep};
@@ -1533,6 +1595,58 @@ mod tests {
 		assert_eq!(last_benign.load(AtomicOrdering::SeqCst), 1);
 	}
 
+	#[test]
+	fn reports_multiple_blocks_in_step() {
+		let tap = AccountProvider::transient_provider();
+		let addr = tap.insert_account(keccak("0").into(), "0").unwrap();
+		let last_malicious = Arc::new(AtomicUsize::new(0));
+		let params = AuthorityRoundParams {
+			step_duration: 1,
+			start_step: Some(1),
+			validators: Box::new(TestSet::new(last_malicious.clone(), Default::default())),
+			validate_score_transition: 0,
+			validate_step_transition: 0,
+			immediate_transitions: true,
+			maximum_uncle_count_transition: 0,
+			maximum_uncle_count: 0,
+			empty_steps_transition: u64::max_value(),
+			maximum_empty_steps: 0,
+			block_reward: Default::default(),
+		};
+
+		let aura = {
+			let mut c_params = ::spec::CommonParams::default();
+			c_params.gas_limit_bound_divisor = 5.into();
+			let machine = ::machine::EthereumMachine::regular(c_params, Default::default());
+			AuthorityRound::new(params, machine).unwrap()
+		};
+
+		let sign = |header: &mut Header| {
+			let signature = tap.sign(addr, Some("0".into()), header.bare_hash()).unwrap();
+			header.set_seal(vec![encode(&1usize).into_vec(), encode(&(&*signature as &[u8])).into_vec()]);
+		};
+
+		let mut header: Header = Header::default();
+		header.set_number(2);
+		header.set_gas_limit("222222".parse::<U256>().unwrap());
+		header.set_author(addr);
+		sign(&mut header);
+
+		// Receiving the same block twice is fine.
+		assert!(aura.verify_block_external(&header).is_ok());
+		assert!(aura.verify_block_external(&header).is_ok());
+		assert_eq!(last_malicious.load(AtomicOrdering::SeqCst), 0);
+		assert_eq!(aura.report_stats(), ReportStats::default());
+
+		let mut sibling = header.clone();
+		sibling.set_gas_limit("222223".parse::<U256>().unwrap());
+		sign(&mut sibling);
+
+		assert!(aura.verify_block_external(&sibling).is_ok());
+		assert_eq!(last_malicious.load(AtomicOrdering::SeqCst), 2);
+		assert_eq!(aura.report_stats(), ReportStats { benign: 0, malicious: 1 });
+	}
+
 	#[test]
 	fn test_uncles_transition() {
 		let last_benign = Arc::new(AtomicUsize::new(0));
@@ -0,0 +1,222 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer set and voting state of the Clique engine.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ethereum_types::Address;
+use header::{Header, BlockNumber};
+use engines::EngineError;
+use error::Error;
+use rlp::{Encodable, Decodable, DecoderError, RlpStream, UntrustedRlp};
+
+use super::{VoteType, recover_signer, header_vote};
+
+/// A vote cast by a signer in one of the blocks since the last checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Vote {
+	/// Signer casting the vote.
+	signer: Address,
+	/// Account the vote is about.
+	beneficiary: Address,
+	/// Whether the beneficiary should be added or removed.
+	kind: VoteType,
+}
+
+impl Encodable for Vote {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		let kind: u8 = match self.kind {
+			VoteType::Add => 1,
+			VoteType::Remove => 0,
+		};
+		s.begin_list(3).append(&self.signer).append(&self.beneficiary).append(&kind);
+	}
+}
+
+impl Decodable for Vote {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		let kind = match rlp.val_at::<u8>(2)? {
+			1 => VoteType::Add,
+			0 => VoteType::Remove,
+			_ => return Err(DecoderError::Custom("Invalid vote type.")),
+		};
+		Ok(Vote {
+			signer: rlp.val_at(0)?,
+			beneficiary: rlp.val_at(1)?,
+			kind: kind,
+		})
+	}
+}
+
+/// State of the Clique engine after given block.
+///
+/// Tracks the set of authorized signers, the signers of recent blocks
+/// and votes cast since the last checkpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliqueBlockState {
+	/// Authorized signers, sorted by address.
+	signers: BTreeSet<Address>,
+	/// Signers of the most recent blocks, keyed by block number.
+	recents: BTreeMap<BlockNumber, Address>,
+	/// Votes cast since the last checkpoint.
+	votes: Vec<Vote>,
+}
+
+impl Encodable for CliqueBlockState {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(3);
+		s.append_list(&self.signers.iter().cloned().collect::<Vec<_>>());
+		s.begin_list(self.recents.len());
+		for (number, signer) in &self.recents {
+			s.begin_list(2).append(number).append(signer);
+		}
+		s.append_list(&self.votes);
+	}
+}
+
+impl Decodable for CliqueBlockState {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		let mut recents = BTreeMap::new();
+		for recent in rlp.at(1)?.iter() {
+			recents.insert(recent.val_at(0)?, recent.val_at(1)?);
+		}
+
+		Ok(CliqueBlockState {
+			signers: rlp.list_at::<Address>(0)?.into_iter().collect(),
+			recents: recents,
+			votes: rlp.list_at(2)?,
+		})
+	}
+}
+
+impl CliqueBlockState {
+	/// Create the state at a checkpoint with given signers.
+	pub fn new(signers: BTreeSet<Address>) -> Self {
+		CliqueBlockState {
+			signers,
+			recents: BTreeMap::new(),
+			votes: Vec::new(),
+		}
+	}
+
+	/// Authorized signers, sorted by address.
+	pub fn signers(&self) -> &BTreeSet<Address> {
+		&self.signers
+	}
+
+	/// Returns true if `signer` is expected to seal block `number`.
+	pub fn is_inturn(&self, number: BlockNumber, signer: &Address) -> bool {
+		match self.signers.iter().position(|s| s == signer) {
+			Some(offset) => number % self.signers.len() as u64 == offset as u64,
+			None => false,
+		}
+	}
+
+	/// Returns true if `signer` is authorized and didn't seal any of the recent blocks,
+	/// so it is allowed to seal block `number`.
+	pub fn can_sign(&self, number: BlockNumber, signer: &Address) -> bool {
+		if !self.signers.contains(signer) {
+			return false;
+		}
+
+		let limit = self.recents_limit();
+		!self.recents.iter().any(|(&seen, recent)| {
+			recent == signer && (number < limit || seen > number - limit)
+		})
+	}
+
+	/// Returns true if the vote would change the signer set,
+	/// i.e. it either adds a new signer or removes an existing one.
+	pub fn is_valid_vote(&self, beneficiary: &Address, kind: VoteType) -> bool {
+		let is_signer = self.signers.contains(beneficiary);
+		match kind {
+			VoteType::Add => !is_signer,
+			VoteType::Remove => is_signer,
+		}
+	}
+
+	/// Apply the next header on top of this state.
+	///
+	/// Verifies that the header is signed by an authorized signer which didn't seal
+	/// any of the recent blocks and processes the vote carried in the header.
+	pub fn apply(&mut self, header: &Header, epoch_length: u64) -> Result<Address, Error> {
+		let number = header.number();
+
+		// Votes are reset at every checkpoint.
+		if number % epoch_length == 0 {
+			self.votes.clear();
+		}
+
+		// Forget the signer that is allowed to sign again.
+		let limit = self.recents_limit();
+		if number >= limit {
+			self.recents.remove(&(number - limit));
+		}
+
+		let signer = recover_signer(header)?;
+		if !self.signers.contains(&signer) {
+			return Err(EngineError::NotAuthorized(signer).into());
+		}
+		if self.recents.values().any(|recent| *recent == signer) {
+			return Err(EngineError::TooRecentlySigned(signer).into());
+		}
+		self.recents.insert(number, signer);
+
+		// Only the latest vote of a signer about the beneficiary counts.
+		let beneficiary = *header.author();
+		self.votes.retain(|vote| !(vote.signer == signer && vote.beneficiary == beneficiary));
+
+		let kind = header_vote(header)?;
+		if self.is_valid_vote(&beneficiary, kind) {
+			self.votes.push(Vote { signer, beneficiary, kind });
+		}
+
+		let tally = self.votes.iter()
+			.filter(|vote| vote.beneficiary == beneficiary && vote.kind == kind)
+			.count();
+
+		if tally > self.signers.len() / 2 {
+			match kind {
+				VoteType::Add => {
+					self.signers.insert(beneficiary);
+				},
+				VoteType::Remove => {
+					self.signers.remove(&beneficiary);
+
+					// The signer list shrunk, so one more signer is allowed to sign again.
+					let limit = self.recents_limit();
+					if number >= limit {
+						self.recents.remove(&(number - limit));
+					}
+
+					// Votes cast by the removed signer don't count any more.
+					self.votes.retain(|vote| vote.signer != beneficiary);
+				},
+			}
+
+			// Votes about the beneficiary are settled.
+			self.votes.retain(|vote| vote.beneficiary != beneficiary);
+		}
+
+		Ok(signer)
+	}
+
+	// Number of most recent blocks in which a signer may have signed only one block.
+	fn recents_limit(&self) -> u64 {
+		(self.signers.len() / 2 + 1) as u64
+	}
+}
@@ -0,0 +1,801 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Clique (EIP-225) proof-of-authority engine.
+//!
+//! Blocks are sealed in turns by a set of authorized signers. The signature is stored
+//! in the last 65 bytes of the header extra data. Signers vote on adding or removing
+//! other signers through the block author (beneficiary) and nonce fields. Every `epoch`
+//! blocks a checkpoint header lists all current signers and pending votes are discarded.
+
+mod block_state;
+
+use std::cmp;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Weak, Arc};
+use std::time::{UNIX_EPOCH, Duration};
+
+use account_provider::AccountProvider;
+use block::*;
+use client::EngineClient;
+use engines::{Engine, Seal, EngineError};
+use error::{Error, BlockError};
+use ethjson;
+use hash::KECCAK_EMPTY_LIST_RLP;
+use header::{self, Header, BlockNumber};
+use ids::BlockId;
+use machine::EthereumMachine;
+
+use ethkey::{public_to_address, recover, Signature};
+use io::{IoContext, IoHandler, TimerToken, IoService};
+use lru_cache::LruCache;
+use parking_lot::RwLock;
+use rand::{self, Rng};
+use rlp::{encode, UntrustedRlp};
+use ethereum_types::{H64, H256, H520, Address, U256};
+use unexpected::{Mismatch, OutOfBounds};
+
+use super::signer::EngineSigner;
+
+use self::block_state::CliqueBlockState;
+
+/// Length of the signer vanity prefix of the extra data.
+pub const VANITY_LENGTH: usize = 32;
+/// Length of the signature suffix of the extra data.
+pub const SIGNATURE_LENGTH: usize = 65;
+/// Default number of blocks after which votes are reset and a checkpoint is written.
+pub const DEFAULT_EPOCH_LENGTH: u64 = 30_000;
+/// Default minimal period between blocks in seconds.
+pub const DEFAULT_PERIOD: u64 = 15;
+
+/// Difficulty of blocks sealed by the in-turn signer.
+const DIFF_INTURN: u64 = 2;
+/// Difficulty of blocks sealed by an out-of-turn signer.
+const DIFF_NOTURN: u64 = 1;
+/// Nonce voting for adding the beneficiary to the signer set.
+const NONCE_AUTH_VOTE: [u8; 8] = [0xff; 8];
+/// Nonce voting for removing the beneficiary from the signer set.
+const NONCE_DROP_VOTE: [u8; 8] = [0; 8];
+/// Delay of an out-of-turn signer per position away from the in-turn one, in milliseconds.
+const OUT_OF_TURN_DELAY_MS: u64 = 500;
+/// How often the engine checks if it's time to seal a block, in milliseconds.
+const SEALING_TIMER_MS: u64 = 500;
+/// Number of recent signer sets kept in memory.
+const STATE_CACHE_SIZE: usize = 128;
+/// Number of blocks between signer sets persisted in the database.
+const SNAPSHOT_INTERVAL: u64 = 1024;
+
+/// Kind of a signer vote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoteType {
+	/// Add the beneficiary to the signer set.
+	Add,
+	/// Remove the beneficiary from the signer set.
+	Remove,
+}
+
+impl VoteType {
+	/// Decode the vote from a header nonce.
+	pub fn from_nonce(nonce: H64) -> Result<Self, Error> {
+		if nonce == NONCE_AUTH_VOTE.into() {
+			Ok(VoteType::Add)
+		} else if nonce == NONCE_DROP_VOTE.into() {
+			Ok(VoteType::Remove)
+		} else {
+			Err(EngineError::InvalidVoteNonce(nonce).into())
+		}
+	}
+
+	/// Encode the vote as a header nonce.
+	pub fn as_nonce(&self) -> H64 {
+		match *self {
+			VoteType::Add => NONCE_AUTH_VOTE.into(),
+			VoteType::Remove => NONCE_DROP_VOTE.into(),
+		}
+	}
+}
+
+/// `Clique` params.
+#[derive(Debug, PartialEq)]
+pub struct CliqueParams {
+	/// Minimal period between blocks in seconds.
+	pub period: u64,
+	/// Number of blocks after which votes are reset and a checkpoint is written.
+	pub epoch: u64,
+}
+
+impl From<ethjson::spec::CliqueParams> for CliqueParams {
+	fn from(p: ethjson::spec::CliqueParams) -> Self {
+		CliqueParams {
+			period: p.period.map_or(DEFAULT_PERIOD, Into::into),
+			epoch: p.epoch.map_or(DEFAULT_EPOCH_LENGTH, Into::into),
+		}
+	}
+}
+
+/// Hash signed by the sealer: the header with the signature stripped from the extra data.
+fn seal_hash(header: &Header) -> Result<H256, Error> {
+	let extra_data = header.extra_data();
+	if extra_data.len() < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds {
+			min: Some(VANITY_LENGTH + SIGNATURE_LENGTH),
+			max: None,
+			found: extra_data.len(),
+		}).into());
+	}
+
+	let mut unsigned = header.clone();
+	unsigned.set_extra_data(extra_data[..extra_data.len() - SIGNATURE_LENGTH].to_vec());
+	Ok(unsigned.rlp_keccak(header::Seal::With))
+}
+
+/// Recover the address of the signer from the signature in the extra data.
+pub fn recover_signer(header: &Header) -> Result<Address, Error> {
+	let hash = seal_hash(header)?;
+	let extra_data = header.extra_data();
+	let signature = H520::from_slice(&extra_data[extra_data.len() - SIGNATURE_LENGTH..]);
+	Ok(public_to_address(&recover(&signature.into(), &hash)?))
+}
+
+/// Vote carried in the header nonce.
+pub fn header_vote(header: &Header) -> Result<VoteType, Error> {
+	let nonce = UntrustedRlp::new(&header.seal()[1]).as_val::<H64>()?;
+	VoteType::from_nonce(nonce)
+}
+
+/// Signers listed in the extra data of a checkpoint header.
+pub fn checkpoint_signers(header: &Header) -> Result<BTreeSet<Address>, Error> {
+	let extra_data = header.extra_data();
+	if extra_data.len() < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds {
+			min: Some(VANITY_LENGTH + SIGNATURE_LENGTH),
+			max: None,
+			found: extra_data.len(),
+		}).into());
+	}
+
+	let signers = &extra_data[VANITY_LENGTH..extra_data.len() - SIGNATURE_LENGTH];
+	if signers.len() % 20 != 0 {
+		return Err(EngineError::InvalidCheckpointSigners(signers.len()).into());
+	}
+
+	Ok(signers.chunks(20).map(Address::from_slice).collect())
+}
+
+fn unix_now() -> Duration {
+	UNIX_EPOCH.elapsed().expect("Valid time has to be set in your system.")
+}
+
+/// Engine using Clique (EIP-225) proof-of-authority consensus.
+pub struct Clique {
+	machine: EthereumMachine,
+	period: u64,
+	epoch_length: u64,
+	client: RwLock<Option<Weak<EngineClient>>>,
+	signer: RwLock<EngineSigner>,
+	block_state_by_hash: RwLock<LruCache<H256, CliqueBlockState>>,
+	proposals: RwLock<HashMap<Address, VoteType>>,
+	sealing_service: IoService<()>,
+}
+
+impl Clique {
+	/// Create a new instance of Clique engine.
+	pub fn new(our_params: CliqueParams, machine: EthereumMachine) -> Result<Arc<Self>, Error> {
+		if our_params.epoch == 0 {
+			return Err(EngineError::InvalidParams("Clique epoch length can't be zero.".into()).into());
+		}
+
+		let engine = Arc::new(
+			Clique {
+				machine: machine,
+				period: our_params.period,
+				epoch_length: our_params.epoch,
+				client: RwLock::new(None),
+				signer: Default::default(),
+				block_state_by_hash: RwLock::new(LruCache::new(STATE_CACHE_SIZE)),
+				proposals: RwLock::new(HashMap::new()),
+				sealing_service: IoService::<()>::start()?,
+			});
+
+		let handler = SealingHandler { engine: Arc::downgrade(&engine) };
+		engine.sealing_service.register_handler(Arc::new(handler))?;
+		Ok(engine)
+	}
+
+	/// Vote for adding (`VoteType::Add`) or removing (`VoteType::Remove`) `address`
+	/// in the blocks sealed by this node.
+	pub fn propose(&self, address: Address, kind: VoteType) {
+		self.proposals.write().insert(address, kind);
+	}
+
+	/// Stop voting about `address`.
+	pub fn discard(&self, address: &Address) {
+		self.proposals.write().remove(address);
+	}
+
+	fn is_checkpoint(&self, number: BlockNumber) -> bool {
+		number % self.epoch_length == 0
+	}
+
+	fn client(&self) -> Option<Arc<EngineClient>> {
+		self.client.read().as_ref().and_then(Weak::upgrade)
+	}
+
+	/// Persist the signer set after `header` every `SNAPSHOT_INTERVAL` blocks,
+	/// so that it doesn't have to be recomputed from the last checkpoint after a restart.
+	fn persist_state(&self, header: &Header, state: &CliqueBlockState) {
+		if header.number() % SNAPSHOT_INTERVAL != 0 {
+			return;
+		}
+
+		if let Some(client) = self.client() {
+			client.insert_engine_data(header.hash(), encode(state).into_vec());
+		}
+	}
+
+	/// Signer set after `header` persisted by `persist_state`, if any.
+	fn persisted_state(&self, header: &Header) -> Option<CliqueBlockState> {
+		if header.number() % SNAPSHOT_INTERVAL != 0 {
+			return None;
+		}
+
+		let data = self.client()?.engine_data(header.hash())?;
+		match UntrustedRlp::new(&data).as_val() {
+			Ok(state) => Some(state),
+			Err(e) => {
+				warn!(target: "engine", "Invalid signer set persisted for block {}: {}", header.hash(), e);
+				None
+			}
+		}
+	}
+
+	/// Signer set and voting state after `header`.
+	///
+	/// Walks back to the closest known state or checkpoint and applies the headers in between.
+	/// Signers of the blocks preceding a checkpoint are not known there, so the recency check is
+	/// more lenient for the first few blocks after a checkpoint that is not in the cache.
+	fn state(&self, header: &Header) -> Result<CliqueBlockState, Error> {
+		let mut chain = Vec::new();
+		let mut current = header.clone();
+		let mut state = loop {
+			if let Some(state) = self.block_state_by_hash.write().get_mut(&current.hash()) {
+				break state.clone();
+			}
+
+			if let Some(state) = self.persisted_state(&current) {
+				break state;
+			}
+
+			if current.number() == 0 {
+				break CliqueBlockState::new(checkpoint_signers(&current)?);
+			}
+
+			if self.is_checkpoint(current.number()) {
+				let mut state = CliqueBlockState::new(checkpoint_signers(&current)?);
+				state.apply(&current, self.epoch_length)?;
+				break state;
+			}
+
+			let parent_hash = *current.parent_hash();
+			let parent = self.client()
+				.ok_or(EngineError::RequiresClient)?
+				.block_header(BlockId::Hash(parent_hash))
+				.ok_or(EngineError::MissingAncestor(parent_hash))?
+				.decode();
+
+			chain.push(current);
+			current = parent;
+		};
+
+		for header in chain.iter().rev() {
+			state.apply(header, self.epoch_length)?;
+			self.persist_state(header, &state);
+		}
+
+		self.block_state_by_hash.write().insert(header.hash(), state.clone());
+		Ok(state)
+	}
+
+	fn step(&self) {
+		if !self.signer.read().is_some() {
+			return;
+		}
+
+		if let Some(client) = self.client() {
+			client.update_sealing();
+		}
+	}
+}
+
+struct SealingHandler {
+	engine: Weak<Clique>,
+}
+
+const ENGINE_TIMEOUT_TOKEN: TimerToken = 23;
+
+impl IoHandler<()> for SealingHandler {
+	fn initialize(&self, io: &IoContext<()>) {
+		io.register_timer(ENGINE_TIMEOUT_TOKEN, SEALING_TIMER_MS)
+			.unwrap_or_else(|e| warn!(target: "engine", "Failed to start Clique sealing timer: {}.", e))
+	}
+
+	fn timeout(&self, _io: &IoContext<()>, timer: TimerToken) {
+		if timer == ENGINE_TIMEOUT_TOKEN {
+			if let Some(engine) = self.engine.upgrade() {
+				engine.step();
+			}
+		}
+	}
+}
+
+impl Engine<EthereumMachine> for Clique {
+	fn name(&self) -> &str { "Clique" }
+
+	fn machine(&self) -> &EthereumMachine { &self.machine }
+
+	// Two fields - mix hash (always zero) and nonce (the vote).
+	fn seal_fields(&self, _header: &Header) -> usize { 2 }
+
+	fn seals_internally(&self) -> Option<bool> {
+		Some(self.signer.read().is_some())
+	}
+
+	fn populate_from_parent(&self, header: &mut Header, parent: &Header) {
+		header.set_timestamp(cmp::max(header.timestamp(), parent.timestamp() + self.period));
+
+		let state = match self.state(parent) {
+			Ok(state) => state,
+			Err(e) => {
+				warn!(target: "engine", "populate_from_parent: unable to compute the signer set: {}", e);
+				return;
+			}
+		};
+
+		let inturn = self.signer.read().address().map_or(false, |signer| state.is_inturn(header.number(), &signer));
+		header.set_difficulty(if inturn { DIFF_INTURN } else { DIFF_NOTURN }.into());
+
+		let vote = if self.is_checkpoint(header.number()) {
+			None
+		} else {
+			let proposals: Vec<_> = self.proposals.read().iter()
+				.filter(|&(address, kind)| state.is_valid_vote(address, *kind))
+				.map(|(address, kind)| (*address, *kind))
+				.collect();
+			rand::thread_rng().choose(&proposals).cloned()
+		};
+
+		let (beneficiary, kind) = vote.unwrap_or((Address::default(), VoteType::Remove));
+		header.set_author(beneficiary);
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&kind.as_nonce()).into_vec()]);
+	}
+
+	/// Attempt to seal the block internally.
+	///
+	/// The signature is added to the extra data in `on_seal_block`.
+	fn generate_seal(&self, block: &ExecutedBlock, parent: &Header) -> Seal {
+		let header = block.header();
+		let signer = match self.signer.read().address() {
+			Some(signer) => signer,
+			None => return Seal::None,
+		};
+
+		// Don't seal empty blocks when blocks are produced on demand.
+		if self.period == 0 && block.transactions().is_empty() {
+			return Seal::None;
+		}
+
+		let state = match self.state(parent) {
+			Ok(state) => state,
+			Err(e) => {
+				trace!(target: "engine", "generate_seal: unable to compute the signer set: {}", e);
+				return Seal::None;
+			}
+		};
+
+		if !state.can_sign(header.number(), &signer) {
+			trace!(target: "engine", "generate_seal: {} is not allowed to seal block {}", signer, header.number());
+			return Seal::None;
+		}
+
+		// Out-of-turn signers wait a bit, further signers longer, so that the in-turn signer
+		// has a chance to seal first and out-of-turn blocks don't compete with each other.
+		let signers = state.signers();
+		let position = signers.iter().position(|s| *s == signer).expect("can_sign checks that signer is authorized; qed") as u64;
+		let offset = (position + signers.len() as u64 - header.number() % signers.len() as u64) % signers.len() as u64;
+		let delay = offset * OUT_OF_TURN_DELAY_MS;
+
+		let now = unix_now();
+		let now_ms = now.as_secs() * 1000 + (now.subsec_nanos() / 1_000_000) as u64;
+		if now_ms < header.timestamp() * 1000 + delay {
+			return Seal::None;
+		}
+
+		Seal::Regular(header.seal().to_vec())
+	}
+
+	fn on_seal_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
+		let header = block.fields_mut().header;
+
+		let mut extra_data = header.extra_data().clone();
+		extra_data.resize(VANITY_LENGTH, 0);
+
+		if self.is_checkpoint(header.number()) {
+			let parent = self.client()
+				.ok_or(EngineError::RequiresClient)?
+				.block_header(BlockId::Hash(*header.parent_hash()))
+				.ok_or(EngineError::MissingAncestor(*header.parent_hash()))?
+				.decode();
+
+			// The signer set doesn't change at checkpoints since they carry no votes.
+			for signer in self.state(&parent)?.signers() {
+				extra_data.extend_from_slice(signer);
+			}
+		}
+
+		extra_data.extend_from_slice(&[0; SIGNATURE_LENGTH]);
+		header.set_extra_data(extra_data);
+
+		let signature = self.sign(seal_hash(header)?)?;
+		let mut extra_data = header.extra_data().clone();
+		let signature_start = extra_data.len() - SIGNATURE_LENGTH;
+		extra_data[signature_start..].copy_from_slice(&H520::from(signature));
+		header.set_extra_data(extra_data);
+		Ok(())
+	}
+
+	fn verify_local_seal(&self, _header: &Header) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn verify_block_basic(&self, header: &Header) -> Result<(), Error> {
+		let extra_data_len = header.extra_data().len();
+		if extra_data_len < VANITY_LENGTH + SIGNATURE_LENGTH {
+			return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds {
+				min: Some(VANITY_LENGTH + SIGNATURE_LENGTH),
+				max: None,
+				found: extra_data_len,
+			}).into());
+		}
+
+		let kind = header_vote(header)?;
+		if self.is_checkpoint(header.number()) {
+			if *header.author() != Address::default() {
+				return Err(EngineError::UnexpectedCheckpointVote(Mismatch {
+					expected: Address::default(),
+					found: *header.author(),
+				}).into());
+			}
+			if kind != VoteType::Remove {
+				return Err(EngineError::InvalidVoteNonce(kind.as_nonce()).into());
+			}
+			checkpoint_signers(header)?;
+		} else if extra_data_len != VANITY_LENGTH + SIGNATURE_LENGTH {
+			return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds {
+				min: Some(VANITY_LENGTH + SIGNATURE_LENGTH),
+				max: Some(VANITY_LENGTH + SIGNATURE_LENGTH),
+				found: extra_data_len,
+			}).into());
+		}
+
+		let mix_hash = UntrustedRlp::new(&header.seal()[0]).as_val::<H256>()?;
+		if !mix_hash.is_zero() {
+			return Err(BlockError::MismatchedH256SealElement(Mismatch {
+				expected: H256::default(),
+				found: mix_hash,
+			}).into());
+		}
+
+		if *header.uncles_hash() != KECCAK_EMPTY_LIST_RLP {
+			return Err(BlockError::InvalidUnclesHash(Mismatch {
+				expected: KECCAK_EMPTY_LIST_RLP,
+				found: *header.uncles_hash(),
+			}).into());
+		}
+
+		let difficulty = *header.difficulty();
+		if difficulty != DIFF_INTURN.into() && difficulty != DIFF_NOTURN.into() {
+			return Err(BlockError::DifficultyOutOfBounds(OutOfBounds {
+				min: Some(DIFF_NOTURN.into()),
+				max: Some(DIFF_INTURN.into()),
+				found: difficulty,
+			}).into());
+		}
+
+		Ok(())
+	}
+
+	fn verify_block_family(&self, header: &Header, parent: &Header) -> Result<(), Error> {
+		let min_timestamp = parent.timestamp() + self.period;
+		if header.timestamp() < min_timestamp {
+			return Err(BlockError::InvalidTimestamp(OutOfBounds {
+				min: Some(min_timestamp),
+				max: None,
+				found: header.timestamp(),
+			}).into());
+		}
+
+		let parent_state = self.state(parent)?;
+		let mut state = parent_state.clone();
+		let signer = state.apply(header, self.epoch_length)?;
+
+		let expected_difficulty: U256 = if parent_state.is_inturn(header.number(), &signer) {
+			DIFF_INTURN
+		} else {
+			DIFF_NOTURN
+		}.into();
+		if *header.difficulty() != expected_difficulty {
+			return Err(BlockError::InvalidDifficulty(Mismatch {
+				expected: expected_difficulty,
+				found: *header.difficulty(),
+			}).into());
+		}
+
+		if self.is_checkpoint(header.number()) && checkpoint_signers(header)? != *state.signers() {
+			return Err(EngineError::MismatchedCheckpointSigners.into());
+		}
+
+		self.persist_state(header, &state);
+		self.block_state_by_hash.write().insert(header.hash(), state);
+		Ok(())
+	}
+
+	fn register_client(&self, client: Weak<EngineClient>) {
+		*self.client.write() = Some(client.clone());
+	}
+
+	fn set_signer(&self, ap: Arc<AccountProvider>, address: Address, password: String) {
+		self.signer.write().set(ap, address, password);
+	}
+
+	fn sign(&self, hash: H256) -> Result<Signature, Error> {
+		self.signer.read().sign(hash).map_err(Into::into)
+	}
+
+	fn stop(&self) {
+		self.sealing_service.stop()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::collections::BTreeSet;
+	use hash::keccak;
+	use ethereum_types::{H256, H520, Address, U256};
+	use rlp::{encode, UntrustedRlp};
+	use block::*;
+	use client::BlockChainClient;
+	use ids::BlockId;
+	use tests::helpers::*;
+	use account_provider::AccountProvider;
+	use header::Header;
+	use spec::Spec;
+	use engines::{Engine, Seal};
+	use super::{Clique, CliqueParams, VoteType, VANITY_LENGTH, SIGNATURE_LENGTH, DIFF_INTURN, DIFF_NOTURN, DEFAULT_EPOCH_LENGTH, SNAPSHOT_INTERVAL};
+	use super::{seal_hash, recover_signer, checkpoint_signers};
+	use super::block_state::CliqueBlockState;
+
+	fn difficulty(signers: &[Address], number: u64, signer: &Address) -> u64 {
+		let mut signers = signers.to_vec();
+		signers.sort();
+		if signers[(number % signers.len() as u64) as usize] == *signer { DIFF_INTURN } else { DIFF_NOTURN }
+	}
+
+	fn sealed_header(tap: &AccountProvider, signer: Address, parent: &Header, signers: &[Address], beneficiary: Address, kind: VoteType) -> Header {
+		let mut header = Header::default();
+		header.set_number(parent.number() + 1);
+		header.set_parent_hash(parent.hash());
+		header.set_timestamp(parent.timestamp() + 1);
+		header.set_gas_limit(*parent.gas_limit());
+		header.set_state_root(*parent.state_root());
+		header.set_difficulty(difficulty(signers, header.number(), &signer).into());
+		header.set_author(beneficiary);
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&kind.as_nonce()).into_vec()]);
+		header.set_extra_data(vec![0; VANITY_LENGTH + SIGNATURE_LENGTH]);
+
+		let signature = tap.sign(signer, Some("".into()), seal_hash(&header).unwrap()).unwrap();
+		let mut extra_data = header.extra_data().clone();
+		extra_data[VANITY_LENGTH..].copy_from_slice(&H520::from(signature));
+		header.set_extra_data(extra_data);
+		header
+	}
+
+	fn accounts() -> (AccountProvider, Address, Address, Address) {
+		let tap = AccountProvider::transient_provider();
+		let v0 = tap.insert_account(keccak("0").into(), "").unwrap();
+		let v1 = tap.insert_account(keccak("1").into(), "").unwrap();
+		let v2 = tap.insert_account(keccak("2").into(), "").unwrap();
+		(tap, v0, v1, v2)
+	}
+
+	#[test]
+	fn has_valid_metadata() {
+		let engine = Spec::new_test_clique().engine;
+		assert_eq!(engine.name(), "Clique");
+		assert_eq!(engine.seal_fields(&Header::default()), 2);
+	}
+
+	#[test]
+	fn rejects_zero_epoch() {
+		let machine = Spec::load_machine(include_bytes!("../../../res/clique.json") as &[u8]).unwrap();
+		assert!(Clique::new(CliqueParams { period: 1, epoch: 0 }, machine).is_err());
+	}
+
+	#[test]
+	fn reads_checkpoint_signers() {
+		let (_, v0, v1, _) = accounts();
+		let genesis = Spec::new_test_clique().genesis_header();
+		let expected: BTreeSet<_> = vec![v0, v1].into_iter().collect();
+		assert_eq!(checkpoint_signers(&genesis).unwrap(), expected);
+	}
+
+	#[test]
+	fn recovers_signer_from_extra_data() {
+		let (tap, v0, v1, _) = accounts();
+		let genesis = Spec::new_test_clique().genesis_header();
+		let header = sealed_header(&tap, v0, &genesis, &[v0, v1], Address::default(), VoteType::Remove);
+		assert_eq!(recover_signer(&header).unwrap(), v0);
+	}
+
+	#[test]
+	fn verifies_difficulty() {
+		let (tap, v0, v1, _) = accounts();
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		let header = sealed_header(&tap, v0, &genesis, &[v0, v1], Address::default(), VoteType::Remove);
+		assert!(engine.verify_block_basic(&header).is_ok());
+		assert!(engine.verify_block_family(&header, &genesis).is_ok());
+
+		let mut wrong = header.clone();
+		let flipped = if *header.difficulty() == U256::from(DIFF_INTURN) { DIFF_NOTURN } else { DIFF_INTURN };
+		wrong.set_difficulty(flipped.into());
+		let signature = tap.sign(v0, Some("".into()), seal_hash(&wrong).unwrap()).unwrap();
+		let mut extra_data = wrong.extra_data().clone();
+		extra_data[VANITY_LENGTH..].copy_from_slice(&H520::from(signature));
+		wrong.set_extra_data(extra_data);
+		assert!(engine.verify_block_family(&wrong, &genesis).is_err());
+	}
+
+	#[test]
+	fn rejects_unauthorized_and_recent_signers() {
+		let (tap, v0, v1, v2) = accounts();
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		let unauthorized = sealed_header(&tap, v2, &genesis, &[v0, v1, v2], Address::default(), VoteType::Remove);
+		assert!(engine.verify_block_family(&unauthorized, &genesis).is_err());
+
+		let block1 = sealed_header(&tap, v0, &genesis, &[v0, v1], Address::default(), VoteType::Remove);
+		engine.verify_block_family(&block1, &genesis).unwrap();
+
+		let block2 = sealed_header(&tap, v0, &block1, &[v0, v1], Address::default(), VoteType::Remove);
+		assert!(engine.verify_block_family(&block2, &block1).is_err());
+
+		let block2 = sealed_header(&tap, v1, &block1, &[v0, v1], Address::default(), VoteType::Remove);
+		assert!(engine.verify_block_family(&block2, &block1).is_ok());
+	}
+
+	#[test]
+	fn votes_add_and_remove_signers() {
+		let (tap, v0, v1, v2) = accounts();
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		let block1 = sealed_header(&tap, v0, &genesis, &[v0, v1], v2, VoteType::Add);
+		engine.verify_block_family(&block1, &genesis).unwrap();
+
+		// A single vote out of two signers is not a majority.
+		let early = sealed_header(&tap, v2, &block1, &[v0, v1, v2], Address::default(), VoteType::Remove);
+		assert!(engine.verify_block_family(&early, &block1).is_err());
+
+		let block2 = sealed_header(&tap, v1, &block1, &[v0, v1], v2, VoteType::Add);
+		engine.verify_block_family(&block2, &block1).unwrap();
+
+		let block3 = sealed_header(&tap, v2, &block2, &[v0, v1, v2], Address::default(), VoteType::Remove);
+		engine.verify_block_family(&block3, &block2).unwrap();
+
+		// Two out of three signers vote to remove the newcomer.
+		let block4 = sealed_header(&tap, v0, &block3, &[v0, v1, v2], v2, VoteType::Remove);
+		engine.verify_block_family(&block4, &block3).unwrap();
+		let block5 = sealed_header(&tap, v1, &block4, &[v0, v1, v2], v2, VoteType::Remove);
+		engine.verify_block_family(&block5, &block4).unwrap();
+
+		let block6 = sealed_header(&tap, v2, &block5, &[v0, v1], Address::default(), VoteType::Remove);
+		assert!(engine.verify_block_family(&block6, &block5).is_err());
+	}
+
+	#[test]
+	fn rejects_votes_in_checkpoints() {
+		let (tap, v0, v1, v2) = accounts();
+		let engine = Spec::new_test_clique().engine;
+		let mut parent = Header::default();
+		parent.set_number(29_999);
+
+		let header = sealed_header(&tap, v0, &parent, &[v0, v1], v2, VoteType::Add);
+		assert!(engine.verify_block_basic(&header).is_err());
+	}
+
+	#[test]
+	fn generates_seal() {
+		let (tap, v0, v1, _) = accounts();
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let tap = Arc::new(tap);
+
+		// Signer of keccak("1") is in turn for the first block.
+		let mut signers = vec![v0, v1];
+		signers.sort();
+		let signer = signers[1];
+		engine.set_signer(tap.clone(), signer, "".into());
+
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, signer, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close_and_lock();
+
+		let seal = match engine.generate_seal(b.block(), &genesis_header) {
+			Seal::Regular(seal) => seal,
+			_ => panic!("in-turn signer should seal the block"),
+		};
+		let sealed = b.seal_internally(engine, seal).unwrap();
+		assert_eq!(recover_signer(sealed.header()).unwrap(), signer);
+		assert!(engine.verify_block_basic(sealed.header()).is_ok());
+		assert!(engine.verify_block_family(sealed.header(), &genesis_header).is_ok());
+	}
+
+	#[test]
+	fn imports_blocks_and_persists_signer_set() {
+		let (tap, v0, v1, _) = accounts();
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_clique, None);
+		client.engine().register_client(Arc::downgrade(&client) as _);
+
+		let mut signers = vec![v0, v1];
+		signers.sort();
+
+		// Every block is sealed by the in-turn signer.
+		let mut parent = client.block_header(BlockId::Latest).unwrap().decode();
+		for number in 1..(SNAPSHOT_INTERVAL + 1) {
+			let signer = signers[(number % 2) as usize];
+			let header = sealed_header(&tap, signer, &parent, &signers, Address::default(), VoteType::Remove);
+			client.import_block(create_test_block(&header)).unwrap();
+			client.flush_queue();
+			client.import_verified_blocks();
+			parent = header;
+		}
+		assert_eq!(client.chain_info().best_block_number, SNAPSHOT_INTERVAL);
+
+		let snapshot = client.block_header(BlockId::Number(SNAPSHOT_INTERVAL)).unwrap().decode();
+		let data = ::client::EngineClient::engine_data(&*client, snapshot.hash()).unwrap();
+		let state: CliqueBlockState = UntrustedRlp::new(&data).as_val().unwrap();
+		assert_eq!(state.signers().iter().cloned().collect::<Vec<_>>(), signers);
+
+		// A restarted engine picks the persisted signer set up instead of walking back to the checkpoint.
+		let machine = Spec::load_machine(include_bytes!("../../../res/clique.json") as &[u8]).unwrap();
+		let engine = Clique::new(CliqueParams { period: 1, epoch: DEFAULT_EPOCH_LENGTH }, machine).unwrap();
+		engine.register_client(Arc::downgrade(&client) as _);
+		assert_eq!(engine.persisted_state(&snapshot), Some(state));
+
+		let signer = signers[((SNAPSHOT_INTERVAL + 1) % 2) as usize];
+		let next = sealed_header(&tap, signer, &snapshot, &signers, Address::default(), VoteType::Remove);
+		assert!(engine.verify_block_family(&next, &snapshot).is_ok());
+	}
+}
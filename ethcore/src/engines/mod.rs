@@ -18,6 +18,7 @@
 
 mod authority_round;
 mod basic_authority;
+mod clique;
 mod instant_seal;
 mod null_engine;
 mod signer;
@@ -30,6 +31,7 @@ pub mod epoch;
 
 pub use self::authority_round::AuthorityRound;
 pub use self::basic_authority::BasicAuthority;
+pub use self::clique::Clique;
 pub use self::epoch::{EpochVerifier, Transition as EpochTransition};
 pub use self::instant_seal::InstantSeal;
 pub use self::null_engine::NullEngine;
@@ -52,7 +54,7 @@ use transaction::{UnverifiedTransaction, SignedTransaction};
 
 use ethkey::Signature;
 use parity_machine::{Machine, LocalizedMachine as Localized};
-use ethereum_types::{H256, U256, Address};
+use ethereum_types::{H64, H256, U256, Address};
 use unexpected::{Mismatch, OutOfBounds};
 use bytes::Bytes;
 
@@ -81,6 +83,20 @@ pub enum EngineError {
 	MalformedMessage(String),
 	/// Requires client ref, but none registered.
 	RequiresClient,
+	/// Engine parameters are invalid.
+	InvalidParams(String),
+	/// Signer sealed one of the recent blocks.
+	TooRecentlySigned(Address),
+	/// Seal nonce is neither an authorization nor a drop vote.
+	InvalidVoteNonce(H64),
+	/// Checkpoint header casts a vote.
+	UnexpectedCheckpointVote(Mismatch<Address>),
+	/// Checkpoint signer list has invalid length.
+	InvalidCheckpointSigners(usize),
+	/// Checkpoint signer list differs from the computed one.
+	MismatchedCheckpointSigners,
+	/// Ancestor required to compute the engine state is missing.
+	MissingAncestor(H256),
 }
 
 impl fmt::Display for EngineError {
@@ -96,12 +112,28 @@ impl fmt::Display for EngineError {
 			FailedSystemCall(ref msg) => format!("Failed to make system call: {}", msg),
 			MalformedMessage(ref msg) => format!("Received malformed consensus message: {}", msg),
 			RequiresClient => format!("Call requires client but none registered"),
+			InvalidParams(ref msg) => format!("Invalid engine params: {}", msg),
+			TooRecentlySigned(ref address) => format!("Signer {} signed a block too recently.", address),
+			InvalidVoteNonce(ref nonce) => format!("Invalid nonce {}, expected an authorization or a drop vote.", nonce),
+			UnexpectedCheckpointVote(ref mis) => format!("Checkpoint block casts a vote for {}", mis.found),
+			InvalidCheckpointSigners(len) => format!("Checkpoint signer list of {} bytes is not a multiple of 20.", len),
+			MismatchedCheckpointSigners => "Checkpoint signer list does not match the signer set.".into(),
+			MissingAncestor(ref hash) => format!("Ancestor {} required to compute the engine state is missing.", hash),
 		};
 
 		f.write_fmt(format_args!("Engine error ({})", msg))
 	}
 }
 
+/// Number of validator misbehaviour reports issued by the engine.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReportStats {
+	/// Reports of benign misbehaviour, e.g. skipped steps.
+	pub benign: usize,
+	/// Reports of malicious misbehaviour, e.g. multiple blocks in a single step.
+	pub malicious: usize,
+}
+
 /// Seal type.
 #[derive(Debug, PartialEq, Eq)]
 pub enum Seal {
@@ -207,6 +239,12 @@ pub trait Engine<M: Machine>: Sync + Send {
 		Ok(())
 	}
 
+	/// Block transformation functions, after an internally generated seal was set.
+	/// Allows engines to put the parts of the seal that don't fit into seal fields into the header.
+	fn on_seal_block(&self, _block: &mut M::LiveBlock) -> Result<(), M::Error> {
+		Ok(())
+	}
+
 	/// None means that it requires external input (e.g. PoW) to seal a block.
 	/// Some(true) means the engine is currently prime for seal generation (i.e. node is the current validator).
 	/// Some(false) means that the node might seal internally but is not qualified now.
@@ -300,6 +338,13 @@ pub trait Engine<M: Machine>: Sync + Send {
 	/// Takes a header of a fully verified block.
 	fn is_proposal(&self, _verified_header: &M::Header) -> bool { false }
 
+	/// Whether the block can no longer be reverted under the consensus rules.
+	/// Takes a header of a fully verified block.
+	fn is_finalized(&self, _verified_header: &M::Header) -> bool { false }
+
+	/// Number of validator misbehaviour reports issued so far.
+	fn report_stats(&self) -> ReportStats { ReportStats::default() }
+
 	/// Register an account which signs consensus messages.
 	fn set_signer(&self, _account_provider: Arc<AccountProvider>, _address: Address, _password: String) {}
 
@@ -114,7 +114,13 @@ impl <F> super::EpochVerifier<EthereumMachine> for EpochVerifier<F>
 	where F: Fn(&Signature, &Message) -> Result<Address, Error> + Send + Sync
 {
 	fn verify_light(&self, header: &Header) -> Result<(), Error> {
-		let message = header.bare_hash();
+		if header.seal().len() != 3 {
+			return Err(BlockError::InvalidSealArity(Mismatch { expected: 3, found: header.seal().len() }).into());
+		}
+
+		// Commit signatures are precommits for the block in its sealed view.
+		let vote_step = VoteStep::new(header.number() as usize, consensus_view(header)?, Step::Precommit);
+		let message = message_hash(vote_step, header.bare_hash());
 
 		let mut addresses = HashSet::new();
 		let ref header_signatures_field = header.seal().get(2).ok_or(BlockError::InvalidSeal)?;
@@ -142,8 +148,8 @@ impl <F> super::EpochVerifier<EthereumMachine> for EpochVerifier<F>
 	}
 
 	fn check_finality_proof(&self, proof: &[u8]) -> Option<Vec<H256>> {
-		let header: Header = ::rlp::decode(proof);
-		self.verify_light(&header).ok().map(|_| vec![header.hash()])
+		UntrustedRlp::new(proof).as_val::<Header>().ok()
+			.and_then(|header| self.verify_light(&header).ok().map(|_| vec![header.hash()]))
 	}
 }
 
@@ -700,6 +706,12 @@ impl Engine<EthereumMachine> for Tendermint {
 		self.step_service.stop()
 	}
 
+	fn is_finalized(&self, header: &Header) -> bool {
+		// A commit carries the precommits of more than 2/3 of the validators,
+		// proposals carry an empty signature list.
+		header.seal().get(2).map_or(false, |signatures| signatures.len() > 1)
+	}
+
 	fn is_proposal(&self, header: &Header) -> bool {
 		let signatures_len = header.seal()[2].len();
 		// Signatures have to be an empty list rlp.
@@ -777,8 +789,10 @@ mod tests {
 	use ethereum_types::Address;
 	use bytes::Bytes;
 	use block::*;
+	use client::{BlockChainClient, MiningBlockChainClient};
 	use error::{Error, BlockError};
 	use header::Header;
+	use ids::BlockId;
 	use miner::MinerService;
 	use tests::helpers::*;
 	use account_provider::AccountProvider;
@@ -826,6 +840,18 @@ mod tests {
 		]
 	}
 
+	fn commit_seal(tap: &Arc<AccountProvider>, header: &Header, view: View, signers: &[Address]) -> Vec<Bytes> {
+		let vote_info = message_info_rlp(&VoteStep::new(header.number() as Height, view, Step::Precommit), Some(header.bare_hash()));
+		let signatures = signers.iter()
+			.map(|signer| H520::from(tap.sign(*signer, None, keccak(&vote_info)).unwrap()))
+			.collect::<Vec<_>>();
+		vec![
+			::rlp::encode(&view).into_vec(),
+			::rlp::NULL_RLP.to_vec(),
+			::rlp::encode_list(&signatures).into_vec()
+		]
+	}
+
 	fn insert_and_unlock(tap: &Arc<AccountProvider>, acc: &str) -> Address {
 		let addr = tap.insert_account(keccak(acc).into(), acc).unwrap();
 		tap.unlock_account_permanently(addr, acc.into()).unwrap();
@@ -975,6 +1001,7 @@ mod tests {
 		let (b, seal) = propose_default(&spec, proposer);
 		let sealed = b.lock().seal(spec.engine.as_ref(), seal).unwrap();
 		assert!(spec.engine.is_proposal(sealed.header()));
+		assert!(!spec.engine.is_finalized(sealed.header()));
 	}
 
 	#[test]
@@ -1128,4 +1155,88 @@ mod tests {
 
 		engine.stop();
 	}
+
+	#[test]
+	fn epoch_verifier_checks_precommit_signatures() {
+		let (spec, tap) = setup();
+		let engine = spec.engine;
+
+		let mut header = Header::default();
+		header.set_number(2);
+		header.set_gas_limit(U256::from_str("222222").unwrap());
+		let proposer = insert_and_unlock(&tap, "1");
+		header.set_author(proposer);
+		let voter = insert_and_unlock(&tap, "0");
+		let mut seal = proposal_seal(&tap, &header, 1);
+		seal[1] = ::rlp::NULL_RLP.to_vec();
+
+		let epoch_verifier = super::EpochVerifier {
+			subchain_validators: SimpleList::new(vec![proposer.clone(), voter.clone()]),
+			recover: |s: &Signature, m: &Message| -> Result<Address, Error> { Ok(public_to_address(&recover(s, m)?)) },
+		};
+
+		// Signatures of the bare hash are not precommits.
+		let bare_hash = header.bare_hash();
+		seal[2] = ::rlp::encode_list(&vec![
+			H520::from(tap.sign(proposer, None, bare_hash).unwrap()),
+			H520::from(tap.sign(voter, None, bare_hash).unwrap()),
+		]).into_vec();
+		header.set_seal(seal.clone());
+		match epoch_verifier.verify_light(&header) {
+			Err(Error::Engine(EngineError::NotAuthorized(_))) => {},
+			_ => panic!(),
+		}
+
+		let vote_info = message_info_rlp(&VoteStep::new(2, 1, Step::Precommit), Some(header.bare_hash()));
+		seal[2] = ::rlp::encode_list(&vec![
+			H520::from(tap.sign(proposer, None, keccak(&vote_info)).unwrap()),
+			H520::from(tap.sign(voter, None, keccak(&vote_info)).unwrap()),
+		]).into_vec();
+		header.set_seal(seal);
+		assert!(epoch_verifier.verify_light(&header).is_ok());
+		assert!(engine.is_finalized(&header));
+
+		let proof = ::rlp::encode(&header).into_vec();
+		assert_eq!(epoch_verifier.check_finality_proof(&proof), Some(vec![header.hash()]));
+		assert_eq!(epoch_verifier.check_finality_proof(&[0xff, 0x00]), None);
+
+		engine.stop();
+	}
+
+	#[test]
+	fn client_tracks_finalized_commits() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let v0 = insert_and_unlock(&tap, "0");
+		let v1 = insert_and_unlock(&tap, "1");
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_tendermint, None);
+		assert_eq!(client.block_hash(BlockId::Finalized), None);
+
+		// Commits gossiped by other validators are final as soon as they are imported.
+		for number in 1..3 {
+			let block = client.prepare_open_block(v0, (3141562.into(), 31415620.into()), vec![]).close_and_lock();
+			let seal = commit_seal(&tap, block.header(), 0, &[v0, v1]);
+			let block = block.seal(client.engine(), seal).unwrap();
+			assert!(client.engine().is_finalized(block.header()));
+
+			client.import_block(block.rlp_bytes()).unwrap();
+			client.flush_queue();
+			client.import_verified_blocks();
+
+			assert_eq!(client.chain_info().best_block_number, number);
+			assert_eq!(client.block_hash(BlockId::Finalized), Some(block.header().hash()));
+			assert_eq!(client.block_number(BlockId::Finalized), Some(number));
+		}
+
+		// A commit without enough precommits is rejected and finality doesn't move.
+		let finalized = client.block_hash(BlockId::Finalized);
+		let block = client.prepare_open_block(v0, (3141562.into(), 31415620.into()), vec![]).close_and_lock();
+		let seal = commit_seal(&tap, block.header(), 0, &[v1]);
+		let block = block.seal(client.engine(), seal).unwrap();
+		client.import_block(block.rlp_bytes()).unwrap();
+		client.flush_queue();
+		client.import_verified_blocks();
+
+		assert_eq!(client.chain_info().best_block_number, 2);
+		assert_eq!(client.block_hash(BlockId::Finalized), finalized);
+	}
 }
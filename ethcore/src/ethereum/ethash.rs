@@ -79,14 +79,11 @@ pub struct EthashParams {
 	pub mcip3_dev_reward: U256,
 	/// MCIP-3 contract address for the developer funds.
 	pub mcip3_dev_contract: Address,
-	/// Block reward in base units.
-	pub block_reward: U256,
-	/// EIP-649 transition block.
-	pub eip649_transition: u64,
-	/// EIP-649 bomb delay.
-	pub eip649_delay: u64,
-	/// EIP-649 base reward.
-	pub eip649_reward: Option<U256>,
+	/// Block reward in base units, keyed by the block number it comes into effect at.
+	pub block_reward: BTreeMap<BlockNumber, U256>,
+	/// Difficulty bomb delays in blocks, keyed by the block number they come into effect at.
+	/// Delays add up, each one applies to all blocks from its transition on.
+	pub difficulty_bomb_delays: BTreeMap<BlockNumber, BlockNumber>,
 	/// EXPIP-2 block height
 	pub expip2_transition: u64,
 	/// EXPIP-2 duration limit
@@ -95,6 +92,36 @@ pub struct EthashParams {
 
 impl From<ethjson::spec::EthashParams> for EthashParams {
 	fn from(p: ethjson::spec::EthashParams) -> Self {
+		let mut block_reward: BTreeMap<BlockNumber, U256> = match p.block_reward {
+			Some(ethjson::spec::BlockReward::Single(reward)) => {
+				let mut rewards = BTreeMap::new();
+				rewards.insert(0, reward.into());
+				rewards
+			},
+			Some(ethjson::spec::BlockReward::Multi(rewards)) => rewards.into_iter()
+				.map(|(block, reward)| (block.into(), reward.into()))
+				.collect(),
+			None => {
+				let mut rewards = BTreeMap::new();
+				rewards.insert(0, U256::zero());
+				rewards
+			},
+		};
+
+		let mut difficulty_bomb_delays: BTreeMap<BlockNumber, BlockNumber> = p.difficulty_bomb_delays
+			.map_or_else(BTreeMap::new, |delays| delays.into_iter()
+				.map(|(block, delay)| (block.into(), delay.into()))
+				.collect());
+
+		// Legacy EIP-649 params are a single entry of the transition maps.
+		// They always overrode the base reward, even when the transition is at genesis.
+		if let Some(transition) = p.eip649_transition.map(Into::into) {
+			if let Some(reward) = p.eip649_reward {
+				block_reward.insert(transition, reward.into());
+			}
+			difficulty_bomb_delays.insert(transition, p.eip649_delay.map_or(DEFAULT_EIP649_DELAY, Into::into));
+		}
+
 		EthashParams {
 			minimum_difficulty: p.minimum_difficulty.into(),
 			difficulty_bound_divisor: p.difficulty_bound_divisor.into(),
@@ -115,10 +142,8 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			mcip3_ubi_contract: p.mcip3_ubi_contract.map_or_else(Address::new, Into::into),
 			mcip3_dev_reward: p.mcip3_dev_reward.map_or(U256::from(0), Into::into),
 			mcip3_dev_contract: p.mcip3_dev_contract.map_or_else(Address::new, Into::into),
-			block_reward: p.block_reward.map_or_else(Default::default, Into::into),
-			eip649_transition: p.eip649_transition.map_or(u64::max_value(), Into::into),
-			eip649_delay: p.eip649_delay.map_or(DEFAULT_EIP649_DELAY, Into::into),
-			eip649_reward: p.eip649_reward.map(Into::into),
+			block_reward: block_reward,
+			difficulty_bomb_delays: difficulty_bomb_delays,
 			expip2_transition: p.expip2_transition.map_or(u64::max_value(), Into::into),
 			expip2_duration_limit: p.expip2_duration_limit.map_or(30, Into::into),
 		}
@@ -207,12 +232,11 @@ impl Engine<EthereumMachine> for Arc<Ethash> {
 		let author = *LiveBlock::header(&*block).author();
 		let number = LiveBlock::header(&*block).number();
 
-		// Applies EIP-649 reward.
-		let reward = if number >= self.ethash_params.eip649_transition {
-			self.ethash_params.eip649_reward.unwrap_or(self.ethash_params.block_reward)
-		} else {
-			self.ethash_params.block_reward
-		};
+		// Applies the latest reward change up to this block.
+		let reward = self.ethash_params.block_reward.iter()
+			.rev()
+			.find(|&(block, _)| *block <= number)
+			.map_or_else(U256::zero, |(_, reward)| *reward);
 
 		// Applies ECIP-1017 eras.
 		let eras_rounds = self.ethash_params.ecip1017_era_rounds;
@@ -398,8 +422,10 @@ impl Ethash {
 		if header.number() < self.ethash_params.bomb_defuse_transition {
 			if header.number() < self.ethash_params.ecip1010_pause_transition {
 				let mut number = header.number();
-				if number >= self.ethash_params.eip649_transition {
-					number = number.saturating_sub(self.ethash_params.eip649_delay);
+				let delays = self.ethash_params.difficulty_bomb_delays.iter()
+					.take_while(|&(block, _)| *block <= header.number());
+				for (_, delay) in delays {
+					number = number.saturating_sub(*delay);
 				}
 				let period = (number / EXP_DIFF_PERIOD) as usize;
 				if period > 1 {
@@ -470,6 +496,7 @@ fn ecip1017_eras_block_reward(era_rounds: u64, mut reward: U256, block_number:u6
 mod tests {
 	use std::str::FromStr;
 	use std::sync::Arc;
+	use std::collections::BTreeMap;
 	use ethereum_types::{H64, H256, U256, Address};
 	use block::*;
 	use tests::helpers::*;
@@ -552,6 +579,49 @@ mod tests {
 		assert_eq!(b.state().balance(&uncle_author).unwrap(), "3cb71f51fc558000".into());
 	}
 
+	#[test]
+	fn on_close_block_applies_block_reward_transitions() {
+		let mut block_reward = BTreeMap::new();
+		block_reward.insert(0, U256::from(1000));
+		block_reward.insert(5, U256::from(500));
+		let ethparams = EthashParams {
+			block_reward: block_reward,
+			..get_default_ethash_params()
+		};
+		let ethash = Ethash::new(&::std::env::temp_dir(), ethparams, new_homestead_test_machine(), None);
+		let spec = test_spec();
+
+		let reward_at = |number: u64| {
+			let mut parent_header = spec.genesis_header();
+			parent_header.set_number(number - 1);
+			let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+			let last_hashes = Arc::new(vec![parent_header.hash()]);
+			let b = OpenBlock::new(&ethash, Default::default(), false, db, &parent_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+			let b = b.close();
+			b.state().balance(&Address::zero()).unwrap()
+		};
+
+		assert_eq!(reward_at(1), U256::from(1000));
+		assert_eq!(reward_at(4), U256::from(1000));
+		assert_eq!(reward_at(5), U256::from(500));
+		assert_eq!(reward_at(6), U256::from(500));
+	}
+
+	#[test]
+	fn legacy_eip649_params_override_genesis_block_reward() {
+		let spec = ::ethjson::spec::Spec::load(&include_bytes!("../../res/ethereum/byzantium_test.json")[..]).unwrap();
+		let ethparams: EthashParams = match spec.engine {
+			::ethjson::spec::Engine::Ethash(ethash) => ethash.params.into(),
+			_ => panic!("Byzantium test spec uses Ethash"),
+		};
+		let mut block_reward = BTreeMap::new();
+		block_reward.insert(0, U256::from_str("29A2241AF62C0000").unwrap());
+		assert_eq!(ethparams.block_reward, block_reward);
+		let mut difficulty_bomb_delays = BTreeMap::new();
+		difficulty_bomb_delays.insert(0, 3000000);
+		assert_eq!(ethparams.difficulty_bomb_delays, difficulty_bomb_delays);
+	}
+
 	#[test]
 	fn has_valid_mcip3_era_block_rewards() {
 		let spec = new_mcip3_test();
@@ -835,6 +905,35 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn difficulty_bomb_delays_add_up() {
+		let mut difficulty_bomb_delays = BTreeMap::new();
+		difficulty_bomb_delays.insert(4_000_000, 1_000_000);
+		difficulty_bomb_delays.insert(5_000_000, 2_000_000);
+		let ethparams = EthashParams {
+			difficulty_bomb_delays: difficulty_bomb_delays,
+			..get_default_ethash_params()
+		};
+		let delayed = Ethash::new(&::std::env::temp_dir(), ethparams, new_homestead_test_machine(), None);
+		let plain = Ethash::new(&::std::env::temp_dir(), get_default_ethash_params(), new_homestead_test_machine(), None);
+
+		let difficulty = |ethash: &Ethash, number: u64| {
+			let mut parent_header = Header::default();
+			parent_header.set_number(number - 1);
+			parent_header.set_difficulty(U256::from_str("1fd0fd70792b").unwrap());
+			parent_header.set_timestamp(1463003133);
+			let mut header = Header::default();
+			header.set_number(number);
+			header.set_timestamp(parent_header.timestamp() + 20);
+			ethash.calculate_difficulty(&header, &parent_header)
+		};
+
+		assert_eq!(difficulty(&delayed, 3_900_000), difficulty(&plain, 3_900_000));
+		assert_eq!(difficulty(&delayed, 4_500_000), difficulty(&plain, 3_500_000));
+		assert_eq!(difficulty(&delayed, 5_500_000), difficulty(&plain, 2_500_000));
+		assert!(difficulty(&delayed, 5_500_000) != difficulty(&plain, 5_500_000));
+	}
+
 	#[test]
 	fn difficulty_max_timestamp() {
 		let machine = new_homestead_test_machine();
@@ -63,6 +63,15 @@ pub fn contract_address(address_scheme: CreateContractAddress, sender: &Address,
 			&mut buffer[20..].copy_from_slice(&code_hash[..]);
 			(From::from(keccak(&buffer[..])), Some(code_hash))
 		},
+		CreateContractAddress::FromSenderSaltAndCodeHash(salt) => {
+			let code_hash = keccak(code);
+			let mut buffer = [0u8; 1 + 20 + 32 + 32];
+			buffer[0] = 0xff;
+			&mut buffer[1..(1 + 20)].copy_from_slice(&sender[..]);
+			&mut buffer[(1 + 20)..(1 + 20 + 32)].copy_from_slice(&salt[..]);
+			&mut buffer[(1 + 20 + 32)..].copy_from_slice(&code_hash[..]);
+			(From::from(keccak(&buffer[..])), Some(code_hash))
+		},
 	}
 }
 
@@ -273,6 +282,9 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 
 		let mut substate = Substate::new();
 
+		// storage values at the beginning of the transaction are the original ones (EIP-1283).
+		self.state.clear_original_storage();
+
 		// NOTE: there can be no invalid transactions from this point.
 		if !schedule.eip86 || !t.is_unsigned() {
 			self.state.inc_nonce(&sender)?;
@@ -595,7 +607,8 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		let schedule = self.machine.schedule(self.info.number);
 
 		// refunds from SSTORE nonzero -> zero
-		let sstore_refunds = U256::from(schedule.sstore_refund_gas) * substate.sstore_clears_count;
+		assert!(substate.sstore_clears_refund >= 0, "On transaction level, sstore clears refund cannot go below zero.");
+		let sstore_refunds = U256::from(substate.sstore_clears_refund as u64);
 		// refunds from contract suicides
 		let suicide_refunds = U256::from(schedule.suicide_refund_gas) * U256::from(substate.suicides.len());
 		let refunds_bound = sstore_refunds + suicide_refunds;
@@ -689,6 +702,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 #[cfg(test)]
 #[allow(dead_code)]
 mod tests {
+	use std::collections::BTreeMap;
 	use std::sync::Arc;
 	use std::str::FromStr;
 	use rustc_hex::FromHex;
@@ -705,6 +719,7 @@ mod tests {
 	use trace::trace;
 	use trace::{FlatTrace, Tracer, NoopTracer, ExecutiveTracer};
 	use trace::{VMTrace, VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff, VMTracer, NoopVMTracer, ExecutiveVMTracer};
+	use trace::{CallTracer, CallFrame, CallFrameType, StructLogger, StructLog};
 	use transaction::{Action, Transaction};
 
 	fn make_frontier_machine(max_depth: usize) -> EthereumMachine {
@@ -720,6 +735,22 @@ mod tests {
 		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderAndNonce, &address, &U256::from(88), &[]).0);
 	}
 
+	#[test]
+	fn test_contract_address_eip1014() {
+		let salt = H256::zero();
+		let zero = Address::zero();
+		let deadbeef = Address::from_str("deadbeef00000000000000000000000000000000").unwrap();
+
+		let expected_address = Address::from_str("4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38").unwrap();
+		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(salt), &zero, &U256::zero(), &[0x00]).0);
+
+		let expected_address = Address::from_str("b928f69bb1d91cd65274e3c79d8986362984fda3").unwrap();
+		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(salt), &deadbeef, &U256::zero(), &[0x00]).0);
+
+		let expected_address = Address::from_str("e33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0").unwrap();
+		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(salt), &zero, &U256::zero(), &[]).0);
+	}
+
 	// TODO: replace params with transactions!
 	evm_test!{test_sender_balance: test_sender_balance_jit, test_sender_balance_int}
 	fn test_sender_balance(factory: Factory) {
@@ -928,6 +959,127 @@ mod tests {
 		assert_eq!(vm_tracer.drain().unwrap(), expected_vm_trace);
 	}
 
+	#[test]
+	fn test_call_tracer() {
+		// same code as in `test_call_to_create`.
+		let code = "7c601080600c6000396000f3006000355415600957005b60203560003555600052601d60036017f0600055".from_hex().unwrap();
+
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.code_address = address.clone();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code));
+		params.value = ActionValue::Transfer(U256::from(100));
+		params.call_type = CallType::Call;
+		let mut state = get_temp_state();
+		state.add_balance(&sender, &U256::from(100), CleanupMode::NoEmpty).unwrap();
+		let info = EnvInfo::default();
+		let machine = make_frontier_machine(5);
+		let mut substate = Substate::new();
+		let mut tracer = CallTracer::default();
+
+		{
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			let output = BytesRef::Fixed(&mut[0u8;0]);
+			ex.call(params, &mut substate, output, &mut tracer, &mut NoopVMTracer).unwrap();
+		}
+
+		let expected_trace = vec![CallFrame {
+			frame_type: CallFrameType::Call,
+			from: "cd1722f3947def4cf144679da39c4c32bdc35681".into(),
+			to: Some("b010143a42d5980c7e5ef0e4a4416dc098a4fed3".into()),
+			value: 100.into(),
+			gas: 100000.into(),
+			gas_used: Some(55_248.into()),
+			input: vec![],
+			output: Some(vec![]),
+			error: None,
+			calls: vec![CallFrame {
+				frame_type: CallFrameType::Create,
+				from: "b010143a42d5980c7e5ef0e4a4416dc098a4fed3".into(),
+				to: Some("c6d80f262ae5e0f164e5fde365044d7ada2bfa34".into()),
+				value: 23.into(),
+				gas: 67979.into(),
+				gas_used: Some(3224.into()),
+				input: vec![96, 16, 128, 96, 12, 96, 0, 57, 96, 0, 243, 0, 96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53, 85],
+				output: Some(vec![96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53]),
+				error: None,
+				calls: vec![],
+			}],
+		}];
+
+		assert_eq!(tracer.drain(), expected_trace);
+	}
+
+	#[test]
+	fn test_struct_logger() {
+		// code:
+		//
+		// 60 2a - push 42
+		// 60 00 - push 0
+		// 55 - sstore
+		// 60 00 - push 0
+		// 54 - sload
+		// 60 00 - push 0
+		// 52 - mstore
+		// 00 - stop
+
+		let code = "602a60005560005460005200".from_hex().unwrap();
+
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.code_address = address.clone();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code));
+		params.call_type = CallType::Call;
+		let mut state = get_temp_state();
+		let info = EnvInfo::default();
+		let machine = make_frontier_machine(5);
+		let mut substate = Substate::new();
+		let mut vm_tracer = StructLogger::toplevel(Default::default());
+
+		{
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			let output = BytesRef::Fixed(&mut[0u8;0]);
+			ex.call(params, &mut substate, output, &mut NoopTracer, &mut vm_tracer).unwrap();
+		}
+
+		let storage: BTreeMap<H256, H256> = vec![(0.into(), 42.into())].into_iter().collect();
+		let mut memory = vec![0u8; 32];
+		memory[31] = 42;
+		let log = |pc: usize, instruction: u8, gas: u64, gas_cost: u64, stack: Vec<u64>, memory: &Vec<u8>, storage: &BTreeMap<H256, H256>| StructLog {
+			pc: pc,
+			instruction: instruction,
+			gas: gas.into(),
+			gas_cost: gas_cost.into(),
+			depth: 1,
+			stack: Some(stack.into_iter().map(Into::into).collect()),
+			memory: Some(memory.clone()),
+			storage: Some(storage.clone()),
+		};
+
+		let expected_logs = vec![
+			log(0, 0x60, 100000, 3, vec![], &vec![], &BTreeMap::new()),
+			log(2, 0x60, 99997, 3, vec![42], &vec![], &BTreeMap::new()),
+			log(4, 0x55, 99994, 20000, vec![42, 0], &vec![], &storage),
+			log(5, 0x60, 79994, 3, vec![], &vec![], &storage),
+			log(7, 0x54, 79991, 50, vec![0], &vec![], &storage),
+			log(8, 0x60, 79941, 3, vec![42], &vec![], &storage),
+			log(10, 0x52, 79938, 6, vec![42, 0], &vec![], &storage),
+			log(11, 0x00, 79932, 0, vec![], &memory, &storage),
+		];
+
+		assert_eq!(vm_tracer.drain().unwrap(), expected_logs);
+	}
+
 	#[test]
 	fn test_trace_reverted_create() {
 		// code:
@@ -1549,4 +1701,53 @@ mod tests {
 		// Since transaction errored due to wasm was not activated, result is just empty
 		assert_eq!(output[..], [0u8; 20][..]);
 	}
+
+	#[test]
+	fn test_eip1283() {
+		fn run(code: &str, gas_used: u64, refund: i128, original: u64) {
+			let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
+			let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+			let machine = ::ethereum::new_constantinople_test_machine();
+			let info = EnvInfo::default();
+
+			let mut state = get_temp_state();
+			state.set_storage(&address, H256::new(), H256::from(original)).unwrap();
+			state.commit().unwrap();
+
+			let mut params = ActionParams::default();
+			params.address = address.clone();
+			params.sender = sender.clone();
+			params.origin = sender.clone();
+			params.gas = U256::from(100_000);
+			params.code = Some(Arc::new(code.from_hex().unwrap()));
+
+			let mut substate = Substate::new();
+			let FinalizationResult { gas_left, .. } = {
+				let mut ex = Executive::new(&mut state, &info, &machine);
+				ex.call(params, &mut substate, BytesRef::Fixed(&mut []), &mut NoopTracer, &mut NoopVMTracer).unwrap()
+			};
+
+			assert_eq!(U256::from(100_000) - gas_left, U256::from(gas_used), "gas used by {}", code);
+			assert_eq!(substate.sstore_clears_refund, refund, "refund of {}", code);
+		}
+
+		// test vectors from EIP-1283
+		run("60006000556000600055", 412, 0, 0);
+		run("60006000556001600055", 20212, 0, 0);
+		run("60016000556000600055", 20212, 19800, 0);
+		run("60016000556002600055", 20212, 0, 0);
+		run("60016000556001600055", 20212, 0, 0);
+		run("60006000556000600055", 5212, 15000, 1);
+		run("60006000556001600055", 5212, 4800, 1);
+		run("60006000556002600055", 5212, 0, 1);
+		run("60026000556000600055", 5212, 15000, 1);
+		run("60026000556003600055", 5212, 0, 1);
+		run("60026000556001600055", 5212, 4800, 1);
+		run("60026000556002600055", 5212, 0, 1);
+		run("60016000556000600055", 5212, 15000, 1);
+		run("60016000556002600055", 5212, 0, 1);
+		run("60016000556001600055", 412, 0, 1);
+		run("600160005560006000556001600055", 40218, 19800, 0);
+		run("600060005560016000556000600055", 10218, 19800, 1);
+	}
 }
@@ -93,6 +93,8 @@ extern crate unexpected;
 extern crate kvdb;
 extern crate kvdb_rocksdb;
 extern crate kvdb_memorydb;
+#[cfg(feature = "logdb")]
+extern crate kvdb_logdb;
 extern crate util_error;
 extern crate snappy;
 extern crate migration;
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
 use std::time::{Instant, Duration};
 use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
@@ -32,6 +33,7 @@ use ethcore_miner::transaction_queue::{
 	RemovalReason,
 	TransactionDetailsProvider as TransactionQueueDetailsProvider,
 	PrioritizationStrategy,
+	EvictionPolicy,
 	AccountDetails,
 	TransactionOrigin,
 };
@@ -124,6 +126,13 @@ pub struct MinerOptions {
 	pub tx_queue_memory_limit: Option<usize>,
 	/// Strategy to use for prioritizing transactions in the queue.
 	pub tx_queue_strategy: PrioritizationStrategy,
+	/// Maximum number of transactions from a single sender in the queue.
+	/// Defaults to 1% of the queue size (but not less than 16) if not specified.
+	pub tx_queue_per_sender: Option<usize>,
+	/// Share of the queue (in percent) reserved for local transactions.
+	pub tx_queue_local_share: u8,
+	/// Order in which transactions are dropped when the queue is full.
+	pub tx_queue_eviction: EvictionPolicy,
 	/// Whether we should fallback to providing all the queue's transactions or just pending.
 	pub pending_set: PendingSet,
 	/// How many historical work packages can we store before running out?
@@ -155,6 +164,9 @@ impl Default for MinerOptions {
 			tx_queue_memory_limit: Some(2 * 1024 * 1024),
 			tx_queue_gas_limit: GasLimit::None,
 			tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
+			tx_queue_per_sender: None,
+			tx_queue_local_share: 0,
+			tx_queue_eviction: EvictionPolicy::LowestPriority,
 			pending_set: PendingSet::AlwaysQueue,
 			reseal_min_period: Duration::from_secs(2),
 			reseal_max_period: Duration::from_secs(120),
@@ -287,13 +299,19 @@ impl Miner {
 		};
 		let mem_limit = options.tx_queue_memory_limit.unwrap_or_else(usize::max_value);
 
-		let txq = TransactionQueue::with_limits(
+		let per_sender = options.tx_queue_per_sender
+			.unwrap_or_else(|| cmp::max(16, options.tx_queue_size / 100));
+
+		let mut txq = TransactionQueue::with_limits(
 			options.tx_queue_strategy,
 			options.tx_queue_size,
 			mem_limit,
 			gas_limit,
 			options.tx_gas_limit
 		);
+		txq.set_max_per_sender(per_sender);
+		txq.set_local_share(options.tx_queue_local_share);
+		txq.set_eviction_policy(options.tx_queue_eviction);
 		let txq = match options.tx_queue_banning {
 			Banning::Disabled => BanningTransactionQueue::new(txq, Threshold::NeverBan, Duration::from_secs(180)),
 			Banning::Enabled { ban_duration, min_offends, .. } => BanningTransactionQueue::new(
@@ -589,7 +607,7 @@ impl Miner {
 					}
 					block
 						.lock()
-						.seal(&*self.engine, seal)
+						.seal_internally(&*self.engine, seal)
 						.map(|sealed| { chain.broadcast_proposal_block(sealed); true })
 						.unwrap_or_else(|e| {
 							warn!("ERROR: seal failed when given internally generated seal: {}", e);
@@ -601,7 +619,7 @@ impl Miner {
 					*self.next_mandatory_reseal.write() = Instant::now() + self.options.reseal_max_period;
 					block
 						.lock()
-						.seal(&*self.engine, seal)
+						.seal_internally(&*self.engine, seal)
 						.map(|sealed| chain.import_sealed_block(sealed).is_ok())
 						.unwrap_or_else(|e| {
 							warn!("ERROR: seal failed when given internally generated seal: {}", e);
@@ -735,7 +753,7 @@ impl Miner {
 								transaction_queue.add(transaction, origin, insertion_time, condition.clone(), &details_provider)?
 							},
 							TransactionOrigin::External => {
-								transaction_queue.add_with_banlist(transaction, insertion_time, &details_provider)?
+								transaction_queue.add_with_banlist(transaction, insertion_time, condition.clone(), &details_provider)?
 							},
 						};
 
@@ -753,6 +771,23 @@ impl Miner {
 		results
 	}
 
+	/// Re-imports transactions restored from a previous run of the node.
+	///
+	/// Transactions are treated as external ones, but keep the conditions they were queued with.
+	pub fn import_restored_transactions(
+		&self,
+		chain: &MiningBlockChainClient,
+		transactions: Vec<PendingTransaction>,
+	) -> Vec<Result<TransactionImportResult, Error>> {
+		trace!(target: "external_tx", "Importing restored transactions");
+		let mut transaction_queue = self.transaction_queue.write();
+		transactions.into_iter()
+			.map(|pending| self.add_transactions_to_queue(
+				chain, vec![pending.transaction.into()], TransactionOrigin::External, pending.condition, &mut transaction_queue
+			).pop().expect("one result returned per added transaction; one added => one result; qed"))
+			.collect()
+	}
+
 	/// Are we allowed to do a non-mandatory reseal?
 	fn tx_reseal_allowed(&self) -> bool { Instant::now() > *self.next_allowed_reseal.lock() }
 
@@ -868,6 +903,30 @@ impl MinerService for Miner {
 		self.transaction_queue.write().set_limit(limit)
 	}
 
+	fn transactions_per_sender_limit(&self) -> usize {
+		self.transaction_queue.read().max_per_sender()
+	}
+
+	fn set_transactions_per_sender_limit(&self, limit: usize) {
+		self.transaction_queue.write().set_max_per_sender(limit)
+	}
+
+	fn local_transactions_share(&self) -> u8 {
+		self.transaction_queue.read().local_share()
+	}
+
+	fn set_local_transactions_share(&self, percent: u8) {
+		self.transaction_queue.write().set_local_share(percent)
+	}
+
+	fn transactions_eviction_policy(&self) -> EvictionPolicy {
+		self.transaction_queue.read().eviction_policy()
+	}
+
+	fn set_transactions_eviction_policy(&self, policy: EvictionPolicy) {
+		self.transaction_queue.write().set_eviction_policy(policy)
+	}
+
 	fn set_tx_gas_limit(&self, limit: U256) {
 		self.transaction_queue.write().set_tx_gas_limit(limit)
 	}
@@ -1298,7 +1357,7 @@ impl<'a> TransactionQueueDetailsProvider for TransactionDetailsProvider<'a> {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use ethcore_miner::transaction_queue::PrioritizationStrategy;
+	use ethcore_miner::transaction_queue::{PrioritizationStrategy, EvictionPolicy};
 	use ethereum_types::U256;
 	use ethkey::{Generator, Random};
 	use hash::keccak;
@@ -1356,6 +1415,9 @@ mod tests {
 				tx_queue_memory_limit: None,
 				tx_queue_gas_limit: GasLimit::None,
 				tx_queue_strategy: PrioritizationStrategy::GasFactorAndGasPrice,
+				tx_queue_per_sender: None,
+				tx_queue_local_share: 0,
+				tx_queue_eviction: EvictionPolicy::LowestPriority,
 				pending_set: PendingSet::AlwaysSealing,
 				work_queue_size: 5,
 				enable_resubmission: true,
@@ -1443,6 +1505,29 @@ mod tests {
 		assert!(miner.prepare_work_sealing(&client));
 	}
 
+	#[test]
+	fn should_keep_condition_of_restored_transaction() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let condition = Some(TransactionCondition::Number(10));
+		let conditional = PendingTransaction::new(transaction(), condition.clone());
+		let plain = PendingTransaction::new(transaction(), None);
+
+		// when
+		let res = miner.import_restored_transactions(&client, vec![conditional.clone(), plain.clone()]);
+
+		// then
+		assert!(res.iter().all(Result::is_ok));
+		let pending = miner.pending_transactions();
+		assert_eq!(pending.len(), 2);
+		assert!(pending.contains(&conditional));
+		assert!(pending.contains(&plain));
+		// the conditional transaction is not ready until block 10.
+		assert_eq!(miner.transaction_queue.read().pending_transactions(0, 0), vec![plain]);
+		assert_eq!(miner.local_transactions().len(), 0);
+	}
+
 	#[test]
 	fn should_not_seal_unless_enabled() {
 		let miner = miner();
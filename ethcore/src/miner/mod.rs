@@ -45,6 +45,7 @@ pub use self::miner::{Miner, MinerOptions, Banning, PendingSet, GasPricer, GasPr
 pub use self::stratum::{Stratum, Error as StratumError, Options as StratumOptions};
 
 pub use ethcore_miner::local_transactions::Status as LocalTransactionStatus;
+pub use ethcore_miner::transaction_queue::EvictionPolicy;
 
 use std::collections::BTreeMap;
 use ethereum_types::{H256, U256, Address};
@@ -103,6 +104,24 @@ pub trait MinerService : Send + Sync {
 	/// Set maximal number of transactions kept in the queue (both current and future).
 	fn set_transactions_limit(&self, limit: usize);
 
+	/// Get current limit of transactions from a single sender in queue.
+	fn transactions_per_sender_limit(&self) -> usize;
+
+	/// Set maximal number of transactions from a single sender kept in the queue.
+	fn set_transactions_per_sender_limit(&self, limit: usize);
+
+	/// Get share of the queue (in percent) reserved for local transactions.
+	fn local_transactions_share(&self) -> u8;
+
+	/// Set share of the queue (in percent) reserved for local transactions.
+	fn set_local_transactions_share(&self, percent: u8);
+
+	/// Get the order in which transactions are dropped when the queue is full.
+	fn transactions_eviction_policy(&self) -> EvictionPolicy;
+
+	/// Set the order in which transactions are dropped when the queue is full.
+	fn set_transactions_eviction_policy(&self, policy: EvictionPolicy);
+
 	/// Set maximum amount of gas allowed for any single transaction to mine.
 	fn set_tx_gas_limit(&self, limit: U256);
 
@@ -20,7 +20,7 @@ use std::sync::Arc;
 use std::path::Path;
 use ethereum_types::H256;
 use kvdb::KeyValueDB;
-use kvdb_rocksdb::{Database, DatabaseConfig};
+use kvdb_rocksdb::DatabaseConfig;
 use bytes::Bytes;
 use io::*;
 use spec::Spec;
@@ -59,7 +59,7 @@ pub struct ClientService {
 	io_service: Arc<IoService<ClientIoMessage>>,
 	client: Arc<Client>,
 	snapshot: Arc<SnapshotService>,
-	database: Arc<Database>,
+	database: Arc<KeyValueDB>,
 	_stop_guard: StopGuard,
 }
 
@@ -84,19 +84,18 @@ impl ClientService {
 		db_config.compaction = config.db_compaction.compaction_profile(client_path);
 		db_config.wal = config.db_wal;
 
-		let db = Arc::new(Database::open(
-			&db_config,
-			&client_path.to_str().expect("DB path could not be converted to string.")
-		).map_err(::client::Error::Database)?);
+		let db = config.db_engine.open(&db_config, client_path).map_err(::client::Error::Database)?;
 
 
 		let pruning = config.pruning;
+		let db_engine = config.db_engine.clone();
 		let client = Client::new(config, &spec, db.clone(), miner, io_service.channel())?;
 
 		let snapshot_params = SnapServiceParams {
 			engine: spec.engine.clone(),
 			genesis_block: spec.genesis_block(),
 			db_config: db_config.clone(),
+			db_engine: db_engine,
 			pruning: pruning,
 			channel: io_service.channel(),
 			snapshot_root: snapshot_path.into(),
@@ -27,7 +27,7 @@ use super::{ManifestData, StateRebuilder, Rebuilder, RestorationStatus, Snapshot
 use super::io::{SnapshotReader, LooseReader, SnapshotWriter, LooseWriter};
 
 use blockchain::BlockChain;
-use client::{BlockChainClient, Client};
+use client::{BlockChainClient, Client, DatabaseEngine};
 use engines::EthEngine;
 use error::Error;
 use ids::BlockId;
@@ -40,7 +40,8 @@ use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use util_error::UtilError;
 use bytes::Bytes;
 use journaldb::Algorithm;
-use kvdb_rocksdb::{Database, DatabaseConfig};
+use kvdb::KeyValueDB;
+use kvdb_rocksdb::DatabaseConfig;
 use snappy;
 
 /// Helper for removing directories in case of error.
@@ -80,7 +81,7 @@ struct Restoration {
 	snappy_buffer: Bytes,
 	final_state_root: H256,
 	guard: Guard,
-	db: Arc<Database>,
+	db: Arc<KeyValueDB>,
 }
 
 struct RestorationParams<'a> {
@@ -88,6 +89,7 @@ struct RestorationParams<'a> {
 	pruning: Algorithm, // pruning algorithm for the database.
 	db_path: PathBuf, // database path
 	db_config: &'a DatabaseConfig, // configuration for the database.
+	db_engine: &'a DatabaseEngine, // backend for the database.
 	writer: Option<LooseWriter>, // writer for recovered snapshot.
 	genesis: &'a [u8], // genesis block of the chain.
 	guard: Guard, // guard for the restoration directory.
@@ -102,8 +104,13 @@ impl Restoration {
 		let state_chunks = manifest.state_hashes.iter().cloned().collect();
 		let block_chunks = manifest.block_hashes.iter().cloned().collect();
 
-		let raw_db = Arc::new(Database::open(params.db_config, &*params.db_path.to_string_lossy())
-			.map_err(UtilError::from)?);
+		let db_engine = match *params.db_engine {
+			// never let a restoration overwrite the dump file of an in-memory client database.
+			DatabaseEngine::Memory { limit, .. } => DatabaseEngine::Memory { limit: limit, dump_path: None },
+			ref engine => engine.clone(),
+		};
+		let raw_db = db_engine.open(params.db_config, &params.db_path)
+			.map_err(UtilError::from)?;
 
 		let chain = BlockChain::new(Default::default(), params.genesis, raw_db.clone());
 		let components = params.engine.snapshot_components()
@@ -214,6 +221,8 @@ pub struct ServiceParams {
 	pub genesis_block: Bytes,
 	/// Database configuration options.
 	pub db_config: DatabaseConfig,
+	/// Database backend.
+	pub db_engine: DatabaseEngine,
 	/// State pruning algorithm.
 	pub pruning: Algorithm,
 	/// Async IO channel for sending messages.
@@ -231,6 +240,7 @@ pub struct Service {
 	restoration: Mutex<Option<Restoration>>,
 	snapshot_root: PathBuf,
 	db_config: DatabaseConfig,
+	db_engine: DatabaseEngine,
 	io_channel: Mutex<Channel>,
 	pruning: Algorithm,
 	status: Mutex<RestorationStatus>,
@@ -252,6 +262,7 @@ impl Service {
 			restoration: Mutex::new(None),
 			snapshot_root: params.snapshot_root,
 			db_config: params.db_config,
+			db_engine: params.db_engine,
 			io_channel: Mutex::new(params.channel),
 			pruning: params.pruning,
 			status: Mutex::new(RestorationStatus::Inactive),
@@ -440,6 +451,7 @@ impl Service {
 			pruning: self.pruning,
 			db_path: self.restoration_db(),
 			db_config: &self.db_config,
+			db_engine: &self.db_engine,
 			writer: writer,
 			genesis: &self.genesis_block,
 			guard: Guard::new(rest_dir),
@@ -659,6 +671,7 @@ mod tests {
 			engine: spec.engine.clone(),
 			genesis_block: spec.genesis_block(),
 			db_config: Default::default(),
+			db_engine: Default::default(),
 			pruning: Algorithm::Archive,
 			channel: service.channel(),
 			snapshot_root: dir,
@@ -712,6 +725,7 @@ mod tests {
 			pruning: Algorithm::Archive,
 			db_path: tempdir.path().to_owned(),
 			db_config: &db_config,
+			db_engine: &DatabaseEngine::default(),
 			writer: None,
 			genesis: &gb,
 			guard: Guard::benign(),
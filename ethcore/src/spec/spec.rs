@@ -35,7 +35,7 @@ use super::genesis::Genesis;
 use super::seal::Generic as GenericSeal;
 
 use builtin::Builtin;
-use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, DEFAULT_BLOCKHASH_CONTRACT};
+use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, Clique, DEFAULT_BLOCKHASH_CONTRACT};
 use error::Error;
 use executive::Executive;
 use factory::Factories;
@@ -103,6 +103,14 @@ pub struct CommonParams {
 	pub eip211_transition: BlockNumber,
 	/// Number of first block where EIP-214 rules begin.
 	pub eip214_transition: BlockNumber,
+	/// Number of first block where EIP-145 rules begin.
+	pub eip145_transition: BlockNumber,
+	/// Number of first block where EIP-1052 rules begin.
+	pub eip1052_transition: BlockNumber,
+	/// Number of first block where EIP-1014 rules (CREATE2) begin.
+	pub eip1014_transition: BlockNumber,
+	/// Number of first block where EIP-1283 rules (net gas metering for SSTORE) begin.
+	pub eip1283_transition: BlockNumber,
 	/// Number of first block where dust cleanup rules (EIP-168 and EIP169) begin.
 	pub dust_protection_transition: BlockNumber,
 	/// Nonce cap increase per block. Nonce cap is only checked if dust protection is enabled.
@@ -133,10 +141,14 @@ impl CommonParams {
 
 	/// Apply common spec config parameters to the schedule.
 	pub fn update_schedule(&self, block_number: u64, schedule: &mut ::vm::Schedule) {
-		schedule.have_create2 = block_number >= self.eip86_transition;
+		schedule.have_create_p2sh = block_number >= self.eip86_transition;
+		schedule.have_create2 = block_number >= self.eip1014_transition;
 		schedule.have_revert = block_number >= self.eip140_transition;
 		schedule.have_static_call = block_number >= self.eip214_transition;
 		schedule.have_return_data = block_number >= self.eip211_transition;
+		schedule.have_bitwise_shifting = block_number >= self.eip145_transition;
+		schedule.have_extcodehash = block_number >= self.eip1052_transition;
+		schedule.eip1283 = block_number >= self.eip1283_transition;
 		if block_number >= self.eip210_transition {
 			schedule.blockhash_gas = 800;
 		}
@@ -213,6 +225,22 @@ impl From<ethjson::spec::Params> for CommonParams {
 				BlockNumber::max_value(),
 				Into::into,
 			),
+			eip145_transition: p.eip145_transition.map_or(
+				BlockNumber::max_value(),
+				Into::into,
+			),
+			eip1052_transition: p.eip1052_transition.map_or(
+				BlockNumber::max_value(),
+				Into::into,
+			),
+			eip1014_transition: p.eip1014_transition.map_or(
+				BlockNumber::max_value(),
+				Into::into,
+			),
+			eip1283_transition: p.eip1283_transition.map_or(
+				BlockNumber::max_value(),
+				Into::into,
+			),
 			eip658_transition: p.eip658_transition.map_or(
 				BlockNumber::max_value(),
 				Into::into,
@@ -363,7 +391,7 @@ fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Er
 
 	let mut s = Spec {
 		name: s.name.clone().into(),
-		engine: Spec::engine(spec_params, s.engine, params, builtins),
+		engine: Spec::engine(spec_params, s.engine, params, builtins)?,
 		data_dir: s.data_dir.unwrap_or(s.name).into(),
 		nodes: s.nodes.unwrap_or_else(Vec::new),
 		parent_hash: g.parent_hash,
@@ -437,10 +465,10 @@ impl Spec {
 		engine_spec: ethjson::spec::Engine,
 		params: CommonParams,
 		builtins: BTreeMap<Address, Builtin>,
-	) -> Arc<EthEngine> {
+	) -> Result<Arc<EthEngine>, Error> {
 		let machine = Self::machine(&engine_spec, params, builtins);
 
-		match engine_spec {
+		let engine: Arc<EthEngine> = match engine_spec {
 			ethjson::spec::Engine::Null(null) => Arc::new(NullEngine::new(null.params.into(), machine)),
 			ethjson::spec::Engine::Ethash(ethash) => Arc::new(::ethereum::Ethash::new(spec_params.cache_dir, ethash.params.into(), machine, spec_params.optimization_setting)),
 			ethjson::spec::Engine::InstantSeal => Arc::new(InstantSeal::new(machine)),
@@ -449,7 +477,10 @@ impl Spec {
 				.expect("Failed to start AuthorityRound consensus engine."),
 			ethjson::spec::Engine::Tendermint(tendermint) => Tendermint::new(tendermint.params.into(), machine)
 				.expect("Failed to start the Tendermint consensus engine."),
-		}
+			ethjson::spec::Engine::Clique(clique) => Clique::new(clique.params.into(), machine)?,
+		};
+
+		Ok(engine)
 	}
 
 	// given a pre-constructor state, run all the given constructors and produce a new state and
@@ -782,6 +813,13 @@ impl Spec {
 		load_bundled!("tendermint")
 	}
 
+	/// Create a new Spec with Clique consensus which does internal sealing (not requiring
+	/// work).
+	/// Accounts with secrets keccak("0") and keccak("1") are the initial signers.
+	pub fn new_test_clique() -> Self {
+		load_bundled!("clique")
+	}
+
 	/// TestList.sol used in both specs: https://github.com/paritytech/contracts/pull/30/files
 	/// Accounts with secrets keccak("0") and keccak("1") are initially the validators.
 	/// Create a new Spec with BasicAuthority which uses a contract at address 5 to determine
@@ -868,4 +906,26 @@ mod tests {
 		assert_eq!(state.storage_at(&address, &H256::zero()).unwrap(), expected);
 		assert_eq!(state.balance(&address).unwrap(), 1.into());
 	}
+
+	#[test]
+	fn create2_transitions() {
+		let params = CommonParams {
+			eip86_transition: 10,
+			eip1014_transition: 20,
+			..Default::default()
+		};
+
+		let mut schedule = ::vm::Schedule::new_post_eip150(24576, true, true, true);
+		params.update_schedule(9, &mut schedule);
+		assert!(!schedule.have_create_p2sh);
+		assert!(!schedule.have_create2);
+
+		params.update_schedule(10, &mut schedule);
+		assert!(schedule.have_create_p2sh);
+		assert!(!schedule.have_create2);
+
+		params.update_schedule(20, &mut schedule);
+		assert!(schedule.have_create_p2sh);
+		assert!(schedule.have_create2);
+	}
 }
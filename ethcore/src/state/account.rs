@@ -60,6 +60,9 @@ pub struct Account {
 	// Modified storage. Accumulates changes to storage made in `set_storage`
 	// Takes precedence over `storage_cache`.
 	storage_changes: HashMap<H256, H256>,
+	// Values of storage keys modified during the current transaction, as they were
+	// at the beginning of it.
+	original_storage: HashMap<H256, H256>,
 	// Code hash of the account.
 	code_hash: H256,
 	// Size of the accoun code.
@@ -80,6 +83,7 @@ impl From<BasicAccount> for Account {
 			storage_root: basic.storage_root,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: basic.code_hash,
 			code_size: None,
 			code_cache: Arc::new(vec![]),
@@ -99,6 +103,7 @@ impl Account {
 			storage_root: KECCAK_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: storage,
+			original_storage: HashMap::new(),
 			code_hash: keccak(&code),
 			code_size: Some(code.len()),
 			code_cache: Arc::new(code),
@@ -119,6 +124,7 @@ impl Account {
 			storage_root: KECCAK_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: pod.storage.into_iter().collect(),
+			original_storage: HashMap::new(),
 			code_hash: pod.code.as_ref().map_or(KECCAK_EMPTY, |c| keccak(c)),
 			code_filth: Filth::Dirty,
 			code_size: Some(pod.code.as_ref().map_or(0, |c| c.len())),
@@ -135,6 +141,7 @@ impl Account {
 			storage_root: KECCAK_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: KECCAK_EMPTY,
 			code_cache: Arc::new(vec![]),
 			code_size: Some(0),
@@ -158,6 +165,7 @@ impl Account {
 			storage_root: KECCAK_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: KECCAK_EMPTY,
 			code_cache: Arc::new(vec![]),
 			code_size: None,
@@ -180,11 +188,35 @@ impl Account {
 		self.init_code(code);
 	}
 
+	/// Replace the whole storage of the account with the given entries.
+	pub fn reset_storage(&mut self, storage: HashMap<H256, H256>) {
+		self.storage_root = KECCAK_NULL_RLP;
+		self.storage_cache = Self::empty_storage_cache();
+		self.storage_changes = storage;
+	}
+
 	/// Set (and cache) the contents of the trie's storage at `key` to `value`.
 	pub fn set_storage(&mut self, key: H256, value: H256) {
 		self.storage_changes.insert(key, value);
 	}
 
+	/// Remember `value` as the value of the storage at `key` at the beginning of the
+	/// current transaction, unless it has already been noted.
+	pub fn note_original_storage(&mut self, key: H256, value: H256) {
+		self.original_storage.entry(key).or_insert(value);
+	}
+
+	/// Get the value of the storage at `key` at the beginning of the current transaction.
+	/// Returns `None` if the key has not been modified during the transaction.
+	pub fn original_storage_at(&self, key: &H256) -> Option<H256> {
+		self.original_storage.get(key).cloned()
+	}
+
+	/// Forget original storage values. Current storage becomes the original one.
+	pub fn clear_original_storage(&mut self) {
+		self.original_storage.clear();
+	}
+
 	/// Get (and cache) the contents of the trie's storage at `key`.
 	/// Takes modifed storage into account.
 	pub fn storage_at(&self, db: &HashDB, key: &H256) -> trie::Result<H256> {
@@ -208,6 +240,10 @@ impl Account {
 		if let Some(value) = self.storage_cache.borrow_mut().get_mut(key) {
 			return Some(value.clone())
 		}
+		// nothing can be found in an empty storage trie.
+		if self.storage_root == KECCAK_NULL_RLP {
+			return Some(H256::new())
+		}
 		None
 	}
 
@@ -344,6 +380,16 @@ impl Account {
 	/// Return the storage overlay.
 	pub fn storage_changes(&self) -> &HashMap<H256, H256> { &self.storage_changes }
 
+	/// Set the nonce of the account.
+	pub fn set_nonce(&mut self, nonce: U256) {
+		self.nonce = nonce;
+	}
+
+	/// Set the balance of the account.
+	pub fn set_balance(&mut self, balance: U256) {
+		self.balance = balance;
+	}
+
 	/// Increment the nonce of the account by one.
 	pub fn inc_nonce(&mut self) {
 		self.nonce = self.nonce + U256::from(1u8);
@@ -374,6 +420,7 @@ impl Account {
 
 			self.storage_cache.borrow_mut().insert(k, v);
 		}
+		self.original_storage.clear();
 		Ok(())
 	}
 
@@ -412,6 +459,7 @@ impl Account {
 			storage_root: self.storage_root.clone(),
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: self.code_hash.clone(),
 			code_size: self.code_size.clone(),
 			code_cache: self.code_cache.clone(),
@@ -424,6 +472,7 @@ impl Account {
 	pub fn clone_dirty(&self) -> Account {
 		let mut account = self.clone_basic();
 		account.storage_changes = self.storage_changes.clone();
+		account.original_storage = self.original_storage.clone();
 		account.code_cache = self.code_cache.clone();
 		account
 	}
@@ -452,6 +501,7 @@ impl Account {
 			cache.insert(k.clone() , v.clone()); //TODO: cloning should not be required here
 		}
 		self.storage_changes = other.storage_changes;
+		self.original_storage = other.original_storage;
 	}
 }
 
@@ -53,12 +53,14 @@ use trie::recorder::Recorder;
 
 
 mod account;
+mod overrides;
 mod substate;
 
 pub mod backend;
 
 pub use self::account::Account;
 pub use self::backend::Backend;
+pub use self::overrides::{AccountOverride, StateOverride};
 pub use self::substate::Substate;
 
 /// Used to return information about an `State::apply` operation.
@@ -645,8 +647,61 @@ impl<B: Backend> State<B> {
 	/// Mutate storage of account `a` so that it is `value` for `key`.
 	pub fn set_storage(&mut self, a: &Address, key: H256, value: H256) -> trie::Result<()> {
 		trace!(target: "state", "set_storage({}:{:x} to {:x})", a, key, value);
-		if self.storage_at(a, &key)? != value {
-			self.require(a, false)?.set_storage(key, value)
+		let current = self.storage_at(a, &key)?;
+		if current != value {
+			let mut account = self.require(a, false)?;
+			account.note_original_storage(key, current);
+			account.set_storage(key, value)
+		}
+
+		Ok(())
+	}
+
+	/// Get the value of storage `key` of account `a` as it was at the beginning of the current transaction.
+	pub fn original_storage_at(&self, a: &Address, key: &H256) -> trie::Result<H256> {
+		let original = self.cache.borrow().get(a)
+			.and_then(|entry| entry.account.as_ref())
+			.and_then(|account| account.original_storage_at(key));
+
+		match original {
+			Some(value) => Ok(value),
+			None => self.storage_at(a, key),
+		}
+	}
+
+	/// Mark the beginning of a new transaction: current storage values of all
+	/// cached accounts become the original ones.
+	pub fn clear_original_storage(&mut self) {
+		for entry in self.cache.get_mut().values_mut() {
+			if let Some(ref mut account) = entry.account {
+				account.clear_original_storage();
+			}
+		}
+	}
+
+	/// Apply the given overrides to the state of the accounts.
+	/// Accounts which don't exist are created.
+	pub fn apply_overrides(&mut self, overrides: &StateOverride) -> trie::Result<()> {
+		for (address, account_override) in overrides {
+			let mut account = self.require(address, false)?;
+
+			if let Some(balance) = account_override.balance {
+				account.set_balance(balance);
+			}
+			if let Some(nonce) = account_override.nonce {
+				account.set_nonce(nonce);
+			}
+			if let Some(ref code) = account_override.code {
+				account.reset_code(code.clone());
+			}
+			if let Some(ref storage) = account_override.state {
+				account.reset_storage(storage.clone());
+			}
+			if let Some(ref storage_diff) = account_override.state_diff {
+				for (key, value) in storage_diff {
+					account.set_storage(*key, *value);
+				}
+			}
 		}
 
 		Ok(())
@@ -2180,4 +2235,59 @@ mod tests {
 		assert!(state.exists(&d).unwrap());
 		assert!(!state.exists(&e).unwrap());
 	}
+
+	#[test]
+	fn original_storage_is_reset_per_transaction() {
+		let a = Address::zero();
+		let key = H256::from(1);
+		let mut state = get_temp_state();
+
+		state.set_storage(&a, key, H256::from(2)).unwrap();
+		state.set_storage(&a, key, H256::from(3)).unwrap();
+		assert_eq!(state.original_storage_at(&a, &key).unwrap(), H256::new());
+		assert_eq!(state.storage_at(&a, &key).unwrap(), H256::from(3));
+
+		state.clear_original_storage();
+		assert_eq!(state.original_storage_at(&a, &key).unwrap(), H256::from(3));
+		state.set_storage(&a, key, H256::from(4)).unwrap();
+		assert_eq!(state.original_storage_at(&a, &key).unwrap(), H256::from(3));
+	}
+
+	#[test]
+	fn apply_overrides() {
+		let a = Address::zero();
+		let b = Address::from(1);
+		let mut state = {
+			let mut state = get_temp_state();
+			state.set_storage(&a, H256::from(1), H256::from(1)).unwrap();
+			state.set_storage(&a, H256::from(2), H256::from(2)).unwrap();
+			state.set_storage(&b, H256::from(1), H256::from(1)).unwrap();
+			state.set_storage(&b, H256::from(2), H256::from(2)).unwrap();
+			state.commit().unwrap();
+			let (root, db) = state.drop();
+			State::from_existing(db, root, U256::from(0u8), Default::default()).unwrap()
+		};
+
+		let mut overrides = StateOverride::new();
+		overrides.insert(a, AccountOverride {
+			balance: Some(69.into()),
+			nonce: Some(42.into()),
+			code: Some(vec![0x60, 0x00]),
+			state: Some(vec![(H256::from(2), H256::from(5))].into_iter().collect()),
+			state_diff: None,
+		});
+		overrides.insert(b, AccountOverride {
+			state_diff: Some(vec![(H256::from(2), H256::from(5))].into_iter().collect()),
+			..Default::default()
+		});
+		state.apply_overrides(&overrides).unwrap();
+
+		assert_eq!(state.balance(&a).unwrap(), 69.into());
+		assert_eq!(state.nonce(&a).unwrap(), 42.into());
+		assert_eq!(state.code(&a).unwrap(), Some(Arc::new(vec![0x60, 0x00])));
+		assert_eq!(state.storage_at(&a, &H256::from(1)).unwrap(), H256::new());
+		assert_eq!(state.storage_at(&a, &H256::from(2)).unwrap(), H256::from(5));
+		assert_eq!(state.storage_at(&b, &H256::from(1)).unwrap(), H256::from(1));
+		assert_eq!(state.storage_at(&b, &H256::from(2)).unwrap(), H256::from(5));
+	}
 }
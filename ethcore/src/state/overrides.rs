@@ -0,0 +1,40 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Account state overrides applied before non-persistent calls.
+
+use std::collections::HashMap;
+use ethereum_types::{H256, U256, Address};
+use bytes::Bytes;
+
+/// Overrides of a single account's state.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AccountOverride {
+	/// Balance to set.
+	pub balance: Option<U256>,
+	/// Nonce to set.
+	pub nonce: Option<U256>,
+	/// Code to set.
+	pub code: Option<Bytes>,
+	/// Storage replacing the whole storage of the account.
+	pub state: Option<HashMap<H256, H256>>,
+	/// Storage entries to set, leaving the rest of the storage intact.
+	/// Applied after `state`.
+	pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+/// Account state overrides by address.
+pub type StateOverride = HashMap<Address, AccountOverride>;
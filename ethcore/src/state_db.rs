@@ -202,6 +202,21 @@ impl StateDB {
 		Ok(records)
 	}
 
+	/// Write all pending insertions directly into the backing database without journalling.
+	/// Used to store state downloaded from the network.
+	pub fn inject(&mut self, batch: &mut DBTransaction) -> Result<u32, UtilError> {
+		{
+			let mut bloom_lock = self.account_bloom.lock();
+			Self::commit_bloom(batch, bloom_lock.drain_journal())?;
+		}
+		self.db.inject(batch)
+	}
+
+	/// Note an account, given by the hash of its address, in the accounts bloom.
+	pub fn note_account_hash(&self, address_hash: &H256) {
+		self.account_bloom.lock().set(&**address_hash);
+	}
+
 	/// Mark a given candidate from an ancient era as canonical, enacting its removals from the
 	/// backing database and reverting any non-canonical historical commit's insertions.
 	pub fn mark_canonical(&mut self, batch: &mut DBTransaction, end_era: u64, canon_id: &H256) -> Result<u32, UtilError> {
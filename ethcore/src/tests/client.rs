@@ -26,7 +26,10 @@ use block::IsBlock;
 use tests::helpers::*;
 use types::filter::Filter;
 use ethereum_types::{U256, Address};
+#[cfg(not(feature = "logdb"))]
 use kvdb_rocksdb::{Database, DatabaseConfig};
+#[cfg(feature = "logdb")]
+use kvdb_logdb::{Database, DatabaseConfig};
 use miner::Miner;
 use spec::Spec;
 use views::BlockView;
@@ -249,6 +252,31 @@ fn can_handle_long_fork() {
 	assert_eq!(2000, client.chain_info().best_block_number);
 }
 
+#[test]
+fn stops_importing_when_memory_database_is_full() {
+	let spec = get_test_spec();
+	let limit = 64 * 1024;
+	let client_db = Arc::new(::kvdb_memorydb::with_limit(::db::NUM_COLUMNS.unwrap_or(0), limit));
+
+	let client = Client::new(
+		ClientConfig::default(),
+		&spec,
+		client_db,
+		Arc::new(Miner::with_spec(&spec)),
+		IoChannel::disconnected(),
+	).unwrap();
+	assert!(client.report().db_memory_used.unwrap() < limit);
+
+	push_blocks_to_client(&client, 53, 1, 200);
+	while client.import_verified_blocks() > 0 {}
+
+	let best_block_number = client.chain_info().best_block_number;
+	assert!(best_block_number > 0 && best_block_number < 200);
+	assert!(client.report().db_memory_used.unwrap() <= limit);
+	// the rejected block is not visible through the chain caches.
+	assert!(client.block_header(BlockId::Number(best_block_number + 1)).is_none());
+}
+
 #[test]
 fn can_mine() {
 	let dummy_blocks = get_good_dummy_block_seq(2);
@@ -368,3 +396,36 @@ fn transaction_proof() {
 	assert_eq!(state.balance(&Address::default()).unwrap(), 5.into());
 	assert_eq!(state.balance(&address).unwrap(), 95.into());
 }
+
+#[test]
+fn call_with_state_override() {
+	use rustc_hex::FromHex;
+	use state::{AccountOverride, StateOverride};
+
+	let client = generate_dummy_client(0);
+	let contract = Address::random();
+
+	let transaction = Transaction {
+		nonce: 0.into(),
+		gas_price: 0.into(),
+		gas: 100000.into(),
+		action: Action::Call(contract),
+		value: 0.into(),
+		data: Vec::new(),
+	}.fake_sign(Address::random());
+
+	let mut state_override = StateOverride::new();
+	state_override.insert(contract, AccountOverride {
+		// return the value at storage slot 0.
+		code: Some("60005460005260206000f3".from_hex().unwrap()),
+		state_diff: Some(vec![(0.into(), 42.into())].into_iter().collect()),
+		..Default::default()
+	});
+
+	let executed = client.call(&transaction, Default::default(), BlockId::Latest, Some(&state_override)).unwrap();
+	assert_eq!(U256::from(&executed.output[..]), 42.into());
+
+	// overrides are not persisted.
+	let executed = client.call(&transaction, Default::default(), BlockId::Latest, None).unwrap();
+	assert!(executed.output.is_empty());
+}
@@ -34,6 +34,7 @@ use rlp::{self, RlpStream};
 use spec::*;
 use state_db::StateDB;
 use state::*;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use transaction::{Action, Transaction, SignedTransaction};
 use views::BlockView;
@@ -368,7 +369,11 @@ pub fn get_default_ethash_params() -> EthashParams {
 		metropolis_difficulty_increment_divisor: 9,
 		homestead_transition: 1150000,
 		duration_limit: 13,
-		block_reward: 0.into(),
+		block_reward: {
+			let mut rewards = BTreeMap::new();
+			rewards.insert(0, 0.into());
+			rewards
+		},
 		difficulty_hardfork_transition: u64::max_value(),
 		difficulty_hardfork_bound_divisor: U256::from(0),
 		bomb_defuse_transition: u64::max_value(),
@@ -382,9 +387,7 @@ pub fn get_default_ethash_params() -> EthashParams {
 		mcip3_ubi_contract: "0000000000000000000000000000000000000001".into(),
 		mcip3_dev_reward: 0.into(),
 		mcip3_dev_contract: "0000000000000000000000000000000000000001".into(),
-		eip649_transition: u64::max_value(),
-		eip649_delay: 3_000_000,
-		eip649_reward: None,
+		difficulty_bomb_delays: BTreeMap::new(),
 		expip2_transition: u64::max_value(),
 		expip2_duration_limit: 30,
 	}
@@ -26,7 +26,10 @@ use spec::*;
 use client::*;
 use tests::helpers::*;
 use client::{BlockChainClient, Client, ClientConfig};
+#[cfg(not(feature = "logdb"))]
 use kvdb_rocksdb::{Database, DatabaseConfig};
+#[cfg(feature = "logdb")]
+use kvdb_logdb::{Database, DatabaseConfig};
 use std::sync::Arc;
 use header::Header;
 use miner::Miner;
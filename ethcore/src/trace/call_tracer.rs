@@ -0,0 +1,135 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Call tree tracer.
+
+use ethereum_types::{U256, Address};
+use bytes::Bytes;
+use vm::ActionParams;
+use trace::trace::{Call, Create, RewardType};
+use trace::debug::{CallFrame, CallFrameType};
+use trace::{Tracer, TraceError};
+
+/// Tracer building a tree of all calls, creates and suicides.
+#[derive(Default)]
+pub struct CallTracer {
+	frames: Vec<CallFrame>,
+}
+
+impl Tracer for CallTracer {
+	type Output = CallFrame;
+
+	fn prepare_trace_call(&self, params: &ActionParams) -> Option<Call> {
+		Some(Call::from(params.clone()))
+	}
+
+	fn prepare_trace_create(&self, params: &ActionParams) -> Option<Create> {
+		Some(Create::from(params.clone()))
+	}
+
+	fn prepare_trace_output(&self) -> Option<Bytes> {
+		Some(vec![])
+	}
+
+	fn trace_call(&mut self, call: Option<Call>, gas_used: U256, output: Option<Bytes>, subs: Vec<CallFrame>) {
+		let call = call.expect("self.prepare_trace_call().is_some(): so we must be tracing: qed");
+		self.frames.push(CallFrame {
+			frame_type: call.call_type.into(),
+			from: call.from,
+			to: Some(call.to),
+			value: call.value,
+			gas: call.gas,
+			gas_used: Some(gas_used),
+			input: call.input,
+			output: Some(output.expect("self.prepare_trace_output().is_some(): so we must be tracing: qed")),
+			error: None,
+			calls: subs,
+		});
+	}
+
+	fn trace_create(&mut self, create: Option<Create>, gas_used: U256, code: Option<Bytes>, address: Address, subs: Vec<CallFrame>) {
+		let create = create.expect("self.prepare_trace_create().is_some(): so we must be tracing: qed");
+		self.frames.push(CallFrame {
+			frame_type: CallFrameType::Create,
+			from: create.from,
+			to: Some(address),
+			value: create.value,
+			gas: create.gas,
+			gas_used: Some(gas_used),
+			input: create.init,
+			output: Some(code.expect("self.prepare_trace_output().is_some(): so we must be tracing: qed")),
+			error: None,
+			calls: subs,
+		});
+	}
+
+	fn trace_failed_call(&mut self, call: Option<Call>, subs: Vec<CallFrame>, error: TraceError) {
+		let call = call.expect("self.prepare_trace_call().is_some(): so we must be tracing: qed");
+		self.frames.push(CallFrame {
+			frame_type: call.call_type.into(),
+			from: call.from,
+			to: Some(call.to),
+			value: call.value,
+			gas: call.gas,
+			gas_used: None,
+			input: call.input,
+			output: None,
+			error: Some(error),
+			calls: subs,
+		});
+	}
+
+	fn trace_failed_create(&mut self, create: Option<Create>, subs: Vec<CallFrame>, error: TraceError) {
+		let create = create.expect("self.prepare_trace_create().is_some(): so we must be tracing: qed");
+		self.frames.push(CallFrame {
+			frame_type: CallFrameType::Create,
+			from: create.from,
+			to: None,
+			value: create.value,
+			gas: create.gas,
+			gas_used: None,
+			input: create.init,
+			output: None,
+			error: Some(error),
+			calls: subs,
+		});
+	}
+
+	fn trace_suicide(&mut self, address: Address, balance: U256, refund_address: Address) {
+		self.frames.push(CallFrame {
+			frame_type: CallFrameType::Suicide,
+			from: address,
+			to: Some(refund_address),
+			value: balance,
+			gas: U256::zero(),
+			gas_used: Some(U256::zero()),
+			input: Vec::new(),
+			output: Some(Vec::new()),
+			error: None,
+			calls: Vec::new(),
+		});
+	}
+
+	fn trace_reward(&mut self, _author: Address, _value: U256, _reward_type: RewardType) {}
+
+	fn subtracer(&self) -> Self {
+		CallTracer::default()
+	}
+
+	fn drain(self) -> Vec<CallFrame> {
+		self.frames
+	}
+}
@@ -16,25 +16,32 @@
 
 //! Tracing
 
+mod call_tracer;
 mod config;
 mod db;
 mod executive_tracer;
 mod import;
 mod noop_tracer;
+mod prestate_tracer;
+mod struct_logger;
 mod types;
 
 pub use self::config::Config;
 pub use self::db::TraceDB;
 pub use self::noop_tracer::{NoopTracer, NoopVMTracer};
 pub use self::executive_tracer::{ExecutiveTracer, ExecutiveVMTracer};
+pub use self::call_tracer::CallTracer;
+pub use self::prestate_tracer::PrestateTracer;
+pub use self::struct_logger::StructLogger;
 pub use self::import::ImportRequest;
 pub use self::localized::LocalizedTrace;
 
-pub use self::types::{filter, flat, localized, trace};
+pub use self::types::{debug, filter, flat, localized, trace};
 pub use self::types::error::Error as TraceError;
 pub use self::types::trace::{VMTrace, VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff, RewardType};
 pub use self::types::flat::{FlatTrace, FlatTransactionTraces, FlatBlockTraces};
 pub use self::types::filter::{Filter, AddressesFilter};
+pub use self::types::debug::{DebugTracer, DebugTrace, StructLog, StructLoggerConfig, CallFrame, CallFrameType, PrestateAccount};
 
 use ethereum_types::{H256, U256, Address};
 use kvdb::DBTransaction;
@@ -0,0 +1,88 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracer collecting the accounts touched by a transaction.
+
+use ethereum_types::{U256, Address};
+use bytes::Bytes;
+use vm::ActionParams;
+use trace::trace::{Call, Create, RewardType};
+use trace::{Tracer, TraceError};
+
+/// Tracer collecting the addresses of all accounts touched by calls, creates and suicides.
+/// The addresses may repeat.
+#[derive(Default)]
+pub struct PrestateTracer {
+	touched: Vec<Address>,
+}
+
+impl Tracer for PrestateTracer {
+	type Output = Address;
+
+	fn prepare_trace_call(&self, params: &ActionParams) -> Option<Call> {
+		Some(Call::from(params.clone()))
+	}
+
+	fn prepare_trace_create(&self, params: &ActionParams) -> Option<Create> {
+		Some(Create::from(params.clone()))
+	}
+
+	fn prepare_trace_output(&self) -> Option<Bytes> {
+		None
+	}
+
+	fn trace_call(&mut self, call: Option<Call>, _gas_used: U256, _output: Option<Bytes>, subs: Vec<Address>) {
+		let call = call.expect("self.prepare_trace_call().is_some(): so we must be tracing: qed");
+		self.touched.push(call.from);
+		self.touched.push(call.to);
+		self.touched.extend(subs);
+	}
+
+	fn trace_create(&mut self, create: Option<Create>, _gas_used: U256, _code: Option<Bytes>, address: Address, subs: Vec<Address>) {
+		let create = create.expect("self.prepare_trace_create().is_some(): so we must be tracing: qed");
+		self.touched.push(create.from);
+		self.touched.push(address);
+		self.touched.extend(subs);
+	}
+
+	fn trace_failed_call(&mut self, call: Option<Call>, subs: Vec<Address>, _error: TraceError) {
+		let call = call.expect("self.prepare_trace_call().is_some(): so we must be tracing: qed");
+		self.touched.push(call.from);
+		self.touched.push(call.to);
+		self.touched.extend(subs);
+	}
+
+	fn trace_failed_create(&mut self, create: Option<Create>, subs: Vec<Address>, _error: TraceError) {
+		let create = create.expect("self.prepare_trace_create().is_some(): so we must be tracing: qed");
+		self.touched.push(create.from);
+		self.touched.extend(subs);
+	}
+
+	fn trace_suicide(&mut self, address: Address, _balance: U256, refund_address: Address) {
+		self.touched.push(address);
+		self.touched.push(refund_address);
+	}
+
+	fn trace_reward(&mut self, _author: Address, _value: U256, _reward_type: RewardType) {}
+
+	fn subtracer(&self) -> Self {
+		PrestateTracer::default()
+	}
+
+	fn drain(self) -> Vec<Address> {
+		self.touched
+	}
+}
@@ -0,0 +1,142 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-style structured VM logger.
+
+use std::collections::BTreeMap;
+use ethereum_types::{H256, U256};
+use evm::INSTRUCTIONS;
+use trace::VMTracer;
+use trace::debug::{StructLog, StructLoggerConfig};
+
+const SLOAD: u8 = 0x54;
+const SSTORE: u8 = 0x55;
+
+/// VM tracer producing structured logs of all executed operations.
+///
+/// The stack, memory and storage are reconstructed from the effects of the executed
+/// operations, so memory which is expanded but never written is not part of the logs.
+pub struct StructLogger {
+	config: StructLoggerConfig,
+	depth: usize,
+	logs: Vec<StructLog>,
+	gas: U256,
+	stack: Vec<U256>,
+	memory: Vec<u8>,
+	storage: BTreeMap<H256, H256>,
+	// instruction prepared for execution and the storage key it loads.
+	pending: Option<(u8, Option<H256>)>,
+}
+
+impl StructLogger {
+	/// Create a new top-level instance.
+	pub fn toplevel(config: StructLoggerConfig) -> Self {
+		StructLogger::new(config, 0)
+	}
+
+	fn new(config: StructLoggerConfig, depth: usize) -> Self {
+		StructLogger {
+			config: config,
+			depth: depth,
+			logs: Vec::new(),
+			gas: U256::zero(),
+			stack: Vec::new(),
+			memory: Vec::new(),
+			storage: BTreeMap::new(),
+			pending: None,
+		}
+	}
+
+	fn peek(&self, n: usize) -> Option<&U256> {
+		self.stack.len().checked_sub(n + 1).map(|i| &self.stack[i])
+	}
+}
+
+impl VMTracer for StructLogger {
+	type Output = Vec<StructLog>;
+
+	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, current_gas: U256) -> bool {
+		self.gas = current_gas;
+		true
+	}
+
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256) {
+		let loaded_key = match instruction {
+			SLOAD => self.peek(0).map(|key| H256::from(*key)),
+			SSTORE => {
+				if let (Some(key), Some(value)) = (self.peek(0).cloned(), self.peek(1).cloned()) {
+					self.storage.insert(key.into(), value.into());
+				}
+				None
+			},
+			_ => None,
+		};
+		self.pending = Some((instruction, loaded_key));
+
+		let log = StructLog {
+			pc: pc,
+			instruction: instruction,
+			gas: self.gas,
+			gas_cost: gas_cost,
+			depth: self.depth,
+			stack: if self.config.disable_stack { None } else { Some(self.stack.clone()) },
+			memory: if self.config.disable_memory { None } else { Some(self.memory.clone()) },
+			storage: if self.config.disable_storage { None } else { Some(self.storage.clone()) },
+		};
+		self.logs.push(log);
+	}
+
+	fn trace_executed(&mut self, _gas_used: U256, stack_push: &[U256], mem_diff: Option<(usize, &[u8])>, _store_diff: Option<(U256, U256)>) {
+		let (instruction, loaded_key) = match self.pending.take() {
+			Some(pending) => pending,
+			None => return,
+		};
+
+		let args = INSTRUCTIONS[instruction as usize].args;
+		let len = self.stack.len().saturating_sub(args);
+		self.stack.truncate(len);
+		self.stack.extend_from_slice(stack_push);
+
+		if let Some((offset, data)) = mem_diff {
+			let end = offset + data.len();
+			if self.memory.len() < end {
+				// memory is expanded in words.
+				self.memory.resize((end + 31) / 32 * 32, 0);
+			}
+			self.memory[offset..end].copy_from_slice(data);
+		}
+
+		if let (Some(key), Some(value)) = (loaded_key, stack_push.first()) {
+			self.storage.insert(key, (*value).into());
+			// the loaded value is a part of the log of the loading operation.
+			if let Some(storage) = self.logs.last_mut().and_then(|log| log.storage.as_mut()) {
+				storage.insert(key, (*value).into());
+			}
+		}
+	}
+
+	fn prepare_subtrace(&self, _code: &[u8]) -> Self {
+		StructLogger::new(self.config, self.depth + 1)
+	}
+
+	fn done_subtrace(&mut self, sub: Self) {
+		self.logs.extend(sub.logs);
+	}
+
+	fn drain(self) -> Option<Vec<StructLog>> {
+		Some(self.logs)
+	}
+}
@@ -0,0 +1,159 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Debug tracing types.
+
+use std::collections::BTreeMap;
+use ethereum_types::{H256, U256, Address};
+use bytes::Bytes;
+use evm::{CallType, INSTRUCTIONS};
+use super::error::Error as TraceError;
+
+/// Tracer used to replay a transaction for debugging.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugTracer {
+	/// Structured per-opcode logs.
+	StructLogger(StructLoggerConfig),
+	/// Tree of calls made by the transaction.
+	CallTracer,
+	/// State of the accounts touched by the transaction, before its execution.
+	PrestateTracer,
+}
+
+/// Output of a transaction replay with a `DebugTracer`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugTrace {
+	/// Structured per-opcode logs.
+	StructLogs {
+		/// Gas used by the transaction.
+		gas: U256,
+		/// True if the outer call/create resulted in an exceptional exit.
+		failed: bool,
+		/// Transaction output.
+		return_value: Bytes,
+		/// Logs of the executed operations.
+		struct_logs: Vec<StructLog>,
+	},
+	/// Top-level call of the transaction.
+	Call(CallFrame),
+	/// Pre-transaction state of the touched accounts.
+	Prestate(BTreeMap<Address, PrestateAccount>),
+}
+
+/// Configuration of the struct logger. Every part of the log is recorded by default.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StructLoggerConfig {
+	/// Don't record the stack.
+	pub disable_stack: bool,
+	/// Don't record the memory.
+	pub disable_memory: bool,
+	/// Don't record the storage.
+	pub disable_storage: bool,
+}
+
+/// Structured log of a single operation, taken before its execution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// Instruction.
+	pub instruction: u8,
+	/// Gas left.
+	pub gas: U256,
+	/// Gas cost of the operation.
+	pub gas_cost: U256,
+	/// Call depth, starting at 1.
+	pub depth: usize,
+	/// Stack, bottom first.
+	pub stack: Option<Vec<U256>>,
+	/// Memory.
+	pub memory: Option<Bytes>,
+	/// Storage of the executing contract accessed so far in this call.
+	pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+impl StructLog {
+	/// Mnemonic name of the instruction. Empty for undefined instructions.
+	pub fn instruction_name(&self) -> &'static str {
+		INSTRUCTIONS[self.instruction as usize].name
+	}
+}
+
+/// Type of a call frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallFrameType {
+	/// CALL.
+	Call,
+	/// CALLCODE.
+	CallCode,
+	/// DELEGATECALL.
+	DelegateCall,
+	/// STATICCALL.
+	StaticCall,
+	/// Contract creation.
+	Create,
+	/// SUICIDE.
+	Suicide,
+}
+
+impl From<CallType> for CallFrameType {
+	fn from(call_type: CallType) -> Self {
+		match call_type {
+			CallType::None | CallType::Call => CallFrameType::Call,
+			CallType::CallCode => CallFrameType::CallCode,
+			CallType::DelegateCall => CallFrameType::DelegateCall,
+			CallType::StaticCall => CallFrameType::StaticCall,
+		}
+	}
+}
+
+/// A single call, create or suicide with its subcalls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallFrame {
+	/// Type of the frame.
+	pub frame_type: CallFrameType,
+	/// The sending account.
+	pub from: Address,
+	/// The destination account. `None` for failed creations.
+	pub to: Option<Address>,
+	/// The value transferred.
+	pub value: U256,
+	/// The gas available.
+	pub gas: U256,
+	/// The gas used. `None` if the call failed.
+	pub gas_used: Option<U256>,
+	/// Call input or init code.
+	pub input: Bytes,
+	/// Call output or deployed code. `None` if the call failed.
+	pub output: Option<Bytes>,
+	/// The error, if the call failed.
+	pub error: Option<TraceError>,
+	/// Subcalls.
+	pub calls: Vec<CallFrame>,
+}
+
+/// Account state before the execution of a transaction.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PrestateAccount {
+	/// Balance.
+	pub balance: U256,
+	/// Nonce.
+	pub nonce: U256,
+	/// Code.
+	pub code: Bytes,
+	/// Storage entries modified by the transaction.
+	pub storage: BTreeMap<H256, H256>,
+}
@@ -33,6 +33,8 @@ pub enum BlockId {
 	Latest,
 	/// Pending block.
 	Pending,
+	/// Latest block known to be final under the consensus rules.
+	Finalized,
 }
 
 /// Uniquely identifies transaction.
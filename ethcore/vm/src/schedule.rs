@@ -22,10 +22,16 @@ pub struct Schedule {
 	pub exceptional_failed_code_deposit: bool,
 	/// Does it have a delegate cal
 	pub have_delegate_call: bool,
-	/// Does it have a CREATE_P2SH instruction
+	/// Does it have a CREATE_P2SH instruction (legacy EIP-86 CREATE2 at 0xfb)
+	pub have_create_p2sh: bool,
+	/// Does it have a CREATE2 instruction (EIP-1014)
 	pub have_create2: bool,
 	/// Does it have a REVERT instruction
 	pub have_revert: bool,
+	/// Does it have SHL, SHR and SAR instructions (EIP-145)
+	pub have_bitwise_shifting: bool,
+	/// Does it have EXTCODEHASH instruction (EIP-1052)
+	pub have_extcodehash: bool,
 	/// VM stack limit
 	pub stack_limit: usize,
 	/// Max number of nested calls/creates
@@ -88,6 +94,8 @@ pub struct Schedule {
 	pub copy_gas: usize,
 	/// Price of EXTCODESIZE
 	pub extcodesize_gas: usize,
+	/// Price of EXTCODEHASH
+	pub extcodehash_gas: usize,
 	/// Base price of EXTCODECOPY
 	pub extcodecopy_base_gas: usize,
 	/// Price of BALANCE
@@ -113,6 +121,8 @@ pub struct Schedule {
 	pub kill_dust: CleanDustMode,
 	/// Enable EIP-86 rules
 	pub eip86: bool,
+	/// Enable EIP-1283 rules
+	pub eip1283: bool,
 	/// Wasm extra schedule settings, if wasm activated
 	pub wasm: Option<WasmCosts>,
 }
@@ -186,9 +196,12 @@ impl Schedule {
 		Schedule {
 			exceptional_failed_code_deposit: true,
 			have_delegate_call: true,
+			have_create_p2sh: false,
 			have_create2: false,
 			have_revert: false,
 			have_return_data: false,
+			have_bitwise_shifting: false,
+			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
 			tier_step_gas: [0, 2, 3, 5, 8, 10, 20, 0],
@@ -220,6 +233,7 @@ impl Schedule {
 			tx_data_non_zero_gas: 68,
 			copy_gas: 3,
 			extcodesize_gas: 700,
+			extcodehash_gas: 400,
 			extcodecopy_base_gas: 700,
 			balance_gas: 400,
 			suicide_gas: 5000,
@@ -231,6 +245,7 @@ impl Schedule {
 			have_static_call: false,
 			kill_dust: CleanDustMode::Off,
 			eip86: false,
+			eip1283: false,
 			wasm: None,
 		}
 	}
@@ -238,20 +253,33 @@ impl Schedule {
 	/// Schedule for the Byzantium fork of the Ethereum main net.
 	pub fn new_byzantium() -> Schedule {
 		let mut schedule = Self::new_post_eip150(24576, true, true, true);
-		schedule.have_create2 = true;
+		schedule.have_create_p2sh = true;
 		schedule.have_revert = true;
 		schedule.have_static_call = true;
 		schedule.have_return_data = true;
 		schedule
 	}
 
+	/// Schedule for the Constantinople fork of the Ethereum main net.
+	pub fn new_constantinople() -> Schedule {
+		let mut schedule = Self::new_byzantium();
+		schedule.have_create2 = true;
+		schedule.have_bitwise_shifting = true;
+		schedule.have_extcodehash = true;
+		schedule.eip1283 = true;
+		schedule
+	}
+
 	fn new(efcd: bool, hdc: bool, tcg: usize) -> Schedule {
 		Schedule {
 			exceptional_failed_code_deposit: efcd,
 			have_delegate_call: hdc,
+			have_create_p2sh: false,
 			have_create2: false,
 			have_revert: false,
 			have_return_data: false,
+			have_bitwise_shifting: false,
+			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
 			tier_step_gas: [0, 2, 3, 5, 8, 10, 20, 0],
@@ -283,6 +311,7 @@ impl Schedule {
 			tx_data_non_zero_gas: 68,
 			copy_gas: 3,
 			extcodesize_gas: 20,
+			extcodehash_gas: 400,
 			extcodecopy_base_gas: 20,
 			balance_gas: 20,
 			suicide_gas: 0,
@@ -294,6 +323,7 @@ impl Schedule {
 			have_static_call: false,
 			kill_dust: CleanDustMode::Off,
 			eip86: false,
+			eip1283: false,
 			wasm: None,
 		}
 	}
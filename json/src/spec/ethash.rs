@@ -16,9 +16,22 @@
 
 //! Ethash params deserialization.
 
+use std::collections::BTreeMap;
 use uint::{self, Uint};
 use hash::Address;
 
+/// Block reward deserialization.
+///
+/// Either a single reward or a map of block number to the reward in effect from that block.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum BlockReward {
+	/// Single reward for all blocks.
+	Single(Uint),
+	/// Reward changes, keyed by the block number they come into effect at.
+	Multi(BTreeMap<Uint, Uint>),
+}
+
 /// Deserializable doppelganger of EthashParams.
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct EthashParams {
@@ -46,7 +59,10 @@ pub struct EthashParams {
 	pub homestead_transition: Option<Uint>,
 	/// Reward per block in wei.
 	#[serde(rename="blockReward")]
-	pub block_reward: Option<Uint>,
+	pub block_reward: Option<BlockReward>,
+	/// See main EthashParams docs.
+	#[serde(rename="difficultyBombDelays")]
+	pub difficulty_bomb_delays: Option<BTreeMap<Uint, Uint>>,
 
 	/// See main EthashParams docs.
 	#[serde(rename="daoHardforkTransition")]
@@ -152,7 +168,8 @@ mod tests {
 	use uint::Uint;
 	use ethereum_types::{H160, U256};
 	use hash::Address;
-	use spec::ethash::{Ethash, EthashParams};
+	use std::collections::BTreeMap;
+	use spec::ethash::{Ethash, EthashParams, BlockReward};
 
 	#[test]
 	fn ethash_deserialization() {
@@ -208,7 +225,8 @@ mod tests {
 				metropolis_difficulty_increment_divisor: None,
 				duration_limit: Some(Uint(U256::from(0x0d))),
 				homestead_transition: Some(Uint(U256::from(0x42))),
-				block_reward: Some(Uint(U256::from(0x100))),
+				block_reward: Some(BlockReward::Single(Uint(U256::from(0x100)))),
+				difficulty_bomb_delays: None,
 				dao_hardfork_transition: Some(Uint(U256::from(0x08))),
 				dao_hardfork_beneficiary: Some(Address(H160::from("0xabcabcabcabcabcabcabcabcabcabcabcabcabca"))),
 				dao_hardfork_accounts: Some(vec![
@@ -278,6 +296,7 @@ mod tests {
 				duration_limit: None,
 				homestead_transition: None,
 				block_reward: None,
+				difficulty_bomb_delays: None,
 				dao_hardfork_transition: None,
 				dao_hardfork_beneficiary: None,
 				dao_hardfork_accounts: None,
@@ -307,6 +326,36 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn ethash_deserialization_transition_maps() {
+		let s = r#"{
+			"params": {
+				"difficultyBoundDivisor": "0x0800",
+				"minimumDifficulty": "0x020000",
+				"blockReward": {
+					"0": "0x4563918244f40000",
+					"0x42ae50": "0x29a2241af62c0000"
+				},
+				"difficultyBombDelays": {
+					"4370000": 3000000,
+					"0x6acfc0": "0x1e8480"
+				}
+			}
+		}"#;
+
+		let deserialized: Ethash = serde_json::from_str(s).unwrap();
+
+		let mut block_reward = BTreeMap::new();
+		block_reward.insert(Uint(U256::from(0)), Uint(U256::from(0x4563918244f40000u64)));
+		block_reward.insert(Uint(U256::from(4370000)), Uint(U256::from(0x29a2241af62c0000u64)));
+		assert_eq!(deserialized.params.block_reward, Some(BlockReward::Multi(block_reward)));
+
+		let mut bomb_delays = BTreeMap::new();
+		bomb_delays.insert(Uint(U256::from(4370000)), Uint(U256::from(3000000)));
+		bomb_delays.insert(Uint(U256::from(7000000)), Uint(U256::from(2000000)));
+		assert_eq!(deserialized.params.difficulty_bomb_delays, Some(bomb_delays));
+	}
+
 	#[test]
 	#[should_panic(expected = "a non-zero value")]
 	fn test_zero_value_divisor() {
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Manages local node data: pending local transactions, sync security level
+//! Manages local node data: pending local transactions, the rest of the transaction pool, sync security level
 
 use std::sync::Arc;
 use std::fmt;
@@ -48,6 +48,7 @@ extern crate ethkey;
 extern crate kvdb_memorydb;
 
 const LOCAL_TRANSACTIONS_KEY: &'static [u8] = &*b"LOCAL_TXS";
+const POOL_TRANSACTIONS_KEY: &'static [u8] = &*b"POOL_TXS";
 
 const UPDATE_TIMER: ::io::TimerToken = 0;
 const UPDATE_TIMEOUT_MS: u64 = 15 * 60 * 1000; // once every 15 minutes.
@@ -134,6 +135,9 @@ impl From<PendingTransaction> for TransactionEntry {
 pub trait NodeInfo: Send + Sync {
 	/// Get all pending transactions of local origin.
 	fn pending_transactions(&self) -> Vec<PendingTransaction>;
+
+	/// Get all remaining transactions in the pool (both ready and future), excluding local ones.
+	fn pool_transactions(&self) -> Vec<PendingTransaction>;
 }
 
 /// Create a new local data store, given a database, a column to write to, and a node.
@@ -148,8 +152,8 @@ pub fn create<T: NodeInfo>(db: Arc<KeyValueDB>, col: Option<u32>, node: T) -> Lo
 
 /// Manages local node data.
 ///
-/// In specific, this will be used to store things like unpropagated local transactions
-/// and the node security level.
+/// In specific, this will be used to store things like unpropagated local transactions,
+/// a snapshot of the transaction pool and the node security level.
 pub struct LocalDataStore<T: NodeInfo> {
 	db: Arc<KeyValueDB>,
 	col: Option<u32>,
@@ -159,17 +163,15 @@ pub struct LocalDataStore<T: NodeInfo> {
 impl<T: NodeInfo> LocalDataStore<T> {
 	/// Attempt to read pending transactions out of the local store.
 	pub fn pending_transactions(&self) -> Result<Vec<PendingTransaction>, Error> {
-		if let Some(val) = self.db.get(self.col, LOCAL_TRANSACTIONS_KEY).map_err(Error::Database)? {
-			let local_txs: Vec<_> = ::serde_json::from_slice::<Vec<TransactionEntry>>(&val)
-				.map_err(Error::Json)?
-				.into_iter()
-				.filter_map(TransactionEntry::into_pending)
-				.collect();
+		self.read_txs(LOCAL_TRANSACTIONS_KEY)
+	}
 
-			Ok(local_txs)
-		} else {
-			Ok(Vec::new())
-		}
+	/// Attempt to read the snapshot of the transaction pool out of the local store.
+	///
+	/// Transactions are only checked for a valid signature, they still need to be
+	/// verified against the current state before being imported.
+	pub fn pool_transactions(&self) -> Result<Vec<PendingTransaction>, Error> {
+		self.read_txs(POOL_TRANSACTIONS_KEY)
 	}
 
 	/// Update the entries in the database.
@@ -181,24 +183,60 @@ impl<T: NodeInfo> LocalDataStore<T> {
 			.map(Into::into)
 			.collect();
 
-		self.write_txs(&local_entries)
+		let pool_entries: Vec<TransactionEntry> = self.node.pool_transactions()
+			.into_iter()
+			.map(Into::into)
+			.collect();
+
+		self.write_txs(&[
+			(LOCAL_TRANSACTIONS_KEY, &local_entries),
+			(POOL_TRANSACTIONS_KEY, &pool_entries),
+		])
 	}
 
 	/// Clear data in this column.
 	pub fn clear(&self) -> Result<(), Error> {
 		trace!(target: "local_store", "Clearing local store entries.");
 
-		self.write_txs(&[])
+		self.write_txs(&[
+			(LOCAL_TRANSACTIONS_KEY, &[]),
+			(POOL_TRANSACTIONS_KEY, &[]),
+		])
+	}
+
+	/// Clear the snapshot of the transaction pool, leaving local transactions intact.
+	pub fn clear_pool(&self) -> Result<(), Error> {
+		trace!(target: "local_store", "Clearing transaction pool entries.");
+
+		self.write_txs(&[(POOL_TRANSACTIONS_KEY, &[])])
+	}
+
+	// helper for reading a vector of transaction entries from disk.
+	fn read_txs(&self, key: &[u8]) -> Result<Vec<PendingTransaction>, Error> {
+		if let Some(val) = self.db.get(self.col, key).map_err(Error::Database)? {
+			let txs: Vec<_> = ::serde_json::from_slice::<Vec<TransactionEntry>>(&val)
+				.map_err(Error::Json)?
+				.into_iter()
+				.filter_map(TransactionEntry::into_pending)
+				.collect();
+
+			Ok(txs)
+		} else {
+			Ok(Vec::new())
+		}
 	}
 
-	// helper for writing a vector of transaction entries to disk.
-	fn write_txs(&self, txs: &[TransactionEntry]) -> Result<(), Error> {
+	// helper for writing vectors of transaction entries to disk in a single batch.
+	fn write_txs(&self, entries: &[(&[u8], &[TransactionEntry])]) -> Result<(), Error> {
 		let mut batch = self.db.transaction();
 
-		let local_json = ::serde_json::to_value(txs).map_err(Error::Json)?;
-		let json_str = format!("{}", local_json);
+		for &(key, txs) in entries {
+			let json = ::serde_json::to_value(txs).map_err(Error::Json)?;
+			let json_str = format!("{}", json);
+
+			batch.put_vec(self.col, key, json_str.into_bytes());
+		}
 
-		batch.put_vec(self.col, LOCAL_TRANSACTIONS_KEY, json_str.into_bytes());
 		self.db.write(batch).map_err(Error::Database)
 	}
 }
@@ -241,6 +279,13 @@ mod tests {
 	struct Dummy(Vec<PendingTransaction>);
 	impl NodeInfo for Dummy {
 		fn pending_transactions(&self) -> Vec<PendingTransaction> { self.0.clone() }
+		fn pool_transactions(&self) -> Vec<PendingTransaction> { Vec::new() }
+	}
+
+	struct PoolDummy(Vec<PendingTransaction>);
+	impl NodeInfo for PoolDummy {
+		fn pending_transactions(&self) -> Vec<PendingTransaction> { Vec::new() }
+		fn pool_transactions(&self) -> Vec<PendingTransaction> { self.0.clone() }
 	}
 
 	#[test]
@@ -327,4 +372,31 @@ mod tests {
 			assert_eq!(loaded, transactions);
 		}
 	}
+
+	#[test]
+	fn keeps_pool_separate_from_local() {
+		let keypair = Brain::new("abcd".into()).generate().unwrap();
+		let transactions: Vec<_> = (0..10u64).map(|nonce| {
+			let mut tx = Transaction::default();
+			tx.nonce = nonce.into();
+
+			let signed = tx.sign(keypair.secret(), None);
+			PendingTransaction::new(signed, None)
+		}).collect();
+
+		let db = Arc::new(::kvdb_memorydb::create(0));
+		{
+			// nothing written yet, will write the pool.
+			let store = super::create(db.clone(), None, PoolDummy(transactions.clone()));
+			assert_eq!(store.pool_transactions().unwrap(), vec![])
+		}
+		{
+			// pool written, local transactions untouched.
+			let store = super::create(db.clone(), None, Dummy(vec![]));
+			assert_eq!(store.pending_transactions().unwrap(), vec![]);
+			assert_eq!(store.pool_transactions().unwrap(), transactions);
+			store.clear_pool().unwrap();
+			assert_eq!(store.pool_transactions().unwrap(), vec![]);
+		}
+	}
 }
@@ -79,6 +79,7 @@ impl BanningTransactionQueue {
 		&mut self,
 		transaction: SignedTransaction,
 		time: QueuingInstant,
+		condition: Option<transaction::Condition>,
 		details_provider: &TransactionDetailsProvider,
 	) -> Result<transaction::ImportResult, transaction::Error> {
 		if let Threshold::BanAfter(threshold) = self.ban_threshold {
@@ -111,7 +112,7 @@ impl BanningTransactionQueue {
 				}
 			}
 		}
-		self.queue.add(transaction, TransactionOrigin::External, time, None, details_provider)
+		self.queue.add(transaction, TransactionOrigin::External, time, condition, details_provider)
 	}
 
 	/// Ban transaction with given hash.
@@ -261,12 +262,12 @@ mod tests {
 		let banlist1 = txq.ban_sender(tx.sender());
 		assert!(!banlist1, "Threshold not reached yet.");
 		// Insert once
-		let import1 = txq.add_with_banlist(tx.clone(), 0, &default_tx_provider()).unwrap();
+		let import1 = txq.add_with_banlist(tx.clone(), 0, None, &default_tx_provider()).unwrap();
 		assert_eq!(import1, transaction::ImportResult::Current);
 
 		// when
 		let banlist2 = txq.ban_sender(tx.sender());
-		let import2 = txq.add_with_banlist(tx.clone(), 0, &default_tx_provider());
+		let import2 = txq.add_with_banlist(tx.clone(), 0, None, &default_tx_provider());
 
 		// then
 		assert!(banlist2, "Threshold should be reached - banned.");
@@ -285,12 +286,12 @@ mod tests {
 		let banlist1 = txq.ban_recipient(recipient);
 		assert!(!banlist1, "Threshold not reached yet.");
 		// Insert once
-		let import1 = txq.add_with_banlist(tx.clone(), 0, &default_tx_provider()).unwrap();
+		let import1 = txq.add_with_banlist(tx.clone(), 0, None, &default_tx_provider()).unwrap();
 		assert_eq!(import1, transaction::ImportResult::Current);
 
 		// when
 		let banlist2 = txq.ban_recipient(recipient);
-		let import2 = txq.add_with_banlist(tx.clone(), 0, &default_tx_provider());
+		let import2 = txq.add_with_banlist(tx.clone(), 0, None, &default_tx_provider());
 
 		// then
 		assert!(banlist2, "Threshold should be reached - banned.");
@@ -307,12 +308,12 @@ mod tests {
 		let banlist1 = txq.ban_codehash(codehash);
 		assert!(!banlist1, "Threshold not reached yet.");
 		// Insert once
-		let import1 = txq.add_with_banlist(tx.clone(), 0, &default_tx_provider()).unwrap();
+		let import1 = txq.add_with_banlist(tx.clone(), 0, None, &default_tx_provider()).unwrap();
 		assert_eq!(import1, transaction::ImportResult::Current);
 
 		// when
 		let banlist2 = txq.ban_codehash(codehash);
-		let import2 = txq.add_with_banlist(tx.clone(), 0, &default_tx_provider());
+		let import2 = txq.add_with_banlist(tx.clone(), 0, None, &default_tx_provider());
 
 		// then
 		assert!(banlist2, "Threshold should be reached - banned.");
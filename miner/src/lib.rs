@@ -28,7 +28,7 @@ extern crate heapsize;
 extern crate keccak_hash as hash;
 extern crate linked_hash_map;
 extern crate parking_lot;
-extern crate table;
+extern crate transaction_pool as txpool;
 extern crate transient_hashmap;
 
 #[macro_use]
@@ -45,6 +45,7 @@ extern crate rustc_hex;
 pub mod banning_queue;
 pub mod external;
 pub mod local_transactions;
+pub mod pool;
 pub mod service_transaction_checker;
 pub mod transaction_queue;
 pub mod work_notify;
@@ -33,7 +33,7 @@ pub enum Status {
 	Mined(SignedTransaction),
 	/// Transaction is dropped because of limit
 	Dropped(SignedTransaction),
-	/// Replaced because of higher gas price of another transaction.
+	/// Replaced by another transaction with the same sender and nonce.
 	Replaced(SignedTransaction, U256, H256),
 	/// Transaction was never accepted to the queue.
 	Rejected(SignedTransaction, transaction::Error),
@@ -0,0 +1,119 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool listener.
+//!
+//! Keeps the list of local transactions up to date
+//! and counts transactions per sender and their total gas.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+use ethereum_types::{U256, Address};
+use txpool::{self, VerifiedTransaction as PoolVerifiedTransaction};
+
+use local_transactions::LocalTransactionsList;
+use super::VerifiedTransaction;
+
+/// Listener notified about all changes to the pool.
+#[derive(Debug, Default)]
+pub struct Listener {
+	local_transactions: LocalTransactionsList,
+	senders: HashMap<Address, usize>,
+	gas: U256,
+}
+
+impl Listener {
+	/// Returns the list of local transactions.
+	pub fn local_transactions(&self) -> &LocalTransactionsList {
+		&self.local_transactions
+	}
+
+	/// Returns the list of local transactions for modification.
+	pub fn local_transactions_mut(&mut self) -> &mut LocalTransactionsList {
+		&mut self.local_transactions
+	}
+
+	/// Returns number of transactions from given sender currently in the pool.
+	pub fn sender_count(&self, sender: &Address) -> usize {
+		self.senders.get(sender).cloned().unwrap_or(0)
+	}
+
+	/// Returns total gas of all transactions currently in the pool.
+	pub fn total_gas(&self) -> U256 {
+		self.gas
+	}
+
+	fn is_local(&self, tx: &VerifiedTransaction) -> bool {
+		self.local_transactions.contains(&tx.signed().hash())
+	}
+
+	fn inserted(&mut self, tx: &VerifiedTransaction) {
+		*self.senders.entry(*tx.sender()).or_insert(0) += 1;
+		self.gas = self.gas.saturating_add(tx.signed().gas);
+	}
+
+	fn removed(&mut self, tx: &VerifiedTransaction) {
+		if let Entry::Occupied(mut entry) = self.senders.entry(*tx.sender()) {
+			*entry.get_mut() -= 1;
+			if *entry.get() == 0 {
+				entry.remove();
+			}
+		}
+		self.gas = self.gas.saturating_sub(tx.signed().gas);
+	}
+}
+
+impl txpool::Listener<VerifiedTransaction> for Listener {
+	fn added(&mut self, tx: &Arc<VerifiedTransaction>, old: Option<&Arc<VerifiedTransaction>>) {
+		self.inserted(tx);
+		if let Some(old) = old {
+			self.removed(old);
+			if self.is_local(old) {
+				self.local_transactions.mark_replaced(old.signed().clone(), tx.signed().gas_price, tx.signed().hash());
+			}
+		}
+	}
+
+	fn dropped(&mut self, tx: &Arc<VerifiedTransaction>) {
+		self.removed(tx);
+		if self.is_local(tx) {
+			self.local_transactions.mark_dropped(tx.signed().clone());
+		}
+	}
+
+	fn invalid(&mut self, tx: &Arc<VerifiedTransaction>) {
+		self.removed(tx);
+		if self.is_local(tx) {
+			self.local_transactions.mark_invalid(tx.signed().clone());
+		}
+	}
+
+	fn cancelled(&mut self, tx: &Arc<VerifiedTransaction>) {
+		self.removed(tx);
+		if self.is_local(tx) {
+			self.local_transactions.mark_canceled(tx.pending());
+		}
+	}
+
+	fn mined(&mut self, tx: &Arc<VerifiedTransaction>) {
+		self.removed(tx);
+		if self.is_local(tx) {
+			self.local_transactions.mark_mined(tx.signed().clone());
+		}
+	}
+}
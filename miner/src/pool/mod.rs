@@ -0,0 +1,143 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ethereum-specific transaction pool components.
+//!
+//! The generic `transaction-pool` crate knows nothing about Ethereum transactions.
+//! This module provides the pieces required to store them in the pool:
+//! - `verifier` performs all the checks before a transaction is imported,
+//! - `scoring` decides on ordering and replacement of transactions,
+//! - `ready` decides if a transaction can be included in the next block,
+//! - `listener` keeps track of local transactions and gas used by the queue.
+
+use ethereum_types::{H256, U256, Address};
+use heapsize::HeapSizeOf;
+use transaction::{self, SignedTransaction, PendingTransaction};
+use txpool;
+
+use transaction_queue::{TransactionOrigin, QueuingInstant};
+
+pub mod listener;
+pub mod ready;
+pub mod scoring;
+pub mod verifier;
+
+/// Verified transaction stored in the pool.
+#[derive(Debug, Clone)]
+pub struct VerifiedTransaction {
+	/// Transaction.
+	transaction: SignedTransaction,
+	/// Transaction hash.
+	hash: H256,
+	/// Transaction sender.
+	sender: Address,
+	/// Transaction origin.
+	origin: TransactionOrigin,
+	/// Delay until specified condition is met.
+	condition: Option<transaction::Condition>,
+	/// Insertion time
+	insertion_time: QueuingInstant,
+	/// ID assigned upon insertion, should be unique.
+	insertion_id: u64,
+	/// Gas usage priority factor (see `PrioritizationStrategy::GasFactorAndGasPrice`).
+	gas_factor: U256,
+	/// Heap usage of this transaction.
+	mem_usage: usize,
+}
+
+impl VerifiedTransaction {
+	/// Creates a new verified transaction.
+	///
+	/// The `minimal_gas_price` is used to compute the gas usage priority factor.
+	pub fn new(
+		transaction: SignedTransaction,
+		origin: TransactionOrigin,
+		condition: Option<transaction::Condition>,
+		insertion_time: QueuingInstant,
+		insertion_id: u64,
+		minimal_gas_price: U256,
+	) -> Self {
+		let hash = transaction.hash();
+		let sender = transaction.sender();
+		let gas_factor = (transaction.gas >> 15).saturating_mul(minimal_gas_price);
+		let mem_usage = transaction.heap_size_of_children();
+
+		VerifiedTransaction {
+			transaction,
+			hash,
+			sender,
+			origin,
+			condition,
+			insertion_time,
+			insertion_id,
+			gas_factor,
+			mem_usage,
+		}
+	}
+
+	/// Gets wrapped `SignedTransaction`.
+	pub fn signed(&self) -> &SignedTransaction {
+		&self.transaction
+	}
+
+	/// Returns the transaction together with its condition.
+	pub fn pending(&self) -> PendingTransaction {
+		PendingTransaction::new(self.transaction.clone(), self.condition.clone())
+	}
+
+	/// Transaction origin.
+	pub fn origin(&self) -> TransactionOrigin {
+		self.origin
+	}
+
+	/// Condition that needs to be met before the transaction is ready.
+	pub fn condition(&self) -> &Option<transaction::Condition> {
+		&self.condition
+	}
+
+	/// Time (block number) when the transaction was inserted.
+	pub fn insertion_time(&self) -> QueuingInstant {
+		self.insertion_time
+	}
+
+	/// Gas usage priority factor.
+	pub fn gas_factor(&self) -> U256 {
+		self.gas_factor
+	}
+
+	/// Maximal amount of Wei the transaction may spend.
+	pub fn cost(&self) -> U256 {
+		self.transaction.value.saturating_add(self.transaction.gas_price.saturating_mul(self.transaction.gas))
+	}
+}
+
+impl txpool::VerifiedTransaction for VerifiedTransaction {
+	fn hash(&self) -> &H256 {
+		&self.hash
+	}
+
+	fn mem_usage(&self) -> usize {
+		self.mem_usage
+	}
+
+	fn sender(&self) -> &Address {
+		&self.sender
+	}
+
+	fn insertion_id(&self) -> u64 {
+		self.insertion_id
+	}
+}
@@ -0,0 +1,129 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction Readiness indicator
+//!
+//! Transaction readiness is responsible for indicating if
+//! particular transaction can be included in the block.
+//!
+//! Regular transactions are ready iff the current state nonce
+//! of the sender equals to the transaction nonce.
+//!
+//! Let's define `S = state nonce`. Transactions are processed
+//! in order, so we first include transaction with nonce `S`,
+//! but then we are able to include the one with `S + 1` nonce.
+//! So bear in mind that transactions can be included in chains
+//! and their readiness is dependent on previous transactions from
+//! the same sender.
+//!
+//! There are three possible outcomes:
+//! - The transaction is old (stalled; state nonce > transaction nonce)
+//! - The transaction is ready (current; state nonce == transaction nonce)
+//! - The transaction is not ready yet (future; state nonce < transaction nonce)
+//!
+//! NOTE The transactions are always checked for readiness in order they are stored within the pool.
+//! First `Readiness::Future` response also causes all subsequent transactions from the same sender
+//! to be marked as `Future`.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use ethereum_types::{U256, Address};
+use transaction;
+use txpool::{self, Readiness};
+
+use super::VerifiedTransaction;
+
+type BlockNumber = u64;
+
+/// Checks readiness of transactions by comparing the nonce to state nonce.
+pub struct State<'a> {
+	nonces: HashMap<Address, U256>,
+	state: &'a HashMap<Address, U256>,
+	max_nonce: Option<U256>,
+}
+
+impl<'a> State<'a> {
+	/// Create new State checker, given known state nonces of senders.
+	///
+	/// Transactions with nonce greater or equal to `max_nonce` are never ready.
+	pub fn new(state: &'a HashMap<Address, U256>, max_nonce: Option<U256>) -> Self {
+		State {
+			nonces: Default::default(),
+			state,
+			max_nonce,
+		}
+	}
+}
+
+impl<'a> txpool::Ready<VerifiedTransaction> for State<'a> {
+	fn is_ready(&mut self, tx: &VerifiedTransaction) -> Readiness {
+		// Check max nonce
+		match self.max_nonce {
+			Some(nonce) if tx.signed().nonce >= nonce => {
+				return Readiness::Future;
+			},
+			_ => {},
+		}
+
+		let sender = tx.signed().sender();
+		let state = self.state;
+		let nonce = match self.nonces.entry(sender) {
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) => match state.get(&sender) {
+				Some(nonce) => entry.insert(*nonce),
+				// We don't know the state nonce of this sender.
+				None => return Readiness::Future,
+			},
+		};
+
+		match tx.signed().nonce.cmp(nonce) {
+			cmp::Ordering::Greater => Readiness::Future,
+			cmp::Ordering::Less => Readiness::Stalled,
+			cmp::Ordering::Equal => {
+				*nonce = *nonce + U256::one();
+				Readiness::Ready
+			},
+		}
+	}
+}
+
+/// Checks readiness of transactions with a condition (block number or timestamp).
+pub struct Condition {
+	block_number: BlockNumber,
+	now: u64,
+}
+
+impl Condition {
+	/// Create a new condition checker given current block number and UTC timestamp.
+	pub fn new(block_number: BlockNumber, now: u64) -> Self {
+		Condition {
+			block_number,
+			now,
+		}
+	}
+}
+
+impl txpool::Ready<VerifiedTransaction> for Condition {
+	fn is_ready(&mut self, tx: &VerifiedTransaction) -> Readiness {
+		match *tx.condition() {
+			Some(transaction::Condition::Number(block)) if block > self.block_number => Readiness::Future,
+			Some(transaction::Condition::Timestamp(time)) if time > self.now => Readiness::Future,
+			_ => Readiness::Ready,
+		}
+	}
+}
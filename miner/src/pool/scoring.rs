@@ -0,0 +1,205 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction Scoring and Ordering
+//!
+//! Ethereum transactions from the same sender are ordered by `nonce`.
+//! Low nonces need to be included first. If there are two transactions from the same sender
+//! and with the same `nonce` only one of them can be included.
+//! We choose the one with the higher gas price, but it needs to be bumped by at least 12.5%
+//! (see `GAS_PRICE_BUMP_SHIFT`) to replace the transaction already in the pool.
+//!
+//! Transactions from different senders are prioritized by their `Score`, which considers (in order):
+//! 1. Number of penalties of the sender (see `ScoringEvent::Penalize`).
+//! 2. Origin of the sender's transactions (retracted and local ones go first).
+//! 3. Nonce height, i.e. position of the transaction within the sender's transactions.
+//! 4. Gas and gas price according to the `PrioritizationStrategy`.
+//! 5. Insertion order.
+
+use std::cmp;
+use std::sync::Arc;
+
+use ethereum_types::U256;
+use txpool::scoring::{self, Change, Choice};
+use txpool::VerifiedTransaction as PoolVerifiedTransaction;
+
+use transaction_queue::{PrioritizationStrategy, TransactionOrigin};
+use super::VerifiedTransaction;
+
+/// Transaction with the same (sender, nonce) can be replaced only if
+/// `new_gas_price > old_gas_price + old_gas_price >> SHIFT`
+const GAS_PRICE_BUMP_SHIFT: usize = 3; // 2 = 25%, 3 = 12.5%, 4 = 6.25%
+
+/// Returns the minimal gas price a transaction needs to replace
+/// another one with the same sender and nonce paying `old_gas_price`.
+pub fn minimal_replacement_gas_price(old_gas_price: U256) -> U256 {
+	old_gas_price.saturating_add(old_gas_price >> GAS_PRICE_BUMP_SHIFT)
+}
+
+/// Events that change scores of transactions already in the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoringEvent {
+	/// Lower the priority of all transactions from the sender.
+	Penalize,
+}
+
+/// Priority of a transaction in the pool.
+///
+/// Higher score means higher priority.
+#[derive(Debug, Clone, Default)]
+pub struct Score {
+	/// Number of penalties of the sender.
+	penalties: usize,
+	/// Rank of the sender's origin (lower is better).
+	origin: u8,
+	/// Position of the transaction within sender's transactions.
+	height: usize,
+	/// Gas (limit) of the transaction. Used only by `GasAndGasPrice` strategy.
+	gas: U256,
+	/// Gas usage priority factor. Used only by `GasFactorAndGasPrice` strategy.
+	gas_factor: U256,
+	/// Gas price of the transaction.
+	gas_price: U256,
+	/// Incremental id assigned when transaction is inserted to the pool.
+	insertion_id: u64,
+}
+
+impl Ord for Score {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		other.penalties.cmp(&self.penalties)
+			.then(other.origin.cmp(&self.origin))
+			.then(other.height.cmp(&self.height))
+			.then(other.gas.cmp(&self.gas))
+			// avoiding overflows
+			// (gp1 - g1) > (gp2 - g2) <=>
+			// (gp1 + g2) > (gp2 + g1)
+			.then_with(|| {
+				let self_price = self.gas_price.saturating_add(other.gas_factor);
+				let other_price = other.gas_price.saturating_add(self.gas_factor);
+				self_price.cmp(&other_price)
+			})
+			.then(self.gas_price.cmp(&other.gas_price))
+			.then(other.insertion_id.cmp(&self.insertion_id))
+	}
+}
+
+impl PartialOrd for Score {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for Score {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == cmp::Ordering::Equal
+	}
+}
+
+impl Eq for Score {}
+
+fn origin_rank(origin: TransactionOrigin) -> u8 {
+	match origin {
+		TransactionOrigin::RetractedBlock => 0,
+		TransactionOrigin::Local => 1,
+		TransactionOrigin::External => 2,
+	}
+}
+
+/// Orders transactions by nonce and prioritizes them according to `PrioritizationStrategy`.
+#[derive(Debug, Clone, Copy)]
+pub struct NonceAndGasPrice(pub PrioritizationStrategy);
+
+impl scoring::Scoring<VerifiedTransaction> for NonceAndGasPrice {
+	type Score = Score;
+	type Event = ScoringEvent;
+
+	fn compare(&self, old: &VerifiedTransaction, other: &VerifiedTransaction) -> cmp::Ordering {
+		old.signed().nonce.cmp(&other.signed().nonce)
+	}
+
+	fn choose(&self, old: &VerifiedTransaction, new: &VerifiedTransaction) -> Choice {
+		if old.signed().nonce != new.signed().nonce {
+			return Choice::InsertNew
+		}
+
+		let old_gas_price = old.signed().gas_price;
+		let new_gas_price = new.signed().gas_price;
+		let min_required_gas_price = minimal_replacement_gas_price(old_gas_price);
+
+		if min_required_gas_price > new_gas_price {
+			trace!(target: "txqueue", "Didn't insert transaction because gas price was too low: {:?} ({:?} stays in the queue)", new.signed().hash(), old.signed().hash());
+			Choice::RejectNew
+		} else {
+			trace!(target: "txqueue", "Replaced transaction: {:?} with transaction with higher gas price: {:?}", old.signed().hash(), new.signed().hash());
+			Choice::ReplaceOld
+		}
+	}
+
+	fn update_scores(&self, txs: &[Arc<VerifiedTransaction>], scores: &mut [Score], change: Change<ScoringEvent>) {
+		// Penalties are kept for all transactions from the sender,
+		// so we carry them over when the scores are re-computed.
+		let penalties = match change {
+			Change::Event(ScoringEvent::Penalize) => {
+				for score in scores.iter_mut() {
+					score.penalties = score.penalties.saturating_add(1);
+				}
+				return;
+			},
+			Change::InsertedAt(index) => scores.iter()
+				.enumerate()
+				.filter(|&(i, _)| i != index)
+				.map(|(_, score)| score.penalties)
+				.max(),
+			_ => scores.iter().map(|score| score.penalties).max(),
+		}.unwrap_or(0);
+
+		// Local (or retracted) transaction gives priority to all transactions from the sender.
+		let origin = txs.iter()
+			.map(|tx| origin_rank(tx.origin()))
+			.min()
+			.unwrap_or(0);
+
+		for (height, (tx, score)) in txs.iter().zip(scores.iter_mut()).enumerate() {
+			*score = Score {
+				penalties,
+				origin,
+				height,
+				gas: match self.0 {
+					PrioritizationStrategy::GasAndGasPrice => tx.signed().gas,
+					_ => U256::zero(),
+				},
+				gas_factor: match self.0 {
+					PrioritizationStrategy::GasFactorAndGasPrice => tx.gas_factor(),
+					_ => U256::zero(),
+				},
+				gas_price: tx.signed().gas_price,
+				insertion_id: tx.insertion_id(),
+			};
+		}
+	}
+
+	fn should_replace(&self, old: &VerifiedTransaction, new: &VerifiedTransaction) -> bool {
+		let old_origin = origin_rank(old.origin());
+		let new_origin = origin_rank(new.origin());
+
+		if old_origin != new_origin {
+			// Own and retracted transactions can push out external ones.
+			return new_origin < old_origin;
+		}
+
+		new.signed().gas_price > old.signed().gas_price
+	}
+}
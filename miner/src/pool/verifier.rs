@@ -0,0 +1,216 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction Verifier
+//!
+//! Responsible for verifying a transaction before importing to the pool.
+//! Should make sure that the transaction is structuraly valid and that
+//! the sender is able to pay for it.
+//!
+//! May have some overlap with `Readiness` since we don't want to keep around
+//! stalled transactions.
+
+use std::cmp;
+
+use ethereum_types::U256;
+use transaction::{self, SignedTransaction};
+use txpool;
+
+use transaction_queue::{TransactionOrigin, TransactionDetailsProvider, AccountDetails, QueuingInstant};
+use super::VerifiedTransaction;
+
+/// Verification options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+	/// Minimal allowed gas price of external transactions.
+	pub minimal_gas_price: U256,
+	/// Minimal gas price required to enter the pool when it's full (zero otherwise).
+	pub pool_gas_price_entry_limit: U256,
+	/// Current block gas limit.
+	pub block_gas_limit: U256,
+	/// Maximal gas limit for a single transaction.
+	pub tx_gas_limit: U256,
+}
+
+/// Transaction to verify.
+#[derive(Debug)]
+pub struct Transaction {
+	/// Signed transaction.
+	pub transaction: SignedTransaction,
+	/// Transaction origin.
+	pub origin: TransactionOrigin,
+	/// Delay until specified condition is met.
+	pub condition: Option<transaction::Condition>,
+	/// Time (block number) of the import.
+	pub insertion_time: QueuingInstant,
+}
+
+/// Verifies transactions before they are imported to the pool.
+pub struct Verifier<'a> {
+	details_provider: &'a TransactionDetailsProvider,
+	options: Options,
+	id: u64,
+}
+
+impl<'a> Verifier<'a> {
+	/// Creates new transaction verifier.
+	///
+	/// The verified transaction will be assigned given insertion `id`.
+	pub fn new(details_provider: &'a TransactionDetailsProvider, options: Options, id: u64) -> Self {
+		Verifier {
+			details_provider,
+			options,
+			id,
+		}
+	}
+
+	/// Verifies the transaction and returns it together with sender's account details.
+	///
+	/// NOTE details_provider methods should be cheap to compute
+	/// otherwise it might open up an attack vector.
+	pub fn verify(&self, tx: Transaction) -> Result<(VerifiedTransaction, AccountDetails), transaction::Error> {
+		let Transaction { transaction: tx, origin, condition, insertion_time } = tx;
+		let is_local = origin.is_local();
+
+		if !is_local && tx.gas_price < self.options.minimal_gas_price {
+			// if it is non-service-transaction => drop
+			let is_service_transaction = tx.gas_price.is_zero();
+			if !is_service_transaction {
+				trace!(target: "txqueue",
+					"Dropping transaction below minimal gas price threshold: {:?} (gp: {} < {})",
+					tx.hash(),
+					tx.gas_price,
+					self.options.minimal_gas_price
+				);
+
+				return Err(transaction::Error::InsufficientGasPrice {
+					minimal: self.options.minimal_gas_price,
+					got: tx.gas_price,
+				});
+			}
+
+			let is_service_transaction_accepted = match self.details_provider.is_service_transaction_acceptable(&tx) {
+				Ok(true) => true,
+				Ok(false) => {
+					trace!(target: "txqueue",
+						"Dropping service transaction as sender is not certified to send service transactions: {:?} (sender: {:?})",
+						tx.hash(),
+						tx.sender(),
+					);
+
+					false
+				},
+				Err(contract_err) => {
+					trace!(target: "txqueue",
+						"Dropping service transaction as service contract returned error: {:?} (error: {:?})",
+						tx.hash(),
+						contract_err,
+					);
+
+					false
+				},
+			};
+
+			if !is_service_transaction_accepted {
+				return Err(transaction::Error::InsufficientGasPrice {
+					minimal: self.options.minimal_gas_price,
+					got: tx.gas_price,
+				});
+			}
+		}
+
+		let full_pool_lowest = self.options.pool_gas_price_entry_limit;
+		if !is_local && tx.gas_price < full_pool_lowest {
+			trace!(target: "txqueue",
+				"Dropping transaction below lowest gas price in a full queue: {:?} (gp: {} < {})",
+				tx.hash(),
+				tx.gas_price,
+				full_pool_lowest
+			);
+
+			return Err(transaction::Error::InsufficientGasPrice {
+				minimal: full_pool_lowest,
+				got: tx.gas_price,
+			});
+		}
+
+		let gas_limit = cmp::min(self.options.tx_gas_limit, self.options.block_gas_limit);
+		if tx.gas > gas_limit {
+			trace!(target: "txqueue",
+				"Dropping transaction above gas limit: {:?} ({} > min({}, {}))",
+				tx.hash(),
+				tx.gas,
+				self.options.block_gas_limit,
+				self.options.tx_gas_limit
+			);
+			return Err(transaction::Error::GasLimitExceeded {
+				limit: gas_limit,
+				got: tx.gas,
+			});
+		}
+
+		let minimal_gas = self.details_provider.estimate_gas_required(&tx);
+		if tx.gas < minimal_gas {
+			trace!(target: "txqueue",
+				"Dropping transaction with insufficient gas: {:?} ({} > {})",
+				tx.hash(),
+				tx.gas,
+				minimal_gas,
+			);
+
+			return Err(transaction::Error::InsufficientGas {
+				minimal: minimal_gas,
+				got: tx.gas,
+			});
+		}
+
+		let account_details = self.details_provider.fetch_account(&tx.sender());
+		let cost = tx.value.saturating_add(tx.gas_price.saturating_mul(tx.gas));
+		if account_details.balance < cost {
+			trace!(target: "txqueue",
+				"Dropping transaction without sufficient balance: {:?} ({} < {})",
+				tx.hash(),
+				account_details.balance,
+				cost
+			);
+
+			return Err(transaction::Error::InsufficientBalance {
+				cost: cost,
+				balance: account_details.balance
+			});
+		}
+		tx.check_low_s()?;
+
+		let verified = VerifiedTransaction::new(
+			tx,
+			origin,
+			condition,
+			insertion_time,
+			self.id,
+			self.options.minimal_gas_price,
+		);
+		Ok((verified, account_details))
+	}
+}
+
+impl<'a> txpool::Verifier<Transaction> for Verifier<'a> {
+	type Error = transaction::Error;
+	type VerifiedTransaction = VerifiedTransaction;
+
+	fn verify_transaction(&self, tx: Transaction) -> Result<Self::VerifiedTransaction, Self::Error> {
+		self.verify(tx).map(|(tx, _)| tx)
+	}
+}
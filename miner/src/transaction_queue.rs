@@ -88,30 +88,38 @@
 //!	}
 //! ```
 //!
-//!	# Maintaing valid state
+//!	# Maintaining valid state
 //!
-//!	1. Whenever transaction is imported to queue (to queue) all other transactions from this sender are revalidated in current. It means that they are moved to future and back again (height recalculation & gap filling).
-//!	2. Whenever invalid transaction is removed:
-//!		- When it's removed from `future` - all `future` transactions heights are recalculated and then
-//!		  we check if the transactions should go to `current` (comparing state nonce)
-//!		- When it's removed from `current` - all transactions from this sender (`current` & `future`) are recalculated.
+//!	The transactions are stored in a generic `transaction-pool` with Ethereum-specific
+//!	verification (`pool::verifier`), ordering (`pool::scoring`) and readiness (`pool::ready`).
+//!	The queue remembers the latest known state nonce of every sender and uses it
+//!	to decide whether a transaction is `current` (ready to be included in a block) or `future`.
+//!
+//!	1. Whenever transaction is imported to queue the state nonce of its sender is updated
+//!	   and stalled transactions from this sender are removed.
+//!	2. Whenever invalid transaction is removed the subsequent transactions from this sender
+//!	   become `future` (since there is a gap now).
 //!	3. `cull` is used to inform the queue about client (state) nonce changes.
-//!     - It removes all transactions (either from `current` or `future`) with nonce < client nonce
-//!     - It moves matching `future` transactions to `current`
-//! 4. `remove_old` is used as convenient method to update the state nonce for all senders in the queue.
+//!		- It removes all transactions with nonce < client nonce
+//!		- Matching `future` transactions become `current`
+//!	4. `remove_old` is used as convenient method to update the state nonce for all senders in the queue.
 //!		- Invokes `cull` with latest state nonce for all senders.
 
-use std::cmp::Ordering;
-use std::cmp;
-use std::collections::{HashSet, HashMap, BTreeSet, BTreeMap};
-use std::ops::Deref;
+use std::cmp::{self, Ordering};
+use std::collections::{HashSet, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
 
 use ethereum_types::{H256, U256, Address};
-use heapsize::HeapSizeOf;
 use linked_hash_map::LinkedHashMap;
-use local_transactions::{LocalTransactionsList, Status as LocalTransactionStatus};
-use table::Table;
+use local_transactions::Status as LocalTransactionStatus;
+use pool::VerifiedTransaction;
+use pool::listener::Listener;
+use pool::ready;
+use pool::scoring::{NonceAndGasPrice, ScoringEvent};
+use pool::verifier::{self, Verifier};
 use transaction::{self, SignedTransaction, PendingTransaction};
+use txpool::{self, VerifiedTransaction as PoolVerifiedTransaction};
 
 type BlockNumber = u64;
 
@@ -148,348 +156,12 @@ impl Ord for TransactionOrigin {
 }
 
 impl TransactionOrigin {
-	fn is_local(&self) -> bool {
+	/// Returns true if the transaction was submitted locally.
+	pub fn is_local(&self) -> bool {
 		*self == TransactionOrigin::Local
 	}
 }
 
-#[derive(Clone, Debug)]
-/// Light structure used to identify transaction and its order
-struct TransactionOrder {
-	/// Primary ordering factory. Difference between transaction nonce and expected nonce in state
-	/// (e.g. Tx(nonce:5), State(nonce:0) -> height: 5)
-	/// High nonce_height = Low priority (processed later)
-	nonce_height: U256,
-	/// Gas Price of the transaction.
-	/// Low gas price = Low priority (processed later)
-	gas_price: U256,
-	/// Gas usage priority factor. Usage depends on strategy.
-	/// Represents the linear increment in required gas price for heavy transactions.
-	///
-	/// High gas limit + Low gas price = Very Low priority
-	/// High gas limit + High gas price = High priority
-	gas_factor: U256,
-	/// Gas (limit) of the transaction. Usage depends on strategy.
-	/// Low gas limit = High priority (processed earlier)
-	gas: U256,
-	/// Heap usage of this transaction.
-	mem_usage: usize,
-	/// Transaction ordering strategy
-	strategy: PrioritizationStrategy,
-	/// Hash to identify associated transaction
-	hash: H256,
-	/// Incremental id assigned when transaction is inserted to the queue.
-	insertion_id: u64,
-	/// Origin of the transaction
-	origin: TransactionOrigin,
-	/// Penalties
-	penalties: usize,
-}
-
-
-impl TransactionOrder {
-
-	fn for_transaction(tx: &VerifiedTransaction, base_nonce: U256, min_gas_price: U256, strategy: PrioritizationStrategy) -> Self {
-		let factor = (tx.transaction.gas >> 15) * min_gas_price;
-		TransactionOrder {
-			nonce_height: tx.nonce() - base_nonce,
-			gas_price: tx.transaction.gas_price,
-			gas_factor: factor,
-			gas: tx.transaction.gas,
-			mem_usage: tx.transaction.heap_size_of_children(),
-			strategy: strategy,
-			hash: tx.hash(),
-			insertion_id: tx.insertion_id,
-			origin: tx.origin,
-			penalties: 0,
-		}
-	}
-
-	fn update_height(mut self, nonce: U256, base_nonce: U256) -> Self {
-		self.nonce_height = nonce - base_nonce;
-		self
-	}
-
-	fn penalize(mut self) -> Self {
-		self.penalties = self.penalties.saturating_add(1);
-		self
-	}
-}
-
-impl Eq for TransactionOrder {}
-impl PartialEq for TransactionOrder {
-	fn eq(&self, other: &TransactionOrder) -> bool {
-		self.cmp(other) == Ordering::Equal
-	}
-}
-impl PartialOrd for TransactionOrder {
-	fn partial_cmp(&self, other: &TransactionOrder) -> Option<Ordering> {
-		Some(self.cmp(other))
-	}
-}
-
-impl Ord for TransactionOrder {
-	fn cmp(&self, b: &TransactionOrder) -> Ordering {
-		// First check number of penalties
-		if self.penalties != b.penalties {
-			return self.penalties.cmp(&b.penalties);
-		}
-
-		// Local transactions should always have priority
-		if self.origin != b.origin {
-			return self.origin.cmp(&b.origin);
-		}
-
-		// Check nonce_height
-		if self.nonce_height != b.nonce_height {
-			return self.nonce_height.cmp(&b.nonce_height);
-		}
-
-		match self.strategy {
-			PrioritizationStrategy::GasAndGasPrice => {
-				if self.gas != b.gas {
-					return self.gas.cmp(&b.gas);
-				}
-			},
-			PrioritizationStrategy::GasFactorAndGasPrice => {
-				// avoiding overflows
-				// (gp1 - g1) > (gp2 - g2) <=>
-				// (gp1 + g2) > (gp2 + g1)
-				let f_a = self.gas_price + b.gas_factor;
-				let f_b = b.gas_price + self.gas_factor;
-				if f_a != f_b {
-					return f_b.cmp(&f_a);
-				}
-			},
-			PrioritizationStrategy::GasPriceOnly => {},
-		}
-
-		// Then compare gas_prices
-		if self.gas_price != b.gas_price {
-			return b.gas_price.cmp(&self.gas_price);
-		}
-
-		// Lastly compare insertion_id
-		self.insertion_id.cmp(&b.insertion_id)
-	}
-}
-
-/// Verified transaction
-#[derive(Debug)]
-struct VerifiedTransaction {
-	/// Transaction.
-	transaction: SignedTransaction,
-	/// Transaction origin.
-	origin: TransactionOrigin,
-	/// Delay until specified condition is met.
-	condition: Option<transaction::Condition>,
-	/// Insertion time
-	insertion_time: QueuingInstant,
-	/// ID assigned upon insertion, should be unique.
-	insertion_id: u64,
-}
-
-impl VerifiedTransaction {
-	fn new(
-		transaction: SignedTransaction,
-		origin: TransactionOrigin,
-		condition: Option<transaction::Condition>,
-		insertion_time: QueuingInstant,
-		insertion_id: u64,
-	) -> Self {
-		VerifiedTransaction {
-			transaction,
-			origin,
-			condition,
-			insertion_time,
-			insertion_id,
-		}
-	}
-
-	fn hash(&self) -> H256 {
-		self.transaction.hash()
-	}
-
-	fn nonce(&self) -> U256 {
-		self.transaction.nonce
-	}
-
-	fn sender(&self) -> Address {
-		self.transaction.sender()
-	}
-
-	fn cost(&self) -> U256 {
-		self.transaction.value + self.transaction.gas_price * self.transaction.gas
-	}
-}
-
-#[derive(Debug, Default)]
-struct GasPriceQueue {
-	backing: BTreeMap<U256, HashSet<H256>>,
-}
-
-impl GasPriceQueue {
-	/// Insert an item into a BTreeMap/HashSet "multimap".
-	pub fn insert(&mut self, gas_price: U256, hash: H256) -> bool {
-		self.backing.entry(gas_price).or_insert_with(Default::default).insert(hash)
-	}
-
-	/// Remove an item from a BTreeMap/HashSet "multimap".
-	/// Returns true if the item was removed successfully.
-	pub fn remove(&mut self, gas_price: &U256, hash: &H256) -> bool {
-		if let Some(hashes) = self.backing.get_mut(gas_price) {
-			let only_one_left = hashes.len() == 1;
-			if !only_one_left {
-				// Operation may be ok: only if hash is in gas-price's Set.
-				return hashes.remove(hash);
-			}
-			if hash != hashes.iter().next().expect("We know there is only one element in collection, tested above; qed") {
-				// Operation failed: hash not the single item in gas-price's Set.
-				return false;
-			}
-		} else {
-			// Operation failed: gas-price not found in Map.
-			return false;
-		}
-		// Operation maybe ok: only if hash not found in gas-price Set.
-		self.backing.remove(gas_price).is_some()
-	}
-}
-
-impl Deref for GasPriceQueue {
-	type Target=BTreeMap<U256, HashSet<H256>>;
-
-	fn deref(&self) -> &Self::Target {
-		&self.backing
-	}
-}
-
-/// Holds transactions accessible by (address, nonce) and by priority
-///
-/// `TransactionSet` keeps number of entries below limit, but it doesn't
-/// automatically happen during `insert/remove` operations.
-/// You have to call `enforce_limit` to remove lowest priority transactions from set.
-struct TransactionSet {
-	by_priority: BTreeSet<TransactionOrder>,
-	by_address: Table<Address, U256, TransactionOrder>,
-	by_gas_price: GasPriceQueue,
-	limit: usize,
-	total_gas_limit: U256,
-	memory_limit: usize,
-}
-
-impl TransactionSet {
-	/// Inserts `TransactionOrder` to this set. Transaction does not need to be unique -
-	/// the same transaction may be validly inserted twice. Any previous transaction that
-	/// it replaces (i.e. with the same `sender` and `nonce`) should be returned.
-	fn insert(&mut self, sender: Address, nonce: U256, order: TransactionOrder) -> Option<TransactionOrder> {
-		if !self.by_priority.insert(order.clone()) {
-			return Some(order.clone());
-		}
-		let order_hash = order.hash.clone();
-		let order_gas_price = order.gas_price.clone();
-		let by_address_replaced = self.by_address.insert(sender, nonce, order);
-		// If transaction was replaced remove it from priority queue
-		if let Some(ref old_order) = by_address_replaced {
-			assert!(self.by_priority.remove(old_order), "hash is in `by_address`; all transactions in `by_address` must be in `by_priority`; qed");
-			assert!(self.by_gas_price.remove(&old_order.gas_price, &old_order.hash),
-				"hash is in `by_address`; all transactions' gas_prices in `by_address` must be in `by_gas_limit`; qed");
-		}
-		self.by_gas_price.insert(order_gas_price, order_hash);
-		assert_eq!(self.by_priority.len(), self.by_address.len());
-		assert_eq!(self.by_gas_price.values().map(|v| v.len()).fold(0, |a, b| a + b), self.by_address.len());
-		by_address_replaced
-	}
-
-	/// Remove low priority transactions if there is more than specified by given `limit`.
-	///
-	/// It drops transactions from this set but also removes associated `VerifiedTransaction`.
-	/// Returns addresses and lowest nonces of transactions removed because of limit.
-	fn enforce_limit(&mut self, by_hash: &mut HashMap<H256, VerifiedTransaction>, local: &mut LocalTransactionsList) -> Option<HashMap<Address, U256>> {
-		let mut count = 0;
-		let mut mem_usage = 0;
-		let mut gas: U256 = 0.into();
-		let to_drop : Vec<(Address, U256)> = {
-			self.by_priority
-				.iter()
-				.filter(|order| {
-					// update transaction count and mem usage
-					count += 1;
-					mem_usage += order.mem_usage;
-
-					// calculate current gas usage
-					let r = gas.overflowing_add(order.gas);
-					if r.1 { return false }
-					gas = r.0;
-
-					let is_own_or_retracted = order.origin.is_local() || order.origin == TransactionOrigin::RetractedBlock;
-					// Own and retracted transactions are allowed to go above all limits.
-					!is_own_or_retracted && (mem_usage > self.memory_limit || count > self.limit || gas > self.total_gas_limit)
-				})
-				.map(|order| by_hash.get(&order.hash)
-					.expect("All transactions in `self.by_priority` and `self.by_address` are kept in sync with `by_hash`."))
-				.map(|tx| (tx.sender(), tx.nonce()))
-				.collect()
-		};
-
-		Some(to_drop.into_iter()
-			.fold(HashMap::new(), |mut removed, (sender, nonce)| {
-				let order = self.drop(&sender, &nonce)
-					.expect("Transaction has just been found in `by_priority`; so it is in `by_address` also.");
-				trace!(target: "txqueue", "Dropped out of limit transaction: {:?}", order.hash);
-
-				let order = by_hash.remove(&order.hash)
-					.expect("hash is in `by_priorty`; all hashes in `by_priority` must be in `by_hash`; qed");
-
-				if order.origin.is_local() {
-					local.mark_dropped(order.transaction);
-				}
-
-				let min = removed.get(&sender).map_or(nonce, |val| cmp::min(*val, nonce));
-				removed.insert(sender, min);
-				removed
-			}))
-	}
-
-	/// Drop transaction from this set (remove from `by_priority` and `by_address`)
-	fn drop(&mut self, sender: &Address, nonce: &U256) -> Option<TransactionOrder> {
-		if let Some(tx_order) = self.by_address.remove(sender, nonce) {
-			assert!(self.by_gas_price.remove(&tx_order.gas_price, &tx_order.hash),
-				"hash is in `by_address`; all transactions' gas_prices in `by_address` must be in `by_gas_limit`; qed");
-			assert!(self.by_priority.remove(&tx_order),
-				"hash is in `by_address`; all transactions' gas_prices in `by_address` must be in `by_priority`; qed");
-			assert_eq!(self.by_priority.len(), self.by_address.len());
-			assert_eq!(self.by_gas_price.values().map(|v| v.len()).fold(0, |a, b| a + b), self.by_address.len());
-			return Some(tx_order);
-		}
-		assert_eq!(self.by_priority.len(), self.by_address.len());
-		assert_eq!(self.by_gas_price.values().map(|v| v.len()).fold(0, |a, b| a + b), self.by_address.len());
-		None
-	}
-
-	/// Drop all transactions.
-	fn clear(&mut self) {
-		self.by_priority.clear();
-		self.by_address.clear();
-		self.by_gas_price.backing.clear();
-	}
-
-	/// Sets new limit for number of transactions in this `TransactionSet`.
-	/// Note the limit is not applied (no transactions are removed) by calling this method.
-	fn set_limit(&mut self, limit: usize) {
-		self.limit = limit;
-	}
-
-	/// Get the minimum gas price that we can accept into this queue that wouldn't cause the transaction to
-	/// immediately be dropped. 0 if the queue isn't at capacity; 1 plus the lowest if it is.
-	fn gas_price_entry_limit(&self) -> U256 {
-		match self.by_gas_price.keys().next() {
-			Some(k) if self.by_priority.len() >= self.limit => *k + 1.into(),
-			_ => U256::default(),
-		}
-	}
-}
-
 #[derive(Debug)]
 /// Current status of the queue
 pub struct TransactionQueueStatus {
@@ -507,10 +179,6 @@ pub struct AccountDetails {
 	pub balance: U256,
 }
 
-/// Transaction with the same (sender, nonce) can be replaced only if
-/// `new_gas_price > old_gas_price + old_gas_price >> SHIFT`
-const GAS_PRICE_BUMP_SHIFT: usize = 3; // 2 = 25%, 3 = 12.5%, 4 = 6.25%
-
 /// Describes the strategy used to prioritize transactions in the queue.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PrioritizationStrategy {
@@ -533,6 +201,39 @@ pub enum PrioritizationStrategy {
 	GasFactorAndGasPrice,
 }
 
+/// Describes which transactions are dropped first when the queue is full.
+///
+/// Own and retracted transactions are never dropped because of the limits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EvictionPolicy {
+	/// Drop transactions with the lowest priority first (see `PrioritizationStrategy`).
+	LowestPriority,
+	/// Drop future transactions (waiting for a nonce gap to be filled) first,
+	/// then fall back to the lowest priority ones.
+	FutureFirst,
+	/// Drop transactions that were imported the earliest first.
+	Oldest,
+}
+
+impl Default for EvictionPolicy {
+	fn default() -> Self {
+		EvictionPolicy::LowestPriority
+	}
+}
+
+impl FromStr for EvictionPolicy {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"priority" => Ok(EvictionPolicy::LowestPriority),
+			"future" => Ok(EvictionPolicy::FutureFirst),
+			"oldest" => Ok(EvictionPolicy::Oldest),
+			other => Err(format!("Invalid eviction policy: {}", other)),
+		}
+	}
+}
+
 /// Reason to remove single transaction from the queue.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum RemovalReason {
@@ -558,30 +259,38 @@ pub trait TransactionDetailsProvider {
 	fn is_service_transaction_acceptable(&self, tx: &SignedTransaction) -> Result<bool, String>;
 }
 
+type Pool = txpool::Pool<VerifiedTransaction, NonceAndGasPrice, Listener>;
+
 /// `TransactionQueue` implementation
 pub struct TransactionQueue {
-	/// Prioritization strategy for this queue
-	strategy: PrioritizationStrategy,
+	/// Verified transactions ordered according to the prioritization strategy.
+	pool: Pool,
+	/// Maximal number of transactions in the queue.
+	limit: usize,
+	/// Maximal number of future transactions in the queue.
+	future_limit: usize,
+	/// Maximal number of external transactions from a single sender.
+	max_per_sender: usize,
+	/// Maximal memory usage of transactions in the queue.
+	memory_limit: usize,
+	/// Percentage of `limit` reserved for local transactions.
+	local_share: u8,
+	/// Decides which transactions are dropped first when the queue is full.
+	eviction_policy: EvictionPolicy,
 	/// Gas Price threshold for transactions that can be imported to this queue (defaults to 0)
 	minimal_gas_price: U256,
 	/// The maximum amount of gas any individual transaction may use.
 	tx_gas_limit: U256,
 	/// Current gas limit (block gas limit). Transactions above the limit will not be accepted (default to !0)
 	block_gas_limit: U256,
+	/// Total gas limit of external transactions in the queue.
+	total_gas_limit: U256,
 	/// Maximal time transaction may occupy the queue.
 	/// When we reach `max_time_in_queue / 2^3` we re-validate
 	/// account balance.
 	max_time_in_queue: QueuingInstant,
-	/// Priority queue for transactions that can go to block
-	current: TransactionSet,
-	/// Priority queue for transactions that has been received but are not yet valid to go to block
-	future: TransactionSet,
-	/// All transactions managed by queue indexed by hash
-	by_hash: HashMap<H256, VerifiedTransaction>,
-	/// Last nonce of transaction in current (to quickly check next expected transaction)
-	last_nonces: HashMap<Address, U256>,
-	/// List of local transactions and their statuses.
-	local_transactions: LocalTransactionsList,
+	/// Last known state nonce of senders in the queue.
+	state_nonces: HashMap<Address, U256>,
 	/// Next id that should be assigned to a transaction imported to the queue.
 	next_transaction_id: u64,
 }
@@ -606,51 +315,92 @@ impl TransactionQueue {
 		total_gas_limit: U256,
 		tx_gas_limit: U256,
 	) -> Self {
-		let current = TransactionSet {
-			by_priority: BTreeSet::new(),
-			by_address: Table::new(),
-			by_gas_price: Default::default(),
-			limit,
-			total_gas_limit,
-			memory_limit,
+		// The limits are enforced by the queue itself,
+		// since own and retracted transactions are allowed to go above them.
+		let options = txpool::Options {
+			max_count: usize::max_value(),
+			max_per_sender: usize::max_value(),
+			max_mem_usage: usize::max_value(),
 		};
 
-		let future = TransactionSet {
-			by_priority: BTreeSet::new(),
-			by_address: Table::new(),
-			by_gas_price: Default::default(),
-			total_gas_limit,
+		TransactionQueue {
+			pool: txpool::Pool::new(Listener::default(), NonceAndGasPrice(strategy), options),
 			limit,
+			future_limit: limit,
+			max_per_sender: usize::max_value(),
 			memory_limit,
-		};
-
-		TransactionQueue {
-			strategy,
+			local_share: 0,
+			eviction_policy: EvictionPolicy::default(),
 			minimal_gas_price: U256::zero(),
 			block_gas_limit: !U256::zero(),
 			tx_gas_limit,
+			total_gas_limit,
 			max_time_in_queue: DEFAULT_QUEUING_PERIOD,
-			current,
-			future,
-			by_hash: HashMap::new(),
-			last_nonces: HashMap::new(),
-			local_transactions: LocalTransactionsList::default(),
+			state_nonces: HashMap::new(),
 			next_transaction_id: 0,
 		}
 	}
 
-	/// Set the new limit for `current` and `future` queue.
+	/// Set the new limit for number of transactions in the queue.
+	/// The same limit applies to the number of future transactions.
 	pub fn set_limit(&mut self, limit: usize) {
-		self.current.set_limit(limit);
-		self.future.set_limit(limit);
+		self.limit = limit;
+		self.future_limit = limit;
 		// And ensure the limits
-		self.current.enforce_limit(&mut self.by_hash, &mut self.local_transactions);
-		self.future.enforce_limit(&mut self.by_hash, &mut self.local_transactions);
+		self.enforce_limits();
 	}
 
 	/// Returns current limit of transactions in the queue.
 	pub fn limit(&self) -> usize {
-		self.current.limit
+		self.limit
+	}
+
+	/// Set the new limit for number of future transactions in the queue.
+	pub fn set_future_limit(&mut self, limit: usize) {
+		self.future_limit = limit;
+		// And ensure the limits
+		self.enforce_limits();
+	}
+
+	/// Returns current limit of future transactions in the queue.
+	pub fn future_limit(&self) -> usize {
+		self.future_limit
+	}
+
+	/// Set the new limit for number of transactions from a single sender.
+	/// Own and retracted transactions don't count towards the limit.
+	///
+	/// NOTE: Senders already above the limit keep their transactions,
+	/// the limit is only checked when importing new ones.
+	pub fn set_max_per_sender(&mut self, max_per_sender: usize) {
+		self.max_per_sender = max_per_sender;
+	}
+
+	/// Returns current limit of transactions from a single sender.
+	pub fn max_per_sender(&self) -> usize {
+		self.max_per_sender
+	}
+
+	/// Sets the share of the queue (in percent) that can't be occupied by external transactions.
+	pub fn set_local_share(&mut self, percent: u8) {
+		self.local_share = cmp::min(percent, 100);
+		// And ensure the limits
+		self.enforce_limits();
+	}
+
+	/// Returns the share of the queue (in percent) reserved for local transactions.
+	pub fn local_share(&self) -> u8 {
+		self.local_share
+	}
+
+	/// Sets the order in which transactions are dropped when the queue is full.
+	pub fn set_eviction_policy(&mut self, policy: EvictionPolicy) {
+		self.eviction_policy = policy;
+	}
+
+	/// Returns the order in which transactions are dropped when the queue is full.
+	pub fn eviction_policy(&self) -> EvictionPolicy {
+		self.eviction_policy
 	}
 
 	/// Get the minimal gas price.
@@ -664,10 +414,30 @@ impl TransactionQueue {
 		self.minimal_gas_price = min_gas_price;
 	}
 
-	/// Get one more than the lowest gas price in the queue iff the pool is
-	/// full, otherwise 0.
+	/// Get one more than the gas price of the worst transaction in the queue iff the queue is
+	/// full (for external transactions) and that transaction is pending, otherwise 0.
 	pub fn effective_minimum_gas_price(&self) -> U256 {
-		self.current.gas_price_entry_limit()
+		let count = self.pool.light_status().transaction_count;
+		if count < cmp::min(self.limit, self.external_limit()) {
+			return U256::zero();
+		}
+		let external_count = count.saturating_sub(self.local_count());
+		if count < self.limit && external_count < self.external_limit() {
+			return U256::zero();
+		}
+
+		let worst = match self.pool.worst_transaction() {
+			Some(worst) => worst,
+			None => return U256::zero(),
+		};
+
+		// A future transaction can be pushed out by anything better.
+		let is_pending = self.pool.pending_from_sender(self.nonce_ready(None), worst.sender())
+			.any(|tx| tx.hash() == worst.hash());
+		match is_pending {
+			true => worst.signed().gas_price.saturating_add(U256::one()),
+			false => U256::zero(),
+		}
 	}
 
 	/// Sets new gas limit. Transactions with gas over the limit will not be accepted.
@@ -678,9 +448,8 @@ impl TransactionQueue {
 
 	/// Sets new total gas limit.
 	pub fn set_total_gas_limit(&mut self, total_gas_limit: U256) {
-		self.current.total_gas_limit = total_gas_limit;
-		self.future.total_gas_limit = total_gas_limit;
-		self.future.enforce_limit(&mut self.by_hash, &mut self.local_transactions);
+		self.total_gas_limit = total_gas_limit;
+		self.enforce_limits();
 	}
 
 	/// Set the new limit for the amount of gas any individual transaction may have.
@@ -691,9 +460,10 @@ impl TransactionQueue {
 
 	/// Returns current status for this queue
 	pub fn status(&self) -> TransactionQueueStatus {
+		let status = self.pool.status(self.nonce_ready(None));
 		TransactionQueueStatus {
-			pending: self.current.by_priority.len(),
-			future: self.future.by_priority.len(),
+			pending: status.pending,
+			future: status.future,
 		}
 	}
 
@@ -715,17 +485,13 @@ impl TransactionQueue {
 
 			let result = self.add_internal(tx, origin, time, condition, details_provider);
 			match result {
-				Ok(transaction::ImportResult::Current) => {
-					self.local_transactions.mark_pending(hash);
-				},
-				Ok(transaction::ImportResult::Future) => {
-					self.local_transactions.mark_future(hash);
-				},
+				Ok(_) => self.mark_replaced_local(&cloned_tx),
 				Err(ref err) => {
 					// Sometimes transactions are re-imported, so
 					// don't overwrite transactions if they are already on the list
-					if !self.local_transactions.contains(&hash) {
-						self.local_transactions.mark_rejected(cloned_tx, err.clone());
+					let local = self.pool.listener_mut().local_transactions_mut();
+					if !local.contains(&hash) {
+						local.mark_rejected(cloned_tx, err.clone());
 					}
 				},
 			}
@@ -735,7 +501,7 @@ impl TransactionQueue {
 		}
 	}
 
-	/// Adds signed transaction to the queue.
+	/// Verifies and imports signed transaction to the pool.
 	fn add_internal(
 		&mut self,
 		tx: SignedTransaction,
@@ -744,187 +510,128 @@ impl TransactionQueue {
 		condition: Option<transaction::Condition>,
 		details_provider: &TransactionDetailsProvider,
 	) -> Result<transaction::ImportResult, transaction::Error> {
-		if origin != TransactionOrigin::Local && tx.gas_price < self.minimal_gas_price {
-			// if it is non-service-transaction => drop
-			let is_service_transaction = tx.gas_price.is_zero();
-			if !is_service_transaction {
-				trace!(target: "txqueue",
-					"Dropping transaction below minimal gas price threshold: {:?} (gp: {} < {})",
-					tx.hash(),
-					tx.gas_price,
-					self.minimal_gas_price
-				);
-
-				return Err(transaction::Error::InsufficientGasPrice {
-					minimal: self.minimal_gas_price,
-					got: tx.gas_price,
-				});
-			}
+		let options = verifier::Options {
+			minimal_gas_price: self.minimal_gas_price,
+			pool_gas_price_entry_limit: self.effective_minimum_gas_price(),
+			block_gas_limit: self.block_gas_limit,
+			tx_gas_limit: self.tx_gas_limit,
+		};
+		let verifier = Verifier::new(details_provider, options, self.next_transaction_id);
+		let (tx, account_details) = verifier.verify(verifier::Transaction {
+			transaction: tx,
+			origin,
+			condition,
+			insertion_time: time,
+		})?;
+		// No invalid transactions beyond this point.
 
-			let is_service_transaction_accepted = match details_provider.is_service_transaction_acceptable(&tx) {
-				Ok(true) => true,
-				Ok(false) => {
-					trace!(target: "txqueue",
-						"Dropping service transaction as sender is not certified to send service transactions: {:?} (sender: {:?})",
-						tx.hash(),
-						tx.sender(),
-					);
+		let hash = *tx.hash();
+		let sender = *tx.sender();
+		let nonce = tx.signed().nonce;
 
-					false
-				},
-				Err(contract_err) => {
-					trace!(target: "txqueue",
-						"Dropping service transaction as service contract returned error: {:?} (error: {:?})",
-						tx.hash(),
-						contract_err,
-					);
-
-					false
-				},
-			};
+		if self.pool.find(&hash).is_some() {
+			// Transaction is already imported.
+			trace!(target: "txqueue", "Dropping already imported transaction: {:?}", hash);
+			return Err(transaction::Error::AlreadyImported);
+		}
 
-			if !is_service_transaction_accepted {
-				return Err(transaction::Error::InsufficientGasPrice {
-					minimal: self.minimal_gas_price,
-					got: tx.gas_price,
-				});
-			}
+		// The transaction might be old, let's check that.
+		if nonce < account_details.nonce {
+			trace!(target: "txqueue", "Dropping old transaction: {:?} (nonce: {} < {})", hash, nonce, account_details.nonce);
+			return Err(transaction::Error::Old);
 		}
 
-		let full_queues_lowest = self.effective_minimum_gas_price();
-		if tx.gas_price < full_queues_lowest && origin != TransactionOrigin::Local {
-			trace!(target: "txqueue",
-				"Dropping transaction below lowest gas price in a full queue: {:?} (gp: {} < {})",
-				tx.hash(),
-				tx.gas_price,
-				full_queues_lowest
-			);
-
-			return Err(transaction::Error::InsufficientGasPrice {
-				minimal: full_queues_lowest,
-				got: tx.gas_price,
+		self.next_transaction_id += 1;
+		// State nonce could be updated, so remove transactions that are now stalled.
+		self.update_state_nonce(sender, account_details.nonce);
+
+		let local_tx = match origin.is_local() {
+			true => Some(tx.signed().clone()),
+			false => None,
+		};
+
+		if let Err(err) = self.pool.import(tx) {
+			return Err(match *err.kind() {
+				txpool::ErrorKind::AlreadyImported(_) => transaction::Error::AlreadyImported,
+				txpool::ErrorKind::TooCheapToEnter(_) => {
+					trace!(target: "txqueue", "Dropped out of limit transaction: {:?}", hash);
+					transaction::Error::LimitReached
+				},
+				txpool::ErrorKind::TooCheapToReplace(ref old_hash, _) => {
+					if let (Some(tx), Some(old)) = (local_tx, self.pool.find(old_hash)) {
+						let gas_price = old.signed().gas_price;
+						self.pool.listener_mut().local_transactions_mut().mark_replaced(tx, gas_price, *old_hash);
+					}
+					transaction::Error::TooCheapToReplace
+				},
+				_ => transaction::Error::LimitReached,
 			});
 		}
 
-		let gas_limit = cmp::min(self.tx_gas_limit, self.block_gas_limit);
-		if tx.gas > gas_limit {
-			trace!(target: "txqueue",
-				"Dropping transaction above gas limit: {:?} ({} > min({}, {}))",
-				tx.hash(),
-				tx.gas,
-				self.block_gas_limit,
-				self.tx_gas_limit
-			);
-			return Err(transaction::Error::GasLimitExceeded {
-				limit: gas_limit,
-				got: tx.gas,
-			});
+		// Own transactions should not count towards the limit of external ones.
+		if origin.is_local() {
+			self.mark_transactions_local(&sender);
 		}
 
-		let minimal_gas = details_provider.estimate_gas_required(&tx);
-		if tx.gas < minimal_gas {
-			trace!(target: "txqueue",
-				"Dropping transaction with insufficient gas: {:?} ({} > {})",
-				tx.hash(),
-				tx.gas,
-				minimal_gas,
-			);
-
-			return Err(transaction::Error::InsufficientGas {
-				minimal: minimal_gas,
-				got: tx.gas,
-			});
+		// Enforce limits (might remove the transaction we've just imported).
+		self.enforce_sender_limit(&sender);
+		self.enforce_limits();
+		if self.pool.find(&hash).is_none() {
+			return Err(transaction::Error::LimitReached);
 		}
 
-		let client_account = details_provider.fetch_account(&tx.sender());
-		let cost = tx.value + tx.gas_price * tx.gas;
-		if client_account.balance < cost {
-			trace!(target: "txqueue",
-				"Dropping transaction without sufficient balance: {:?} ({} < {})",
-				tx.hash(),
-				client_account.balance,
-				cost
-			);
-
-			return Err(transaction::Error::InsufficientBalance {
-				cost: cost,
-				balance: client_account.balance
-			});
+		let is_current = self.pool.pending_from_sender(self.nonce_ready(None), &sender)
+			.any(|tx| *tx.hash() == hash);
+		self.update_local_statuses(&sender);
+
+		if is_current {
+			debug!(target: "txqueue", "Imported transaction to current: {:?}", hash);
+			debug!(target: "txqueue", "status: {:?}", self.status());
+			Ok(transaction::ImportResult::Current)
+		} else {
+			debug!(target: "txqueue", "Importing transaction to future: {:?}", hash);
+			debug!(target: "txqueue", "status: {:?}", self.status());
+			Ok(transaction::ImportResult::Future)
 		}
-		tx.check_low_s()?;
-		// No invalid transactions beyond this point.
-		let id = self.next_transaction_id;
-		self.next_transaction_id += 1;
-		let vtx = VerifiedTransaction::new(tx, origin, condition, time, id);
-		let r = self.import_tx(vtx, client_account.nonce);
-		assert_eq!(self.future.by_priority.len() + self.current.by_priority.len(), self.by_hash.len());
-		r
 	}
 
 	/// Removes all transactions from particular sender up to (excluding) given client (state) nonce.
 	/// Client (State) Nonce = next valid nonce for this sender.
 	pub fn cull(&mut self, sender: Address, client_nonce: U256) {
-		// Check if there is anything in current...
-		let should_check_in_current = self.current.by_address.row(&sender)
-			// If nonce == client_nonce nothing is changed
-			.and_then(|by_nonce| by_nonce.keys().find(|nonce| *nonce < &client_nonce))
-			.map(|_| ());
-		// ... or future
-		let should_check_in_future = self.future.by_address.row(&sender)
-			// if nonce == client_nonce we need to promote to current
-			.and_then(|by_nonce| by_nonce.keys().find(|nonce| *nonce <= &client_nonce))
-			.map(|_| ());
-
-		if should_check_in_current.or(should_check_in_future).is_none() {
-			return;
-		}
-
-		self.cull_internal(sender, client_nonce);
-	}
-
-	/// Always updates future and moves transactions from current to future.
-	fn cull_internal(&mut self, sender: Address, client_nonce: U256) {
-		// We will either move transaction to future or remove it completely
-		// so there will be no transactions from this sender in current
-		self.last_nonces.remove(&sender);
-		// First update height of transactions in future to avoid collisions
-		self.update_future(&sender, client_nonce);
-		// This should move all current transactions to future and remove old transactions
-		self.move_all_to_future(&sender, client_nonce);
-		// And now lets check if there is some batch of transactions in future
-		// that should be placed in current. It should also update last_nonces.
-		self.move_matching_future_to_current(sender, client_nonce, client_nonce);
-		assert_eq!(self.future.by_priority.len() + self.current.by_priority.len(), self.by_hash.len());
+		self.update_state_nonce(sender, client_nonce);
+		self.update_local_statuses(&sender);
 	}
 
 	/// Checks the current nonce for all transactions' senders in the queue and removes the old transactions.
 	pub fn remove_old<F>(&mut self, fetch_account: &F, current_time: QueuingInstant) where
 		F: Fn(&Address) -> AccountDetails,
 	{
-		let senders = self.current.by_address.keys()
-			.chain(self.future.by_address.keys())
-			.map(|sender| (*sender, fetch_account(sender)))
+		let senders = self.pool.unordered_transactions()
+			.map(|tx| *tx.sender())
+			.collect::<HashSet<_>>()
+			.into_iter()
+			.map(|sender| (sender, fetch_account(&sender)))
 			.collect::<HashMap<_, _>>();
 
-		for (sender, details) in senders.iter() {
-			self.cull(*sender, details.nonce);
-		}
+		// Forget the senders that are not in the queue any more.
+		self.state_nonces = senders.iter().map(|(sender, details)| (*sender, details.nonce)).collect();
+		self.pool.cull(None, ready::State::new(&self.state_nonces, None));
 
 		let max_time = self.max_time_in_queue;
 		let balance_check = max_time >> 3;
 		// Clear transactions occupying the queue too long
-		let invalid = self.by_hash.iter()
-			.filter(|&(_, ref tx)| !tx.origin.is_local())
-			.map(|(hash, tx)| (hash, tx, current_time.saturating_sub(tx.insertion_time)))
-			.filter_map(|(hash, tx, time_diff)| {
+		let invalid = self.pool.unordered_transactions()
+			.filter(|tx| !tx.origin().is_local())
+			.map(|tx| (tx, current_time.saturating_sub(tx.insertion_time())))
+			.filter_map(|(tx, time_diff)| {
 				if time_diff > max_time {
-					return Some(*hash);
+					return Some(*tx.hash());
 				}
 
 				if time_diff > balance_check {
-					return match senders.get(&tx.sender()) {
+					return match senders.get(tx.sender()) {
 						Some(details) if tx.cost() > details.balance => {
-							Some(*hash)
+							Some(*tx.hash())
 						},
 						_ => None,
 					};
@@ -934,11 +641,15 @@ impl TransactionQueue {
 			})
 			.collect::<Vec<_>>();
 		let fetch_nonce = |a: &Address| senders.get(a)
-			.expect("We fetch details for all senders from both current and future")
+			.expect("We fetch details for all senders in the queue")
 			.nonce;
 		for hash in invalid {
 			self.remove(&hash, &fetch_nonce, RemovalReason::Invalid);
 		}
+
+		for sender in senders.keys() {
+			self.update_local_statuses(sender);
+		}
 	}
 
 	/// Penalize transactions from sender of transaction with given hash.
@@ -948,36 +659,18 @@ impl TransactionQueue {
 	/// to avoid breaking invariants in queue (ordered by nonces).
 	/// Consecutive transactions from this sender would fail otherwise (because of invalid nonce).
 	pub fn penalize(&mut self, transaction_hash: &H256) {
-		let transaction = match self.by_hash.get(transaction_hash) {
+		let transaction = match self.pool.find(transaction_hash) {
 			None => return,
 			Some(t) => t,
 		};
 
 		// Never penalize local transactions
-		if transaction.origin.is_local() {
+		if self.is_local(&transaction) {
 			return;
 		}
 
-		let sender = transaction.sender();
-
 		// Penalize all transactions from this sender
-		let nonces_from_sender = match self.current.by_address.row(&sender) {
-			Some(row_map) => row_map.keys().cloned().collect::<Vec<U256>>(),
-			None => vec![],
-		};
-		for k in nonces_from_sender {
-			let order = self.current.drop(&sender, &k).expect("transaction known to be in self.current; qed");
-			self.current.insert(sender, k, order.penalize());
-		}
-		// Same thing for future
-		let nonces_from_sender = match self.future.by_address.row(&sender) {
-			Some(row_map) => row_map.keys().cloned().collect::<Vec<U256>>(),
-			None => vec![],
-		};
-		for k in nonces_from_sender {
-			let order = self.future.drop(&sender, &k).expect("transaction known to be in self.future; qed");
-			self.future.insert(sender, k, order.penalize());
-		}
+		self.pool.update_scores(transaction.sender(), ScoringEvent::Penalize);
 	}
 
 	/// Removes invalid transaction identified by hash from queue.
@@ -988,459 +681,328 @@ impl TransactionQueue {
 	pub fn remove<F>(&mut self, transaction_hash: &H256, fetch_nonce: &F, reason: RemovalReason)
 		where F: Fn(&Address) -> U256 {
 
-		assert_eq!(self.future.by_priority.len() + self.current.by_priority.len(), self.by_hash.len());
-		let transaction = self.by_hash.remove(transaction_hash);
-		if transaction.is_none() {
+		let sender = match self.pool.find(transaction_hash) {
 			// We don't know this transaction
-			return;
-		}
-
-		let transaction = transaction.expect("None is tested in early-exit condition above; qed");
-		let sender = transaction.sender();
-		let nonce = transaction.nonce();
-		let current_nonce = fetch_nonce(&sender);
-
-		trace!(target: "txqueue", "Removing invalid transaction: {:?}", transaction.hash());
-
-		// Mark in locals
-		if self.local_transactions.contains(transaction_hash) {
-			match reason {
-				RemovalReason::Invalid => self.local_transactions.mark_invalid(
-					transaction.transaction.into()
-				),
-				RemovalReason::NotAllowed => self.local_transactions.mark_invalid(
-					transaction.transaction.into()
-				),
-				RemovalReason::Canceled => self.local_transactions.mark_canceled(
-					PendingTransaction::new(transaction.transaction, transaction.condition)
-				),
-			}
-		}
-
-		// Remove from future
-		let order = self.future.drop(&sender, &nonce);
-		if order.is_some() {
-			self.update_future(&sender, current_nonce);
-			// And now lets check if there is some chain of transactions in future
-			// that should be placed in current
-			self.move_matching_future_to_current(sender, current_nonce, current_nonce);
-			assert_eq!(self.future.by_priority.len() + self.current.by_priority.len(), self.by_hash.len());
-			return;
-		}
-
-		// Remove from current
-		let order = self.current.drop(&sender, &nonce);
-		if order.is_some() {
-			// This will keep consistency in queue
-			// Moves all to future and then promotes a batch from current:
-			self.cull_internal(sender, current_nonce);
-			assert_eq!(self.future.by_priority.len() + self.current.by_priority.len(), self.by_hash.len());
-			return;
-		}
-	}
-
-	/// Marks all transactions from particular sender as local transactions
-	fn mark_transactions_local(&mut self, sender: &Address) {
-		fn mark_local<F: FnMut(H256)>(sender: &Address, set: &mut TransactionSet, mut mark: F) {
-			// Mark all transactions from this sender as local
-			let nonces_from_sender = set.by_address.row(sender)
-				.map(|row_map| {
-					row_map.iter().filter_map(|(nonce, order)| if order.origin.is_local() {
-						None
-					} else {
-						Some(*nonce)
-					}).collect::<Vec<U256>>()
-				})
-				.unwrap_or_else(Vec::new);
-
-			for k in nonces_from_sender {
-				let mut order = set.drop(sender, &k).expect("transaction known to be in self.current/self.future; qed");
-				order.origin = TransactionOrigin::Local;
-				mark(order.hash);
-				set.insert(*sender, k, order);
-			}
-		}
-
-		let local = &mut self.local_transactions;
-		mark_local(sender, &mut self.current, |hash| local.mark_pending(hash));
-		mark_local(sender, &mut self.future, |hash| local.mark_future(hash));
-	}
-
-	/// Update height of all transactions in future transactions set.
-	fn update_future(&mut self, sender: &Address, current_nonce: U256) {
-		// We need to drain all transactions for current sender from future and reinsert them with updated height
-		let all_nonces_from_sender = match self.future.by_address.row(sender) {
-			Some(row_map) => row_map.keys().cloned().collect::<Vec<U256>>(),
-			None => vec![],
+			None => return,
+			Some(transaction) => *transaction.sender(),
 		};
-		for k in all_nonces_from_sender {
-			let order = self.future.drop(sender, &k).expect("iterating over a collection that has been retrieved above; qed");
-			if k >= current_nonce {
-				self.future.insert(*sender, k, order.update_height(k, current_nonce));
-			} else {
-				trace!(target: "txqueue", "Removing old transaction: {:?} (nonce: {} < {})", order.hash, k, current_nonce);
-				// Remove the transaction completely
-				self.by_hash.remove(&order.hash).expect("All transactions in `future` are also in `by_hash`");
-			}
-		}
-	}
 
-	/// Drop all transactions from given sender from `current`.
-	/// Either moves them to `future` or removes them from queue completely.
-	fn move_all_to_future(&mut self, sender: &Address, current_nonce: U256) {
-		let all_nonces_from_sender = match self.current.by_address.row(sender) {
-			Some(row_map) => row_map.keys().cloned().collect::<Vec<U256>>(),
-			None => vec![],
-		};
+		trace!(target: "txqueue", "Removing invalid transaction: {:?}", transaction_hash);
+		let is_invalid = reason != RemovalReason::Canceled;
+		self.pool.remove(transaction_hash, is_invalid);
 
-		for k in all_nonces_from_sender {
-			// Goes to future or is removed
-			let order = self.current.drop(sender, &k).expect("iterating over a collection that has been retrieved above;
-															 qed");
-			if k >= current_nonce {
-				let order = order.update_height(k, current_nonce);
-				if order.origin.is_local() {
-					self.local_transactions.mark_future(order.hash);
-				}
-				if let Some(old) = self.future.insert(*sender, k, order.clone()) {
-					Self::replace_orders(*sender, k, old, order, &mut self.future, &mut self.by_hash, &mut self.local_transactions);
-				}
-			} else {
-				trace!(target: "txqueue", "Removing old transaction: {:?} (nonce: {} < {})", order.hash, k, current_nonce);
-				let tx = self.by_hash.remove(&order.hash).expect("All transactions in `future` are also in `by_hash`");
-				if tx.origin.is_local() {
-					self.local_transactions.mark_mined(tx.transaction);
-				}
-			}
-		}
-		self.future.enforce_limit(&mut self.by_hash, &mut self.local_transactions);
+		// This will keep consistency in queue
+		self.cull(sender, fetch_nonce(&sender));
 	}
 
 	/// Returns top transactions from the queue ordered by priority.
 	pub fn top_transactions(&self) -> Vec<SignedTransaction> {
 		self.top_transactions_at(BlockNumber::max_value(), u64::max_value(), None)
-
-	}
-
-	fn filter_pending_transaction<F>(&self, best_block: BlockNumber, best_timestamp: u64, nonce_cap: Option<U256>, mut f: F)
-		where F: FnMut(&VerifiedTransaction) {
-
-		let mut delayed = HashSet::new();
-		for t in self.current.by_priority.iter() {
-			let tx = self.by_hash.get(&t.hash).expect("All transactions in `current` and `future` are always included in `by_hash`");
-			let sender = tx.sender();
-			if delayed.contains(&sender) {
-				continue;
-			}
-			if let Some(max_nonce) = nonce_cap {
-				if tx.nonce() >= max_nonce {
-					continue;
-				}
-			}
-			let delay = match tx.condition {
-				Some(transaction::Condition::Number(n)) => n > best_block,
-				Some(transaction::Condition::Timestamp(t)) => t > best_timestamp,
-				None => false,
-			};
-			if delay {
-				delayed.insert(sender);
-				continue;
-			}
-			f(&tx);
-		}
 	}
 
 	/// Returns top transactions from the queue ordered by priority.
 	pub fn top_transactions_at(&self, best_block: BlockNumber, best_timestamp: u64, nonce_cap: Option<U256>) -> Vec<SignedTransaction> {
-		let mut r = Vec::new();
-		self.filter_pending_transaction(best_block, best_timestamp, nonce_cap, |tx| r.push(tx.transaction.clone()));
-		r
+		let ready = (self.nonce_ready(nonce_cap), ready::Condition::new(best_block, best_timestamp));
+		self.pool.pending(ready)
+			.map(|tx| tx.signed().clone())
+			.collect()
 	}
 
 	/// Return all ready transactions.
 	pub fn pending_transactions(&self, best_block: BlockNumber, best_timestamp: u64) -> Vec<PendingTransaction> {
-		let mut r = Vec::new();
-		self.filter_pending_transaction(best_block, best_timestamp, None, |tx| r.push(PendingTransaction::new(tx.transaction.clone(), tx.condition.clone())));
-		r
+		let ready = (self.nonce_ready(None), ready::Condition::new(best_block, best_timestamp));
+		self.pool.pending(ready)
+			.map(|tx| tx.pending())
+			.collect()
 	}
 
-	/// Return all future transactions.
+	/// Return all future transactions ordered by priority.
 	pub fn future_transactions(&self) -> Vec<PendingTransaction> {
-		self.future.by_priority
-			.iter()
-			.map(|t| self.by_hash.get(&t.hash).expect("All transactions in `current` and `future` are always included in `by_hash`"))
-			.map(|t| PendingTransaction { transaction: t.transaction.clone(), condition: t.condition.clone() })
+		self.future()
+			.into_iter()
+			.map(|tx| tx.pending())
 			.collect()
 	}
 
 	/// Returns local transactions (some of them might not be part of the queue anymore).
 	pub fn local_transactions(&self) -> &LinkedHashMap<H256, LocalTransactionStatus> {
-		self.local_transactions.all_transactions()
+		self.pool.listener().local_transactions().all_transactions()
 	}
 
 	/// Returns hashes of all transactions from current, ordered by priority.
 	pub fn pending_hashes(&self) -> Vec<H256> {
-		self.current.by_priority
-			.iter()
-			.map(|t| t.hash)
+		self.pool.pending(self.nonce_ready(None))
+			.map(|tx| *tx.hash())
 			.collect()
 	}
 
 	/// Returns true if there is at least one local transaction pending
 	pub fn has_local_pending_transactions(&self) -> bool {
-		self.current.by_priority.iter().any(|tx| tx.origin == TransactionOrigin::Local)
+		self.pool.pending(self.nonce_ready(None))
+			.any(|tx| self.is_local(&tx))
 	}
 
 	/// Finds transaction in the queue by hash (if any)
 	pub fn find(&self, hash: &H256) -> Option<PendingTransaction> {
-		self.by_hash.get(hash).map(|tx| PendingTransaction { transaction: tx.transaction.clone(), condition: tx.condition.clone() })
+		self.pool.find(hash).map(|tx| tx.pending())
 	}
 
 	/// Removes all elements (in any state) from the queue
 	pub fn clear(&mut self) {
-		self.current.clear();
-		self.future.clear();
-		self.by_hash.clear();
-		self.last_nonces.clear();
+		self.pool.clear();
+		self.state_nonces.clear();
 	}
 
 	/// Returns highest transaction nonce for given address.
 	pub fn last_nonce(&self, address: &Address) -> Option<U256> {
-		self.last_nonces.get(address).cloned()
+		self.pool.pending_from_sender(self.nonce_ready(None), address)
+			.last()
+			.map(|tx| tx.signed().nonce)
 	}
 
-	/// Checks if there are any transactions in `future` that should actually be promoted to `current`
-	/// (because nonce matches).
-	fn move_matching_future_to_current(&mut self, address: Address, mut current_nonce: U256, first_nonce: U256) {
-		let mut update_last_nonce_to = None;
-		{
-			let by_nonce = self.future.by_address.row_mut(&address);
-			if by_nonce.is_none() {
-				return;
-			}
-			let by_nonce = by_nonce.expect("None is tested in early-exit condition above; qed");
-			while let Some(order) = by_nonce.remove(&current_nonce) {
-				// remove also from priority and gas_price
-				self.future.by_priority.remove(&order);
-				self.future.by_gas_price.remove(&order.gas_price, &order.hash);
-				// Put to current
-				let order = order.update_height(current_nonce, first_nonce);
-				if order.origin.is_local() {
-					self.local_transactions.mark_pending(order.hash);
-				}
-				if let Some(old) = self.current.insert(address, current_nonce, order.clone()) {
-					Self::replace_orders(address, current_nonce, old, order, &mut self.current, &mut self.by_hash, &mut self.local_transactions);
-				}
-				update_last_nonce_to = Some(current_nonce);
-				current_nonce = current_nonce + U256::one();
-			}
-		}
-		self.future.by_address.clear_if_empty(&address);
-		if let Some(x) = update_last_nonce_to {
-			// Update last inserted nonce
-			self.last_nonces.insert(address, x);
-		}
+	/// Returns all future transactions ordered by priority.
+	fn future(&self) -> Vec<Arc<VerifiedTransaction>> {
+		let current = self.pending_hashes().into_iter().collect::<HashSet<_>>();
+		self.by_priority()
+			.into_iter()
+			.filter(|tx| !current.contains(tx.hash()))
+			.collect()
 	}
 
-	/// Adds VerifiedTransaction to this queue.
-	///
-	/// Determines if it should be placed in current or future. When transaction is
-	/// imported to `current` also checks if there are any `future` transactions that should be promoted because of
-	/// this.
-	///
-	/// It ignores transactions that has already been imported (same `hash`) and replaces the transaction
-	/// iff `(address, nonce)` is the same but `gas_price` is higher.
-	///
-	/// Returns `true` when transaction was imported successfuly
-	fn import_tx(&mut self, tx: VerifiedTransaction, state_nonce: U256) -> Result<transaction::ImportResult,
-	transaction::Error> {
+	/// Returns all transactions ordered by priority.
+	fn by_priority(&self) -> Vec<Arc<VerifiedTransaction>> {
+		// Treating everything as ready yields all transactions in the order of their scores.
+		self.pool.pending(|_: &VerifiedTransaction| txpool::Readiness::Ready).collect()
+	}
 
-		if self.by_hash.get(&tx.hash()).is_some() {
-			// Transaction is already imported.
-			trace!(target: "txqueue", "Dropping already imported transaction: {:?}", tx.hash());
-			return Err(transaction::Error::AlreadyImported);
+	/// Returns nonce-based readiness checker using last known state nonces.
+	fn nonce_ready(&self, nonce_cap: Option<U256>) -> ready::State {
+		ready::State::new(&self.state_nonces, nonce_cap)
+	}
+
+	/// Returns true if the transaction was submitted locally or belongs to a sender with local transactions.
+	fn is_local(&self, tx: &VerifiedTransaction) -> bool {
+		tx.origin().is_local() || self.pool.listener().local_transactions().contains(tx.hash())
+	}
+
+	/// Updates state nonce of given sender and removes transactions that are stalled.
+	fn update_state_nonce(&mut self, sender: Address, state_nonce: U256) {
+		self.state_nonces.insert(sender, state_nonce);
+		let removed = self.pool.cull(Some(&[sender]), ready::State::new(&self.state_nonces, None));
+		if removed > 0 {
+			trace!(target: "txqueue", "Removed {} old transactions of {:?} (nonce < {})", removed, sender, state_nonce);
 		}
+	}
 
-		let min_gas_price = (self.minimal_gas_price, self.strategy);
-		let address = tx.sender();
-		let nonce = tx.nonce();
-		let hash = tx.hash();
+	/// Returns number of local transactions currently in the queue.
+	fn local_count(&self) -> usize {
+		self.pool.listener().local_transactions().all_transactions()
+			.values()
+			.filter(|status| **status == LocalTransactionStatus::Pending || **status == LocalTransactionStatus::Future)
+			.count()
+	}
 
-		// The transaction might be old, let's check that.
-		// This has to be the first test, otherwise calculating
-		// nonce height would result in overflow.
-		if nonce < state_nonce {
-			// Droping transaction
-			trace!(target: "txqueue", "Dropping old transaction: {:?} (nonce: {} < {})", tx.hash(), nonce, state_nonce);
-			return Err(transaction::Error::Old);
+	/// Returns maximal number of transactions that can be occupied by non-local transactions.
+	fn external_limit(&self) -> usize {
+		let reserved = self.limit.saturating_mul(self.local_share as usize) / 100;
+		self.limit - reserved
+	}
+
+	/// Returns total gas of transactions that can be dropped because of the limits.
+	fn external_gas(&self) -> U256 {
+		self.pool.unordered_transactions()
+			.filter(|tx| self.is_evictable(tx))
+			.fold(U256::zero(), |gas, tx| gas.saturating_add(tx.signed().gas))
+	}
+
+	/// Removes external transactions from given sender above the per-sender limit, highest nonces first.
+	///
+	/// Own and retracted transactions are allowed to go above the limit.
+	fn enforce_sender_limit(&mut self, sender: &Address) {
+		// Counts all transactions from the sender, so only look closer if it's above the limit.
+		if self.pool.listener().sender_count(sender) <= self.max_per_sender {
+			return;
 		}
 
-		// Update nonces of transactions in future (remove old transactions)
-		self.update_future(&address, state_nonce);
-		// State nonce could be updated. Maybe there are some more items waiting in future?
-		self.move_matching_future_to_current(address, state_nonce, state_nonce);
-		// Check the next expected nonce (might be updated by move above)
-		let next_nonce = self.last_nonces
-			.get(&address)
-			.cloned()
-			.map_or(state_nonce, |n| n + U256::one());
-
-		if tx.origin.is_local() {
-			self.mark_transactions_local(&address);
+		let mut transactions = self.pool.unordered_transactions()
+			.filter(|tx| tx.sender() == sender && self.is_evictable(tx))
+			.map(|tx| (tx.signed().nonce, *tx.hash()))
+			.collect::<Vec<_>>();
+		if transactions.len() <= self.max_per_sender {
+			return;
 		}
 
-		// Future transaction
-		if nonce > next_nonce {
-			// We have a gap - put to future.
-			// Insert transaction (or replace old one with lower gas price)
-			check_too_cheap(
-				Self::replace_transaction(tx, state_nonce, min_gas_price, &mut self.future, &mut self.by_hash, &mut self.local_transactions)
-			)?;
-			// Enforce limit in Future
-			let removed = self.future.enforce_limit(&mut self.by_hash, &mut self.local_transactions);
-			// Return an error if this transaction was not imported because of limit.
-			check_if_removed(&address, &nonce, removed)?;
+		transactions.sort();
+		for (_, hash) in transactions.drain(self.max_per_sender..) {
+			trace!(target: "txqueue", "Dropped transaction above the limit of {:?}: {:?}", sender, hash);
+			self.pool.remove(&hash, false);
+		}
+	}
 
-			debug!(target: "txqueue", "Importing transaction to future: {:?}", hash);
-			debug!(target: "txqueue", "status: {:?}", self.status());
-			return Ok(transaction::ImportResult::Future);
+	/// Removes external transactions (according to the eviction policy) while the queue is above any of its limits.
+	/// Future transactions above the future limit are dropped first, starting with the lowest priority.
+	///
+	/// Own and retracted transactions are allowed to go above the limits.
+	///
+	/// NOTE: Future transactions are collected once, transactions that become future
+	/// because of a nonce gap introduced here are accounted for by the next enforcement.
+	fn enforce_limits(&mut self) {
+		// The pool counters include own transactions and can only overestimate
+		// the external ones, so there is nothing to do if they fit within all limits.
+		let status = self.pool.light_status();
+		let count_limit = cmp::min(cmp::min(self.limit, self.external_limit()), self.future_limit);
+		if status.transaction_count <= count_limit
+			&& status.mem_usage <= self.memory_limit
+			&& self.pool.listener().total_gas() <= self.total_gas_limit {
+			return;
 		}
 
-		// We might have filled a gap - move some more transactions from future
-		self.move_matching_future_to_current(address, nonce, state_nonce);
-		self.move_matching_future_to_current(address, nonce + U256::one(), state_nonce);
-
-		// Replace transaction if any
-		check_too_cheap(
-			Self::replace_transaction(tx, state_nonce, min_gas_price, &mut self.current, &mut self.by_hash, &mut self.local_transactions)
-		)?;
-		// Keep track of highest nonce stored in current
-		let new_max = self.last_nonces.get(&address).map_or(nonce, |n| cmp::max(nonce, *n));
-		self.last_nonces.insert(address, new_max);
-
-		// Also enforce the limit
-		let removed = self.current.enforce_limit(&mut self.by_hash, &mut self.local_transactions);
-		// If some transaction were removed because of limit we need to update last_nonces also.
-		self.update_last_nonces(&removed);
-		// Trigger error if the transaction we are importing was removed.
-		check_if_removed(&address, &nonce, removed)?;
-
-		debug!(target: "txqueue", "Imported transaction to current: {:?}", hash);
-		debug!(target: "txqueue", "status: {:?}", self.status());
-		Ok(transaction::ImportResult::Current)
-	}
-
-	/// Updates
-	fn update_last_nonces(&mut self, removed_min_nonces: &Option<HashMap<Address, U256>>) {
-		if let Some(ref min_nonces) = *removed_min_nonces {
-			for (sender, nonce) in min_nonces.iter() {
-				if *nonce == U256::zero() {
-					self.last_nonces.remove(sender);
-				} else {
-					self.last_nonces.insert(*sender, *nonce - U256::one());
-				}
+		// Own transactions are never dropped below, so their number doesn't change.
+		let local_count = self.local_count();
+		let mut future = self.future();
+		let mut external_gas = self.external_gas();
+
+		loop {
+			let status = self.pool.light_status();
+			let external_count = status.transaction_count.saturating_sub(local_count);
+			let over_future_limit = future.len() > self.future_limit;
+			let over_limit = status.transaction_count > self.limit
+				|| external_count > self.external_limit()
+				|| status.mem_usage > self.memory_limit
+				|| external_gas > self.total_gas_limit;
+			if !over_limit && !over_future_limit {
+				return;
+			}
+
+			let worst_future = match over_future_limit {
+				true => self.worst_evictable(&future),
+				false => None,
+			};
+			let to_drop = match worst_future.or_else(|| if over_limit { self.eviction_candidate(&future) } else { None }) {
+				Some(hash) => hash,
+				None => return,
+			};
+
+			trace!(target: "txqueue", "Dropped out of limit transaction: {:?}", to_drop);
+			if let Some(tx) = self.pool.remove(&to_drop, false) {
+				external_gas = external_gas.saturating_sub(tx.signed().gas);
 			}
+			future.retain(|tx| *tx.hash() != to_drop);
 		}
 	}
 
-	/// Replaces transaction in given set (could be `future` or `current`).
-	///
-	/// If there is already transaction with same `(sender, nonce)` it will be replaced iff `gas_price` is higher.
-	/// One of the transactions is dropped from set and also removed from queue entirely (from `by_hash`).
-	///
-	/// Returns `true` if transaction actually got to the queue (`false` if there was already a transaction with higher
-	/// gas_price)
-	fn replace_transaction(
-		tx: VerifiedTransaction,
-		base_nonce: U256,
-		min_gas_price: (U256, PrioritizationStrategy),
-		set: &mut TransactionSet,
-		by_hash: &mut HashMap<H256, VerifiedTransaction>,
-		local: &mut LocalTransactionsList,
-	) -> bool {
-		let order = TransactionOrder::for_transaction(&tx, base_nonce, min_gas_price.0, min_gas_price.1);
-		let hash = tx.hash();
-		let address = tx.sender();
-		let nonce = tx.nonce();
-
-		let old_hash = by_hash.insert(hash, tx);
-		assert!(old_hash.is_none(), "Each hash has to be inserted exactly once.");
-
-		trace!(target: "txqueue", "Inserting: {:?}", order);
-
-		if let Some(old) = set.insert(address, nonce, order.clone()) {
-			Self::replace_orders(address, nonce, old, order, set, by_hash, local)
-		} else {
-			true
+	/// Returns the lowest priority transaction from given ones (ordered by priority) that can be dropped.
+	fn worst_evictable(&self, transactions: &[Arc<VerifiedTransaction>]) -> Option<H256> {
+		transactions.iter()
+			.rev()
+			.find(|tx| self.is_evictable(tx))
+			.map(|tx| *tx.hash())
+	}
+
+	/// Returns true if the transaction can be dropped because of the limits.
+	fn is_evictable(&self, tx: &VerifiedTransaction) -> bool {
+		tx.origin() == TransactionOrigin::External && !self.is_local(tx)
+	}
+
+	/// Selects a transaction that should be dropped first according to the eviction policy.
+	/// `future` are the future transactions ordered by priority.
+	fn eviction_candidate(&self, future: &[Arc<VerifiedTransaction>]) -> Option<H256> {
+		let lowest_priority = || self.pool.worst_transaction()
+			.and_then(|tx| match self.is_evictable(&tx) {
+				true => Some(*tx.hash()),
+				// Own and retracted transactions can be the worst ones, look for the worst evictable instead.
+				false => self.worst_evictable(&self.by_priority()),
+			});
+
+		match self.eviction_policy {
+			EvictionPolicy::LowestPriority => lowest_priority(),
+			EvictionPolicy::FutureFirst => self.worst_evictable(future).or_else(lowest_priority),
+			// NOTE: dropping the oldest transaction may introduce a nonce gap,
+			// subsequent transactions from that sender become future.
+			EvictionPolicy::Oldest => self.pool.unordered_transactions()
+				.filter(|tx| self.is_evictable(tx))
+				.min_by_key(|tx| tx.insertion_id())
+				.map(|tx| *tx.hash()),
 		}
 	}
 
-	fn replace_orders(
-		address: Address,
-		nonce: U256,
-		old: TransactionOrder,
-		order: TransactionOrder,
-		set: &mut TransactionSet,
-		by_hash: &mut HashMap<H256, VerifiedTransaction>,
-		local: &mut LocalTransactionsList,
-	) -> bool {
-		// There was already transaction in queue. Let's check which one should stay
-		let old_hash = old.hash;
-		let new_hash = order.hash;
-
-		let old_gas_price = old.gas_price;
-		let new_gas_price = order.gas_price;
-		let min_required_gas_price = old_gas_price + (old_gas_price >> GAS_PRICE_BUMP_SHIFT);
-
-		if min_required_gas_price > new_gas_price {
-			trace!(target: "txqueue", "Didn't insert transaction because gas price was too low: {:?} ({:?} stays in the queue)", order.hash, old.hash);
-			// Put back old transaction since it has greater priority (higher gas_price)
-			set.insert(address, nonce, old);
-			// and remove new one
-			let order = by_hash.remove(&order.hash).expect("The hash has been just inserted and no other line is altering `by_hash`.");
-			if order.origin.is_local() {
-				local.mark_replaced(order.transaction, old_gas_price, old_hash);
-			}
-			false
-		} else {
-			trace!(target: "txqueue", "Replaced transaction: {:?} with transaction with higher gas price: {:?}", old.hash, order.hash);
-			// Make sure we remove old transaction entirely
-			let old = by_hash.remove(&old.hash).expect("The hash is coming from `future` so it has to be in `by_hash`.");
-			if old.origin.is_local() {
-				local.mark_replaced(old.transaction, new_gas_price, new_hash);
+	/// Marks all transactions from particular sender as local transactions
+	fn mark_transactions_local(&mut self, sender: &Address) {
+		let current = self.pool.pending_from_sender(self.nonce_ready(None), sender)
+			.map(|tx| *tx.hash())
+			.collect::<HashSet<_>>();
+		let all = self.pool.unordered_transactions()
+			.filter(|tx| tx.sender() == sender)
+			.map(|tx| *tx.hash())
+			.collect::<Vec<_>>();
+
+		let local = self.pool.listener_mut().local_transactions_mut();
+		for hash in all {
+			if current.contains(&hash) {
+				local.mark_pending(hash);
+			} else {
+				local.mark_future(hash);
 			}
-			true
 		}
 	}
-}
 
-fn check_too_cheap(is_in: bool) -> Result<(), transaction::Error> {
-	if is_in {
-		Ok(())
-	} else {
-		Err(transaction::Error::TooCheapToReplace)
+	/// Marks own transactions that are no longer in the queue as replaced by given transaction
+	/// with the same sender and nonce. Replacing queued ones is recorded by the pool listener.
+	fn mark_replaced_local(&mut self, tx: &SignedTransaction) {
+		let sender = tx.sender();
+		let replaced = self.pool.listener().local_transactions().all_transactions()
+			.values()
+			.filter_map(|status| match *status {
+				LocalTransactionStatus::Dropped(ref old) |
+				LocalTransactionStatus::Rejected(ref old, _) |
+				LocalTransactionStatus::Invalid(ref old) => Some(old.clone()),
+				LocalTransactionStatus::Canceled(ref old) => Some(old.transaction.clone()),
+				_ => None,
+			})
+			.filter(|old| old.hash() != tx.hash() && old.sender() == sender && old.nonce == tx.nonce)
+			.collect::<Vec<_>>();
+
+		let local = self.pool.listener_mut().local_transactions_mut();
+		for old in replaced {
+			local.mark_replaced(old, tx.gas_price, tx.hash());
+		}
 	}
-}
 
-fn check_if_removed(sender: &Address, nonce: &U256, dropped: Option<HashMap<Address, U256>>) -> Result<(),
-   transaction::Error> {
-	match dropped {
-		Some(ref dropped) => match dropped.get(sender) {
-			Some(min) if nonce >= min => {
-				Err(transaction::Error::LimitReached)
-			},
-			_ => Ok(()),
-		},
-		_ => Ok(()),
+	/// Updates `Pending` / `Future` statuses of local transactions from given sender.
+	fn update_local_statuses(&mut self, sender: &Address) {
+		let local_hashes = {
+			let pool = &self.pool;
+			pool.listener().local_transactions().all_transactions()
+				.iter()
+				.filter(|&(_, status)| *status == LocalTransactionStatus::Pending || *status == LocalTransactionStatus::Future)
+				.filter(|&(hash, _)| pool.find(hash).map_or(false, |tx| tx.sender() == sender))
+				.map(|(hash, status)| (*hash, status.clone()))
+				.collect::<Vec<_>>()
+		};
+
+		if local_hashes.is_empty() {
+			return;
+		}
+
+		let current = self.pool.pending_from_sender(self.nonce_ready(None), sender)
+			.map(|tx| *tx.hash())
+			.collect::<HashSet<_>>();
+		let local = self.pool.listener_mut().local_transactions_mut();
+		for (hash, status) in local_hashes {
+			match (current.contains(&hash), status) {
+				(true, LocalTransactionStatus::Future) => local.mark_pending(hash),
+				(false, LocalTransactionStatus::Pending) => local.mark_future(hash),
+				_ => {},
+			}
+		}
 	}
 }
 
-
 #[cfg(test)]
 pub mod test {
 	use ethereum_types::{U256, Address};
 	use super::*;
-	use ethkey::{Random, Generator};
+	use ethkey::{Random, Generator, KeyPair};
 	use rustc_hex::FromHex;
 	use transaction::Transaction;
 
@@ -1592,10 +1154,6 @@ pub mod test {
 		assert_eq!(TransactionOrigin::External.cmp(&TransactionOrigin::RetractedBlock), Ordering::Greater);
 	}
 
-	fn transaction_order(tx: &VerifiedTransaction, nonce: U256) -> TransactionOrder {
-		TransactionOrder::for_transaction(tx, nonce, 0.into(), PrioritizationStrategy::GasPriceOnly)
-	}
-
 	#[test]
 	fn should_return_correct_nonces_when_dropped_because_of_limit() {
 		// given
@@ -1636,138 +1194,6 @@ pub mod test {
 		assert_eq!(txq.last_nonce(&sender), Some(tx2.nonce));
 	}
 
-	#[test]
-	fn should_create_transaction_set() {
-		// given
-		let mut local = LocalTransactionsList::default();
-		let mut set = TransactionSet {
-			by_priority: BTreeSet::new(),
-			by_address: Table::new(),
-			by_gas_price: Default::default(),
-			limit: 1,
-			total_gas_limit: !U256::zero(),
-			memory_limit: usize::max_value(),
-		};
-		let (tx1, tx2) = new_tx_pair_default(1.into(), 0.into());
-		let tx1 = VerifiedTransaction::new(tx1, TransactionOrigin::External, None, 0, 0);
-		let tx2 = VerifiedTransaction::new(tx2, TransactionOrigin::External, None, 0, 1);
-		let mut by_hash = {
-			let mut x = HashMap::new();
-			let tx1 = VerifiedTransaction::new(tx1.transaction.clone(), TransactionOrigin::External, None, 0, 0);
-			let tx2 = VerifiedTransaction::new(tx2.transaction.clone(), TransactionOrigin::External, None, 0, 1);
-			x.insert(tx1.hash(), tx1);
-			x.insert(tx2.hash(), tx2);
-			x
-		};
-		// Insert both transactions
-		let order1 = transaction_order(&tx1, U256::zero());
-		set.insert(tx1.sender(), tx1.nonce(), order1.clone());
-		let order2 = transaction_order(&tx2, U256::zero());
-		set.insert(tx2.sender(), tx2.nonce(), order2.clone());
-		assert_eq!(set.by_priority.len(), 2);
-		assert_eq!(set.by_address.len(), 2);
-
-		// when
-		set.enforce_limit(&mut by_hash, &mut local);
-
-		// then
-		assert_eq!(by_hash.len(), 1);
-		assert_eq!(set.by_priority.len(), 1);
-		assert_eq!(set.by_address.len(), 1);
-		assert_eq!(set.by_priority.iter().next().unwrap().clone(), order1);
-		set.clear();
-		assert_eq!(set.by_priority.len(), 0);
-		assert_eq!(set.by_address.len(), 0);
-	}
-
-	#[test]
-	fn should_replace_transaction_in_set() {
-		let mut set = TransactionSet {
-			by_priority: BTreeSet::new(),
-			by_address: Table::new(),
-			by_gas_price: Default::default(),
-			limit: 1,
-			total_gas_limit: !U256::zero(),
-			memory_limit: 0,
-		};
-		// Create two transactions with same nonce
-		// (same hash)
-		let (tx1, tx2) = new_tx_pair_default(0.into(), 0.into());
-		let tx1 = VerifiedTransaction::new(tx1, TransactionOrigin::External, None, 0, 0);
-		let tx2 = VerifiedTransaction::new(tx2, TransactionOrigin::External, None, 0, 1);
-		let by_hash = {
-			let mut x = HashMap::new();
-			let tx1 = VerifiedTransaction::new(tx1.transaction.clone(), TransactionOrigin::External, None, 0, 0);
-			let tx2 = VerifiedTransaction::new(tx2.transaction.clone(), TransactionOrigin::External, None, 0, 1);
-			x.insert(tx1.hash(), tx1);
-			x.insert(tx2.hash(), tx2);
-			x
-		};
-		// Insert both transactions
-		let order1 = transaction_order(&tx1, U256::zero());
-		set.insert(tx1.sender(), tx1.nonce(), order1.clone());
-		assert_eq!(set.by_priority.len(), 1);
-		assert_eq!(set.by_address.len(), 1);
-		assert_eq!(set.by_gas_price.len(), 1);
-		assert_eq!(*set.by_gas_price.iter().next().unwrap().0, 1.into());
-		assert_eq!(set.by_gas_price.iter().next().unwrap().1.len(), 1);
-		// Two different orders (imagine nonce changed in the meantime)
-		let order2 = transaction_order(&tx2, U256::one());
-		set.insert(tx2.sender(), tx2.nonce(), order2.clone());
-		assert_eq!(set.by_priority.len(), 1);
-		assert_eq!(set.by_address.len(), 1);
-		assert_eq!(set.by_gas_price.len(), 1);
-		assert_eq!(*set.by_gas_price.iter().next().unwrap().0, 1.into());
-		assert_eq!(set.by_gas_price.iter().next().unwrap().1.len(), 1);
-
-		// then
-		assert_eq!(by_hash.len(), 1);
-		assert_eq!(set.by_priority.len(), 1);
-		assert_eq!(set.by_address.len(), 1);
-		assert_eq!(set.by_gas_price.len(), 1);
-		assert_eq!(*set.by_gas_price.iter().next().unwrap().0, 1.into());
-		assert_eq!(set.by_gas_price.iter().next().unwrap().1.len(), 1);
-		assert_eq!(set.by_priority.iter().next().unwrap().clone(), order2);
-	}
-
-	#[test]
-	fn should_not_insert_same_transaction_twice_into_set() {
-		let mut set = TransactionSet {
-			by_priority: BTreeSet::new(),
-			by_address: Table::new(),
-			by_gas_price: Default::default(),
-			limit: 2,
-			total_gas_limit: !U256::zero(),
-			memory_limit: 0,
-		};
-		let tx = new_tx_default();
-		let tx1 = VerifiedTransaction::new(tx.clone(), TransactionOrigin::External, None, 0, 0);
-		let order1 = TransactionOrder::for_transaction(&tx1, 0.into(), 1.into(), PrioritizationStrategy::GasPriceOnly);
-		assert!(set.insert(tx1.sender(), tx1.nonce(), order1).is_none());
-		let tx2 = VerifiedTransaction::new(tx, TransactionOrigin::External, None, 0, 1);
-		let order2 = TransactionOrder::for_transaction(&tx2, 0.into(), 1.into(), PrioritizationStrategy::GasPriceOnly);
-		assert!(set.insert(tx2.sender(), tx2.nonce(), order2).is_some());
-	}
-
-	#[test]
-	fn should_give_correct_gas_price_entry_limit() {
-		let mut set = TransactionSet {
-			by_priority: BTreeSet::new(),
-			by_address: Table::new(),
-			by_gas_price: Default::default(),
-			limit: 1,
-			total_gas_limit: !U256::zero(),
-			memory_limit: 0,
-		};
-
-		assert_eq!(set.gas_price_entry_limit(), 0.into());
-		let tx = new_tx_default();
-		let tx1 = VerifiedTransaction::new(tx.clone(), TransactionOrigin::External, None, 0, 0);
-		let order1 = TransactionOrder::for_transaction(&tx1, 0.into(), 1.into(), PrioritizationStrategy::GasPriceOnly);
-		assert!(set.insert(tx1.sender(), tx1.nonce(), order1.clone()).is_none());
-		assert_eq!(set.gas_price_entry_limit(), 2.into());
-	}
-
 	#[test]
 	fn should_handle_same_transaction_imported_twice_with_different_state_nonces() {
 		// given
@@ -1787,8 +1213,6 @@ pub mod test {
 		assert_eq!(res.unwrap(), transaction::ImportResult::Current);
 		assert_eq!(txq.status().pending, 1);
 		assert_eq!(txq.status().future, 0);
-		assert_eq!(txq.current.by_priority.len(), 1);
-		assert_eq!(txq.current.by_address.len(), 1);
 		let top = txq.top_transactions();
 		assert_eq!(top[0], tx2);
 	}
@@ -1812,8 +1236,6 @@ pub mod test {
 		assert_eq!(res.unwrap(), transaction::ImportResult::Current);
 		assert_eq!(txq.status().pending, 2);
 		assert_eq!(txq.status().future, 0);
-		assert_eq!(txq.current.by_priority.len(), 2);
-		assert_eq!(txq.current.by_address.len(), 2);
 		let top = txq.top_transactions();
 		assert_eq!(top[0], tx);
 		assert_eq!(top[1], tx2);
@@ -2301,9 +1723,7 @@ pub mod test {
 		let stats = txq.status();
 		assert_eq!(stats.pending, 3);
 		assert_eq!(stats.future, 0);
-		assert_eq!(txq.future.by_priority.len(), 0);
-		assert_eq!(txq.future.by_address.len(), 0);
-		assert_eq!(txq.future.by_gas_price.len(), 0);
+		assert_eq!(txq.future_transactions().len(), 0);
 	}
 
 	#[test]
@@ -2404,7 +1824,7 @@ pub mod test {
 			!U256::zero(),
 			!U256::zero(),
 		);
-		txq.current.set_limit(10);
+		txq.limit = 10;
 		let (tx1, tx2) = new_tx_pair_default(4.into(), 1.into());
 		let (tx3, tx4) = new_tx_pair_default(4.into(), 2.into());
 		txq.add(tx1.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
@@ -2450,14 +1870,19 @@ pub mod test {
 		);
 		let (tx1, tx2) = new_tx_pair_default(U256::from(1), U256::from(1));
 		let (tx3, tx4) = new_tx_pair_default(U256::from(1), U256::from(2));
-		let (tx5, _) = new_tx_pair_default(U256::from(1), U256::from(2));
+		let (tx5, tx6) = new_tx_pair_default(U256::from(1), U256::from(2));
+		let (tx7, _) = new_tx_pair_default(U256::from(1), U256::from(2));
 		txq.add(tx1.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
 		txq.add(tx2.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
-		// Not accepted because of limit
-		txq.add(tx5.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap_err();
 		txq.add(tx3.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
 		txq.add(tx4.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
 		assert_eq!(txq.status().pending, 4);
+		// Own transactions don't count towards the gas limit of external ones
+		txq.add(tx5.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx6.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		// Not accepted because of limit
+		txq.add(tx7.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap_err();
+		assert_eq!(txq.status().pending, 6);
 	}
 
 	#[test]
@@ -2585,6 +2010,29 @@ pub mod test {
 		assert_eq!(txq.top_transactions()[0].gas_price, U256::from(20));
 	}
 
+	#[test]
+	fn should_mark_own_transaction_not_in_the_queue_as_replaced() {
+		// given
+		let mut txq = TransactionQueue::default();
+		let keypair = Random.generate().unwrap();
+		let tx = new_unsigned_tx(123.into(), default_gas_val(), 10.into()).sign(keypair.secret(), None);
+		let tx2 = {
+			let mut tx2 = (**tx).clone();
+			tx2.gas_price = U256::from(5);
+			tx2.sign(keypair.secret(), None)
+		};
+		txq.add(tx.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+		txq.remove(&tx.hash(), &|_| default_nonce(), RemovalReason::Invalid);
+		assert_eq!(txq.local_transactions()[&tx.hash()], LocalTransactionStatus::Invalid(tx.clone()));
+
+		// when
+		txq.add(tx2.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(txq.local_transactions()[&tx.hash()], LocalTransactionStatus::Replaced(tx, 5.into(), tx2.hash()));
+		assert_eq!(txq.local_transactions()[&tx2.hash()], LocalTransactionStatus::Pending);
+	}
+
 	#[test]
 	fn should_replace_same_transaction_when_importing_to_futures() {
 		// given
@@ -2733,8 +2181,9 @@ pub mod test {
 		assert_eq!(txq.add(tx1.clone(), TransactionOrigin::External, 0, None, &default_tx_provider().with_account_nonce(prev_nonce)).unwrap(), transaction::ImportResult::Future);
 
 		// then
-		assert_eq!(txq.future.by_priority.len(), 1);
-		assert_eq!(txq.future.by_priority.iter().next().unwrap().hash, tx1.hash());
+		let future = txq.future_transactions();
+		assert_eq!(future.len(), 1);
+		assert_eq!(future[0].transaction.hash(), tx1.hash());
 	}
 
 	#[test]
@@ -2758,8 +2207,8 @@ pub mod test {
 		txq.add(tx1, TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
 		txq.add(tx2, TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
 		txq.add(tx3, TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
-		assert_eq!(txq.future.by_priority.len(), 0);
-		assert_eq!(txq.current.by_priority.len(), 3);
+		assert_eq!(txq.status().future, 0);
+		assert_eq!(txq.status().pending, 3);
 
 		// when
 		let res = txq.add(tx2_2, TransactionOrigin::Local, 0, None, &default_tx_provider());
@@ -2767,7 +2216,7 @@ pub mod test {
 		// then
 		assert_eq!(txq.last_nonce(&sender).unwrap(), 125.into());
 		assert_eq!(res.unwrap(), transaction::ImportResult::Current);
-		assert_eq!(txq.current.by_priority.len(), 3);
+		assert_eq!(txq.status().pending, 3);
 	}
 
 	#[test]
@@ -2941,4 +2390,160 @@ pub mod test {
 		// then
 		assert_eq!(txq.top_transactions_at(BlockNumber::max_value(), u64::max_value(), Some(127.into())).len(), 4);
 	}
+
+	#[test]
+	fn should_limit_transactions_per_sender() {
+		// given
+		let mut txq = TransactionQueue::default();
+		txq.set_max_per_sender(2);
+		let keypair = Random.generate().unwrap();
+		let tx1 = new_unsigned_tx(123.into(), default_gas_val(), 1.into()).sign(keypair.secret(), None);
+		let tx2 = new_unsigned_tx(124.into(), default_gas_val(), 1.into()).sign(keypair.secret(), None);
+		let tx3 = new_unsigned_tx(125.into(), default_gas_val(), 1.into()).sign(keypair.secret(), None);
+		txq.add(tx1, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx2, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		let res = txq.add(tx3, TransactionOrigin::External, 0, None, &default_tx_provider());
+
+		// then
+		assert_eq!(unwrap_tx_err(res), transaction::Error::LimitReached);
+		assert_eq!(txq.status().pending, 2);
+		assert_eq!(txq.max_per_sender(), 2);
+	}
+
+	#[test]
+	fn should_not_limit_own_and_retracted_transactions_per_sender() {
+		// given
+		let mut txq = TransactionQueue::default();
+		txq.set_max_per_sender(1);
+		let local = Random.generate().unwrap();
+		let retracted = Random.generate().unwrap();
+		let tx = |keypair: &KeyPair, nonce: u64| new_unsigned_tx(nonce.into(), default_gas_val(), 1.into()).sign(keypair.secret(), None);
+
+		// when
+		txq.add(tx(&local, 123), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx(&local, 124), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx(&local, 125), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx(&retracted, 123), TransactionOrigin::RetractedBlock, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx(&retracted, 124), TransactionOrigin::RetractedBlock, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx(&retracted, 125), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		let res = txq.add(tx(&retracted, 126), TransactionOrigin::External, 0, None, &default_tx_provider());
+
+		// then
+		assert_eq!(unwrap_tx_err(res), transaction::Error::LimitReached);
+		assert_eq!(txq.status().pending, 6);
+	}
+
+	#[test]
+	fn should_reserve_share_of_the_queue_for_local_transactions() {
+		// given
+		let mut txq = TransactionQueue::with_limits(
+			PrioritizationStrategy::GasPriceOnly,
+			4,
+			usize::max_value(),
+			!U256::zero(),
+			!U256::zero(),
+		);
+		txq.set_local_share(50);
+		txq.add(new_tx_default(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(new_tx_default(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		let res = txq.add(new_tx_default(), TransactionOrigin::External, 0, None, &default_tx_provider());
+		txq.add(new_tx_default(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+		txq.add(new_tx_default(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(unwrap_tx_err(res), transaction::Error::InsufficientGasPrice {
+			minimal: 2.into(),
+			got: 1.into(),
+		});
+		assert_eq!(txq.status().pending, 4);
+	}
+
+	#[test]
+	fn should_drop_future_transactions_first() {
+		// given
+		let mut txq = TransactionQueue::with_limits(
+			PrioritizationStrategy::GasPriceOnly,
+			2,
+			usize::max_value(),
+			!U256::zero(),
+			!U256::zero(),
+		);
+		txq.set_eviction_policy(EvictionPolicy::FutureFirst);
+		let tx1 = new_tx(123.into(), 1.into());
+		let tx2 = new_tx(125.into(), 10.into());
+		let tx3 = new_tx(123.into(), 2.into());
+		txq.add(tx1.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		assert_eq!(txq.add(tx2, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap(), transaction::ImportResult::Future);
+
+		// when
+		txq.add(tx3.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(txq.status().future, 0);
+		assert_eq!(txq.top_transactions(), vec![tx3, tx1]);
+	}
+
+	#[test]
+	fn should_drop_oldest_transactions_first() {
+		// given
+		let mut txq = TransactionQueue::with_limits(
+			PrioritizationStrategy::GasPriceOnly,
+			2,
+			usize::max_value(),
+			!U256::zero(),
+			!U256::zero(),
+		);
+		txq.set_eviction_policy(EvictionPolicy::Oldest);
+		let tx1 = new_tx(123.into(), 5.into());
+		let tx2 = new_tx(123.into(), 1.into());
+		let tx3 = new_tx(123.into(), 2.into());
+		txq.add(tx1, TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx2.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		txq.add(tx3.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(txq.top_transactions(), vec![tx3, tx2]);
+	}
+
+	#[test]
+	fn should_evict_external_transaction_when_the_worst_one_is_local() {
+		// given
+		let mut txq = TransactionQueue::with_limits(
+			PrioritizationStrategy::GasPriceOnly,
+			3,
+			usize::max_value(),
+			!U256::zero(),
+			!U256::zero(),
+		);
+		let txa = new_tx(default_nonce(), 1.into());
+		let (tx1, tx2) = new_tx_pair(default_nonce(), 5.into(), 1.into(), 0.into());
+		let tx3 = new_tx(default_nonce(), 10.into());
+		txq.add(txa.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx1.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.penalize(&tx1.hash());
+		// penalized sender becomes local, so its transactions are the worst ones but can't be evicted
+		txq.add(tx2.clone(), TransactionOrigin::Local, 0, None, &default_tx_provider()).unwrap();
+
+		// when
+		txq.add(tx3.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(txq.status().pending, 3);
+		assert!(txq.find(&txa.hash()).is_none());
+		assert!(txq.find(&tx3.hash()).is_some());
+	}
+
+	#[test]
+	fn should_parse_eviction_policy() {
+		assert_eq!("priority".parse(), Ok(EvictionPolicy::LowestPriority));
+		assert_eq!("future".parse(), Ok(EvictionPolicy::FutureFirst));
+		assert_eq!("oldest".parse(), Ok(EvictionPolicy::Oldest));
+		assert!("newest".parse::<EvictionPolicy>().is_err());
+	}
 }
@@ -26,10 +26,14 @@ use ethereum_types::{U256, H256, Address};
 use bytes::ToPretty;
 use rlp::PayloadInfo;
 use ethcore::service::ClientService;
-use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, BlockImportError, BlockChainClient, BlockId};
+use ethcore::client::{Mode, DatabaseCompactionProfile, DatabaseEngine, VMType, BlockImportError, BlockChainClient, BlockId, CallAnalytics};
+use ethcore::header::BlockNumber;
+use ethcore::trace::LocalizedTrace;
 use ethcore::error::ImportError;
 use ethcore::miner::Miner;
 use ethcore::verification::queue::VerifierSettings;
+use parity_rpc::v1::traces::LocalizedTrace as RpcLocalizedTrace;
+use serde_json;
 use cache::CacheConfig;
 use informant::{Informant, FullNodeInformantData, MillisecondDuration};
 use params::{SpecType, Pruning, Switch, tracing_switch_to_bool, fatdb_switch_to_bool};
@@ -68,6 +72,7 @@ pub enum BlockchainCmd {
 	Import(ImportBlockchain),
 	Export(ExportBlockchain),
 	ExportState(ExportState),
+	ExportTraces(ExportTraces),
 }
 
 #[derive(Debug, PartialEq)]
@@ -89,6 +94,7 @@ pub struct ImportBlockchain {
 	pub pruning_memory: usize,
 	pub compaction: DatabaseCompactionProfile,
 	pub wal: bool,
+	pub db_engine: DatabaseEngine,
 	pub tracing: Switch,
 	pub fat_db: Switch,
 	pub vm_type: VMType,
@@ -110,6 +116,7 @@ pub struct ExportBlockchain {
 	pub pruning_memory: usize,
 	pub compaction: DatabaseCompactionProfile,
 	pub wal: bool,
+	pub db_engine: DatabaseEngine,
 	pub fat_db: Switch,
 	pub tracing: Switch,
 	pub from_block: BlockId,
@@ -129,6 +136,7 @@ pub struct ExportState {
 	pub pruning_memory: usize,
 	pub compaction: DatabaseCompactionProfile,
 	pub wal: bool,
+	pub db_engine: DatabaseEngine,
 	pub fat_db: Switch,
 	pub tracing: Switch,
 	pub at: BlockId,
@@ -138,6 +146,24 @@ pub struct ExportState {
 	pub max_balance: Option<U256>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct ExportTraces {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub file_path: Option<String>,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub db_engine: DatabaseEngine,
+	pub fat_db: Switch,
+	pub tracing: Switch,
+	pub from_block: BlockId,
+	pub to_block: BlockId,
+}
+
 pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 	match cmd {
 		BlockchainCmd::Kill(kill_cmd) => kill_db(kill_cmd),
@@ -150,6 +176,7 @@ pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 		}
 		BlockchainCmd::Export(export_cmd) => execute_export(export_cmd),
 		BlockchainCmd::ExportState(export_cmd) => execute_export_state(export_cmd),
+		BlockchainCmd::ExportTraces(export_cmd) => execute_export_traces(export_cmd),
 	}
 }
 
@@ -185,7 +212,7 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 
 	// execute upgrades
 	let compaction = cmd.compaction.compaction_profile(db_dirs.db_root_path().as_path());
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, compaction)?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, compaction, &cmd.db_engine)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(false, false, false)?;
@@ -200,6 +227,7 @@ fn execute_import_light(cmd: ImportBlockchain) -> Result<(), String> {
 		db_cache_size: Some(cmd.cache_config.blockchain() as usize * 1024 * 1024),
 		db_compaction: compaction,
 		db_wal: cmd.wal,
+		db_engine: cmd.db_engine.clone(),
 		verify_full: true,
 		check_seal: cmd.check_seal,
 	};
@@ -337,7 +365,7 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 	let snapshot_path = db_dirs.snapshot_path();
 
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, cmd.compaction.compaction_profile(db_dirs.db_root_path().as_path()))?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, cmd.compaction.compaction_profile(db_dirs.db_root_path().as_path()), &cmd.db_engine)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(false, false, false)?;
@@ -351,6 +379,7 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 		fat_db,
 		cmd.compaction,
 		cmd.wal,
+		cmd.db_engine,
 		cmd.vm_type,
 		"".into(),
 		algorithm,
@@ -484,6 +513,7 @@ fn start_client(
 	fat_db: Switch,
 	compaction: DatabaseCompactionProfile,
 	wal: bool,
+	db_engine: DatabaseEngine,
 	cache_config: CacheConfig,
 	require_fat_db: bool,
 ) -> Result<ClientService, String> {
@@ -522,7 +552,7 @@ fn start_client(
 	let snapshot_path = db_dirs.snapshot_path();
 
 	// execute upgrades
-	execute_upgrades(&dirs.base, &db_dirs, algorithm, compaction.compaction_profile(db_dirs.db_root_path().as_path()))?;
+	execute_upgrades(&dirs.base, &db_dirs, algorithm, compaction.compaction_profile(db_dirs.db_root_path().as_path()), &db_engine)?;
 
 	// create dirs used by parity
 	dirs.create_dirs(false, false, false)?;
@@ -536,6 +566,7 @@ fn start_client(
 		fat_db,
 		compaction,
 		wal,
+		db_engine,
 		VMType::default(),
 		"".into(),
 		algorithm,
@@ -568,6 +599,7 @@ fn execute_export(cmd: ExportBlockchain) -> Result<(), String> {
 		cmd.fat_db,
 		cmd.compaction,
 		cmd.wal,
+		cmd.db_engine,
 		cmd.cache_config,
 		false,
 	)?;
@@ -613,6 +645,7 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 		cmd.fat_db,
 		cmd.compaction,
 		cmd.wal,
+		cmd.db_engine,
 		cmd.cache_config,
 		true
 	)?;
@@ -689,6 +722,85 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 	Ok(())
 }
 
+fn execute_export_traces(cmd: ExportTraces) -> Result<(), String> {
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		cmd.tracing,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.wal,
+		cmd.db_engine,
+		cmd.cache_config,
+		false,
+	)?;
+
+	let client = service.client();
+
+	let mut out: Box<io::Write> = match cmd.file_path {
+		Some(f) => Box::new(fs::File::create(&f).map_err(|_| format!("Cannot write to file given: {}", f))?),
+		None => Box::new(io::stdout()),
+	};
+
+	let from = client.block_number(cmd.from_block).ok_or("From block could not be found")?;
+	let to = client.block_number(cmd.to_block).ok_or("To block could not be found")?;
+
+	for i in from..(to + 1) {
+		if i % 10000 == 0 {
+			info!("#{}", i);
+		}
+
+		// blocks imported without tracing have no traces in the database.
+		let traces = match client.block_traces(BlockId::Number(i)) {
+			Some(traces) => traces,
+			None => replay_block_traces(&*client, i)?,
+		};
+
+		for trace in traces {
+			let trace = serde_json::to_string(&RpcLocalizedTrace::from(trace))
+				.map_err(|e| format!("Couldn't serialize trace. Cause: {}", e))?;
+			out.write_fmt(format_args!("{}\n", trace)).map_err(|e| format!("Couldn't write to stream. Cause: {}", e))?;
+		}
+	}
+
+	info!("Export completed.");
+	Ok(())
+}
+
+/// Replays the transactions of the given block to get their traces.
+/// Block reward traces are not available this way.
+fn replay_block_traces(client: &BlockChainClient, number: BlockNumber) -> Result<Vec<LocalizedTrace>, String> {
+	let id = BlockId::Number(number);
+	let block_hash = client.block_hash(id).ok_or("Error exporting incomplete chain")?;
+	let transaction_hashes = client.block_body(id).ok_or("Error exporting incomplete chain")?.transaction_hashes();
+	let analytics = CallAnalytics {
+		transaction_tracing: true,
+		vm_tracing: false,
+		state_diffing: false,
+	};
+
+	let executed = client.replay_block_transactions(id, analytics)
+		.map_err(|e| format!("Couldn't replay block #{}. Cause: {}", number, e))?;
+
+	Ok(executed.zip(transaction_hashes).enumerate()
+		.flat_map(|(transaction_number, (executed, transaction_hash))| {
+			executed.trace.into_iter().map(move |trace| LocalizedTrace {
+				action: trace.action,
+				result: trace.result,
+				subtraces: trace.subtraces,
+				trace_address: trace.trace_address.into_iter().collect(),
+				transaction_number: Some(transaction_number),
+				transaction_hash: Some(transaction_hash),
+				block_number: number,
+				block_hash: block_hash,
+			})
+		})
+		.collect())
+}
+
 pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
 	let genesis_hash = spec.genesis_header().hash();
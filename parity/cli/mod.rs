@@ -154,6 +154,23 @@ usage! {
 				"[FILE]",
 				"Path to the exported file",
 			}
+
+			CMD cmd_export_traces
+			{
+				"Export traces as newline-delimited JSON",
+
+				ARG arg_export_traces_from: (String) = "1",
+				"--from=[BLOCK]",
+				"Export from block BLOCK, which may be an index or hash.",
+
+				ARG arg_export_traces_to: (String) = "latest",
+				"--to=[BLOCK]",
+				"Export to (including) block BLOCK, which may be an index, hash or latest.",
+
+				ARG arg_export_traces_file: (Option<String>) = None,
+				"--file=[FILE]",
+				"Path to the exported file. Traces are written to the standard output if not given.",
+			}
 		}
 
 		CMD cmd_signer
@@ -368,6 +385,10 @@ usage! {
 			"--no-warp",
 			"Disable syncing from the snapshot over the network.",
 
+			ARG arg_sync_mode: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.sync_mode.clone(),
+			"--sync-mode=[MODE]",
+			"Specify how to sync with the network. MODE may be one of: warp - restore a snapshot from peers; fast - download blocks up to a recent block and fetch its state trie node by node; full - import and execute every block. Defaults to warp, or full with --no-warp.",
+
 			FLAG flag_no_discovery: (bool) = false, or |c: &Config| c.network.as_ref()?.discovery.map(|d| !d).clone(),
 			"--no-discovery",
 			"Disable new peer discovery.",
@@ -412,13 +433,21 @@ usage! {
 			"--max-pending-peers=[NUM]",
 			"Allow up to NUM pending connections.",
 
+			ARG arg_max_upload_rate: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.max_upload_rate.clone(),
+			"--max-upload-rate=[KBPS]",
+			"Limit the upload rate of all protocol traffic to KBPS kilobytes per second. Packets over the limit are delayed.",
+
+			ARG arg_snapshot_serve_rate: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.snapshot_serve_rate.clone(),
+			"--snapshot-serve-rate=[KBPS]",
+			"Limit the upload rate of snapshot data served to other peers to KBPS kilobytes per second.",
+
 			ARG arg_network_id: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.id.clone(),
 			"--network-id=[INDEX]",
 			"Override the network identifier from the chain we are on.",
 
 			ARG arg_bootnodes: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.bootnodes.as_ref().map(|vec| vec.join(",")),
 			"--bootnodes=[NODES]",
-			"Override the bootnodes from our chain. NODES should be comma-delimited enodes.",
+			"Override the bootnodes from our chain. NODES should be comma-delimited enodes or enr: node records.",
 
 			ARG arg_node_key: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.node_key.clone(),
 			"--node-key=[KEY]",
@@ -426,7 +455,7 @@ usage! {
 
 			ARG arg_reserved_peers: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.reserved_peers.clone(),
 			"--reserved-peers=[FILE]",
-			"Provide a file containing enodes, one per line. These nodes will always have a reserved slot on top of the normal maximum peers.",
+			"Provide a file containing enodes or enr: node records, one per line. These nodes will always have a reserved slot on top of the normal maximum peers.",
 
 		["API and console options – RPC"]
 			FLAG flag_no_jsonrpc: (bool) = false, or |c: &Config| c.rpc.as_ref()?.disable.clone(),
@@ -443,7 +472,7 @@ usage! {
 
 			ARG arg_jsonrpc_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,traces,rpc,shh,shh_pubsub", or |c: &Config| c.rpc.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-apis=[APIS]",
-			"Specify the APIs available through the JSONRPC interface. APIS is a comma-delimited list of API name. Possible name are all, safe, web3, eth, net, personal, parity, parity_set, traces, rpc, parity_accounts, pubsub, parity_pubsub, shh, shh_pubsub, signer, secretstore. You can also disable a specific API by putting '-' in the front: all,-personal.",
+			"Specify the APIs available through the JSONRPC interface. APIS is a comma-delimited list of API name. Possible name are all, safe, web3, eth, net, personal, parity, parity_set, traces, debug, rpc, parity_accounts, pubsub, parity_pubsub, shh, shh_pubsub, signer, secretstore. You can also disable a specific API by putting '-' in the front: all,-personal.",
 
 			ARG arg_jsonrpc_hosts: (String) = "none", or |c: &Config| c.rpc.as_ref()?.hosts.as_ref().map(|vec| vec.join(",")),
 			"--jsonrpc-hosts=[HOSTS]",
@@ -476,7 +505,7 @@ usage! {
 
 			ARG arg_ws_apis: (String) = "web3,eth,pubsub,net,parity,parity_pubsub,traces,rpc,shh,shh_pubsub", or |c: &Config| c.websockets.as_ref()?.apis.as_ref().map(|vec| vec.join(",")),
 			"--ws-apis=[APIS]",
-			"Specify the APIs available through the WebSockets interface. APIS is a comma-delimited list of API name. Possible name are web3, eth, pubsub, net, personal, parity, parity_set, traces, rpc, parity_accounts, pubsub, parity_pubsub, shh, shh_pubsub, signer, secretstore.",
+			"Specify the APIs available through the WebSockets interface. APIS is a comma-delimited list of API name. Possible name are web3, eth, pubsub, net, personal, parity, parity_set, traces, debug, rpc, parity_accounts, pubsub, parity_pubsub, shh, shh_pubsub, signer, secretstore.",
 
 			ARG arg_ws_origins: (String) = "chrome-extension://*,moz-extension://*", or |c: &Config| c.websockets.as_ref()?.origins.as_ref().map(|vec| vec.join(",")),
 			"--ws-origins=[URL]",
@@ -607,6 +636,10 @@ usage! {
 			"--no-persistent-txqueue",
 			"Don't save pending local transactions to disk to be restored whenever the node restarts.",
 
+			FLAG flag_no_persistent_pool: (bool) = false, or |c: &Config| c.parity.as_ref()?.no_persistent_pool,
+			"--no-persistent-pool",
+			"Don't save the rest of the transaction queue (transactions received from the network) to disk to be restored whenever the node restarts.",
+
 			FLAG flag_stratum: (bool) = false, or |c: &Config| Some(c.stratum.is_some()),
 			"--stratum",
 			"Run Stratum server for miner push notification.",
@@ -667,6 +700,18 @@ usage! {
 			"--tx-queue-strategy=[S]",
 			"Prioritization strategy used to order transactions in the queue. S may be: gas - Prioritize txs with low gas limit; gas_price - Prioritize txs with high gas price; gas_factor - Prioritize txs using gas price and gas limit ratio.",
 
+			ARG arg_tx_queue_per_sender: (Option<usize>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_per_sender.clone(),
+			"--tx-queue-per-sender=[LIMIT]",
+			"Maximum number of transactions per sender in the queue. By default it's 1% of the entire queue, but not less than 16.",
+
+			ARG arg_tx_queue_local_share: (u8) = 0u8, or |c: &Config| c.mining.as_ref()?.tx_queue_local_share.clone(),
+			"--tx-queue-local-share=[PERCENT]",
+			"Percentage of the queue reserved for local transactions. External transactions can't take that space, local transactions can still use the rest of the queue.",
+
+			ARG arg_tx_queue_eviction: (String) = "priority", or |c: &Config| c.mining.as_ref()?.tx_queue_eviction.clone(),
+			"--tx-queue-eviction=[POLICY]",
+			"Order in which external transactions are dropped when the queue is full. POLICY may be: priority - Drop txs with the lowest priority first; future - Drop txs that are not ready to be included first; oldest - Drop txs that entered the queue first.",
+
 			ARG arg_tx_queue_ban_count: (u16) = 1u16, or |c: &Config| c.mining.as_ref()?.tx_queue_ban_count.clone(),
 			"--tx-queue-ban-count=[C]",
 			"Number of times maximal time for execution (--tx-time-limit) can be exceeded before banning sender/recipient/code.",
@@ -794,6 +839,18 @@ usage! {
 			"--db-compaction=[TYPE]",
 			"Database compaction type. TYPE may be one of: ssd - suitable for SSDs and fast HDDs; hdd - suitable for slow HDDs; auto - determine automatically.",
 
+			ARG arg_db_engine: (String) = "rocksdb", or |c: &Config| c.footprint.as_ref()?.db_engine.clone(),
+			"--db-engine=[ENGINE]",
+			"Database backend. ENGINE may be one of: rocksdb - store everything on disk; memory - keep the whole database in memory, nothing is written to the data directory. The memory engine disables warp sync. Builds with the logdb feature also accept logdb - a log-structured store on disk written in pure Rust.",
+
+			ARG arg_db_memory_limit: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.db_memory_limit.clone(),
+			"--db-memory-limit=[MB]",
+			"Maximum size of the in-memory database. Writes that would exceed it are rejected and block import stops. Only used with --db-engine memory.",
+
+			ARG arg_db_memory_file: (Option<String>) = None, or |c: &Config| c.footprint.as_ref()?.db_memory_file.clone(),
+			"--db-memory-file=[FILE]",
+			"Load the in-memory database from FILE on start and write it back on shutdown. Only used with --db-engine memory.",
+
 			ARG arg_fat_db: (String) = "auto", or |c: &Config| c.footprint.as_ref()?.fat_db.clone(),
 			"--fat-db=[BOOL]",
 			"Build appropriate information to allow enumeration of all accounts and storage keys. Doubles the size of the state database. BOOL may be one of on, off or auto.",
@@ -1006,6 +1063,7 @@ struct Operating {
 	identity: Option<String>,
 	light: Option<bool>,
 	no_persistent_txqueue: Option<bool>,
+	no_persistent_pool: Option<bool>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1034,11 +1092,14 @@ struct Ui {
 #[serde(deny_unknown_fields)]
 struct Network {
 	warp: Option<bool>,
+	sync_mode: Option<String>,
 	port: Option<u16>,
 	min_peers: Option<u16>,
 	max_peers: Option<u16>,
 	snapshot_peers: Option<u16>,
 	max_pending_peers: Option<u16>,
+	max_upload_rate: Option<u64>,
+	snapshot_serve_rate: Option<u64>,
 	nat: Option<String>,
 	allow_ips: Option<String>,
 	id: Option<u64>,
@@ -1149,6 +1210,9 @@ struct Mining {
 	tx_queue_mem_limit: Option<u32>,
 	tx_queue_gas: Option<String>,
 	tx_queue_strategy: Option<String>,
+	tx_queue_per_sender: Option<usize>,
+	tx_queue_local_share: Option<u8>,
+	tx_queue_eviction: Option<String>,
 	tx_queue_ban_count: Option<u16>,
 	tx_queue_ban_time: Option<u16>,
 	remove_solved: Option<bool>,
@@ -1179,6 +1243,9 @@ struct Footprint {
 	cache_size_queue: Option<u32>,
 	cache_size_state: Option<u32>,
 	db_compaction: Option<String>,
+	db_engine: Option<String>,
+	db_memory_limit: Option<u32>,
+	db_memory_file: Option<String>,
 	fat_db: Option<String>,
 	scale_verifiers: Option<bool>,
 	num_verifiers: Option<usize>,
@@ -1270,6 +1337,11 @@ mod tests {
 
 		let args = Args::parse(&["parity", "export", "state", "--min-balance","123"]).unwrap();
 		assert_eq!(args.arg_export_state_min_balance, Some("123".to_string()));
+
+		let args = Args::parse(&["parity", "export", "traces", "--from", "10", "--file", "traces.json"]).unwrap();
+		assert_eq!(args.arg_export_traces_from, "10");
+		assert_eq!(args.arg_export_traces_to, "latest");
+		assert_eq!(args.arg_export_traces_file, Some("traces.json".to_string()));
 	}
 
 	#[test]
@@ -1398,6 +1470,7 @@ mod tests {
 			cmd_export: false,
 			cmd_export_blocks: false,
 			cmd_export_state: false,
+			cmd_export_traces: false,
 			cmd_signer: false,
 			cmd_signer_list: false,
 			cmd_signer_sign: false,
@@ -1418,6 +1491,7 @@ mod tests {
 			arg_export_blocks_format: None,
 			arg_export_state_file: None,
 			arg_export_state_format: None,
+			arg_export_traces_file: None,
 			arg_snapshot_file: None,
 			arg_restore_file: None,
 			arg_tools_hash_file: None,
@@ -1444,6 +1518,7 @@ mod tests {
 			arg_identity: "".into(),
 			flag_light: false,
 			flag_no_persistent_txqueue: false,
+			flag_no_persistent_pool: false,
 			flag_force_direct: false,
 
 			// -- Convenience Options
@@ -1469,11 +1544,14 @@ mod tests {
 
 			// -- Networking Options
 			flag_no_warp: false,
+			arg_sync_mode: None,
 			arg_port: 30303u16,
 			arg_min_peers: 25u16,
 			arg_max_peers: 50u16,
 			arg_max_pending_peers: 64u16,
 			arg_snapshot_peers: 0u16,
+			arg_max_upload_rate: None,
+			arg_snapshot_serve_rate: None,
 			arg_allow_ips: "all".into(),
 			arg_nat: "any".into(),
 			arg_network_id: Some(1),
@@ -1559,6 +1637,9 @@ mod tests {
 			arg_tx_queue_mem_limit: 2u32,
 			arg_tx_queue_gas: "off".into(),
 			arg_tx_queue_strategy: "gas_factor".into(),
+			arg_tx_queue_per_sender: None,
+			arg_tx_queue_local_share: 0u8,
+			arg_tx_queue_eviction: "priority".into(),
 			arg_tx_queue_ban_count: 1u16,
 			arg_tx_queue_ban_time: 180u16,
 			flag_remove_solved: false,
@@ -1583,6 +1664,9 @@ mod tests {
 			arg_cache_size: Some(128),
 			flag_fast_and_loose: false,
 			arg_db_compaction: "ssd".into(),
+			arg_db_engine: "rocksdb".into(),
+			arg_db_memory_limit: None,
+			arg_db_memory_file: None,
 			arg_fat_db: "auto".into(),
 			flag_scale_verifiers: true,
 			arg_num_verifiers: Some(6),
@@ -1595,6 +1679,8 @@ mod tests {
 			flag_export_state_no_storage: false,
 			arg_export_state_min_balance: None,
 			arg_export_state_max_balance: None,
+			arg_export_traces_from: "1".into(),
+			arg_export_traces_to: "latest".into(),
 
 			// -- Snapshot Optons
 			arg_export_state_at: "latest".into(),
@@ -1698,6 +1784,7 @@ mod tests {
 				identity: None,
 				light: None,
 				no_persistent_txqueue: None,
+				no_persistent_pool: None,
 			}),
 			account: Some(Account {
 				unlock: Some(vec!["0x1".into(), "0x2".into(), "0x3".into()]),
@@ -1717,11 +1804,14 @@ mod tests {
 			}),
 			network: Some(Network {
 				warp: Some(false),
+				sync_mode: None,
 				port: None,
 				min_peers: Some(10),
 				max_peers: Some(20),
 				max_pending_peers: Some(30),
 				snapshot_peers: Some(40),
+				max_upload_rate: None,
+				snapshot_serve_rate: None,
 				allow_ips: Some("public".into()),
 				nat: Some("any".into()),
 				id: None,
@@ -1808,6 +1898,9 @@ mod tests {
 				tx_queue_mem_limit: None,
 				tx_queue_gas: Some("off".into()),
 				tx_queue_strategy: None,
+				tx_queue_per_sender: None,
+				tx_queue_local_share: None,
+				tx_queue_eviction: None,
 				tx_queue_ban_count: None,
 				tx_queue_ban_time: None,
 				tx_gas_limit: None,
@@ -1830,6 +1923,9 @@ mod tests {
 				cache_size_queue: Some(100),
 				cache_size_state: Some(25),
 				db_compaction: Some("ssd".into()),
+				db_engine: None,
+				db_memory_limit: None,
+				db_memory_file: None,
 				fat_db: Some("off".into()),
 				scale_verifiers: Some(false),
 				num_verifiers: None,
@@ -29,7 +29,7 @@ use bytes::Bytes;
 use ansi_term::Colour;
 use ethsync::{NetworkConfiguration, validate_node_url, self};
 use ethcore::ethstore::ethkey::{Secret, Public};
-use ethcore::client::{VMType};
+use ethcore::client::{VMType, DatabaseEngine};
 use ethcore::miner::{MinerOptions, Banning, StratumOptions};
 use ethcore::verification::queue::VerifierSettings;
 
@@ -38,7 +38,7 @@ use rpc_apis::ApiSet;
 use parity_rpc::NetworkSettings;
 use cache::CacheConfig;
 use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path,
-to_bootnodes, to_addresses, to_address, to_gas_limit, to_queue_strategy};
+to_bootnodes, to_addresses, to_address, to_gas_limit, to_queue_strategy, to_queue_eviction};
 use dir::helpers::{replace_home, replace_home_and_local};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
 use ethcore_logger::Config as LogConfig;
@@ -48,7 +48,7 @@ use ipfs::Configuration as IpfsConfiguration;
 use secretstore::{NodeSecretKey, Configuration as SecretStoreConfiguration, ContractAddress as SecretStoreContractAddress};
 use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 use run::RunCmd;
-use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, DataFormat};
+use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, ExportTraces, DataFormat};
 use presale::ImportWallet;
 use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts};
 use snapshot::{self, SnapshotCommand};
@@ -128,7 +128,7 @@ impl Configuration {
 		let compaction = self.args.arg_db_compaction.parse()?;
 		let wal = !self.args.flag_fast_and_loose;
 		let public_node = self.args.flag_public_node;
-		let warp_sync = !self.args.flag_no_warp;
+		let (warp_sync, fast_sync) = self.sync_mode()?;
 		let geth_compatibility = self.args.flag_geth;
 		let dapps_conf = self.dapps_config();
 		let ipfs_conf = self.ipfs_config();
@@ -230,6 +230,7 @@ impl Configuration {
 				pruning_memory: self.args.arg_pruning_memory,
 				compaction: compaction,
 				wal: wal,
+				db_engine: self.db_engine()?,
 				tracing: tracing,
 				fat_db: fat_db,
 				vm_type: vm_type,
@@ -252,6 +253,7 @@ impl Configuration {
 					pruning_memory: self.args.arg_pruning_memory,
 					compaction: compaction,
 					wal: wal,
+					db_engine: self.db_engine()?,
 					tracing: tracing,
 					fat_db: fat_db,
 					from_block: to_block_id(&self.args.arg_export_blocks_from)?,
@@ -271,6 +273,7 @@ impl Configuration {
 					pruning_memory: self.args.arg_pruning_memory,
 					compaction: compaction,
 					wal: wal,
+					db_engine: self.db_engine()?,
 					tracing: tracing,
 					fat_db: fat_db,
 					at: to_block_id(&self.args.arg_export_state_at)?,
@@ -280,6 +283,24 @@ impl Configuration {
 					max_balance: self.args.arg_export_state_max_balance.and_then(|s| to_u256(&s).ok()),
 				};
 				Cmd::Blockchain(BlockchainCmd::ExportState(export_cmd))
+			} else if self.args.cmd_export_traces {
+				let export_cmd = ExportTraces {
+					spec: spec,
+					cache_config: cache_config,
+					dirs: dirs,
+					file_path: self.args.arg_export_traces_file.clone(),
+					pruning: pruning,
+					pruning_history: pruning_history,
+					pruning_memory: self.args.arg_pruning_memory,
+					compaction: compaction,
+					wal: wal,
+					db_engine: self.db_engine()?,
+					tracing: tracing,
+					fat_db: fat_db,
+					from_block: to_block_id(&self.args.arg_export_traces_from)?,
+					to_block: to_block_id(&self.args.arg_export_traces_to)?,
+				};
+				Cmd::Blockchain(BlockchainCmd::ExportTraces(export_cmd))
 			} else {
 				unreachable!();
 			}
@@ -296,6 +317,7 @@ impl Configuration {
 				compaction: compaction,
 				file_path: self.args.arg_snapshot_file.clone(),
 				wal: wal,
+				db_engine: self.db_engine()?,
 				kind: snapshot::Kind::Take,
 				block_at: to_block_id(&self.args.arg_snapshot_at)?,
 			};
@@ -313,6 +335,7 @@ impl Configuration {
 				compaction: compaction,
 				file_path: self.args.arg_restore_file.clone(),
 				wal: wal,
+				db_engine: self.db_engine()?,
 				kind: snapshot::Kind::Restore,
 				block_at: to_block_id("latest")?, // unimportant.
 			};
@@ -354,8 +377,10 @@ impl Configuration {
 				fat_db: fat_db,
 				compaction: compaction,
 				wal: wal,
+				db_engine: self.db_engine()?,
 				vm_type: vm_type,
 				warp_sync: warp_sync,
+				fast_sync: fast_sync,
 				public_node: public_node,
 				geth_compatibility: geth_compatibility,
 				net_settings: self.network_settings()?,
@@ -374,6 +399,7 @@ impl Configuration {
 				serve_light: !self.args.flag_no_serve_light,
 				light: self.args.flag_light,
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
+				no_persistent_pool: self.args.flag_no_persistent_pool,
 				whisper: whisper_config,
 			};
 			Cmd::Run(run_cmd)
@@ -385,6 +411,17 @@ impl Configuration {
 		})
 	}
 
+	/// Returns whether warp sync and fast sync are enabled.
+	fn sync_mode(&self) -> Result<(bool, bool), String> {
+		match self.args.arg_sync_mode.as_ref().map(String::as_str) {
+			None => Ok((!self.args.flag_no_warp, false)),
+			Some("warp") => Ok((true, false)),
+			Some("fast") => Ok((false, true)),
+			Some("full") => Ok((false, false)),
+			Some(mode) => Err(format!("Invalid sync mode: {}. Expected one of: warp, fast, full.", mode)),
+		}
+	}
+
 	fn vm_type(&self) -> Result<VMType, String> {
 		if self.args.flag_jitvm {
 			VMType::jit().ok_or("Parity is built without the JIT EVM.".into())
@@ -422,6 +459,16 @@ impl Configuration {
 		}
 	}
 
+	fn db_engine(&self) -> Result<DatabaseEngine, String> {
+		match self.args.arg_db_engine.parse()? {
+			DatabaseEngine::Memory { .. } => Ok(DatabaseEngine::Memory {
+				limit: self.args.arg_db_memory_limit.map(|mb| mb as usize * 1024 * 1024),
+				dump_path: self.args.arg_db_memory_file.as_ref().map(|file| replace_home(&self.directories().base, file).into()),
+			}),
+			engine => Ok(engine),
+		}
+	}
+
 	fn cache_config(&self) -> CacheConfig {
 		match self.args.arg_cache_size.or(self.args.arg_cache) {
 			Some(size) => CacheConfig::new_with_total_cache_size(size),
@@ -531,6 +578,9 @@ impl Configuration {
 			} else { None },
 			tx_queue_gas_limit: to_gas_limit(&self.args.arg_tx_queue_gas)?,
 			tx_queue_strategy: to_queue_strategy(&self.args.arg_tx_queue_strategy)?,
+			tx_queue_per_sender: self.args.arg_tx_queue_per_sender,
+			tx_queue_local_share: self.args.arg_tx_queue_local_share,
+			tx_queue_eviction: to_queue_eviction(&self.args.arg_tx_queue_eviction)?,
 			pending_set: to_pending_set(&self.args.arg_relay_set)?,
 			reseal_min_period: Duration::from_millis(self.args.arg_reseal_min_period),
 			reseal_max_period: Duration::from_millis(self.args.arg_reseal_max_period),
@@ -755,6 +805,8 @@ impl Configuration {
 		ret.snapshot_peers = self.snapshot_peers();
 		ret.ip_filter = self.ip_filter()?;
 		ret.max_pending_peers = self.max_pending_peers();
+		ret.max_upload_rate = upload_rate("--max-upload-rate", self.args.arg_max_upload_rate)?;
+		ret.snapshot_serve_rate = upload_rate("--snapshot-serve-rate", self.args.arg_snapshot_serve_rate)?;
 		let mut net_path = PathBuf::from(self.directories().base);
 		net_path.push("network");
 		ret.config_path = Some(net_path.to_str().unwrap().to_owned());
@@ -1134,6 +1186,17 @@ impl Configuration {
 	}
 }
 
+/// Converts an upload rate given in kilobytes per second to bytes per second.
+fn upload_rate(arg: &str, rate: Option<u64>) -> Result<Option<u64>, String> {
+	match rate {
+		None => Ok(None),
+		Some(0) => Err(format!("Invalid upload rate given with `{}`: must be greater than 0", arg)),
+		Some(rate) => rate.checked_mul(1024)
+			.map(Some)
+			.ok_or_else(|| format!("Invalid upload rate given with `{}`: {} is too large", arg, rate)),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::io::Write;
@@ -1143,12 +1206,12 @@ mod tests {
 	use tempdir::TempDir;
 	use ethcore::client::{VMType, BlockId};
 	use ethcore::miner::MinerOptions;
-	use miner::transaction_queue::PrioritizationStrategy;
+	use miner::transaction_queue::{PrioritizationStrategy, EvictionPolicy};
 	use parity_rpc::NetworkSettings;
 	use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 
 	use account::{AccountCmd, NewAccount, ImportAccounts, ListAccounts};
-	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat, ExportState};
+	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat, ExportState, ExportTraces};
 	use cli::Args;
 	use dir::{Directories, default_hypervisor_path};
 	use helpers::{default_network_config};
@@ -1244,6 +1307,7 @@ mod tests {
 			pruning_memory: 32,
 			compaction: Default::default(),
 			wal: true,
+			db_engine: Default::default(),
 			tracing: Default::default(),
 			fat_db: Default::default(),
 			vm_type: VMType::Interpreter,
@@ -1269,6 +1333,7 @@ mod tests {
 			format: Default::default(),
 			compaction: Default::default(),
 			wal: true,
+			db_engine: Default::default(),
 			tracing: Default::default(),
 			fat_db: Default::default(),
 			from_block: BlockId::Number(1),
@@ -1292,6 +1357,7 @@ mod tests {
 			format: Default::default(),
 			compaction: Default::default(),
 			wal: true,
+			db_engine: Default::default(),
 			tracing: Default::default(),
 			fat_db: Default::default(),
 			at: BlockId::Latest,
@@ -1302,6 +1368,28 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_traces_export() {
+		let args = vec!["parity", "export", "traces", "--from", "10", "--to", "20", "--file", "traces.json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::ExportTraces(ExportTraces {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			file_path: Some("traces.json".into()),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			db_engine: Default::default(),
+			tracing: Default::default(),
+			fat_db: Default::default(),
+			from_block: BlockId::Number(10),
+			to_block: BlockId::Number(20),
+		})));
+	}
+
 	#[test]
 	fn test_command_blockchain_export_with_custom_format() {
 		let args = vec!["parity", "export", "blocks", "--format", "hex", "blockchain.json"];
@@ -1317,6 +1405,7 @@ mod tests {
 			format: Some(DataFormat::Hex),
 			compaction: Default::default(),
 			wal: true,
+			db_engine: Default::default(),
 			tracing: Default::default(),
 			fat_db: Default::default(),
 			from_block: BlockId::Number(1),
@@ -1381,6 +1470,7 @@ mod tests {
 			network_id: None,
 			public_node: false,
 			warp_sync: true,
+			fast_sync: false,
 			acc_conf: Default::default(),
 			gas_pricer_conf: Default::default(),
 			miner_extras: Default::default(),
@@ -1395,6 +1485,7 @@ mod tests {
 			tracing: Default::default(),
 			compaction: Default::default(),
 			wal: true,
+			db_engine: Default::default(),
 			vm_type: Default::default(),
 			geth_compatibility: false,
 			net_settings: Default::default(),
@@ -1415,6 +1506,7 @@ mod tests {
 			serve_light: true,
 			light: false,
 			no_persistent_txqueue: false,
+			no_persistent_pool: false,
 			whisper: Default::default(),
 		};
 		expected.secretstore_conf.enabled = cfg!(feature = "secretstore");
@@ -1443,6 +1535,41 @@ mod tests {
 		assert_eq!(conf3.miner_options().unwrap(), mining_options);
 	}
 
+	#[test]
+	fn should_parse_queue_limits_options() {
+		// given
+		let mut mining_options = MinerOptions::default();
+
+		// when
+		let conf0 = parse(&["parity", "--tx-queue-per-sender", "32", "--tx-queue-local-share", "20"]);
+		let conf1 = parse(&["parity", "--tx-queue-eviction", "oldest"]);
+		let conf2 = parse(&["parity", "--tx-queue-eviction", "newest"]);
+
+		// then
+		mining_options.tx_queue_per_sender = Some(32);
+		mining_options.tx_queue_local_share = 20;
+		assert_eq!(conf0.miner_options().unwrap(), mining_options);
+		let mut mining_options = MinerOptions::default();
+		mining_options.tx_queue_eviction = EvictionPolicy::Oldest;
+		assert_eq!(conf1.miner_options().unwrap(), mining_options);
+		assert!(conf2.miner_options().is_err());
+	}
+
+	#[test]
+	fn should_parse_upload_rates() {
+		// when
+		let conf0 = parse(&["parity", "--max-upload-rate", "100", "--snapshot-serve-rate", "50"]);
+		let conf1 = parse(&["parity", "--max-upload-rate", "0"]);
+		let conf2 = parse(&["parity", "--snapshot-serve-rate", "18446744073709551615"]);
+
+		// then
+		let net_config = conf0.net_config().unwrap();
+		assert_eq!(net_config.max_upload_rate, Some(100 * 1024));
+		assert_eq!(net_config.snapshot_serve_rate, Some(50 * 1024));
+		assert!(conf1.net_config().is_err());
+		assert!(conf2.net_config().is_err());
+	}
+
 	#[test]
 	fn should_fail_on_force_reseal_and_reseal_min_period() {
 		let conf = parse(&["parity", "--chain", "dev", "--force-sealing", "--reseal-min-period", "0"]);
@@ -1683,6 +1810,41 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_memory_db_engine() {
+		let args = vec!["parity", "--db-engine", "memory", "--db-memory-limit", "64", "--db-memory-file", "/tmp/db.rlp"];
+		let conf = Configuration::parse(&args, None).unwrap();
+		match conf.into_command().unwrap().cmd {
+			Cmd::Run(c) => {
+				assert_eq!(c.db_engine, DatabaseEngine::Memory {
+					limit: Some(64 * 1024 * 1024),
+					dump_path: Some("/tmp/db.rlp".into()),
+				});
+			},
+			_ => panic!("Should be Cmd::Run"),
+		}
+
+		let args = vec!["parity", "--db-engine", "leveldb"];
+		let conf = Configuration::parse(&args, None).unwrap();
+		assert!(conf.into_command().is_err());
+	}
+
+	#[test]
+	fn should_parse_sync_mode() {
+		let sync_mode = |args: &[&str]| match Configuration::parse(args, None).unwrap().into_command().unwrap().cmd {
+			Cmd::Run(c) => (c.warp_sync, c.fast_sync),
+			_ => panic!("Should be Cmd::Run"),
+		};
+		assert_eq!(sync_mode(&["parity"]), (true, false));
+		assert_eq!(sync_mode(&["parity", "--no-warp"]), (false, false));
+		assert_eq!(sync_mode(&["parity", "--sync-mode", "fast"]), (false, true));
+		assert_eq!(sync_mode(&["parity", "--sync-mode", "full"]), (false, false));
+		assert_eq!(sync_mode(&["parity", "--no-warp", "--sync-mode", "warp"]), (true, false));
+
+		let conf = Configuration::parse(&["parity", "--sync-mode", "light"], None).unwrap();
+		assert!(conf.into_command().is_err());
+	}
+
 	#[test]
 	fn test_mining_preset() {
 		let args = vec!["parity", "--config", "mining"];
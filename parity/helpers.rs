@@ -21,13 +21,14 @@ use std::fs::File;
 use ethereum_types::{U256, clean_0x, Address};
 use kvdb_rocksdb::CompactionProfile;
 use journaldb::Algorithm;
-use ethcore::client::{Mode, BlockId, VMType, DatabaseCompactionProfile, ClientConfig, VerifierType};
+use ethcore::client::{Mode, BlockId, VMType, DatabaseCompactionProfile, DatabaseEngine, ClientConfig, VerifierType};
 use ethcore::miner::{PendingSet, GasLimit};
-use miner::transaction_queue::PrioritizationStrategy;
+use miner::transaction_queue::{PrioritizationStrategy, EvictionPolicy};
 use cache::CacheConfig;
 use dir::DatabaseDirectories;
 use dir::helpers::replace_home;
 use upgrade::{upgrade, upgrade_data_paths};
+use migr;
 use migration::migrate;
 use ethsync::{validate_node_url, self};
 use path;
@@ -114,6 +115,10 @@ pub fn to_queue_strategy(s: &str) -> Result<PrioritizationStrategy, String> {
 	}
 }
 
+pub fn to_queue_eviction(s: &str) -> Result<EvictionPolicy, String> {
+	s.parse()
+}
+
 pub fn to_address(s: Option<String>) -> Result<Address, String> {
 	match s {
 		Some(ref a) => clean_0x(a).parse().map_err(|_| format!("Invalid address: {:?}", a)),
@@ -201,6 +206,9 @@ pub fn default_network_config() -> ::ethsync::NetworkConfiguration {
 		reserved_nodes: Vec::new(),
 		allow_non_reserved: true,
 		client_version: ::parity_version::version(),
+		chain_id: None,
+		max_upload_rate: None,
+		snapshot_serve_rate: None,
 	}
 }
 
@@ -212,6 +220,7 @@ pub fn to_client_config(
 		fat_db: bool,
 		compaction: DatabaseCompactionProfile,
 		wal: bool,
+		db_engine: DatabaseEngine,
 		vm_type: VMType,
 		name: String,
 		pruning: Algorithm,
@@ -248,6 +257,7 @@ pub fn to_client_config(
 	client_config.history = pruning_history;
 	client_config.db_compaction = compaction;
 	client_config.db_wal = wal;
+	client_config.db_engine = db_engine;
 	client_config.vm_type = vm_type;
 	client_config.name = name;
 	client_config.verifier_type = if check_seal { VerifierType::Canon } else { VerifierType::CanonNoSeal };
@@ -259,8 +269,13 @@ pub fn execute_upgrades(
 	base_path: &str,
 	dirs: &DatabaseDirectories,
 	pruning: Algorithm,
-	compaction_profile: CompactionProfile
+	compaction_profile: CompactionProfile,
+	db_engine: &DatabaseEngine,
 ) -> Result<(), String> {
+	// an in-memory database leaves nothing on disk to upgrade.
+	if db_engine.is_memory() {
+		return Ok(())
+	}
 
 	upgrade_data_paths(base_path, dirs, pruning);
 
@@ -275,7 +290,12 @@ pub fn execute_upgrades(
 	}
 
 	let client_path = dirs.db_path(pruning);
-	migrate(&client_path, compaction_profile).map_err(|e| format!("{}", e))
+	let engine = match *db_engine {
+		#[cfg(feature = "logdb")]
+		DatabaseEngine::LogDb => migr::Engine::LogDb,
+		_ => migr::Engine::RocksDb,
+	};
+	migrate(&client_path, compaction_profile, engine).map_err(|e| format!("{}", e))
 }
 
 /// Prompts user asking for password.
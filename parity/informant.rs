@@ -19,15 +19,17 @@ use self::ansi_term::Colour::{White, Yellow, Green, Cyan, Blue};
 use self::ansi_term::{Colour, Style};
 
 use std::sync::{Arc};
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::time::{Instant, Duration};
 
 use ethcore::client::{BlockId, BlockChainClient, BlockChainInfo, BlockQueueInfo, ChainNotify, ClientReport, Client};
+use ethcore::engines::ReportStats;
 use ethcore::header::BlockNumber;
 use ethcore::service::ClientIoMessage;
 use ethcore::snapshot::{RestorationStatus, SnapshotService as SS};
 use ethcore::snapshot::service::Service as SnapshotService;
-use ethsync::{LightSyncProvider, LightSync, SyncProvider, ManageNetwork};
+use ethsync::{LightSyncProvider, LightSync, SyncProvider, ManageNetwork, TrafficStats};
 use io::{TimerToken, IoContext, IoHandler};
 use isatty::{stdout_isatty};
 use light::Cache as LightDataCache;
@@ -91,6 +93,9 @@ pub struct SyncInfo {
 	num_peers: usize,
 	max_peers: u32,
 	snapshot_sync: bool,
+	fast_sync: Option<(BlockNumber, usize, usize)>,
+	traffic: TrafficStats,
+	protocol_traffic: BTreeMap<String, TrafficStats>,
 }
 
 pub struct Report {
@@ -100,6 +105,7 @@ pub struct Report {
 	queue_info: BlockQueueInfo,
 	cache_sizes: CacheSizes,
 	sync_info: Option<SyncInfo>,
+	engine_reports: ReportStats,
 }
 
 /// Something which can provide data to the informant.
@@ -139,6 +145,9 @@ impl InformantData for FullNodeInformantData {
 		cache_sizes.insert("db", client_report.state_db_mem);
 		cache_sizes.insert("queue", queue_info.mem_used);
 		cache_sizes.insert("chain", blockchain_cache_info.total());
+		if let Some(used) = client_report.db_memory_used {
+			cache_sizes.insert("memdb", used);
+		}
 
 		let (importing, sync_info) = match (self.sync.as_ref(), self.net.as_ref()) {
 			(Some(sync), Some(net)) => {
@@ -154,6 +163,12 @@ impl InformantData for FullNodeInformantData {
 					num_peers: status.num_peers,
 					max_peers: status.current_max_peers(net_config.min_peers, net_config.max_peers),
 					snapshot_sync: status.is_snapshot_syncing(),
+					fast_sync: match status.is_fast_syncing() {
+						true => status.fast_sync_pivot_number.map(|n| (n, status.state_nodes_done, status.state_nodes_pending)),
+						false => None,
+					},
+					traffic: net.network_traffic(),
+					protocol_traffic: net.protocol_traffic(),
 				}))
 			}
 			_ => (is_major_importing(self.sync.as_ref().map(|s| s.status().state), queue_info.clone()), None),
@@ -166,6 +181,7 @@ impl InformantData for FullNodeInformantData {
 			queue_info,
 			cache_sizes,
 			sync_info,
+			engine_reports: self.client.engine().report_stats(),
 		}
 	}
 }
@@ -191,6 +207,9 @@ impl InformantData for LightNodeInformantData {
 		let mut cache_sizes = CacheSizes::default();
 		cache_sizes.insert("queue", queue_info.mem_used);
 		cache_sizes.insert("cache", self.cache.lock().mem_used());
+		if let Some(used) = client_report.db_memory_used {
+			cache_sizes.insert("memdb", used);
+		}
 
 		let peer_numbers = self.sync.peer_numbers();
 		let sync_info = Some(SyncInfo {
@@ -199,6 +218,9 @@ impl InformantData for LightNodeInformantData {
 			num_peers: peer_numbers.connected,
 			max_peers: peer_numbers.max as u32,
 			snapshot_sync: false,
+			fast_sync: None,
+			traffic: self.sync.network_traffic(),
+			protocol_traffic: self.sync.protocol_traffic(),
 		});
 
 		Report {
@@ -208,6 +230,7 @@ impl InformantData for LightNodeInformantData {
 			queue_info,
 			cache_sizes,
 			sync_info,
+			engine_reports: self.client.engine().report_stats(),
 		}
 	}
 }
@@ -223,6 +246,7 @@ pub struct Informant<T> {
 	skipped_txs: AtomicUsize,
 	in_shutdown: AtomicBool,
 	last_report: Mutex<ClientReport>,
+	last_engine_reports: Mutex<ReportStats>,
 }
 
 impl<T: InformantData> Informant<T> {
@@ -244,6 +268,7 @@ impl<T: InformantData> Informant<T> {
 			skipped_txs: AtomicUsize::new(0),
 			in_shutdown: AtomicBool::new(false),
 			last_report: Mutex::new(Default::default()),
+			last_engine_reports: Mutex::new(Default::default()),
 		}
 	}
 
@@ -272,9 +297,21 @@ impl<T: InformantData> Informant<T> {
 			queue_info,
 			cache_sizes,
 			sync_info,
+			engine_reports,
 			..
 		} = full_report;
 
+		{
+			let mut last_engine_reports = self.last_engine_reports.lock();
+			if engine_reports != *last_engine_reports {
+				info!(target: "engine", "Reported {} benign and {} malicious validator misbehaviours.",
+					engine_reports.benign - last_engine_reports.benign,
+					engine_reports.malicious - last_engine_reports.malicious,
+				);
+				*last_engine_reports = engine_reports;
+			}
+		}
+
 		let rpc_stats = self.rpc_stats.as_ref();
 
 		let (snapshot_sync, snapshot_current, snapshot_total) = self.snapshot.as_ref().map_or((false, 0, 0), |s|
@@ -285,6 +322,7 @@ impl<T: InformantData> Informant<T> {
 			}
 		);
 		let snapshot_sync = snapshot_sync && sync_info.as_ref().map_or(false, |s| s.snapshot_sync);
+		let fast_sync = sync_info.as_ref().and_then(|s| s.fast_sync);
 		if !importing && !snapshot_sync && elapsed < Duration::from_secs(30) {
 			return;
 		}
@@ -298,8 +336,8 @@ impl<T: InformantData> Informant<T> {
 
 		info!(target: "import", "{}  {}  {}  {}",
 			match importing {
-				true => match snapshot_sync {
-					false => format!("Syncing {} {}  {}  {}+{} Qed",
+				true => match (snapshot_sync, fast_sync) {
+					(false, None) => format!("Syncing {} {}  {}  {}+{} Qed",
 						paint(White.bold(), format!("{:>8}", format!("#{}", chain_info.best_block_number))),
 						paint(White.bold(), format!("{}", chain_info.best_block_hash)),
 						if self.target.executes_transactions() {
@@ -316,12 +354,17 @@ impl<T: InformantData> Informant<T> {
 						paint(Green.bold(), format!("{:5}", queue_info.unverified_queue_size)),
 						paint(Green.bold(), format!("{:5}", queue_info.verified_queue_size))
 					),
-					true => format!("Syncing snapshot {}/{}", snapshot_current, snapshot_total),
+					(false, Some((pivot, nodes_done, nodes_pending))) => format!("Syncing state {} {}/{} nodes",
+						paint(White.bold(), format!("{:>8}", format!("#{}", pivot))),
+						paint(Yellow.bold(), format!("{}", nodes_done)),
+						paint(Yellow.bold(), format!("{}", nodes_done + nodes_pending)),
+					),
+					(true, _) => format!("Syncing snapshot {}/{}", snapshot_current, snapshot_total),
 				},
 				false => String::new(),
 			},
 			match sync_info.as_ref() {
-				Some(ref sync_info) => format!("{}{}/{} peers",
+				Some(ref sync_info) => format!("{}{}/{} peers {} in {} out",
 					match importing {
 						true => format!("{}   ", paint(Green.bold(), format!("{:>8}", format!("#{}", sync_info.last_imported_block_number)))),
 						false => match sync_info.last_imported_old_block_number {
@@ -331,6 +374,8 @@ impl<T: InformantData> Informant<T> {
 					},
 					paint(Cyan.bold(), format!("{:2}", sync_info.num_peers)),
 					paint(Cyan.bold(), format!("{:2}", sync_info.max_peers)),
+					paint(Cyan.bold(), format_bytes(sync_info.traffic.ingress as usize)),
+					paint(Cyan.bold(), format_bytes(sync_info.traffic.egress as usize)),
 				),
 				_ => String::new(),
 			},
@@ -345,6 +390,20 @@ impl<T: InformantData> Informant<T> {
 				_ => String::new(),
 			},
 		);
+
+		if let Some(ref sync_info) = sync_info {
+			if !sync_info.protocol_traffic.is_empty() {
+				info!(target: "import", "Traffic: {}", sync_info.protocol_traffic.iter()
+					.map(|(protocol, traffic)| format!("{} {} in {} out",
+						protocol,
+						paint(Cyan.bold(), format_bytes(traffic.ingress as usize)),
+						paint(Cyan.bold(), format_bytes(traffic.egress as usize)),
+					))
+					.collect::<Vec<_>>()
+					.join(", ")
+				);
+			}
+		}
 	}
 }
 
@@ -124,16 +124,17 @@ fn backup_database_path(path: &Path) -> PathBuf {
 }
 
 /// Default migration settings.
-pub fn default_migration_settings(compaction_profile: &CompactionProfile) -> MigrationConfig {
+pub fn default_migration_settings(compaction_profile: &CompactionProfile, engine: migr::Engine) -> MigrationConfig {
 	MigrationConfig {
 		batch_size: BATCH_SIZE,
 		compaction_profile: *compaction_profile,
+		engine: engine,
 	}
 }
 
 /// Migrations on the consolidated database.
-fn consolidated_database_migrations(compaction_profile: &CompactionProfile) -> Result<MigrationManager, Error> {
-	let mut manager = MigrationManager::new(default_migration_settings(compaction_profile));
+fn consolidated_database_migrations(compaction_profile: &CompactionProfile, engine: migr::Engine) -> Result<MigrationManager, Error> {
+	let mut manager = MigrationManager::new(default_migration_settings(compaction_profile, engine));
 	manager.add_migration(migrations::TO_V11).map_err(|_| Error::MigrationImpossible)?;
 	manager.add_migration(migrations::TO_V12).map_err(|_| Error::MigrationImpossible)?;
 	manager.add_migration(migrations::ToV13::default()).map_err(|_| Error::MigrationImpossible)?;
@@ -177,7 +178,7 @@ fn exists(path: &Path) -> bool {
 }
 
 /// Migrates the database.
-pub fn migrate(path: &Path, compaction_profile: CompactionProfile) -> Result<(), Error> {
+pub fn migrate(path: &Path, compaction_profile: CompactionProfile, engine: migr::Engine) -> Result<(), Error> {
 	// read version file.
 	let version = current_version(path)?;
 
@@ -195,7 +196,7 @@ pub fn migrate(path: &Path, compaction_profile: CompactionProfile) -> Result<(),
 	// Further migrations
 	if version >= CONSOLIDATION_VERSION && version < CURRENT_VERSION && exists(&consolidated_database_path(path)) {
 		println!("Migrating database from version {} to {}", ::std::cmp::max(CONSOLIDATION_VERSION, version), CURRENT_VERSION);
-		migrate_database(version, consolidated_database_path(path), consolidated_database_migrations(&compaction_profile)?)?;
+		migrate_database(version, consolidated_database_path(path), consolidated_database_migrations(&compaction_profile, engine)?)?;
 		println!("Migration finished");
 	}
 
@@ -23,7 +23,7 @@ use std::net::{TcpListener};
 use ansi_term::Colour;
 use ctrlc::CtrlC;
 use ethcore::account_provider::{AccountProvider, AccountProviderSettings};
-use ethcore::client::{Client, Mode, DatabaseCompactionProfile, VMType, BlockChainClient};
+use ethcore::client::{Client, Mode, DatabaseCompactionProfile, DatabaseEngine, VMType, BlockChainClient};
 use ethcore::ethstore::ethkey;
 use ethcore::miner::{Miner, MinerService, MinerOptions};
 use ethcore::miner::{StratumOptions, Stratum};
@@ -99,6 +99,7 @@ pub struct RunCmd {
 	pub net_conf: ethsync::NetworkConfiguration,
 	pub network_id: Option<u64>,
 	pub warp_sync: bool,
+	pub fast_sync: bool,
 	pub public_node: bool,
 	pub acc_conf: AccountsConfig,
 	pub gas_pricer_conf: GasPricerConfig,
@@ -109,6 +110,7 @@ pub struct RunCmd {
 	pub fat_db: Switch,
 	pub compaction: DatabaseCompactionProfile,
 	pub wal: bool,
+	pub db_engine: DatabaseEngine,
 	pub vm_type: VMType,
 	pub geth_compatibility: bool,
 	pub net_settings: NetworkSettings,
@@ -128,6 +130,7 @@ pub struct RunCmd {
 	pub serve_light: bool,
 	pub light: bool,
 	pub no_persistent_txqueue: bool,
+	pub no_persistent_pool: bool,
 	pub whisper: ::whisper::Config
 }
 
@@ -157,6 +160,7 @@ pub fn open_dapp(dapps_conf: &dapps::Configuration, rpc_conf: &rpc::HttpConfigur
 // node info fetcher for the local store.
 struct FullNodeInfo {
 	miner: Option<Arc<Miner>>, // TODO: only TXQ needed, just use that after decoupling.
+	persist_pool: bool,
 }
 
 impl ::local_store::NodeInfo for FullNodeInfo {
@@ -173,6 +177,20 @@ impl ::local_store::NodeInfo for FullNodeInfo {
 			.filter(|tx| local_txs.contains_key(&tx.hash()))
 			.collect()
 	}
+
+	fn pool_transactions(&self) -> Vec<::transaction::PendingTransaction> {
+		let miner = match self.miner.as_ref() {
+			Some(m) if self.persist_pool => m,
+			_ => return Vec::new(),
+		};
+
+		let local_txs = miner.local_transactions();
+		miner.pending_transactions()
+			.into_iter()
+			.chain(miner.future_transactions())
+			.filter(|tx| !local_txs.contains_key(&tx.hash()))
+			.collect()
+	}
 }
 
 type LightClient = ::light::client::Client<::light_helpers::EpochFetch>;
@@ -204,7 +222,7 @@ fn execute_light_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger
 	let compaction = cmd.compaction.compaction_profile(db_dirs.db_root_path().as_path());
 
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, compaction.clone())?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, compaction.clone(), &cmd.db_engine)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(cmd.dapps_conf.enabled, cmd.ui_conf.enabled, cmd.secretstore_conf.enabled)?;
@@ -225,6 +243,7 @@ fn execute_light_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger
 		db_cache_size: Some(cmd.cache_config.blockchain() as usize * 1024 * 1024),
 		db_compaction: compaction,
 		db_wal: cmd.wal,
+		db_engine: cmd.db_engine.clone(),
 		verify_full: true,
 		check_seal: cmd.check_seal,
 	};
@@ -265,6 +284,7 @@ fn execute_light_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger
 
 	// set network path.
 	net_conf.net_config_path = Some(db_dirs.network_path().to_string_lossy().into_owned());
+	net_conf.chain_id = Some(spec.chain_id());
 	let sync_params = LightSyncParams {
 		network_config: net_conf.into_basic().map_err(|e| format!("Failed to produce network config: {}", e))?,
 		client: Arc::new(provider),
@@ -448,7 +468,7 @@ pub fn execute_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>)
 	let snapshot_path = db_dirs.snapshot_path();
 
 	// execute upgrades
-	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, cmd.compaction.compaction_profile(db_dirs.db_root_path().as_path()))?;
+	execute_upgrades(&cmd.dirs.base, &db_dirs, algorithm, cmd.compaction.compaction_profile(db_dirs.db_root_path().as_path()), &cmd.db_engine)?;
 
 	// create dirs used by parity
 	cmd.dirs.create_dirs(cmd.dapps_conf.enabled, cmd.ui_conf.enabled, cmd.secretstore_conf.enabled)?;
@@ -505,9 +525,29 @@ pub fn execute_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>)
 		} else if algorithm != Algorithm::OverlayRecent {
 			warn!("Warning: Warp Sync is disabled because of non-default pruning mode.");
 			warp_sync = false;
+		} else if cmd.db_engine.is_memory() {
+			warn!("Warning: Warp Sync is disabled because the in-memory database cannot be restored from a snapshot.");
+			warp_sync = false;
 		}
 	}
 	sync_config.warp_sync = spec.engine.supports_warp() && warp_sync;
+	let mut fast_sync = cmd.fast_sync;
+	if fast_sync {
+		if fat_db {
+			warn!("Warning: Fast Sync is disabled because Fat DB is turned on.");
+			fast_sync = false;
+		} else if tracing {
+			warn!("Warning: Fast Sync is disabled because tracing is turned on.");
+			fast_sync = false;
+		} else if algorithm != Algorithm::OverlayRecent {
+			warn!("Warning: Fast Sync is disabled because of non-default pruning mode.");
+			fast_sync = false;
+		} else if !spec.engine.supports_warp() {
+			warn!("Warning: Fast Sync is disabled because the consensus engine does not support it.");
+			fast_sync = false;
+		}
+	}
+	sync_config.fast_sync = fast_sync;
 	sync_config.download_old_blocks = cmd.download_old_blocks;
 	sync_config.serve_light = cmd.serve_light;
 
@@ -556,6 +596,7 @@ pub fn execute_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>)
 		fat_db,
 		cmd.compaction,
 		cmd.wal,
+		cmd.db_engine.clone(),
 		cmd.vm_type,
 		cmd.name,
 		algorithm,
@@ -574,6 +615,7 @@ pub fn execute_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>)
 
 	// set network path.
 	net_conf.net_config_path = Some(db_dirs.network_path().to_string_lossy().into_owned());
+	net_conf.chain_id = Some(spec.chain_id());
 
 	// create client service.
 	let service = ClientService::start(
@@ -601,7 +643,8 @@ pub fn execute_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>)
 			miner: match cmd.no_persistent_txqueue {
 				true => None,
 				false => Some(miner.clone()),
-			}
+			},
+			persist_pool: !cmd.no_persistent_pool,
 		};
 
 		let store = ::local_store::create(db, ::ethcore::db::COL_NODE_INFO, node_info);
@@ -612,6 +655,12 @@ pub fn execute_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>)
 			if let Err(e) = store.clear() {
 				warn!("Error clearing persistent transaction queue: {}", e);
 			}
+		} else if cmd.no_persistent_pool {
+			info!("Running without a persistent transaction pool.");
+
+			if let Err(e) = store.clear_pool() {
+				warn!("Error clearing persistent transaction pool: {}", e);
+			}
 		}
 
 		// re-queue pending transactions.
@@ -626,6 +675,26 @@ pub fn execute_impl(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>)
 			Err(e) => warn!("Error loading cached pending transactions from disk: {}", e),
 		}
 
+		// re-verify and re-queue the rest of the pool, skipping transactions made stale while we were offline.
+		match store.pool_transactions() {
+			Ok(pool) => {
+				let total = pool.len();
+				let transactions: Vec<_> = pool.into_iter()
+					.filter(|pending| pending.nonce >= client.latest_nonce(&pending.sender()))
+					.collect();
+				let stale = total - transactions.len();
+
+				let imported = miner.import_restored_transactions(&*client, transactions)
+					.into_iter()
+					.filter(Result::is_ok)
+					.count();
+				if total > 0 {
+					info!("Restored {} of {} saved pool transactions ({} stale).", imported, total, stale);
+				}
+			}
+			Err(e) => warn!("Error loading cached pool transactions from disk: {}", e),
+		}
+
 		Arc::new(store)
 	};
 
@@ -34,11 +34,12 @@ use stats::Corpus;
 use ethkey::Signature;
 use ethsync::LightSync;
 use ethcore::ids::BlockId;
-use ethcore::miner::MinerService;
+use ethcore::miner::{MinerService, LocalTransactionStatus};
 use ethcore::client::MiningBlockChainClient;
 use ethcore::account_provider::AccountProvider;
 use crypto::DEFAULT_MAC;
-use transaction::{Action, SignedTransaction, PendingTransaction, Transaction};
+use miner::pool::scoring::minimal_replacement_gas_price;
+use transaction::{Action, SignedTransaction, PendingTransaction, Transaction, Error as TransactionError};
 
 use jsonrpc_core::{BoxFuture, Result, Error};
 use jsonrpc_core::futures::{future, Future, Poll, Async};
@@ -77,6 +78,20 @@ pub trait Dispatcher: Send + Sync + Clone {
 	/// "Dispatch" a local transaction.
 	fn dispatch_transaction(&self, signed_transaction: PendingTransaction)
 		-> Result<H256>;
+
+	/// Find a not yet mined transaction with given hash, which can still be replaced.
+	/// Looks into the transaction queue and the list of transactions sent from this node.
+	fn replaceable_transaction(&self, hash: &H256) -> Result<ReplaceableTransaction>;
+}
+
+/// A not yet mined transaction which can be replaced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaceableTransaction {
+	/// The original transaction.
+	pub transaction: SignedTransaction,
+	/// Whether the original transaction is still in the queue,
+	/// i.e. whether the replacement has to outbid it.
+	pub queued: bool,
 }
 
 /// A dispatcher which uses references to a client and miner in order to sign
@@ -183,6 +198,30 @@ impl<C: MiningBlockChainClient, M: MinerService> Dispatcher for FullDispatcher<C
 	fn dispatch_transaction(&self, signed_transaction: PendingTransaction) -> Result<H256> {
 		Self::dispatch_transaction(&*self.client, &*self.miner, signed_transaction)
 	}
+
+	fn replaceable_transaction(&self, hash: &H256) -> Result<ReplaceableTransaction> {
+		let best_block = self.client.best_block_header().number();
+		if let Some(pending) = self.miner.transaction(best_block, hash) {
+			return Ok(ReplaceableTransaction { transaction: pending.transaction, queued: true });
+		}
+
+		let dropped = |transaction| ReplaceableTransaction { transaction, queued: false };
+		match self.miner.local_transactions().remove(hash) {
+			Some(LocalTransactionStatus::Mined(_)) => {
+				Err(errors::transaction_not_replaceable("Transaction is already mined.", hash))
+			},
+			Some(LocalTransactionStatus::Replaced(_, _, new_hash)) => {
+				Err(errors::transaction_not_replaceable("Transaction was already replaced.", new_hash))
+			},
+			Some(LocalTransactionStatus::Dropped(tx)) |
+			Some(LocalTransactionStatus::Rejected(tx, _)) |
+			Some(LocalTransactionStatus::Invalid(tx)) => Ok(dropped(tx)),
+			Some(LocalTransactionStatus::Canceled(pending)) => Ok(dropped(pending.transaction)),
+			Some(LocalTransactionStatus::Pending) | Some(LocalTransactionStatus::Future) | None => {
+				Err(errors::transaction_not_found())
+			},
+		}
+	}
 }
 
 /// Get a recent gas price corpus.
@@ -414,6 +453,12 @@ impl Dispatcher for LightDispatcher {
 			.map_err(errors::transaction)
 			.map(|_| hash)
 	}
+
+	fn replaceable_transaction(&self, hash: &H256) -> Result<ReplaceableTransaction> {
+		self.transaction_queue.read().transaction(hash)
+			.map(|transaction| ReplaceableTransaction { transaction, queued: true })
+			.ok_or_else(errors::transaction_not_found)
+	}
 }
 
 fn sign_transaction(
@@ -753,6 +798,59 @@ pub fn default_gas_price<C, M>(client: &C, miner: &M, percentile: usize) -> U256
 	client.gas_price_corpus(100).percentile(percentile).cloned().unwrap_or_else(|| miner.sensible_gas_price())
 }
 
+/// Create a request replacing given transaction, i.e. sent from the same account with the same nonce.
+///
+/// Fields missing in the `request` are copied from the original transaction. If the original is
+/// still in the queue the gas price defaults to the minimal price required to replace it,
+/// otherwise (e.g. it was dropped or found invalid) the original gas price is reused.
+pub fn replacement_request(original: &ReplaceableTransaction, request: TransactionRequest) -> Result<TransactionRequest> {
+	let original_queued = original.queued;
+	let original = &original.transaction;
+	let sender = original.sender();
+	if request.from.map_or(false, |from| from != sender) || request.nonce.map_or(false, |nonce| nonce != original.nonce) {
+		return Err(errors::invalid_params("request", "Sender and nonce of the replacement can't be changed."));
+	}
+
+	let gas_price = if original_queued {
+		let minimal_gas_price = minimal_replacement_gas_price(original.gas_price);
+		let gas_price = request.gas_price.unwrap_or(minimal_gas_price);
+		if gas_price < minimal_gas_price {
+			return Err(errors::transaction(TransactionError::TooCheapToReplace));
+		}
+		gas_price
+	} else {
+		request.gas_price.unwrap_or(original.gas_price)
+	};
+
+	Ok(TransactionRequest {
+		from: Some(sender),
+		to: request.to.or_else(|| match original.action {
+			Action::Call(to) => Some(to),
+			Action::Create => None,
+		}),
+		gas_price: Some(gas_price),
+		gas: Some(request.gas.unwrap_or(original.gas)),
+		value: Some(request.value.unwrap_or(original.value)),
+		data: Some(request.data.unwrap_or_else(|| original.data.clone())),
+		nonce: Some(original.nonce),
+		condition: request.condition,
+	})
+}
+
+/// Create a request cancelling given transaction.
+///
+/// The original is replaced with an empty transfer from the sender to itself.
+pub fn cancellation_request(original: &ReplaceableTransaction) -> Result<TransactionRequest> {
+	let sender = original.transaction.sender();
+	replacement_request(original, TransactionRequest {
+		to: Some(sender),
+		gas: Some(21_000.into()),
+		value: Some(0.into()),
+		data: Some(Vec::new()),
+		..Default::default()
+	})
+}
+
 /// Convert RPC confirmation payload to signer confirmation payload.
 /// May need to resolve in the future to fetch things like gas price.
 pub fn from_rpc<D>(payload: RpcConfirmationPayload, default_account: Address, dispatcher: &D) -> BoxFuture<ConfirmationPayload>
@@ -105,6 +105,22 @@ pub fn request_rejected_limit() -> Error {
 	}
 }
 
+pub fn transaction_not_found() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::TRANSACTION_ERROR),
+		message: "Transaction not found. Only transactions still in the queue or sent from this node can be replaced.".into(),
+		data: None,
+	}
+}
+
+pub fn transaction_not_replaceable<T: fmt::Debug>(error: &str, details: T) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::TRANSACTION_ERROR),
+		message: error.into(),
+		data: Some(Value::String(format!("{:?}", details))),
+	}
+}
+
 pub fn account<T: fmt::Debug>(error: &str, details: T) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::ACCOUNT_ERROR),
@@ -18,6 +18,7 @@
 
 use std::sync::Arc;
 
+use bytes::Bytes;
 use ethcore::basic_account::BasicAccount;
 use ethcore::encoded;
 use ethcore::executed::{Executed, ExecutionError};
@@ -38,7 +39,7 @@ use light::request::Field;
 
 use ethsync::LightSync;
 use ethereum_types::{U256, Address};
-use hash::H256;
+use hash::{H256, KECCAK_NULL_RLP};
 use parking_lot::Mutex;
 use transaction::{Action, Transaction as EthTransaction, SignedTransaction, LocalizedTransaction};
 
@@ -189,12 +190,79 @@ impl LightFetch {
 		})
 	}
 
+	/// Helper for getting account info and values of the given storage keys at a given block,
+	/// along with the Merkle proofs of all of them.
+	/// `None` indicates the account doesn't exist at the given block.
+	pub fn proof(&self, address: Address, keys: Vec<H256>, id: BlockId)
+		-> BoxFuture<(Vec<Bytes>, Option<BasicAccount>, Vec<(Vec<Bytes>, H256)>)>
+	{
+		let mut reqs = Vec::new();
+		let header_ref = match self.make_header_requests(id, &mut reqs) {
+			Ok(r) => r,
+			Err(e) => return Box::new(future::err(e)),
+		};
+
+		reqs.push(request::AccountProof { header: header_ref.clone(), address: address }.into());
+
+		let account_fut = self.send_requests(reqs, move |mut res| {
+			let account = match res.pop() {
+				Some(OnDemandResponse::AccountProof(acc)) => acc,
+				_ => panic!("responses correspond directly with requests in amount and type; qed"),
+			};
+			let header = extract_header(&res, header_ref)
+				.expect("these responses correspond to requests that header_ref belongs to \
+						therefore it will not fail; qed");
+
+			(header, account)
+		});
+
+		// storage proofs can only be checked against a proved storage root,
+		// so they are requested once the account is known.
+		let fetcher = self.clone();
+		Box::new(account_fut.and_then(move |(header, (account_proof, account))| {
+			let storage_root = account.as_ref().map_or(KECCAK_NULL_RLP, |acc| acc.storage_root);
+			if keys.is_empty() || storage_root == KECCAK_NULL_RLP {
+				let storage = keys.iter().map(|_| (Vec::new(), H256::new())).collect();
+				return Either::A(future::ok((account_proof, account, storage)));
+			}
+
+			let reqs: Vec<OnDemandRequest> = keys.into_iter().map(|key| request::Storage {
+				header: header.clone().into(),
+				address: address,
+				storage_root: storage_root,
+				key: key,
+			}.into()).collect();
+
+			Either::B(fetcher.send_requests(reqs, move |res| {
+				let storage = res.into_iter().map(|res| match res {
+					OnDemandResponse::Storage(storage) => storage,
+					_ => panic!("responses correspond directly with requests in amount and type; qed"),
+				}).collect();
+
+				(account_proof, account, storage)
+			}))
+		}))
+	}
+
+	/// Helper for getting the value of a storage key at a given block,
+	/// verified against the account's proved storage root.
+	/// Unset keys and missing accounts both yield zero.
+	pub fn storage(&self, address: Address, key: H256, id: BlockId) -> BoxFuture<H256> {
+		Box::new(self.proof(address, vec![key], id).map(|(_, _, mut storage)| {
+			storage.pop().map_or_else(H256::new, |(_, value)| value)
+		}))
+	}
+
 	/// Helper for getting proved execution.
 	pub fn proved_execution(&self, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<ExecutionResult> {
 		const DEFAULT_GAS_PRICE: u64 = 21_000;
 		// starting gas when gas not provided.
 		const START_GAS: u64 = 50_000;
 
+		if req.state_override.is_some() {
+			return Box::new(future::err(errors::light_unimplemented(Some("State overrides are not supported by light clients.".into()))));
+		}
+
 		let (sync, on_demand, client) = (self.sync.clone(), self.on_demand.clone(), self.client.clone());
 		let req: CallRequestHelper = req.into();
 		let id = num.unwrap_or_default().into();
@@ -311,6 +379,7 @@ impl LightFetch {
 			BlockId::Latest | BlockId::Pending => Some(best_number),
 			BlockId::Hash(h) => self.client.block_header(BlockId::Hash(h)).map(|hdr| hdr.number()),
 			BlockId::Number(x) => Some(x),
+			BlockId::Finalized => None,
 		};
 
 		match (block_number(filter.to_block), block_number(filter.from_block)) {
@@ -27,6 +27,7 @@ pub mod light_fetch;
 pub mod nonce;
 pub mod oneshot;
 pub mod secretstore;
+pub mod sync_status;
 
 mod network_settings;
 mod poll_filter;
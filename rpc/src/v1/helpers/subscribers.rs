@@ -97,11 +97,15 @@ impl<T> Subscribers<T> {
 
 impl<T> Subscribers<Sink<T>> {
 	/// Assigns id and adds a subscriber to the list.
-	pub fn push(&mut self, sub: Subscriber<T>) {
+	/// Returns the sink of the added subscriber, if it's still connected.
+	pub fn push(&mut self, sub: Subscriber<T>) -> Option<&Sink<T>> {
 		let id = self.next_id();
-		if let Ok(sink) = sub.assign_id(SubscriptionId::String(id.as_string())) {
-			debug!(target: "pubsub", "Adding subscription id={:?}", id);
-			self.subscriptions.insert(id, sink);
+		match sub.assign_id(SubscriptionId::String(id.as_string())) {
+			Ok(sink) => {
+				debug!(target: "pubsub", "Adding subscription id={:?}", id);
+				Some(&*self.subscriptions.entry(id).or_insert(sink))
+			},
+			Err(_) => None,
 		}
 	}
 }
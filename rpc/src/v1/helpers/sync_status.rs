@@ -0,0 +1,114 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sync progress reported by `eth_syncing` and the `syncing` subscription.
+
+use std::sync::Arc;
+
+use ethcore::client::BlockChainClient;
+use ethcore::snapshot::{SnapshotService, RestorationStatus};
+use ethereum_types::U256;
+use ethsync::{SyncProvider, LightSync};
+use light::client::LightChainClient;
+
+use v1::helpers::block_import::is_major_importing;
+use v1::helpers::light_fetch::LightFetch;
+use v1::types::SyncInfo;
+
+/// Source of the node's sync progress.
+pub trait SyncStatusProvider: Send + Sync {
+	/// Current sync progress, or `None` if the node is not syncing.
+	fn sync_info(&self) -> Option<SyncInfo>;
+}
+
+/// Sync progress of a full node, or `None` if it is neither major syncing nor restoring a snapshot.
+pub fn full_sync_info<C, S, SN>(client: &C, sync: &S, snapshot: &SN) -> Option<SyncInfo> where
+	C: BlockChainClient + ?Sized,
+	S: SyncProvider + ?Sized,
+	SN: SnapshotService + ?Sized,
+{
+	let status = sync.status();
+
+	let (warping, warp_chunks_amount, warp_chunks_processed) = match snapshot.status() {
+		RestorationStatus::Ongoing { state_chunks, block_chunks, state_chunks_done, block_chunks_done } =>
+			(true, Some(block_chunks + state_chunks), Some(block_chunks_done + state_chunks_done)),
+		_ => (false, None, None),
+	};
+
+	if !warping && !is_major_importing(Some(status.state), client.queue_info()) {
+		return None;
+	}
+
+	let current_block = U256::from(client.chain_info().best_block_number);
+	let highest_block = U256::from(status.highest_block_number.unwrap_or(status.start_block_number));
+
+	Some(SyncInfo {
+		starting_block: status.start_block_number.into(),
+		current_block: current_block.into(),
+		highest_block: highest_block.into(),
+		warp_chunks_amount: warp_chunks_amount.map(|x| U256::from(x as u64)).map(Into::into),
+		warp_chunks_processed: warp_chunks_processed.map(|x| U256::from(x as u64)).map(Into::into),
+	})
+}
+
+/// Sync progress of a light client, or `None` if it is not major syncing.
+pub fn light_sync_info<C: LightChainClient + ?Sized>(client: &C, sync: &LightSync) -> Option<SyncInfo> {
+	if !sync.is_major_importing() {
+		return None;
+	}
+
+	let current_block = U256::from(client.chain_info().best_block_number);
+	let highest_block = sync.highest_block().map(U256::from)
+		.unwrap_or_else(|| current_block.clone());
+
+	Some(SyncInfo {
+		starting_block: U256::from(sync.start_block()).into(),
+		current_block: current_block.into(),
+		highest_block: highest_block.into(),
+		warp_chunks_amount: None,
+		warp_chunks_processed: None,
+	})
+}
+
+/// Sync progress of a full node.
+pub struct FullSyncStatus<C: ?Sized> {
+	client: Arc<C>,
+	sync: Arc<SyncProvider>,
+	snapshot: Arc<SnapshotService>,
+}
+
+impl<C: ?Sized> FullSyncStatus<C> {
+	/// Creates new `FullSyncStatus`.
+	pub fn new(client: Arc<C>, sync: Arc<SyncProvider>, snapshot: Arc<SnapshotService>) -> Self {
+		FullSyncStatus {
+			client,
+			sync,
+			snapshot,
+		}
+	}
+}
+
+impl<C: BlockChainClient + ?Sized> SyncStatusProvider for FullSyncStatus<C> {
+	fn sync_info(&self) -> Option<SyncInfo> {
+		full_sync_info(&*self.client, &*self.sync, &*self.snapshot)
+	}
+}
+
+impl SyncStatusProvider for LightFetch {
+	fn sync_info(&self) -> Option<SyncInfo> {
+		light_sync_info(&*self.client, &*self.sync)
+	}
+}
@@ -26,9 +26,10 @@ use ethereum_types::{U256, H64, H160, H256, Address};
 use parking_lot::Mutex;
 
 use ethash::SeedHashCompute;
+use hash::keccak;
 use ethcore::account_provider::{AccountProvider, DappId};
 use ethcore::block::IsBlock;
-use ethcore::client::{MiningBlockChainClient, BlockId, TransactionId, UncleId};
+use ethcore::client::{MiningBlockChainClient, ProvingBlockChainClient, BlockId, TransactionId, UncleId};
 use ethcore::ethereum::Ethash;
 use ethcore::filter::Filter as EthcoreFilter;
 use ethcore::header::{Header as BlockHeader, BlockNumber as EthBlockNumber};
@@ -45,12 +46,12 @@ use jsonrpc_macros::Trailing;
 
 use v1::helpers::{errors, limit_logs, fake_sign};
 use v1::helpers::dispatch::{FullDispatcher, default_gas_price};
-use v1::helpers::block_import::is_major_importing;
+use v1::helpers::sync_status::full_sync_info;
 use v1::helpers::accounts::unwrap_provider;
 use v1::traits::Eth;
 use v1::types::{
-	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -92,7 +93,7 @@ impl Default for EthClientOptions {
 
 /// Eth rpc implementation.
 pub struct EthClient<C, SN: ?Sized, S: ?Sized, M, EM> where
-	C: MiningBlockChainClient,
+	C: MiningBlockChainClient + ProvingBlockChainClient,
 	SN: SnapshotService,
 	S: SyncProvider,
 	M: MinerService,
@@ -110,7 +111,7 @@ pub struct EthClient<C, SN: ?Sized, S: ?Sized, M, EM> where
 }
 
 impl<C, SN: ?Sized, S: ?Sized, M, EM> EthClient<C, SN, S, M, EM> where
-	C: MiningBlockChainClient,
+	C: MiningBlockChainClient + ProvingBlockChainClient,
 	SN: SnapshotService,
 	S: SyncProvider,
 	M: MinerService,
@@ -275,7 +276,7 @@ fn check_known<C>(client: &C, number: BlockNumber) -> Result<()> where C: Mining
 const MAX_QUEUE_SIZE_TO_MINE_ON: usize = 4;	// because uncles go back 6.
 
 impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
-	C: MiningBlockChainClient + 'static,
+	C: MiningBlockChainClient + ProvingBlockChainClient + 'static,
 	SN: SnapshotService + 'static,
 	S: SyncProvider + 'static,
 	M: MinerService + 'static,
@@ -289,34 +290,9 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 	}
 
 	fn syncing(&self) -> Result<SyncStatus> {
-		use ethcore::snapshot::RestorationStatus;
-
-		let status = self.sync.status();
-		let client = &self.client;
-		let snapshot_status = self.snapshot.status();
-
-		let (warping, warp_chunks_amount, warp_chunks_processed) = match snapshot_status {
-			RestorationStatus::Ongoing { state_chunks, block_chunks, state_chunks_done, block_chunks_done } =>
-				(true, Some(block_chunks + state_chunks), Some(block_chunks_done + state_chunks_done)),
-			_ => (false, None, None),
-		};
-
-
-		if warping || is_major_importing(Some(status.state), client.queue_info()) {
-			let chain_info = client.chain_info();
-			let current_block = U256::from(chain_info.best_block_number);
-			let highest_block = U256::from(status.highest_block_number.unwrap_or(status.start_block_number));
-
-			let info = SyncInfo {
-				starting_block: status.start_block_number.into(),
-				current_block: current_block.into(),
-				highest_block: highest_block.into(),
-				warp_chunks_amount: warp_chunks_amount.map(|x| U256::from(x as u64)).map(Into::into),
-				warp_chunks_processed: warp_chunks_processed.map(|x| U256::from(x as u64)).map(Into::into),
-			};
-			Ok(SyncStatus::Info(info))
-		} else {
-			Ok(SyncStatus::None)
+		match full_sync_info(&*self.client, &*self.sync, &*self.snapshot) {
+			Some(info) => Ok(SyncStatus::Info(info)),
+			None => Ok(SyncStatus::None),
 		}
 	}
 
@@ -383,6 +359,42 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 		Box::new(future::done(res))
 	}
 
+	fn proof(&self, address: RpcH160, values: Vec<RpcH256>, num: Trailing<BlockNumber>) -> BoxFuture<EthAccount> {
+		let account_address: Address = address.clone().into();
+		let account_key = keccak(account_address);
+
+		let id = num.unwrap_or_default();
+		try_bf!(check_known(&*self.client, id.clone()));
+		let id: BlockId = id.into();
+
+		let (account_proof, account) = match self.client.prove_account(account_key, id) {
+			Some(res) => res,
+			None => return Box::new(future::err(errors::state_pruned())),
+		};
+
+		let storage_proof = values.into_iter().map(|key| {
+			let key: H256 = key.into();
+			match self.client.prove_storage(account_key, keccak(key), id) {
+				Some((proof, value)) => Ok(StorageProof {
+					key: U256::from(key).into(),
+					value: U256::from(value).into(),
+					proof: proof.into_iter().map(Bytes::new).collect(),
+				}),
+				None => Err(errors::state_pruned()),
+			}
+		}).collect::<Result<Vec<_>>>();
+
+		Box::new(future::done(storage_proof.map(|storage_proof| EthAccount {
+			address: address,
+			balance: account.balance.into(),
+			nonce: account.nonce.into(),
+			code_hash: account.code_hash.into(),
+			storage_hash: account.storage_root.into(),
+			account_proof: account_proof.into_iter().map(Bytes::new).collect(),
+			storage_proof: storage_proof,
+		})))
+	}
+
 	fn transaction_count(&self, address: RpcH160, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
 		let address: Address = RpcH160::into(address);
 
@@ -625,12 +637,16 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 		self.send_raw_transaction(raw)
 	}
 
-	fn call(&self, meta: Self::Metadata, request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<Bytes> {
+	fn call(&self, meta: Self::Metadata, mut request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<Bytes> {
+		let state_override = match request.state_override.take() {
+			Some(state_override) => Some(try_bf!(state_override.try_into())),
+			None => None,
+		};
 		let request = CallRequest::into(request);
 		let signed = try_bf!(fake_sign::sign_call(request, meta.is_dapp()));
 
 		let num = num.unwrap_or_default();
-		let result = self.client.call(&signed, Default::default(), num.into());
+		let result = self.client.call(&signed, Default::default(), num.into(), state_override.as_ref());
 
 		Box::new(future::done(result
 			.map(|b| b.output.into())
@@ -638,10 +654,14 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 		))
 	}
 
-	fn estimate_gas(&self, meta: Self::Metadata, request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
+	fn estimate_gas(&self, meta: Self::Metadata, mut request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
+		let state_override = match request.state_override.take() {
+			Some(state_override) => Some(try_bf!(state_override.try_into())),
+			None => None,
+		};
 		let request = CallRequest::into(request);
 		let signed = try_bf!(fake_sign::sign_call(request, meta.is_dapp()));
-		Box::new(future::done(self.client.estimate_gas(&signed, num.unwrap_or_default().into())
+		Box::new(future::done(self.client.estimate_gas(&signed, num.unwrap_or_default().into(), state_override.as_ref())
 			.map(Into::into)
 			.map_err(errors::call)
 		))
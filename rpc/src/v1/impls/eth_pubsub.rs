@@ -18,15 +18,18 @@
 
 use std::sync::{Arc, Weak};
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 use jsonrpc_core::{BoxFuture, Result, Error};
-use jsonrpc_core::futures::{self, Future, IntoFuture};
+use jsonrpc_core::futures::{self, Future, IntoFuture, Stream};
 use jsonrpc_macros::Trailing;
 use jsonrpc_macros::pubsub::{Sink, Subscriber};
 use jsonrpc_pubsub::SubscriptionId;
+use tokio_timer;
 
 use v1::helpers::{errors, limit_logs, Subscribers};
 use v1::helpers::light_fetch::LightFetch;
+use v1::helpers::sync_status::SyncStatusProvider;
 use v1::metadata::Metadata;
 use v1::traits::EthPubSub;
 use v1::types::{pubsub, RichHeader, Log};
@@ -45,20 +48,30 @@ use parking_lot::{RwLock, Mutex};
 
 type Client = Sink<pubsub::Result>;
 
+/// How often the sync status is checked for `syncing` subscribers.
+const SYNC_STATUS_POLL_INTERVAL_MS: u64 = 1000;
+
 /// Eth PubSub implementation.
 pub struct EthPubSubClient<C> {
 	handler: Arc<ChainNotificationHandler<C>>,
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	sync_subscribers: Arc<RwLock<Subscribers<Client>>>,
 }
 
 impl<C> EthPubSubClient<C> {
 	/// Creates new `EthPubSubClient`.
+	/// `syncing` subscriptions are rejected; use `with_sync_status` to serve them.
 	pub fn new(client: Arc<C>, remote: Remote) -> Self {
+		Self::build(client, None, remote)
+	}
+
+	fn build(client: Arc<C>, sync_status: Option<Arc<SyncStatusProvider>>, remote: Remote) -> Self {
 		let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let sync_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 
 		EthPubSubClient {
 			handler: Arc::new(ChainNotificationHandler {
@@ -67,21 +80,37 @@ impl<C> EthPubSubClient<C> {
 				heads_subscribers: heads_subscribers.clone(),
 				logs_subscribers: logs_subscribers.clone(),
 				transactions_subscribers: transactions_subscribers.clone(),
+				sync_subscribers: sync_subscribers.clone(),
+				sync_status,
+				last_sync_state: Mutex::new(None),
 			}),
 			heads_subscribers,
 			logs_subscribers,
 			transactions_subscribers,
+			sync_subscribers,
 		}
 	}
 
 	/// Creates new `EthPubSubCient` with deterministic subscription ids.
 	#[cfg(test)]
 	pub fn new_test(client: Arc<C>, remote: Remote) -> Self {
-		let client = Self::new(client, remote);
-		*client.heads_subscribers.write() = Subscribers::new_test();
-		*client.logs_subscribers.write() = Subscribers::new_test();
-		*client.transactions_subscribers.write() = Subscribers::new_test();
-		client
+		Self::new(client, remote).into_test()
+	}
+
+	/// Creates new `EthPubSubCient` serving `syncing` subscriptions with deterministic subscription ids.
+	/// The sync status is not polled; call `ChainNotificationHandler::poll_sync_status` instead.
+	#[cfg(test)]
+	pub fn new_test_with_sync_status(client: Arc<C>, sync_status: Arc<SyncStatusProvider>, remote: Remote) -> Self {
+		Self::build(client, Some(sync_status), remote).into_test()
+	}
+
+	#[cfg(test)]
+	fn into_test(self) -> Self {
+		*self.heads_subscribers.write() = Subscribers::new_test();
+		*self.logs_subscribers.write() = Subscribers::new_test();
+		*self.transactions_subscribers.write() = Subscribers::new_test();
+		*self.sync_subscribers.write() = Subscribers::new_test();
+		self
 	}
 
 	/// Returns a chain notification handler.
@@ -90,6 +119,34 @@ impl<C> EthPubSubClient<C> {
 	}
 }
 
+impl<C: Send + Sync + 'static> EthPubSubClient<C> {
+	/// Creates new `EthPubSubClient` which also serves `syncing` subscriptions,
+	/// periodically checking `sync_status` for changes.
+	pub fn with_sync_status(client: Arc<C>, sync_status: Arc<SyncStatusProvider>, remote: Remote) -> Self {
+		let pubsub = Self::build(client, Some(sync_status), remote.clone());
+		let handler = pubsub.handler();
+
+		let timer = tokio_timer::wheel()
+			.tick_duration(Duration::from_millis(500))
+			.build();
+
+		let interval = timer.interval(Duration::from_millis(SYNC_STATUS_POLL_INTERVAL_MS));
+		remote.spawn(interval
+			.map_err(|e| warn!("Polling timer error: {:?}", e))
+			.for_each(move |_| match handler.upgrade() {
+				Some(handler) => {
+					handler.poll_sync_status();
+					Ok(())
+				},
+				// the client is gone, stop polling.
+				None => Err(()),
+			})
+		);
+
+		pubsub
+	}
+}
+
 impl EthPubSubClient<LightFetch> {
 	/// Creates a new `EthPubSubClient` for `LightClient`.
 	pub fn light(
@@ -100,14 +157,14 @@ impl EthPubSubClient<LightFetch> {
 		remote: Remote,
 		gas_price_percentile: usize,
 	) -> Self {
-		let fetch = LightFetch {
+		let fetch = Arc::new(LightFetch {
 			client,
 			on_demand,
 			sync,
 			cache,
 			gas_price_percentile,
-		};
-		EthPubSubClient::new(Arc::new(fetch), remote)
+		});
+		EthPubSubClient::with_sync_status(fetch.clone(), fetch, remote)
 	}
 }
 
@@ -118,6 +175,9 @@ pub struct ChainNotificationHandler<C> {
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	sync_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	sync_status: Option<Arc<SyncStatusProvider>>,
+	last_sync_state: Mutex<Option<pubsub::SyncState>>,
 }
 
 impl<C> ChainNotificationHandler<C> {
@@ -180,6 +240,62 @@ impl<C> ChainNotificationHandler<C> {
 			}
 		}
 	}
+
+	/// Notify `syncing` subscribers if the sync status changed since the last check.
+	pub fn poll_sync_status(&self) {
+		let sync_status = match self.sync_status {
+			Some(ref sync_status) => sync_status,
+			None => return,
+		};
+
+		let subscribers = self.sync_subscribers.read();
+		let mut last_sync_state = self.last_sync_state.lock();
+
+		// forget the last state, so that the next subscriber learns the current one.
+		if subscribers.is_empty() {
+			*last_sync_state = None;
+			return;
+		}
+
+		let state = pubsub::SyncState::from(sync_status.sync_info());
+		Self::update_sync_state(&self.remote, &subscribers, &mut last_sync_state, state);
+	}
+
+	/// Add a `syncing` subscriber and send it the current sync status.
+	fn push_sync_subscriber(&self, subscriber: Subscriber<pubsub::Result>) {
+		let sync_status = match self.sync_status {
+			Some(ref sync_status) => sync_status,
+			None => return,
+		};
+
+		let mut subscribers = self.sync_subscribers.write();
+		let mut last_sync_state = self.last_sync_state.lock();
+
+		// bring the existing subscribers up to date first,
+		// so that the last state is the one the new subscriber gets.
+		let state = pubsub::SyncState::from(sync_status.sync_info());
+		Self::update_sync_state(&self.remote, &subscribers, &mut last_sync_state, state.clone());
+
+		if let Some(subscriber) = subscribers.push(subscriber) {
+			Self::notify(&self.remote, subscriber, pubsub::Result::SyncState(state));
+		}
+	}
+
+	fn update_sync_state(
+		remote: &Remote,
+		subscribers: &Subscribers<Client>,
+		last_sync_state: &mut Option<pubsub::SyncState>,
+		state: pubsub::SyncState,
+	) {
+		if last_sync_state.as_ref() == Some(&state) {
+			return;
+		}
+
+		for subscriber in subscribers.values() {
+			Self::notify(remote, subscriber, pubsub::Result::SyncState(state.clone()));
+		}
+		*last_sync_state = Some(state);
+	}
 }
 
 /// A light client wrapper struct.
@@ -289,9 +405,16 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 			(pubsub::Kind::NewPendingTransactions, _) => {
 				errors::invalid_params("newPendingTransactions", "Expected no parameters.")
 			},
-			_ => {
+			(pubsub::Kind::Syncing, None) if self.handler.sync_status.is_some() => {
+				self.handler.push_sync_subscriber(subscriber);
+				return;
+			},
+			(pubsub::Kind::Syncing, None) => {
 				errors::unimplemented(None)
 			},
+			(pubsub::Kind::Syncing, _) => {
+				errors::invalid_params("syncing", "Expected no parameters.")
+			},
 		};
 
 		let _ = subscriber.reject(error);
@@ -301,7 +424,8 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 		let res = self.heads_subscribers.write().remove(&id).is_some();
 		let res2 = self.logs_subscribers.write().remove(&id).is_some();
 		let res3 = self.transactions_subscribers.write().remove(&id).is_some();
+		let res4 = self.sync_subscribers.write().remove(&id).is_some();
 
-		Ok(res || res2 || res3)
+		Ok(res || res2 || res3 || res4)
 	}
 }
@@ -33,8 +33,8 @@ use ethcore::encoded;
 use ethcore::filter::Filter as EthcoreFilter;
 use ethcore::ids::BlockId;
 use ethsync::LightSync;
-use hash::{KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
-use ethereum_types::U256;
+use hash::{KECCAK_EMPTY, KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
+use ethereum_types::{H256, U256};
 use parking_lot::{RwLock, Mutex};
 use rlp::UntrustedRlp;
 use transaction::SignedTransaction;
@@ -43,10 +43,11 @@ use v1::impls::eth_filter::Filterable;
 use v1::helpers::{errors, limit_logs};
 use v1::helpers::{PollFilter, PollManager};
 use v1::helpers::light_fetch::{self, LightFetch};
+use v1::helpers::sync_status::light_sync_info;
 use v1::traits::Eth;
 use v1::types::{
-	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -212,21 +213,9 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	}
 
 	fn syncing(&self) -> Result<SyncStatus> {
-		if self.sync.is_major_importing() {
-			let chain_info = self.client.chain_info();
-			let current_block = U256::from(chain_info.best_block_number);
-			let highest_block = self.sync.highest_block().map(U256::from)
-				.unwrap_or_else(|| current_block.clone());
-
-			Ok(SyncStatus::Info(SyncInfo {
-				starting_block: U256::from(self.sync.start_block()).into(),
-				current_block: current_block.into(),
-				highest_block: highest_block.into(),
-				warp_chunks_amount: None,
-				warp_chunks_processed: None,
-			}))
-		} else {
-			Ok(SyncStatus::None)
+		match light_sync_info(&*self.client, &*self.sync) {
+			Some(info) => Ok(SyncStatus::Info(info)),
+			None => Ok(SyncStatus::None),
 		}
 	}
 
@@ -268,8 +257,28 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 			.map(|acc| acc.map_or(0.into(), |a| a.balance).into()))
 	}
 
-	fn storage_at(&self, _address: RpcH160, _key: RpcU256, _num: Trailing<BlockNumber>) -> BoxFuture<RpcH256> {
-		Box::new(future::err(errors::unimplemented(None)))
+	fn storage_at(&self, address: RpcH160, pos: RpcU256, num: Trailing<BlockNumber>) -> BoxFuture<RpcH256> {
+		let position: U256 = RpcU256::into(pos);
+		Box::new(self.fetcher().storage(address.into(), H256::from(position), num.unwrap_or_default().into()).map(Into::into))
+	}
+
+	fn proof(&self, address: RpcH160, values: Vec<RpcH256>, num: Trailing<BlockNumber>) -> BoxFuture<EthAccount> {
+		let keys: Vec<H256> = values.into_iter().map(Into::into).collect();
+
+		Box::new(self.fetcher().proof(address.clone().into(), keys.clone(), num.unwrap_or_default().into())
+			.map(move |(account_proof, account, storage)| EthAccount {
+				address: address,
+				balance: account.as_ref().map_or(0.into(), |a| a.balance).into(),
+				nonce: account.as_ref().map_or(0.into(), |a| a.nonce).into(),
+				code_hash: account.as_ref().map_or(KECCAK_EMPTY, |a| a.code_hash).into(),
+				storage_hash: account.as_ref().map_or(KECCAK_NULL_RLP, |a| a.storage_root).into(),
+				account_proof: account_proof.into_iter().map(Bytes::new).collect(),
+				storage_proof: keys.into_iter().zip(storage).map(|(key, (proof, value))| StorageProof {
+					key: U256::from(key).into(),
+					value: U256::from(value).into(),
+					proof: proof.into_iter().map(Bytes::new).collect(),
+				}).collect(),
+			}))
 	}
 
 	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>> {
@@ -23,7 +23,7 @@ use version::version_data;
 use crypto::{ecies, DEFAULT_MAC};
 use ethkey::{Brain, Generator};
 use ethstore::random_phrase;
-use ethsync::LightSyncProvider;
+use ethsync::{LightSyncProvider, ManageNetwork};
 use ethcore::account_provider::AccountProvider;
 use ethcore_logger::RotatingLogger;
 use node_health::{NodeHealth, Health};
@@ -196,6 +196,7 @@ impl Parity for ParityClient {
 			connected: peer_numbers.connected,
 			max: peer_numbers.max as u32,
 			peers: peers,
+			traffic: self.light_dispatch.sync.protocol_traffic().into_iter().map(|(p, t)| (p, t.into())).collect(),
 		})
 	}
 
@@ -254,7 +255,8 @@ impl Parity for ParityClient {
 	}
 
 	fn list_storage_keys(&self, _: H160, _: u64, _: Option<H256>, _: Trailing<BlockNumber>) -> Result<Option<Vec<H256>>> {
-		Err(errors::light_unimplemented(None))
+		// storage proofs only cover the requested keys, so the set of keys can't be enumerated.
+		Err(errors::light_unimplemented(Some("Storage keys can't be listed from storage proofs; use eth_getStorageAt for known keys.".into())))
 	}
 
 	fn encrypt_message(&self, key: H512, phrase: Bytes) -> Result<Bytes> {
@@ -342,6 +344,10 @@ impl Parity for ParityClient {
 		self.light_dispatch.sync.enode().ok_or_else(errors::network_disabled)
 	}
 
+	fn enr(&self) -> Result<String> {
+		self.light_dispatch.sync.enr().ok_or_else(errors::network_disabled)
+	}
+
 	fn consensus_capability(&self) -> Result<ConsensusCapability> {
 		Err(errors::light_unimplemented(None))
 	}
@@ -362,6 +368,7 @@ impl Parity for ParityClient {
 
 		Ok(ChainStatus {
 			block_gap: gap.map(|(x, y)| (x.into(), y.into())),
+			database_memory_used: self.light_dispatch.client.report().db_memory_used.map(U256::from),
 		})
 	}
 
@@ -408,6 +415,10 @@ impl Parity for ParityClient {
 		Box::new(self.fetcher().header(number.unwrap_or_default().into()).map(from_encoded))
 	}
 
+	fn finalized_block(&self) -> Result<Option<RichHeader>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn ipfs_cid(&self, content: Bytes) -> Result<String> {
 		ipfs::cid(content)
 	}
@@ -78,6 +78,18 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn set_transactions_per_sender_limit(&self, _limit: usize) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn set_local_transactions_share(&self, _percent: u8) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn set_transactions_eviction_policy(&self, _policy: String) -> Result<bool> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn set_tx_gas_limit(&self, _limit: U256) -> Result<bool> {
 		Err(errors::light_unimplemented(None))
 	}
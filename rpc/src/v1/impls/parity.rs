@@ -216,7 +216,8 @@ impl<C, M, U> Parity for ParityClient<C, M, U> where
 			active: sync_status.num_active_peers,
 			connected: sync_status.num_peers,
 			max: sync_status.current_max_peers(net_config.min_peers, net_config.max_peers),
-			peers: peers
+			peers: peers,
+			traffic: self.net.protocol_traffic().into_iter().map(|(p, t)| (p, t.into())).collect(),
 		})
 	}
 
@@ -358,6 +359,10 @@ impl<C, M, U> Parity for ParityClient<C, M, U> where
 		self.sync.enode().ok_or_else(errors::network_disabled)
 	}
 
+	fn enr(&self) -> Result<String> {
+		self.sync.enr().ok_or_else(errors::network_disabled)
+	}
+
 	fn consensus_capability(&self) -> Result<ConsensusCapability> {
 		Ok(self.updater.capability().into())
 	}
@@ -378,6 +383,7 @@ impl<C, M, U> Parity for ParityClient<C, M, U> where
 
 		Ok(ChainStatus {
 			block_gap: gap.map(|(x, y)| (x.into(), y.into())),
+			database_memory_used: self.client.report().db_memory_used.map(U256::from),
 		})
 	}
 
@@ -410,6 +416,16 @@ impl<C, M, U> Parity for ParityClient<C, M, U> where
 		}))
 	}
 
+	fn finalized_block(&self) -> Result<Option<RichHeader>> {
+		const EXTRA_INFO_PROOF: &'static str = "Object exists in in blockchain (fetched earlier), extra_info is always available if object exists; qed";
+
+		let id = BlockId::Finalized;
+		Ok(self.client.block_header(id).map(|encoded| RichHeader {
+			inner: encoded.into(),
+			extra_info: self.client.block_extra_info(id).expect(EXTRA_INFO_PROOF),
+		}))
+	}
+
 	fn ipfs_cid(&self, content: Bytes) -> Result<String> {
 		ipfs::cid(content)
 	}
@@ -417,10 +433,15 @@ impl<C, M, U> Parity for ParityClient<C, M, U> where
 	fn call(&self, meta: Self::Metadata, requests: Vec<CallRequest>, block: Trailing<BlockNumber>) -> Result<Vec<Bytes>> {
 		let requests = requests
 			.into_iter()
-			.map(|request| Ok((
-				fake_sign::sign_call(request.into(), meta.is_dapp())?,
-				Default::default()
-			)))
+			.map(|request| {
+				if request.state_override.is_some() {
+					return Err(errors::invalid_params("stateOverride", "State overrides are not supported by parity_call."));
+				}
+				Ok((
+					fake_sign::sign_call(request.into(), meta.is_dapp())?,
+					Default::default()
+				))
+			})
 			.collect::<Result<Vec<_>>>()?;
 
 		let block = block.unwrap_or_default();
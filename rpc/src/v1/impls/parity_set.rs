@@ -110,6 +110,26 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		Ok(true)
 	}
 
+	fn set_transactions_per_sender_limit(&self, limit: usize) -> Result<bool> {
+		self.miner.set_transactions_per_sender_limit(limit);
+		Ok(true)
+	}
+
+	fn set_local_transactions_share(&self, percent: u8) -> Result<bool> {
+		if percent > 100 {
+			return Err(errors::invalid_params("percent", "Share can't be greater than 100"));
+		}
+
+		self.miner.set_local_transactions_share(percent);
+		Ok(true)
+	}
+
+	fn set_transactions_eviction_policy(&self, policy: String) -> Result<bool> {
+		let policy = policy.parse().map_err(|e| errors::invalid_params("policy", e))?;
+		self.miner.set_transactions_eviction_policy(policy);
+		Ok(true)
+	}
+
 	fn set_tx_gas_limit(&self, limit: U256) -> Result<bool> {
 		self.miner.set_tx_gas_limit(limit.into());
 		Ok(true)
@@ -193,4 +193,18 @@ impl<D: Dispatcher + 'static> Personal for PersonalClient<D> {
 		warn!("Using deprecated personal_signAndSendTransaction, use personal_sendTransaction instead.");
 		self.send_transaction(meta, request, password)
 	}
+
+	fn replace_transaction(&self, meta: Metadata, hash: RpcH256, request: TransactionRequest, password: String) -> BoxFuture<RpcH256> {
+		let original = try_bf!(self.dispatcher.replaceable_transaction(&hash.into()));
+		let request = try_bf!(dispatch::replacement_request(&original, request.into()));
+
+		self.send_transaction(meta, request.into(), password)
+	}
+
+	fn cancel_transaction(&self, meta: Metadata, hash: RpcH256, password: String) -> BoxFuture<RpcH256> {
+		let original = try_bf!(self.dispatcher.replaceable_transaction(&hash.into()));
+		let request = try_bf!(dispatch::cancellation_request(&original));
+
+		self.send_transaction(meta, request.into(), password)
+	}
 }
@@ -204,6 +204,20 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningQueueClient<D> {
 			}
 		}))
 	}
+
+	fn replace_transaction(&self, meta: Metadata, hash: RpcH256, request: RpcTransactionRequest) -> BoxFuture<RpcH256> {
+		let original = try_bf!(self.dispatcher.replaceable_transaction(&hash.into()));
+		let request = try_bf!(dispatch::replacement_request(&original, request.into()));
+
+		EthSigning::send_transaction(self, meta, request.into())
+	}
+
+	fn cancel_transaction(&self, meta: Metadata, hash: RpcH256) -> BoxFuture<RpcH256> {
+		let original = try_bf!(self.dispatcher.replaceable_transaction(&hash.into()));
+		let request = try_bf!(dispatch::cancellation_request(&original));
+
+		EthSigning::send_transaction(self, meta, request.into())
+	}
 }
 
 impl<D: Dispatcher + 'static> EthSigning for SigningQueueClient<D> {
@@ -136,4 +136,18 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningUnsafeClient<D> {
 		// We don't support this in non-signer mode.
 		Err(errors::signer_disabled())
 	}
+
+	fn replace_transaction(&self, meta: Metadata, hash: RpcH256, request: RpcTransactionRequest) -> BoxFuture<RpcH256> {
+		let original = try_bf!(self.dispatcher.replaceable_transaction(&hash.into()));
+		let request = try_bf!(dispatch::replacement_request(&original, request.into()));
+
+		EthSigning::send_transaction(self, meta, request.into())
+	}
+
+	fn cancel_transaction(&self, meta: Metadata, hash: RpcH256) -> BoxFuture<RpcH256> {
+		let original = try_bf!(self.dispatcher.replaceable_transaction(&hash.into()));
+		let request = try_bf!(dispatch::cancellation_request(&original));
+
+		EthSigning::send_transaction(self, meta, request.into())
+	}
 }
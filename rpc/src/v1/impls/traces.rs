@@ -79,13 +79,17 @@ impl<C> Traces for TracesClient<C> where C: MiningBlockChainClient + 'static {
 			.map(LocalizedTrace::from))
 	}
 
-	fn call(&self, meta: Self::Metadata, request: CallRequest, flags: TraceOptions, block: Trailing<BlockNumber>) -> Result<TraceResults> {
+	fn call(&self, meta: Self::Metadata, mut request: CallRequest, flags: TraceOptions, block: Trailing<BlockNumber>) -> Result<TraceResults> {
 		let block = block.unwrap_or_default();
 
+		let state_override = match request.state_override.take() {
+			Some(state_override) => Some(state_override.try_into()?),
+			None => None,
+		};
 		let request = CallRequest::into(request);
 		let signed = fake_sign::sign_call(request, meta.is_dapp())?;
 
-		self.client.call(&signed, to_call_analytics(flags), block.into())
+		self.client.call(&signed, to_call_analytics(flags), block.into(), state_override.as_ref())
 			.map(TraceResults::from)
 			.map_err(errors::call)
 	}
@@ -95,6 +99,9 @@ impl<C> Traces for TracesClient<C> where C: MiningBlockChainClient + 'static {
 
 		let requests = requests.into_iter()
 			.map(|(request, flags)| {
+				if request.state_override.is_some() {
+					return Err(errors::invalid_params("stateOverride", "State overrides are not supported by trace_callMany."));
+				}
 				let request = CallRequest::into(request);
 				let signed = fake_sign::sign_call(request, meta.is_dapp())?;
 				Ok((signed, to_call_analytics(flags)))
@@ -112,7 +119,7 @@ impl<C> Traces for TracesClient<C> where C: MiningBlockChainClient + 'static {
 		let tx = UntrustedRlp::new(&raw_transaction.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
 		let signed = SignedTransaction::new(tx).map_err(errors::transaction)?;
 
-		self.client.call(&signed, to_call_analytics(flags), block.into())
+		self.client.call(&signed, to_call_analytics(flags), block.into(), None)
 			.map(TraceResults::from)
 			.map_err(errors::call)
 	}
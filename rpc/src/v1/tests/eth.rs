@@ -33,7 +33,7 @@ use ethjson::state::test::ForkSpec;
 use io::IoChannel;
 use kvdb_memorydb;
 use miner::external::ExternalMiner;
-use miner::transaction_queue::PrioritizationStrategy;
+use miner::transaction_queue::{PrioritizationStrategy, EvictionPolicy};
 use parking_lot::Mutex;
 
 use jsonrpc_core::IoHandler;
@@ -68,6 +68,9 @@ fn miner_service(spec: &Spec, accounts: Arc<AccountProvider>) -> Arc<Miner> {
 			tx_queue_size: 1024,
 			tx_gas_limit: !U256::zero(),
 			tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
+			tx_queue_per_sender: None,
+			tx_queue_local_share: 0,
+			tx_queue_eviction: EvictionPolicy::LowestPriority,
 			tx_queue_gas_limit: GasLimit::None,
 			tx_queue_banning: Banning::Disabled,
 			tx_queue_memory_limit: None,
@@ -205,6 +208,30 @@ fn eth_get_balance() {
 	assert_eq!(tester.handler.handle_request_sync(req_new_acc).unwrap(), res_new_acc);
 }
 
+#[test]
+fn eth_get_proof() {
+	use serde_json::{self, Value};
+
+	let chain = extract_chain!("BlockchainTests/bcWalletTest/wallet2outOf3txs");
+	let tester = EthTester::from_chain(&chain);
+	let req = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getProof",
+		"params": ["0xaaaf5374fce5edbc8e2a8697c15331677e6ebaaa", ["0x0000000000000000000000000000000000000000000000000000000000000000"], "latest"],
+		"id": 1
+	}"#;
+
+	let res: Value = serde_json::from_str(&tester.handler.handle_request_sync(req).unwrap()).unwrap();
+	let account = &res["result"];
+	assert_eq!(account["address"], "0xaaaf5374fce5edbc8e2a8697c15331677e6ebaaa");
+	assert_eq!(account["balance"], "0x9");
+	assert_eq!(account["codeHash"], "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+	assert_eq!(account["storageHash"], "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+	assert!(!account["accountProof"].as_array().unwrap().is_empty());
+	assert_eq!(account["storageProof"][0]["key"], "0x0");
+	assert_eq!(account["storageProof"][0]["value"], "0x0");
+}
+
 #[test]
 fn eth_block_number() {
 	let chain = extract_chain!("BlockchainTests/bcGasPricerTest/RPC_API_Test");
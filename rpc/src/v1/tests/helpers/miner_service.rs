@@ -25,7 +25,7 @@ use ethcore::block::ClosedBlock;
 use ethcore::client::MiningBlockChainClient;
 use ethcore::error::Error;
 use ethcore::header::BlockNumber;
-use ethcore::miner::{MinerService, MinerStatus};
+use ethcore::miner::{MinerService, MinerStatus, EvictionPolicy};
 use miner::local_transactions::Status as LocalTransactionStatus;
 use ethcore::receipt::{Receipt, RichReceipt};
 use parking_lot::{RwLock, Mutex};
@@ -53,6 +53,9 @@ pub struct TestMinerService {
 	author: RwLock<Address>,
 	extra_data: RwLock<Bytes>,
 	limit: RwLock<usize>,
+	per_sender_limit: RwLock<usize>,
+	local_share: RwLock<u8>,
+	eviction_policy: RwLock<EvictionPolicy>,
 	tx_gas_limit: RwLock<U256>,
 }
 
@@ -71,6 +74,9 @@ impl Default for TestMinerService {
 			password: RwLock::new(String::new()),
 			extra_data: RwLock::new(vec![1, 2, 3, 4]),
 			limit: RwLock::new(1024),
+			per_sender_limit: RwLock::new(16),
+			local_share: RwLock::new(0),
+			eviction_policy: RwLock::new(EvictionPolicy::LowestPriority),
 			tx_gas_limit: RwLock::new(!U256::zero()),
 		}
 	}
@@ -143,6 +149,30 @@ impl MinerService for TestMinerService {
 		*self.limit.read()
 	}
 
+	fn transactions_per_sender_limit(&self) -> usize {
+		*self.per_sender_limit.read()
+	}
+
+	fn set_transactions_per_sender_limit(&self, limit: usize) {
+		*self.per_sender_limit.write() = limit;
+	}
+
+	fn local_transactions_share(&self) -> u8 {
+		*self.local_share.read()
+	}
+
+	fn set_local_transactions_share(&self, percent: u8) {
+		*self.local_share.write() = percent;
+	}
+
+	fn transactions_eviction_policy(&self) -> EvictionPolicy {
+		*self.eviction_policy.read()
+	}
+
+	fn set_transactions_eviction_policy(&self, policy: EvictionPolicy) {
+		*self.eviction_policy.write() = policy;
+	}
+
 	fn author(&self) -> Address {
 		*self.author.read()
 	}
@@ -54,6 +54,9 @@ impl TestSyncProvider {
 				num_snapshot_chunks: 0,
 				snapshot_chunks_done: 0,
 				last_imported_old_block_number: None,
+				fast_sync_pivot_number: None,
+				state_nodes_done: 0,
+				state_nodes_pending: 0,
 			}),
 		}
 	}
@@ -79,6 +82,9 @@ impl SyncProvider for TestSyncProvider {
 				capabilities: vec!["eth/62".to_owned(), "eth/63".to_owned()],
 				remote_address: "127.0.0.1:7777".to_owned(),
 				local_address: "127.0.0.1:8888".to_owned(),
+				reputation: Some(10),
+				ingress: 2048,
+				egress: 1024,
 				eth_info: Some(EthProtocolInfo {
 					version: 62,
 					difficulty: Some(40.into()),
@@ -92,6 +98,9 @@ impl SyncProvider for TestSyncProvider {
 				capabilities: vec!["eth/63".to_owned(), "eth/64".to_owned()],
 				remote_address: "Handshake".to_owned(),
 				local_address: "127.0.0.1:3333".to_owned(),
+				reputation: None,
+				ingress: 0,
+				egress: 0,
 				eth_info: Some(EthProtocolInfo {
 					version: 64,
 					difficulty: None,
@@ -106,6 +115,10 @@ impl SyncProvider for TestSyncProvider {
 		None
 	}
 
+	fn enr(&self) -> Option<String> {
+		None
+	}
+
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats> {
 		map![
 			1.into() => TransactionStats {
@@ -0,0 +1,74 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::executed::CallError;
+use ethcore::trace::{DebugTrace, CallFrame, CallFrameType};
+use ethcore::client::TestBlockChainClient;
+
+use jsonrpc_core::IoHandler;
+use v1::{Debug, DebugClient};
+
+fn io() -> (Arc<TestBlockChainClient>, IoHandler) {
+	let client = Arc::new(TestBlockChainClient::new());
+	let mut io = IoHandler::default();
+	io.extend_with(DebugClient::new(&client).to_delegate());
+	(client, io)
+}
+
+#[test]
+fn rpc_debug_trace_transaction_call_tracer() {
+	let (client, io) = io();
+	client.set_debug_trace_result(Ok(DebugTrace::Call(CallFrame {
+		frame_type: CallFrameType::Call,
+		from: 0xf.into(),
+		to: Some(0x10.into()),
+		value: 0x1.into(),
+		gas: 0x100.into(),
+		gas_used: Some(0x10.into()),
+		input: vec![1, 2, 3],
+		output: Some(vec![]),
+		error: None,
+		calls: vec![],
+	})));
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"tracer":"callTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"type":"CALL","from":"0x000000000000000000000000000000000000000f","to":"0x0000000000000000000000000000000000000010","value":"0x1","gas":"0x100","gasUsed":"0x10","input":"0x010203","output":"0x"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_transaction_unknown_tracer() {
+	let (_client, io) = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"tracer":"jsTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: tracer","data":"\"Unknown tracer: jsTracer\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_transaction_state_pruned() {
+	let (client, io) = io();
+	client.set_debug_trace_result(Err(CallError::StatePruned));
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -15,12 +15,15 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
+use parking_lot::Mutex;
 
 use jsonrpc_core::MetaIoHandler;
 use jsonrpc_core::futures::{self, Stream, Future};
 use jsonrpc_pubsub::Session;
 
 use v1::{EthPubSub, EthPubSubClient, Metadata};
+use v1::helpers::sync_status::SyncStatusProvider;
+use v1::types::SyncInfo;
 
 use ethcore::client::{TestBlockChainClient, EachBlockWith, ChainNotify};
 use parity_reactor::EventLoop;
@@ -218,3 +221,78 @@ fn should_return_unimplemented() {
 	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["syncing"], "id": 1}"#;
 	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
 }
+
+struct TestSyncStatus(Mutex<Option<SyncInfo>>);
+
+impl SyncStatusProvider for TestSyncStatus {
+	fn sync_info(&self) -> Option<SyncInfo> {
+		self.0.lock().clone()
+	}
+}
+
+#[test]
+fn should_subscribe_to_syncing() {
+	// given
+	let el = EventLoop::spawn();
+	let client = TestBlockChainClient::new();
+	let sync_status = Arc::new(TestSyncStatus(Mutex::new(Some(SyncInfo {
+		starting_block: 0.into(),
+		current_block: 1.into(),
+		highest_block: 10.into(),
+		warp_chunks_amount: None,
+		warp_chunks_processed: None,
+	}))));
+	let pubsub = EthPubSubClient::new_test_with_sync_status(Arc::new(client), sync_status.clone(), el.remote());
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["syncing"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// Current status is sent on subscribe
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"status":{"currentBlock":"0x1","highestBlock":"0xa","startingBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null},"syncing":true},"subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// Unchanged status is not sent again, progress and the end of sync are
+	handler.poll_sync_status();
+	sync_status.0.lock().as_mut().unwrap().current_block = 5.into();
+	handler.poll_sync_status();
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"status":{"currentBlock":"0x5","highestBlock":"0xa","startingBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null},"syncing":true},"subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// A later subscriber gets the current status as well
+	let mut metadata2 = Metadata::default();
+	let (sender2, receiver2) = futures::sync::mpsc::channel(8);
+	metadata2.session = Some(Arc::new(Session::new(sender2)));
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["syncing"], "id": 1}"#;
+	assert!(io.handle_request_sync(request, metadata2).is_some());
+
+	let (res, _receiver2) = receiver2.into_future().wait().unwrap();
+	let res: ::serde_json::Value = ::serde_json::from_str(&res.unwrap()).unwrap();
+	assert_eq!(res["params"]["result"]["status"]["currentBlock"], "0x5");
+
+	*sync_status.0.lock() = None;
+	handler.poll_sync_status();
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"syncing":false},"subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// And unsubscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_unsubscribe", "params": ["0x416d77337e24399d"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata), Some(response.to_owned()));
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
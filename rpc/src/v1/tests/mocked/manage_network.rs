@@ -14,7 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethsync::{ManageNetwork, NetworkConfiguration};
+use std::collections::BTreeMap;
+use ethsync::{ManageNetwork, NetworkConfiguration, TrafficStats};
 use self::ethcore_network::{ProtocolId, NetworkContext};
 
 extern crate ethcore_network;
@@ -30,5 +31,11 @@ impl ManageNetwork for TestManageNetwork {
 	fn start_network(&self) {}
 	fn stop_network(&self) {}
 	fn network_config(&self) -> NetworkConfiguration { NetworkConfiguration::new_local() }
+	fn network_traffic(&self) -> TrafficStats { TrafficStats::default() }
+	fn protocol_traffic(&self) -> BTreeMap<String, TrafficStats> {
+		let mut traffic = BTreeMap::new();
+		traffic.insert("eth".into(), TrafficStats { ingress: 4096, egress: 1024 });
+		traffic
+	}
 	fn with_proto_context(&self, _: ProtocolId, _: &mut FnMut(&NetworkContext)) { }
 }
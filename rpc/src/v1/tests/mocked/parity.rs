@@ -16,14 +16,22 @@
 
 use std::sync::Arc;
 use ethcore::account_provider::AccountProvider;
-use ethcore::client::{TestBlockChainClient, Executed};
-use ethcore::miner::LocalTransactionStatus;
+use ethcore::block::IsBlock;
+use ethcore::client::{TestBlockChainClient, Executed, BlockChainClient, MiningBlockChainClient, Client, ClientConfig};
+use ethcore::ids::BlockId;
+use ethcore::miner::{LocalTransactionStatus, Miner};
+use ethcore::spec::Spec;
 use ethcore_logger::RotatingLogger;
 use ethstore::ethkey::{Generator, Random};
 use ethsync::ManageNetwork;
 use node_health::{self, NodeHealth};
 use parity_reactor;
-use ethereum_types::{Address, U256, H256};
+use ethereum_types::{Address, U256, H256, H520};
+use hash::keccak;
+use io::IoChannel;
+use kvdb_memorydb;
+use rlp::{self, RlpStream};
+use serde_json::{self, Value};
 
 use jsonrpc_core::IoHandler;
 use v1::{Parity, ParityClient};
@@ -315,6 +323,17 @@ fn rpc_parity_net_chain() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_enr() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_enr", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Network is disabled or not yet up."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_chain() {
 	let deps = Dependencies::new();
@@ -332,7 +351,7 @@ fn rpc_parity_net_peers() {
 	let io = deps.default_client();
 
 	let request = r#"{"jsonrpc": "2.0", "method": "parity_netPeers", "params":[], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"active":0,"connected":120,"max":50,"peers":[{"caps":["eth/62","eth/63"],"id":"node1","name":"Parity/1","network":{"localAddress":"127.0.0.1:8888","remoteAddress":"127.0.0.1:7777"},"protocols":{"eth":{"difficulty":"0x28","head":"0000000000000000000000000000000000000000000000000000000000000032","version":62},"pip":null}},{"caps":["eth/63","eth/64"],"id":null,"name":"Parity/2","network":{"localAddress":"127.0.0.1:3333","remoteAddress":"Handshake"},"protocols":{"eth":{"difficulty":null,"head":"000000000000000000000000000000000000000000000000000000000000003c","version":64},"pip":null}}]},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"active":0,"connected":120,"max":50,"peers":[{"caps":["eth/62","eth/63"],"id":"node1","name":"Parity/1","network":{"egress":1024,"ingress":2048,"localAddress":"127.0.0.1:8888","remoteAddress":"127.0.0.1:7777"},"protocols":{"eth":{"difficulty":"0x28","head":"0000000000000000000000000000000000000000000000000000000000000032","version":62},"pip":null},"reputation":10},{"caps":["eth/63","eth/64"],"id":null,"name":"Parity/2","network":{"egress":0,"ingress":0,"localAddress":"127.0.0.1:3333","remoteAddress":"Handshake"},"protocols":{"eth":{"difficulty":null,"head":"000000000000000000000000000000000000000000000000000000000000003c","version":64},"pip":null},"reputation":null}],"traffic":{"eth":{"egress":1024,"ingress":4096}}},"id":1}"#;
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -504,7 +523,7 @@ fn rpc_parity_chain_status() {
 	*deps.client.first_block.write() = Some((H256::from(U256::from(1234)), 3333));
 
 	let request = r#"{"jsonrpc": "2.0", "method": "parity_chainStatus", "params":[], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"blockGap":["0x6","0xd05"]},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockGap":["0x6","0xd05"],"databaseMemoryUsed":null},"id":1}"#;
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -568,6 +587,79 @@ fn rpc_parity_call() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_finalized_block() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_finalizedBlock", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_finalized_block_follows_tendermint_commits() {
+	let deps = Dependencies::new();
+	let spec = Spec::new_test_tendermint();
+	let client = Client::new(
+		ClientConfig::default(),
+		&spec,
+		Arc::new(kvdb_memorydb::create(::ethcore::db::NUM_COLUMNS.unwrap_or(0))),
+		Arc::new(Miner::with_spec(&spec)),
+		IoChannel::disconnected(),
+	).unwrap();
+
+	let mut io = IoHandler::default();
+	io.extend_with(ParityClient::new(
+		client.clone(),
+		deps.miner.clone(),
+		deps.sync.clone(),
+		deps.updater.clone(),
+		deps.network.clone(),
+		deps.health.clone(),
+		Some(deps.accounts.clone()),
+		deps.logger.clone(),
+		deps.settings.clone(),
+		None,
+		deps.dapps_address.clone(),
+		deps.ws_address.clone(),
+	).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_finalizedBlock", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	// Accounts "0" and "1" are the validators of the test spec.
+	let validators = ["0", "1"].iter()
+		.map(|acc| {
+			let address = deps.accounts.insert_account(keccak(acc).into(), acc).unwrap();
+			deps.accounts.unlock_account_permanently(address, (*acc).into()).unwrap();
+			address
+		})
+		.collect::<Vec<_>>();
+
+	let block = client.prepare_open_block(validators[0], (3141562.into(), 31415620.into()), vec![]).close_and_lock();
+	// Commit seal: view, no proposal signature, precommits of (height, view, step, block hash).
+	let mut vote_info = RlpStream::new_list(4);
+	vote_info.append(&block.header().number()).append(&0usize).append(&2u8).append(&block.header().bare_hash());
+	let vote_hash = keccak(vote_info.out());
+	let precommits = validators.iter()
+		.map(|validator| H520::from(deps.accounts.sign(*validator, None, vote_hash).unwrap()))
+		.collect::<Vec<_>>();
+	let seal = vec![rlp::encode(&0usize).into_vec(), rlp::NULL_RLP.to_vec(), rlp::encode_list(&precommits).into_vec()];
+	let block = block.seal(client.engine(), seal).unwrap();
+
+	client.import_block(block.rlp_bytes()).unwrap();
+	client.flush_queue();
+	client.import_verified_blocks();
+	assert_eq!(client.block_hash(BlockId::Finalized), Some(block.header().hash()));
+
+	let res: Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+	assert_eq!(res["result"]["number"], "0x1");
+	assert_eq!(res["result"]["hash"], format!("0x{:?}", block.header().hash()));
+}
+
 #[test]
 fn rpc_parity_node_health() {
 	let deps = Dependencies::new();
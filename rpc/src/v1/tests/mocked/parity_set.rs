@@ -19,7 +19,7 @@ use std::str::FromStr;
 use rustc_hex::FromHex;
 use ethereum_types::{U256, Address};
 
-use ethcore::miner::MinerService;
+use ethcore::miner::{MinerService, EvictionPolicy};
 use ethcore::client::TestBlockChainClient;
 use ethsync::ManageNetwork;
 
@@ -195,6 +195,62 @@ fn rpc_parity_set_transactions_limit() {
 	assert_eq!(miner.transactions_limit(), 10_240_240);
 }
 
+#[test]
+fn rpc_parity_set_transactions_per_sender_limit() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setTransactionsPerSenderLimit", "params":[64], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(miner.transactions_per_sender_limit(), 64);
+}
+
+#[test]
+fn rpc_parity_set_local_transactions_share() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setLocalTransactionsShare", "params":[25], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(miner.local_transactions_share(), 25);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setLocalTransactionsShare", "params":[101], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: percent","data":"\"Share can't be greater than 100\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(miner.local_transactions_share(), 25);
+}
+
+#[test]
+fn rpc_parity_set_transactions_eviction_policy() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setTransactionsEvictionPolicy", "params":["future"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(miner.transactions_eviction_policy(), EvictionPolicy::FutureFirst);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setTransactionsEvictionPolicy", "params":["newest"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: policy","data":"\"Invalid eviction policy: newest\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+	assert_eq!(miner.transactions_eviction_policy(), EvictionPolicy::FutureFirst);
+}
+
 #[test]
 fn rpc_parity_set_hash_content() {
 	let miner = miner_service();
@@ -21,9 +21,10 @@ use bytes::ToPretty;
 use ethereum_types::{U256, Address};
 use ethcore::account_provider::AccountProvider;
 use ethcore::client::TestBlockChainClient;
+use ethcore::miner::LocalTransactionStatus;
 use jsonrpc_core::IoHandler;
 use parking_lot::Mutex;
-use transaction::{Action, Transaction};
+use transaction::{Action, Transaction, SignedTransaction};
 
 use v1::{PersonalClient, Personal, Metadata};
 use v1::helpers::nonce;
@@ -325,3 +326,80 @@ fn should_unlock_account_permanently() {
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
 	assert!(tester.accounts.sign(address, None, Default::default()).is_ok(), "Should unlock account.");
 }
+
+fn original_transaction(tester: &PersonalTester, address: Address) -> SignedTransaction {
+	let t = Transaction {
+		nonce: U256::from(5),
+		gas_price: U256::from(0x1000),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(0x9184e72au64),
+		data: vec![1, 2, 3],
+	};
+	let signature = tester.accounts.sign(address, Some("password123".into()), t.hash(None)).unwrap();
+	SignedTransaction::new(t.with_signature(signature, None)).unwrap()
+}
+
+#[test]
+fn should_replace_dropped_transaction_without_gas_price_bump() {
+	// given
+	let tester = setup();
+	let address = tester.accounts.new_account("password123").unwrap();
+	let original = original_transaction(&tester, address);
+	tester.miner.local_transactions.lock().insert(original.hash(), LocalTransactionStatus::Dropped(original.clone()));
+
+	let t = Transaction {
+		nonce: U256::from(5),
+		gas_price: U256::from(0x1000),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(1),
+		data: vec![1, 2, 3],
+	};
+	let signature = tester.accounts.sign(address, Some("password123".into()), t.hash(None)).unwrap();
+	let t = t.with_signature(signature, None);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "personal_replaceTransaction",
+		"params": [""#.to_owned() + format!("0x{:?}", original.hash()).as_ref() + r#"", {"value": "0x1"}, "password123"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:?}", t.hash()).as_ref() + r#"","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+}
+
+#[test]
+fn should_cancel_queued_transaction_with_gas_price_bump() {
+	// given
+	let tester = setup();
+	let address = tester.accounts.new_account("password123").unwrap();
+	let original = original_transaction(&tester, address);
+	tester.miner.pending_transactions.lock().insert(original.hash(), original.clone());
+
+	let t = Transaction {
+		nonce: U256::from(5),
+		gas_price: U256::from(0x1200),
+		gas: U256::from(21_000),
+		action: Action::Call(address),
+		value: U256::zero(),
+		data: vec![],
+	};
+	let signature = tester.accounts.sign(address, Some("password123".into()), t.hash(None)).unwrap();
+	let t = t.with_signature(signature, None);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "personal_cancelTransaction",
+		"params": [""#.to_owned() + format!("0x{:?}", original.hash()).as_ref() + r#"", "password123"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:?}", t.hash()).as_ref() + r#"","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+}
@@ -478,3 +478,156 @@ fn should_compose_transaction() {
 	let res = tester.io.handle_request(&request).wait().unwrap();
 	assert_eq!(res, Some(response.to_owned()));
 }
+
+fn queue_original_transaction(tester: &SigningTester, acc: Address) -> SignedTransaction {
+	let t = Transaction {
+		nonce: U256::from(5),
+		gas_price: U256::from(0x1000),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(0x9184e72au64),
+		data: vec![1, 2, 3],
+	};
+	let signature = tester.accounts.sign(acc, None, t.hash(None)).unwrap();
+	let t = SignedTransaction::new(t.with_signature(signature, None)).unwrap();
+	tester.miner.pending_transactions.lock().insert(t.hash(), t.clone());
+	t
+}
+
+#[test]
+fn should_replace_transaction_with_minimal_bump() {
+	// given
+	let tester = eth_signing();
+	let acc = tester.accounts.new_account("test").unwrap();
+	tester.accounts.unlock_account_permanently(acc, "test".into()).unwrap();
+	let original = queue_original_transaction(&tester, acc);
+
+	let t = Transaction {
+		nonce: U256::from(5),
+		gas_price: U256::from(0x1200),
+		gas: U256::from(0x76c0),
+		action: Action::Call(Address::from_str("d46e8dd67c5d32be8058bb8eb970870f07244567").unwrap()),
+		value: U256::from(1),
+		data: vec![1, 2, 3],
+	};
+	let signature = tester.accounts.sign(acc, None, t.hash(None)).unwrap();
+	let t = t.with_signature(signature, None);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_replaceTransaction",
+		"params": [""#.to_owned() + format!("0x{:?}", original.hash()).as_ref() + r#"", {"value": "0x1"}],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:?}", t.hash()).as_ref() + r#"","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn should_cancel_transaction() {
+	// given
+	let tester = eth_signing();
+	let acc = tester.accounts.new_account("test").unwrap();
+	tester.accounts.unlock_account_permanently(acc, "test".into()).unwrap();
+	let original = queue_original_transaction(&tester, acc);
+
+	let t = Transaction {
+		nonce: U256::from(5),
+		gas_price: U256::from(0x1200),
+		gas: U256::from(21_000),
+		action: Action::Call(acc),
+		value: U256::zero(),
+		data: vec![],
+	};
+	let signature = tester.accounts.sign(acc, None, t.hash(None)).unwrap();
+	let t = t.with_signature(signature, None);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_cancelTransaction",
+		"params": [""#.to_owned() + format!("0x{:?}", original.hash()).as_ref() + r#""],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:?}", t.hash()).as_ref() + r#"","id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
+
+#[test]
+fn should_add_replacement_to_queue_if_account_is_locked() {
+	// given
+	let tester = eth_signing();
+	let acc = tester.accounts.new_account("test").unwrap();
+	let original = queue_original_transaction(&tester, acc);
+	assert_eq!(tester.signer.requests().len(), 0);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_cancelTransaction",
+		"params": [""#.to_owned() + format!("0x{:?}", original.hash()).as_ref() + r#""],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0000000000000000000000000000000000000000000000000000000000000000","id":1}"#;
+
+	// then
+	let promise = tester.io.handle_request(&request);
+
+	// the future must be polled at least once before request is queued.
+	let signer = tester.signer.clone();
+	::std::thread::spawn(move || loop {
+		if signer.requests().len() == 1 {
+			// respond
+			signer.request_confirmed(1.into(), Ok(ConfirmationResponse::SendTransaction(0.into())));
+			break
+		}
+		::std::thread::sleep(Duration::from_millis(100))
+	});
+
+	let res = promise.wait().unwrap();
+	assert_eq!(res, Some(response.to_owned()));
+}
+
+#[test]
+fn should_reject_replacement_with_insufficient_bump() {
+	// given
+	let tester = eth_signing();
+	let acc = tester.accounts.new_account("test").unwrap();
+	let original = queue_original_transaction(&tester, acc);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_replaceTransaction",
+		"params": [""#.to_owned() + format!("0x{:?}", original.hash()).as_ref() + r#"", {"gasPrice": "0x11ff"}],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32010,"message":"Transaction gas price is too low. There is another transaction with same nonce in the queue. Try increasing the gas price or incrementing the nonce."},"id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+	assert_eq!(tester.signer.requests().len(), 0);
+}
+
+#[test]
+fn should_not_replace_unknown_transaction() {
+	// given
+	let tester = eth_signing();
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_cancelTransaction",
+		"params": ["0x0000000000000000000000000000000000000000000000000000000000000005"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32010,"message":"Transaction not found. Only transactions still in the queue or sent from this node can be replaced."},"id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.to_owned()));
+}
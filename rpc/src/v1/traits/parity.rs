@@ -185,6 +185,10 @@ build_rpc_trait! {
 		#[rpc(name = "parity_enode")]
 		fn enode(&self) -> Result<String>;
 
+		/// Get the signed node record (EIP-778) of this node.
+		#[rpc(name = "parity_enr")]
+		fn enr(&self) -> Result<String>;
+
 		/// Returns information on current consensus capability.
 		#[rpc(name = "parity_consensusCapability")]
 		fn consensus_capability(&self) -> Result<ConsensusCapability>;
@@ -210,6 +214,11 @@ build_rpc_trait! {
 		#[rpc(name = "parity_getBlockHeaderByNumber")]
 		fn block_header(&self, Trailing<BlockNumber>) -> BoxFuture<RichHeader>;
 
+		/// Get header of the latest block which is final under the consensus rules.
+		/// Returns `null` if the engine gives no finality guarantees or no block is final yet.
+		#[rpc(name = "parity_finalizedBlock")]
+		fn finalized_block(&self) -> Result<Option<RichHeader>>;
+
 		/// Get IPFS CIDv0 given protobuf encoded bytes.
 		#[rpc(name = "parity_cidV0")]
 		fn ipfs_cid(&self, Bytes) -> Result<String>;
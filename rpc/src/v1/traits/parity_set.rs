@@ -51,6 +51,19 @@ build_rpc_trait! {
 		#[rpc(name = "parity_setTransactionsLimit")]
 		fn set_transactions_limit(&self, usize) -> Result<bool>;
 
+		/// Sets the maximal number of transactions from a single sender in the transaction queue.
+		#[rpc(name = "parity_setTransactionsPerSenderLimit")]
+		fn set_transactions_per_sender_limit(&self, usize) -> Result<bool>;
+
+		/// Sets the share of the transaction queue (in percent) reserved for local transactions.
+		#[rpc(name = "parity_setLocalTransactionsShare")]
+		fn set_local_transactions_share(&self, u8) -> Result<bool>;
+
+		/// Sets the order in which transactions are dropped when the transaction queue is full.
+		/// Argument must be one of: "priority", "future", "oldest".
+		#[rpc(name = "parity_setTransactionsEvictionPolicy")]
+		fn set_transactions_eviction_policy(&self, String) -> Result<bool>;
+
 		/// Sets the maximum amount of gas a single transaction may consume.
 		#[rpc(name = "parity_setMaxTransactionGas")]
 		fn set_tx_gas_limit(&self, U256) -> Result<bool>;
@@ -17,7 +17,7 @@
 //! ParitySigning rpc interface.
 use jsonrpc_core::{BoxFuture, Result};
 
-use v1::types::{U256, H160, Bytes, ConfirmationResponse, TransactionRequest, Either};
+use v1::types::{U256, H160, H256, Bytes, ConfirmationResponse, TransactionRequest, Either};
 
 build_rpc_trait! {
 	/// Signing methods implementation.
@@ -48,5 +48,17 @@ build_rpc_trait! {
 		/// First parameter is the address with which it is encrypted, second is the ciphertext.
 		#[rpc(meta, name = "parity_decryptMessage")]
 		fn decrypt_message(&self, Self::Metadata, H160, Bytes) -> BoxFuture<Bytes>;
+
+		/// Replaces a not yet mined transaction with a new one using the same sender and nonce.
+		/// Fields missing in the request are copied from the original transaction, the gas price
+		/// defaults to the minimal bump required to replace the transaction in the queue.
+		/// Returns the hash of the replacement transaction.
+		#[rpc(meta, name = "parity_replaceTransaction")]
+		fn replace_transaction(&self, Self::Metadata, H256, TransactionRequest) -> BoxFuture<H256>;
+
+		/// Cancels a not yet mined transaction by replacing it with an empty transfer to the sender.
+		/// Returns the hash of the cancelling transaction.
+		#[rpc(meta, name = "parity_cancelTransaction")]
+		fn cancel_transaction(&self, Self::Metadata, H256) -> BoxFuture<H256>;
 	}
 }
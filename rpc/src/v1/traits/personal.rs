@@ -59,5 +59,15 @@ build_rpc_trait! {
 		#[rpc(meta, name = "personal_signAndSendTransaction")]
 		fn sign_and_send_transaction(&self, Self::Metadata, TransactionRequest, String) -> BoxFuture<H256>;
 
+		/// Replaces a not yet mined transaction with a new one using the same sender and nonce,
+		/// signed with given password. See `parity_replaceTransaction`.
+		#[rpc(meta, name = "personal_replaceTransaction")]
+		fn replace_transaction(&self, Self::Metadata, H256, TransactionRequest, String) -> BoxFuture<H256>;
+
+		/// Cancels a not yet mined transaction, signing the cancellation with given password.
+		/// See `parity_cancelTransaction`.
+		#[rpc(meta, name = "personal_cancelTransaction")]
+		fn cancel_transaction(&self, Self::Metadata, H256, String) -> BoxFuture<H256>;
+
 	}
 }
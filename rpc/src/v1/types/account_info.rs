@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use v1::types::{Bytes, H160, H256, U256};
+
 /// Account information.
 #[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct AccountInfo {
@@ -42,3 +44,37 @@ pub struct HwAccountInfo {
 	pub manufacturer: String,
 }
 
+
+/// Account state along with Merkle proofs (used by `eth_getProof`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct EthAccount {
+	/// Account address.
+	pub address: H160,
+	/// Account balance.
+	pub balance: U256,
+	/// Account nonce.
+	pub nonce: U256,
+	/// Hash of the account's code.
+	#[serde(rename="codeHash")]
+	pub code_hash: H256,
+	/// Root of the account's storage trie.
+	#[serde(rename="storageHash")]
+	pub storage_hash: H256,
+	/// State trie nodes proving the account, in order from the root.
+	#[serde(rename="accountProof")]
+	pub account_proof: Vec<Bytes>,
+	/// Proofs of the requested storage keys.
+	#[serde(rename="storageProof")]
+	pub storage_proof: Vec<StorageProof>,
+}
+
+/// Storage value along with its Merkle proof.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct StorageProof {
+	/// Storage key.
+	pub key: U256,
+	/// Storage value.
+	pub value: U256,
+	/// Storage trie nodes proving the value, in order from the root.
+	pub proof: Vec<Bytes>,
+}
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use v1::helpers::CallRequest as Request;
-use v1::types::{Bytes, H160, U256};
+use v1::types::{Bytes, H160, U256, StateOverride};
 
 /// Call request
 #[derive(Debug, Default, PartialEq, Deserialize)]
@@ -36,6 +36,9 @@ pub struct CallRequest {
 	pub data: Option<Bytes>,
 	/// Nonce
 	pub nonce: Option<U256>,
+	/// State override
+	#[serde(rename="stateOverride")]
+	pub state_override: Option<StateOverride>,
 }
 
 impl Into<Request> for CallRequest {
@@ -57,7 +60,8 @@ mod tests {
 	use std::str::FromStr;
 	use rustc_hex::FromHex;
 	use serde_json;
-	use v1::types::{U256, H160};
+	use std::collections::BTreeMap;
+	use v1::types::{U256, H160, AccountOverride, StateOverride};
 	use super::CallRequest;
 
 	#[test]
@@ -81,6 +85,7 @@ mod tests {
 			value: Some(U256::from(3)),
 			data: Some(vec![0x12, 0x34, 0x56].into()),
 			nonce: Some(U256::from(4)),
+			state_override: None,
 		});
 	}
 
@@ -103,7 +108,8 @@ mod tests {
 			gas: Some(U256::from_str("76c0").unwrap()),
 			value: Some(U256::from_str("9184e72a").unwrap()),
 			data: Some("d46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675".from_hex().unwrap().into()),
-			nonce: None
+			nonce: None,
+			state_override: None,
 		});
 	}
 
@@ -120,6 +126,31 @@ mod tests {
 			value: None,
 			data: None,
 			nonce: None,
+			state_override: None,
+		});
+	}
+
+	#[test]
+	fn call_request_deserialize_state_override() {
+		let s = r#"{
+			"to":"0x0000000000000000000000000000000000000002",
+			"stateOverride": {
+				"0x0000000000000000000000000000000000000002": {
+					"balance": "0x1"
+				}
+			}
+		}"#;
+		let deserialized: CallRequest = serde_json::from_str(s).unwrap();
+
+		let mut state_override = BTreeMap::new();
+		state_override.insert(H160::from(2), AccountOverride {
+			balance: Some(U256::from(1)),
+			..Default::default()
+		});
+		assert_eq!(deserialized, CallRequest {
+			to: Some(H160::from(2)),
+			state_override: Some(StateOverride(state_override)),
+			..Default::default()
 		});
 	}
 }
@@ -0,0 +1,286 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Debug tracing types.
+
+use std::collections::BTreeMap;
+use serde::{Serialize, Serializer};
+use ethcore::trace::{self as et, DebugTracer, StructLoggerConfig};
+use jsonrpc_core::Error;
+use v1::helpers::errors;
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Options of a debug trace.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DebugTraceOptions {
+	/// Don't record the stack.
+	#[serde(rename="disableStack")]
+	pub disable_stack: Option<bool>,
+	/// Don't record the memory.
+	#[serde(rename="disableMemory")]
+	pub disable_memory: Option<bool>,
+	/// Don't record the storage.
+	#[serde(rename="disableStorage")]
+	pub disable_storage: Option<bool>,
+	/// Built-in tracer to use instead of the struct logger: `callTracer` or `prestateTracer`.
+	pub tracer: Option<String>,
+}
+
+impl DebugTraceOptions {
+	/// Converts into the client debug tracer.
+	pub fn try_into(self) -> Result<DebugTracer, Error> {
+		match self.tracer.as_ref().map(String::as_str) {
+			None => Ok(DebugTracer::StructLogger(StructLoggerConfig {
+				disable_stack: self.disable_stack.unwrap_or(false),
+				disable_memory: self.disable_memory.unwrap_or(false),
+				disable_storage: self.disable_storage.unwrap_or(false),
+			})),
+			Some("callTracer") => Ok(DebugTracer::CallTracer),
+			Some("prestateTracer") => Ok(DebugTracer::PrestateTracer),
+			Some(tracer) => Err(errors::invalid_params("tracer", format!("Unknown tracer: {}", tracer))),
+		}
+	}
+}
+
+/// Structured log of a single operation.
+#[derive(Debug, Serialize)]
+pub struct StructLog {
+	/// Program counter
+	pub pc: usize,
+	/// Instruction name
+	pub op: String,
+	/// Gas left
+	pub gas: U256,
+	/// Gas cost
+	#[serde(rename="gasCost")]
+	pub gas_cost: U256,
+	/// Call depth
+	pub depth: usize,
+	/// Stack, bottom first
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<H256>>,
+	/// Memory in 32-byte words
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<H256>>,
+	/// Accessed storage
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+impl From<et::StructLog> for StructLog {
+	fn from(log: et::StructLog) -> Self {
+		let op = match log.instruction_name() {
+			"" => format!("0x{:02x}", log.instruction),
+			name => name.to_owned(),
+		};
+
+		StructLog {
+			pc: log.pc,
+			op: op,
+			gas: log.gas.into(),
+			gas_cost: log.gas_cost.into(),
+			depth: log.depth,
+			stack: log.stack.map(|stack| stack.into_iter().map(|item| H256::from(item)).collect()),
+			memory: log.memory.map(|memory| memory.chunks(32).map(|word| H256::from(word)).collect()),
+			storage: log.storage.map(|storage| storage.into_iter().map(|(k, v)| (k.into(), v.into())).collect()),
+		}
+	}
+}
+
+/// Call frame
+#[derive(Debug, Serialize)]
+pub struct CallFrame {
+	/// Type
+	#[serde(rename="type")]
+	pub frame_type: String,
+	/// Sender
+	pub from: H160,
+	/// Destination
+	pub to: Option<H160>,
+	/// Value
+	pub value: U256,
+	/// Gas
+	pub gas: U256,
+	/// Gas used
+	#[serde(rename="gasUsed", skip_serializing_if = "Option::is_none")]
+	pub gas_used: Option<U256>,
+	/// Input
+	pub input: Bytes,
+	/// Output
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<Bytes>,
+	/// Error
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Subcalls
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+impl From<et::CallFrame> for CallFrame {
+	fn from(frame: et::CallFrame) -> Self {
+		let frame_type = match frame.frame_type {
+			et::CallFrameType::Call => "CALL",
+			et::CallFrameType::CallCode => "CALLCODE",
+			et::CallFrameType::DelegateCall => "DELEGATECALL",
+			et::CallFrameType::StaticCall => "STATICCALL",
+			et::CallFrameType::Create => "CREATE",
+			et::CallFrameType::Suicide => "SELFDESTRUCT",
+		};
+
+		CallFrame {
+			frame_type: frame_type.into(),
+			from: frame.from.into(),
+			to: frame.to.map(Into::into),
+			value: frame.value.into(),
+			gas: frame.gas.into(),
+			gas_used: frame.gas_used.map(Into::into),
+			input: frame.input.into(),
+			output: frame.output.map(Into::into),
+			error: frame.error.map(|e| e.to_string()),
+			calls: frame.calls.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// Account state before the transaction
+#[derive(Debug, Serialize)]
+pub struct PrestateAccount {
+	/// Balance
+	pub balance: U256,
+	/// Nonce
+	pub nonce: U256,
+	/// Code
+	pub code: Bytes,
+	/// Storage
+	pub storage: BTreeMap<H256, H256>,
+}
+
+impl From<et::PrestateAccount> for PrestateAccount {
+	fn from(account: et::PrestateAccount) -> Self {
+		PrestateAccount {
+			balance: account.balance.into(),
+			nonce: account.nonce.into(),
+			code: account.code.into(),
+			storage: account.storage.into_iter().map(|(k, v)| (k.into(), v.into())).collect(),
+		}
+	}
+}
+
+/// Debug trace
+#[derive(Debug)]
+pub enum DebugTrace {
+	/// Struct logs
+	StructLogs {
+		/// Gas used
+		gas: U256,
+		/// Failed
+		failed: bool,
+		/// Return value
+		return_value: Bytes,
+		/// Logs
+		struct_logs: Vec<StructLog>,
+	},
+	/// Call tree
+	Call(CallFrame),
+	/// Pre-transaction state
+	Prestate(BTreeMap<H160, PrestateAccount>),
+}
+
+impl Serialize for DebugTrace {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where S: Serializer
+	{
+		use serde::ser::SerializeStruct;
+
+		match *self {
+			DebugTrace::StructLogs { ref gas, ref failed, ref return_value, ref struct_logs } => {
+				let mut struc = serializer.serialize_struct("DebugTrace", 4)?;
+				struc.serialize_field("gas", gas)?;
+				struc.serialize_field("failed", failed)?;
+				struc.serialize_field("returnValue", return_value)?;
+				struc.serialize_field("structLogs", struct_logs)?;
+				struc.end()
+			},
+			DebugTrace::Call(ref frame) => frame.serialize(serializer),
+			DebugTrace::Prestate(ref prestate) => prestate.serialize(serializer),
+		}
+	}
+}
+
+impl From<et::DebugTrace> for DebugTrace {
+	fn from(trace: et::DebugTrace) -> Self {
+		match trace {
+			et::DebugTrace::StructLogs { gas, failed, return_value, struct_logs } => DebugTrace::StructLogs {
+				gas: gas.into(),
+				failed: failed,
+				return_value: return_value.into(),
+				struct_logs: struct_logs.into_iter().map(Into::into).collect(),
+			},
+			et::DebugTrace::Call(frame) => DebugTrace::Call(frame.into()),
+			et::DebugTrace::Prestate(prestate) => DebugTrace::Prestate(
+				prestate.into_iter().map(|(address, account)| (address.into(), account.into())).collect()
+			),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::trace::{self as et, DebugTracer, StructLoggerConfig};
+	use super::{DebugTraceOptions, DebugTrace};
+
+	#[test]
+	fn debug_trace_options_deserialize() {
+		let s = r#"{"disableStorage":true}"#;
+		let options: DebugTraceOptions = serde_json::from_str(s).unwrap();
+		assert_eq!(options.try_into().unwrap(), DebugTracer::StructLogger(StructLoggerConfig {
+			disable_storage: true,
+			..Default::default()
+		}));
+
+		let s = r#"{"tracer":"callTracer"}"#;
+		let options: DebugTraceOptions = serde_json::from_str(s).unwrap();
+		assert_eq!(options.try_into().unwrap(), DebugTracer::CallTracer);
+
+		let s = r#"{"tracer":"jsTracer"}"#;
+		let options: DebugTraceOptions = serde_json::from_str(s).unwrap();
+		assert!(options.try_into().is_err());
+	}
+
+	#[test]
+	fn struct_logs_serialize() {
+		let trace = et::DebugTrace::StructLogs {
+			gas: 21003.into(),
+			failed: false,
+			return_value: vec![],
+			struct_logs: vec![et::StructLog {
+				pc: 0,
+				instruction: 0x60,
+				gas: 3.into(),
+				gas_cost: 3.into(),
+				depth: 1,
+				stack: Some(vec![]),
+				memory: None,
+				storage: None,
+			}],
+		};
+		let serialized = serde_json::to_string(&DebugTrace::from(trace)).unwrap();
+		assert_eq!(serialized, r#"{"gas":"0x520b","failed":false,"returnValue":"0x","structLogs":[{"pc":0,"op":"PUSH1","gas":"0x3","gasCost":"0x3","depth":1,"stack":[]}]}"#);
+	}
+}
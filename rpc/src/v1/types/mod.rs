@@ -25,6 +25,7 @@ mod call_request;
 mod confirmations;
 mod consensus_status;
 mod dapps;
+mod debug;
 mod derivation;
 mod filter;
 mod hash;
@@ -35,6 +36,7 @@ mod node_kind;
 mod provenance;
 mod receipt;
 mod rpc_settings;
+mod state_override;
 mod sync;
 mod trace;
 mod trace_filter;
@@ -46,7 +48,7 @@ mod work;
 
 pub mod pubsub;
 
-pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo};
+pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo, EthAccount, StorageProof};
 pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::BlockNumber;
@@ -57,6 +59,7 @@ pub use self::confirmations::{
 };
 pub use self::consensus_status::*;
 pub use self::dapps::LocalDapp;
+pub use self::debug::{DebugTraceOptions, DebugTrace};
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
 pub use self::filter::{Filter, FilterChanges};
 pub use self::hash::{H64, H160, H256, H512, H520, H2048};
@@ -67,9 +70,10 @@ pub use self::node_kind::{NodeKind, Availability, Capability};
 pub use self::provenance::{Origin, DappId};
 pub use self::receipt::Receipt;
 pub use self::rpc_settings::RpcSettings;
+pub use self::state_override::{AccountOverride, StateOverride};
 pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
-	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
+	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo, ProtocolTraffic,
 };
 pub use self::trace::{LocalizedTrace, TraceResults};
 pub use self::trace_filter::TraceFilter;
@@ -19,7 +19,7 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use serde_json::{Value, from_value};
-use v1::types::{RichHeader, Filter, Log, H256};
+use v1::types::{RichHeader, Filter, Log, H256, SyncInfo};
 
 /// Subscription result.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,6 +30,27 @@ pub enum Result {
 	Log(Log),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Sync status
+	SyncState(SyncState),
+}
+
+/// Sync status pushed to `syncing` subscribers.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SyncState {
+	/// Whether the node is syncing.
+	pub syncing: bool,
+	/// Sync progress, present only while syncing.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status: Option<SyncInfo>,
+}
+
+impl From<Option<SyncInfo>> for SyncState {
+	fn from(info: Option<SyncInfo>) -> Self {
+		SyncState {
+			syncing: info.is_some(),
+			status: info,
+		}
+	}
 }
 
 impl Serialize for Result {
@@ -40,6 +61,7 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
+			Result::SyncState(ref state) => state.serialize(serializer),
 		}
 	}
 }
@@ -94,8 +116,8 @@ impl<'a> Deserialize<'a> for Params {
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use super::{Result, Kind, Params};
-	use v1::types::{RichHeader, Header, Filter};
+	use super::{Result, Kind, Params, SyncState};
+	use v1::types::{RichHeader, Header, Filter, SyncInfo};
 	use v1::types::filter::VariadicValue;
 
 	#[test]
@@ -168,4 +190,20 @@ mod tests {
 		let expected = r#"{"author":"0x0000000000000000000000000000000000000000","difficulty":"0x0","extraData":"0x","gasLimit":"0x0","gasUsed":"0x0","hash":"0x0000000000000000000000000000000000000000000000000000000000000000","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","miner":"0x0000000000000000000000000000000000000000","number":"0x0","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","receiptsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","sealFields":["0x","0x"],"sha3Uncles":"0x0000000000000000000000000000000000000000000000000000000000000000","size":"0x45","stateRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","timestamp":"0x0","transactionsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000"}"#;
 		assert_eq!(serde_json::to_string(&header).unwrap(), expected);
 	}
+
+	#[test]
+	fn should_serialize_sync_state() {
+		let syncing = Result::SyncState(Some(SyncInfo {
+			starting_block: 0x10.into(),
+			current_block: 0x20.into(),
+			highest_block: 0x30.into(),
+			warp_chunks_amount: None,
+			warp_chunks_processed: None,
+		}).into());
+		let expected = r#"{"syncing":true,"status":{"startingBlock":"0x10","currentBlock":"0x20","highestBlock":"0x30","warpChunksAmount":null,"warpChunksProcessed":null}}"#;
+		assert_eq!(serde_json::to_string(&syncing).unwrap(), expected);
+
+		let idle = Result::SyncState(SyncState::from(None));
+		assert_eq!(serde_json::to_string(&idle).unwrap(), r#"{"syncing":false}"#);
+	}
 }
@@ -0,0 +1,128 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethcore::state::{AccountOverride as EthAccountOverride, StateOverride as EthStateOverride};
+use jsonrpc_core::Error;
+use v1::helpers::errors;
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Account state override
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountOverride {
+	/// Balance
+	pub balance: Option<U256>,
+	/// Nonce
+	pub nonce: Option<U256>,
+	/// Code
+	pub code: Option<Bytes>,
+	/// Full storage
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Storage difference
+	#[serde(rename="stateDiff")]
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// State override
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct StateOverride(pub BTreeMap<H160, AccountOverride>);
+
+impl Into<EthAccountOverride> for AccountOverride {
+	fn into(self) -> EthAccountOverride {
+		EthAccountOverride {
+			balance: self.balance.map(Into::into),
+			nonce: self.nonce.map(Into::into),
+			code: self.code.map(Into::into),
+			state: self.state.map(|s| s.into_iter().map(|(k, v)| (k.into(), v.into())).collect()),
+			state_diff: self.state_diff.map(|s| s.into_iter().map(|(k, v)| (k.into(), v.into())).collect()),
+		}
+	}
+}
+
+impl StateOverride {
+	/// Converts into the client state override, rejecting accounts
+	/// which set both the full storage and the storage difference.
+	pub fn try_into(self) -> Result<EthStateOverride, Error> {
+		self.0.into_iter()
+			.map(|(address, account)| {
+				if account.state.is_some() && account.state_diff.is_some() {
+					return Err(errors::invalid_params(
+						"stateOverride",
+						format!("Both state and stateDiff given for account {:?}", address),
+					));
+				}
+				Ok((address.into(), account.into()))
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use serde_json;
+	use v1::types::{H160, H256, U256};
+	use super::{AccountOverride, StateOverride};
+
+	#[test]
+	fn state_override_deserialize() {
+		let s = r#"{
+			"0x0000000000000000000000000000000000000001": {
+				"balance": "0x1",
+				"nonce": "0x2",
+				"code": "0x6000",
+				"state": {
+					"0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+				}
+			},
+			"0x0000000000000000000000000000000000000002": {
+				"stateDiff": {
+					"0x0000000000000000000000000000000000000000000000000000000000000003": "0x0000000000000000000000000000000000000000000000000000000000000004"
+				}
+			}
+		}"#;
+		let deserialized: StateOverride = serde_json::from_str(s).unwrap();
+
+		let mut expected = BTreeMap::new();
+		expected.insert(H160::from(1), AccountOverride {
+			balance: Some(U256::from(1)),
+			nonce: Some(U256::from(2)),
+			code: Some(vec![0x60, 0x00].into()),
+			state: Some(vec![(H256::from(1), H256::from(2))].into_iter().collect()),
+			state_diff: None,
+		});
+		expected.insert(H160::from(2), AccountOverride {
+			state_diff: Some(vec![(H256::from(3), H256::from(4))].into_iter().collect()),
+			..Default::default()
+		});
+		assert_eq!(deserialized, StateOverride(expected));
+	}
+
+	#[test]
+	fn state_override_rejects_state_and_state_diff() {
+		let s = r#"{
+			"0x0000000000000000000000000000000000000001": {
+				"state": {},
+				"stateDiff": {}
+			}
+		}"#;
+		let deserialized: StateOverride = serde_json::from_str(s).unwrap();
+
+		assert!(deserialized.try_into().is_err());
+	}
+}
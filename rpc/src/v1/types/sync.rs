@@ -20,7 +20,7 @@ use serde::{Serialize, Serializer};
 use v1::types::{U256, H512};
 
 /// Sync info
-#[derive(Default, Debug, Serialize, PartialEq)]
+#[derive(Default, Debug, Clone, Serialize, PartialEq, Eq)]
 pub struct SyncInfo {
 	/// Starting block
 	#[serde(rename="startingBlock")]
@@ -50,6 +50,26 @@ pub struct Peers {
 	pub max: u32,
 	/// Detailed information on peers
 	pub peers: Vec<PeerInfo>,
+	/// Bytes transferred by each subprotocol
+	pub traffic: BTreeMap<String, ProtocolTraffic>,
+}
+
+/// Subprotocol traffic information
+#[derive(Default, Debug, Serialize)]
+pub struct ProtocolTraffic {
+	/// Bytes received
+	pub ingress: u64,
+	/// Bytes sent
+	pub egress: u64,
+}
+
+impl From<ethsync::TrafficStats> for ProtocolTraffic {
+	fn from(stats: ethsync::TrafficStats) -> Self {
+		ProtocolTraffic {
+			ingress: stats.ingress,
+			egress: stats.egress,
+		}
+	}
 }
 
 /// Peer connection information
@@ -65,6 +85,8 @@ pub struct PeerInfo {
 	pub network: PeerNetworkInfo,
 	/// Protocols information
 	pub protocols: PeerProtocolsInfo,
+	/// Reputation score
+	pub reputation: Option<i32>,
 }
 
 /// Peer network information
@@ -76,6 +98,10 @@ pub struct PeerNetworkInfo {
 	/// Local endpoint address
 	#[serde(rename="localAddress")]
 	pub local_address: String,
+	/// Bytes received from the peer
+	pub ingress: u64,
+	/// Bytes sent to the peer
+	pub egress: u64,
 }
 
 /// Peer protocols information
@@ -168,11 +194,14 @@ impl From<SyncPeerInfo> for PeerInfo {
 			network: PeerNetworkInfo {
 				remote_address: p.remote_address,
 				local_address: p.local_address,
+				ingress: p.ingress,
+				egress: p.egress,
 			},
 			protocols: PeerProtocolsInfo {
 				eth: p.eth_info.map(Into::into),
 				pip: p.pip_info.map(Into::into),
 			},
+			reputation: p.reputation,
 		}
 	}
 }
@@ -195,6 +224,9 @@ pub struct ChainStatus {
 	/// Describes the gap in the blockchain, if there is one: (first, last)
 	#[serde(rename="blockGap")]
 	pub block_gap: Option<(U256, U256)>,
+	/// Bytes held by the database, if it lives entirely in memory.
+	#[serde(rename="databaseMemoryUsed")]
+	pub database_memory_used: Option<U256>,
 }
 
 #[cfg(test)]
@@ -229,15 +261,16 @@ mod tests {
 	}
 
 	#[test]
-	fn test_serialize_block_gap() {
+	fn test_serialize_chain_status() {
 		let mut t = ChainStatus::default();
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"{"blockGap":null}"#);
+		assert_eq!(serialized, r#"{"blockGap":null,"databaseMemoryUsed":null}"#);
 
 		t.block_gap = Some((1.into(), 5.into()));
+		t.database_memory_used = Some(1024.into());
 
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"{"blockGap":["0x1","0x5"]}"#);
+		assert_eq!(serialized, r#"{"blockGap":["0x1","0x5"],"databaseMemoryUsed":"0x400"}"#);
 	}
 
 	#[test]
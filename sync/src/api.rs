@@ -19,7 +19,7 @@ use std::collections::{HashMap, BTreeMap};
 use std::io;
 use bytes::Bytes;
 use network::{NetworkProtocolHandler, NetworkService, NetworkContext, HostInfo, PeerId, ProtocolId,
-	NetworkConfiguration as BasicNetworkConfiguration, NonReservedPeerMode, Error, ErrorKind, ConnectionFilter};
+	NetworkConfiguration as BasicNetworkConfiguration, NonReservedPeerMode, Error, ErrorKind, ConnectionFilter, NetworkStats, TrafficStats};
 use ethereum_types::{H256, H512, U256};
 use io::{TimerToken};
 use ethcore::ethstore::ethkey::Secret;
@@ -31,7 +31,7 @@ use chain::{ChainSync, SyncStatus as EthSyncStatus};
 use std::net::{SocketAddr, AddrParseError};
 use std::str::FromStr;
 use parking_lot::RwLock;
-use chain::{ETH_PACKET_COUNT, SNAPSHOT_SYNC_PACKET_COUNT};
+use chain::{ETH_PACKET_COUNT, SNAPSHOT_SYNC_PACKET_COUNT, SNAPSHOT_DATA_PACKET};
 use light::client::AsLightClient;
 use light::Provider;
 use light::net::{self as light_net, LightProtocol, Params as LightParams, Capabilities, Handler as LightHandler, EventContext};
@@ -61,6 +61,8 @@ pub struct SyncConfig {
 	pub fork_block: Option<(BlockNumber, H256)>,
 	/// Enable snapshot sync
 	pub warp_sync: bool,
+	/// Enable fast sync: download blocks up to a recent pivot block and fetch its state trie with `GetNodeData`.
+	pub fast_sync: bool,
 	/// Enable light client server.
 	pub serve_light: bool,
 }
@@ -75,6 +77,7 @@ impl Default for SyncConfig {
 			light_subprotocol_name: LIGHT_PROTOCOL,
 			fork_block: None,
 			warp_sync: false,
+			fast_sync: false,
 			serve_light: false,
 		}
 	}
@@ -91,6 +94,9 @@ pub trait SyncProvider: Send + Sync {
 	/// Get the enode if available.
 	fn enode(&self) -> Option<String>;
 
+	/// Get the local node record if available.
+	fn enr(&self) -> Option<String>;
+
 	/// Returns propagation count for pending transactions.
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats>;
 }
@@ -117,6 +123,12 @@ pub struct PeerInfo {
 	pub remote_address: String,
 	/// Local endpoint address
 	pub local_address: String,
+	/// Reputation score
+	pub reputation: Option<i32>,
+	/// Bytes received from the peer
+	pub ingress: u64,
+	/// Bytes sent to the peer
+	pub egress: u64,
 	/// Eth protocol info.
 	pub eth_info: Option<EthProtocolInfo>,
 	/// Light protocol info.
@@ -298,6 +310,9 @@ impl SyncProvider for EthSync {
 					capabilities: session_info.peer_capabilities.into_iter().map(|c| c.to_string()).collect(),
 					remote_address: session_info.remote_address,
 					local_address: session_info.local_address,
+					reputation: self.network.peer_reputation(peer_id),
+					ingress: session_info.ingress,
+					egress: session_info.egress,
 					eth_info: eth_sync.peer_info(&peer_id),
 					pip_info: light_proto.as_ref().and_then(|lp| lp.peer_status(&peer_id)).map(Into::into),
 				})
@@ -309,6 +324,10 @@ impl SyncProvider for EthSync {
 		self.network.external_url()
 	}
 
+	fn enr(&self) -> Option<String> {
+		self.network.external_record()
+	}
+
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats> {
 		let sync = self.eth_handler.sync.read();
 		sync.transactions_stats()
@@ -473,11 +492,21 @@ pub trait ManageNetwork : Send + Sync {
 	fn stop_network(&self);
 	/// Query the current configuration of the network
 	fn network_config(&self) -> NetworkConfiguration;
+	/// Get the total number of bytes transferred over the network
+	fn network_traffic(&self) -> TrafficStats;
+	/// Get the number of bytes transferred by each subprotocol
+	fn protocol_traffic(&self) -> BTreeMap<String, TrafficStats>;
 	/// Get network context for protocol.
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext));
 }
 
 
+fn protocol_traffic(stats: &NetworkStats) -> BTreeMap<String, TrafficStats> {
+	stats.protocols().into_iter()
+		.map(|(protocol, traffic)| (String::from_utf8_lossy(&protocol).into_owned(), traffic))
+		.collect()
+}
+
 impl ManageNetwork for EthSync {
 	fn accept_unreserved_peers(&self) {
 		self.network.set_non_reserved_mode(NonReservedPeerMode::Accept);
@@ -516,6 +545,18 @@ impl ManageNetwork for EthSync {
 		NetworkConfiguration::from(self.network.config().clone())
 	}
 
+	fn network_traffic(&self) -> TrafficStats {
+		let stats = self.network.stats();
+		TrafficStats {
+			ingress: stats.recv() as u64,
+			egress: stats.send() as u64,
+		}
+	}
+
+	fn protocol_traffic(&self) -> BTreeMap<String, TrafficStats> {
+		protocol_traffic(self.network.stats())
+	}
+
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
@@ -558,6 +599,12 @@ pub struct NetworkConfiguration {
 	pub ip_filter: IpFilter,
 	/// Client version string
 	pub client_version: String,
+	/// Chain id advertised in the local node record
+	pub chain_id: Option<u64>,
+	/// Maximum upload rate for all subprotocol traffic in bytes per second
+	pub max_upload_rate: Option<u64>,
+	/// Maximum upload rate for serving snapshot data in bytes per second
+	pub snapshot_serve_rate: Option<u64>,
 }
 
 impl NetworkConfiguration {
@@ -591,6 +638,9 @@ impl NetworkConfiguration {
 			ip_filter: self.ip_filter,
 			non_reserved_mode: if self.allow_non_reserved { NonReservedPeerMode::Accept } else { NonReservedPeerMode::Deny },
 			client_version: self.client_version,
+			chain_id: self.chain_id,
+			max_upload_rate: self.max_upload_rate,
+			packet_upload_rates: self.snapshot_serve_rate.map_or_else(HashMap::new, |rate| hash_map![(WARP_SYNC_PROTOCOL_ID, SNAPSHOT_DATA_PACKET) => rate]),
 		})
 	}
 }
@@ -615,6 +665,9 @@ impl From<BasicNetworkConfiguration> for NetworkConfiguration {
 			ip_filter: other.ip_filter,
 			allow_non_reserved: match other.non_reserved_mode { NonReservedPeerMode::Accept => true, _ => false } ,
 			client_version: other.client_version,
+			chain_id: other.chain_id,
+			max_upload_rate: other.max_upload_rate,
+			snapshot_serve_rate: other.packet_upload_rates.get(&(WARP_SYNC_PROTOCOL_ID, SNAPSHOT_DATA_PACKET)).cloned(),
 		}
 	}
 }
@@ -657,6 +710,9 @@ pub trait LightSyncProvider {
 	/// Get the enode if available.
 	fn enode(&self) -> Option<String>;
 
+	/// Get the local node record if available.
+	fn enr(&self) -> Option<String>;
+
 	/// Returns propagation count for pending transactions.
 	fn transactions_stats(&self) -> BTreeMap<H256, TransactionStats>;
 }
@@ -779,6 +835,10 @@ impl ManageNetwork for LightSync {
 		self.network.register_protocol(light_proto, self.subprotocol_name, ::light::net::PACKET_COUNT, ::light::net::PROTOCOL_VERSIONS)
 			.unwrap_or_else(|e| warn!("Error registering light client protocol: {:?}", e));
 
+		// only peers serving the light protocol are useful to us.
+		let subprotocol_name = self.subprotocol_name;
+		self.network.with_context(subprotocol_name, |context| context.query_topic(subprotocol_name));
+
 		for proto in &self.attached_protos { proto.register(&self.network) }
 	}
 
@@ -793,6 +853,18 @@ impl ManageNetwork for LightSync {
 		NetworkConfiguration::from(self.network.config().clone())
 	}
 
+	fn network_traffic(&self) -> TrafficStats {
+		let stats = self.network.stats();
+		TrafficStats {
+			ingress: stats.recv() as u64,
+			egress: stats.send() as u64,
+		}
+	}
+
+	fn protocol_traffic(&self) -> BTreeMap<String, TrafficStats> {
+		protocol_traffic(self.network.stats())
+	}
+
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
@@ -826,6 +898,9 @@ impl LightSyncProvider for LightSync {
 					capabilities: session_info.peer_capabilities.into_iter().map(|c| c.to_string()).collect(),
 					remote_address: session_info.remote_address,
 					local_address: session_info.local_address,
+					reputation: self.network.peer_reputation(peer_id),
+					ingress: session_info.ingress,
+					egress: session_info.egress,
 					eth_info: None,
 					pip_info: self.proto.peer_status(&peer_id).map(Into::into),
 				})
@@ -837,6 +912,10 @@ impl LightSyncProvider for LightSync {
 		self.network.external_url()
 	}
 
+	fn enr(&self) -> Option<String> {
+		self.network.external_record()
+	}
+
 	fn network_id(&self) -> u64 {
 		self.network_id
 	}
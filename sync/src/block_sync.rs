@@ -29,7 +29,7 @@ use ethcore::client::{BlockStatus, BlockId, BlockImportError};
 use ethcore::block::Block;
 use ethcore::error::{ImportError, BlockError};
 use sync_io::SyncIo;
-use blocks::BlockCollection;
+use blocks::{BlockCollection, BlockAndReceipts};
 
 const MAX_HEADERS_TO_REQUEST: usize = 128;
 const MAX_BODIES_TO_REQUEST: usize = 32;
@@ -106,6 +106,8 @@ pub struct BlockDownloader {
 	download_receipts: bool,
 	/// Sync up to the block with this hash.
 	target_hash: Option<H256>,
+	/// Target block data, kept when the target is reached.
+	target_block: Option<BlockAndReceipts>,
 	/// Probing range for seeking common best block.
 	retract_step: u64,
 	/// Whether reorg should be limited.
@@ -128,6 +130,7 @@ impl BlockDownloader {
 			round_parents: VecDeque::new(),
 			download_receipts: sync_receipts,
 			target_hash: None,
+			target_block: None,
 			retract_step: 1,
 			limit_reorg: true,
 		}
@@ -147,6 +150,7 @@ impl BlockDownloader {
 			round_parents: VecDeque::new(),
 			download_receipts: sync_receipts,
 			target_hash: None,
+			target_block: None,
 			retract_step: 1,
 			limit_reorg: false,
 		}
@@ -184,6 +188,11 @@ impl BlockDownloader {
 		self.target_hash = Some(hash.clone());
 	}
 
+	/// Take the target block once the download is complete.
+	pub fn take_target_block(&mut self) -> Option<BlockAndReceipts> {
+		self.target_block.take()
+	}
+
 	/// Unmark header as being downloaded.
 	pub fn clear_header_download(&mut self, hash: &H256) {
 		self.blocks.clear_header_download(hash)
@@ -491,6 +500,7 @@ impl BlockDownloader {
 
 			if self.target_hash.as_ref().map_or(false, |t| t == &h) {
 				self.state = State::Complete;
+				self.target_block = Some(BlockAndReceipts { block: block, receipts: receipts });
 				trace!(target: "sync", "Sync target reached");
 				return Ok(());
 			}
@@ -97,7 +97,7 @@ use plain_hasher::H256FastMap;
 use parking_lot::RwLock;
 use bytes::Bytes;
 use rlp::*;
-use network::{self, PeerId, PacketId};
+use network::{self, PeerId, PacketId, PeerBehaviour};
 use ethcore::header::{BlockNumber, Header as BlockHeader};
 use ethcore::client::{BlockChainClient, BlockStatus, BlockId, BlockChainInfo, BlockImportError, BlockQueueInfo};
 use ethcore::error::*;
@@ -107,6 +107,8 @@ use sync_io::SyncIo;
 use time;
 use super::SyncConfig;
 use block_sync::{BlockDownloader, BlockRequest, BlockDownloaderImportError as DownloaderImportError, DownloadAction};
+use blocks::BlockAndReceipts;
+use state_sync::StateDownloader;
 use rand::Rng;
 use snapshot::{Snapshot, ChunkType};
 use api::{EthProtocolInfo as PeerInfoDigest, WARP_SYNC_PROTOCOL_ID};
@@ -138,6 +140,13 @@ const MAX_TRANSACTIONS_TO_PROPAGATE: usize = 64;
 // Min number of blocks to be behind for a snapshot sync
 const SNAPSHOT_RESTORE_THRESHOLD: BlockNumber = 30000;
 const SNAPSHOT_MIN_PEERS: usize = 3;
+// Number of blocks between the fast sync pivot and the network best block
+const FAST_SYNC_PIVOT_DISTANCE: BlockNumber = 32;
+// Pick a new pivot once the network best block is this many blocks past the pivot distance.
+// Peers prune old state, so the pivot state must stay recent.
+const FAST_SYNC_MAX_PIVOT_AGE: BlockNumber = 32;
+const FAST_SYNC_MIN_PEERS: usize = 3;
+const MAX_NODE_DATA_TO_REQUEST: usize = 384;
 
 const STATUS_PACKET: u8 = 0x00;
 const NEW_BLOCK_HASHES_PACKET: u8 = 0x01;
@@ -158,7 +167,7 @@ pub const ETH_PACKET_COUNT: u8 = 0x11;
 const GET_SNAPSHOT_MANIFEST_PACKET: u8 = 0x11;
 const SNAPSHOT_MANIFEST_PACKET: u8 = 0x12;
 const GET_SNAPSHOT_DATA_PACKET: u8 = 0x13;
-const SNAPSHOT_DATA_PACKET: u8 = 0x14;
+pub const SNAPSHOT_DATA_PACKET: u8 = 0x14;
 const CONSENSUS_DATA_PACKET: u8 = 0x15;
 
 pub const SNAPSHOT_SYNC_PACKET_COUNT: u8 = 0x16;
@@ -173,6 +182,7 @@ const RECEIPTS_TIMEOUT_SEC: u64 = 10;
 const FORK_HEADER_TIMEOUT_SEC: u64 = 3;
 const SNAPSHOT_MANIFEST_TIMEOUT_SEC: u64 = 5;
 const SNAPSHOT_DATA_TIMEOUT_SEC: u64 = 120;
+const NODE_DATA_TIMEOUT_SEC: u64 = 10;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 /// Sync state
@@ -193,6 +203,10 @@ pub enum SyncState {
 	Waiting,
 	/// Downloading blocks learned from `NewHashes` packet
 	NewBlocks,
+	/// Waiting for the fast sync pivot block header
+	FastSyncPivot,
+	/// Downloading blocks and state up to the fast sync pivot block
+	FastSync,
 }
 
 /// Syncing status and statistics
@@ -226,6 +240,12 @@ pub struct SyncStatus {
 	pub snapshot_chunks_done: usize,
 	/// Last fully downloaded and imported ancient block number (if any).
 	pub last_imported_old_block_number: Option<BlockNumber>,
+	/// Fast sync pivot block number (if any).
+	pub fast_sync_pivot_number: Option<BlockNumber>,
+	/// State trie nodes downloaded for the fast sync pivot block.
+	pub state_nodes_done: usize,
+	/// State trie nodes known to be missing for the fast sync pivot block.
+	pub state_nodes_pending: usize,
 }
 
 impl SyncStatus {
@@ -236,6 +256,11 @@ impl SyncStatus {
 			|| self.state == SyncState::SnapshotWaiting
 	}
 
+	/// Indicates if fast sync is in progress
+	pub fn is_fast_syncing(&self) -> bool {
+		self.state == SyncState::FastSyncPivot || self.state == SyncState::FastSync
+	}
+
 	/// Returns max no of peers to display in informants
 	pub fn current_max_peers(&self, min_peers: u32, max_peers: u32) -> u32 {
 		if self.num_peers as u32 > min_peers {
@@ -263,6 +288,8 @@ enum PeerAsking {
 	BlockReceipts,
 	SnapshotManifest,
 	SnapshotData,
+	PivotHeaders,
+	NodeData,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -306,6 +333,8 @@ struct PeerInfo {
 	asking_snapshot_data: Option<H256>,
 	/// Request timestamp
 	ask_time: u64,
+	/// Request was delayed by our upload limits, so its latency is not the peer's
+	ask_throttled: bool,
 	/// Holds a set of transactions recently sent to this peer to avoid spamming.
 	last_sent_transactions: HashSet<H256>,
 	/// Pending request is expired and result should be ignored
@@ -339,6 +368,18 @@ impl PeerInfo {
 	}
 }
 
+/// Fast sync progress.
+struct FastSync {
+	/// Pivot block number.
+	pivot_number: BlockNumber,
+	/// Pivot block hash.
+	pivot_hash: H256,
+	/// Pivot block state download.
+	state: StateDownloader,
+	/// Downloaded pivot block. Imported once the state is complete.
+	pivot_block: Option<BlockAndReceipts>,
+}
+
 #[cfg(not(test))]
 mod random {
 	use rand;
@@ -386,6 +427,10 @@ pub struct ChainSync {
 	download_old_blocks: bool,
 	/// Enable warp sync.
 	enable_warp_sync: bool,
+	/// Fast sync is enabled and not yet complete.
+	enable_fast_sync: bool,
+	/// Fast sync progress.
+	fast_sync: Option<FastSync>,
 }
 
 type RlpResponseResult = Result<Option<(PacketId, RlpStream)>, PacketDecodeError>;
@@ -395,7 +440,7 @@ impl ChainSync {
 	pub fn new(config: SyncConfig, chain: &BlockChainClient) -> ChainSync {
 		let chain_info = chain.chain_info();
 		let mut sync = ChainSync {
-			state: if config.warp_sync { SyncState::WaitingPeers } else { SyncState::Idle },
+			state: if config.warp_sync || config.fast_sync { SyncState::WaitingPeers } else { SyncState::Idle },
 			starting_block: chain.chain_info().best_block_number,
 			highest_block: None,
 			peers: HashMap::new(),
@@ -411,6 +456,8 @@ impl ChainSync {
 			sync_start_time: None,
 			transactions_stats: TransactionsStats::default(),
 			enable_warp_sync: config.warp_sync,
+			enable_fast_sync: config.fast_sync,
+			fast_sync: None,
 		};
 		sync.update_targets(chain);
 		sync
@@ -433,9 +480,13 @@ impl ChainSync {
 			num_active_peers: self.peers.values().filter(|p| p.is_allowed() && p.asking != PeerAsking::Nothing).count(),
 			num_snapshot_chunks: self.snapshot.total_chunks(),
 			snapshot_chunks_done: self.snapshot.done_chunks(),
+			fast_sync_pivot_number: self.fast_sync.as_ref().map(|f| f.pivot_number),
+			state_nodes_done: self.fast_sync.as_ref().map_or(0, |f| f.state.done_count()),
+			state_nodes_pending: self.fast_sync.as_ref().map_or(0, |f| f.state.pending_count()),
 			mem_used:
 				self.new_blocks.heap_size()
 				+ self.old_blocks.as_ref().map_or(0, |d| d.heap_size())
+				+ self.fast_sync.as_ref().map_or(0, |f| f.state.heap_size())
 				+ self.peers.heap_size_of_children(),
 		}
 	}
@@ -483,6 +534,13 @@ impl ChainSync {
 			}
 		}
 		self.state = SyncState::Idle;
+		if let Some(ref mut fast_sync) = self.fast_sync {
+			// node requests of the reset peers are lost
+			fast_sync.state.reset_downloads();
+			self.state = SyncState::FastSync;
+		} else if self.enable_fast_sync {
+			self.state = SyncState::WaitingPeers;
+		}
 		// Reactivate peers only if some progress has been made
 		// since the last sync round of if starting fresh.
 		self.active_peers = self.peers.keys().cloned().collect();
@@ -576,6 +634,149 @@ impl ChainSync {
 		}
 	}
 
+	/// Request a fast sync pivot header when starting and when the current pivot gets too old.
+	fn maybe_start_fast_sync(&mut self, io: &mut SyncIo) {
+		if !self.enable_fast_sync {
+			return;
+		}
+		if self.peers.values().any(|p| p.asking == PeerAsking::PivotHeaders) {
+			return;
+		}
+		match self.state {
+			SyncState::WaitingPeers => {
+				let confirmed_peers = self.peers.values().filter(|p| p.can_sync()).count();
+				let timeout = self.sync_start_time.map_or(false, |t| ((time::precise_time_ns() - t) / 1_000_000_000) > WAIT_PEERS_TIMEOUT_SEC);
+				if confirmed_peers < FAST_SYNC_MIN_PEERS && !timeout {
+					return;
+				}
+			},
+			SyncState::FastSyncPivot => {
+				// the pivot request failed, try another peer
+				self.state = SyncState::WaitingPeers;
+				return;
+			},
+			SyncState::FastSync => {
+				let pivot_number = match self.fast_sync {
+					Some(ref fast_sync) if !fast_sync.state.is_complete() => fast_sync.pivot_number,
+					_ => return,
+				};
+				if self.highest_block.map_or(true, |n| n <= pivot_number + FAST_SYNC_PIVOT_DISTANCE + FAST_SYNC_MAX_PIVOT_AGE) {
+					return;
+				}
+			},
+			_ => return,
+		}
+
+		let best_peer = self.peers.iter()
+			.filter(|&(_, p)| p.can_sync() && p.asking == PeerAsking::Nothing)
+			.max_by_key(|&(_, p)| p.difficulty)
+			.map(|(id, _)| *id);
+		match best_peer {
+			Some(peer_id) => {
+				self.request_pivot_headers(io, peer_id);
+				if self.state == SyncState::WaitingPeers {
+					self.state = SyncState::FastSyncPivot;
+				}
+			},
+			None => trace!(target: "sync", "No peers to pick a fast sync pivot from"),
+		}
+	}
+
+	/// Called by peer once it has the fast sync pivot headers
+	fn on_peer_pivot_headers(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		let expected_hash = self.peers.get(&peer_id).and_then(|p| p.asking_hash);
+		if !self.reset_peer_asking(io, peer_id, PeerAsking::PivotHeaders) || expected_hash.is_none() {
+			trace!(target: "sync", "{}: Ignored unexpected pivot headers", peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
+			peer.asking_hash = None;
+		}
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{} -> PivotHeaders ({} entries)", peer_id, item_count);
+		let head: Option<BlockHeader> = if item_count > 0 { Some(r.val_at(0)?) } else { None };
+		let pivot: Option<BlockHeader> = if item_count > 1 { Some(r.val_at(1)?) } else { None };
+		let head_number = match head {
+			Some(ref head) if Some(head.hash()) == expected_hash && pivot.as_ref().map_or(true, |p| p.number() + FAST_SYNC_PIVOT_DISTANCE == head.number()) => head.number(),
+			_ => {
+				trace!(target: "sync", "{}: Invalid pivot headers", peer_id);
+				io.report_peer(peer_id, PeerBehaviour::UselessResponse);
+				self.deactivate_peer(io, peer_id);
+				if self.state == SyncState::FastSyncPivot {
+					self.state = SyncState::WaitingPeers;
+				}
+				self.continue_sync(io);
+				return Ok(());
+			},
+		};
+		if head_number > self.highest_block.unwrap_or(0) {
+			self.highest_block = Some(head_number);
+		}
+		let best_block = io.chain().chain_info().best_block_number;
+		// a node with a chain of its own only skips block execution when it is far behind.
+		let far_behind = |pivot: &BlockHeader| best_block == 0 || pivot.number() - best_block > SNAPSHOT_RESTORE_THRESHOLD;
+		match pivot {
+			Some(ref pivot) if pivot.number() > best_block && self.fast_sync.as_ref().map_or(far_behind(pivot), |f| pivot.number() > f.pivot_number) => {
+				self.set_fast_sync_pivot(io, pivot);
+			},
+			_ if self.fast_sync.is_none() => {
+				debug!(target: "sync", "Best block #{} is too close to chain head #{} for fast sync, starting full sync", best_block, head_number);
+				self.enable_fast_sync = false;
+				self.state = SyncState::Idle;
+			},
+			_ => (),
+		}
+		self.continue_sync(io);
+		Ok(())
+	}
+
+	/// Start or retarget the fast sync download to a new pivot block.
+	fn set_fast_sync_pivot(&mut self, io: &mut SyncIo, pivot: &BlockHeader) {
+		match self.fast_sync {
+			Some(ref fast_sync) => info!(target: "sync", "Moving fast sync pivot from #{} to #{} ({})", fast_sync.pivot_number, pivot.number(), pivot.hash()),
+			None => info!(target: "sync", "Starting fast sync to pivot block #{} ({})", pivot.number(), pivot.hash()),
+		}
+		// already downloaded subtries of the old state are skipped
+		self.fast_sync = Some(FastSync {
+			pivot_number: pivot.number(),
+			pivot_hash: pivot.hash(),
+			state: StateDownloader::new(io.chain(), *pivot.state_root()),
+			pivot_block: None,
+		});
+		// block requests for the old target are no longer tracked
+		for (_, ref mut p) in self.peers.iter_mut().filter(|&(_, ref p)| p.block_set == Some(BlockSet::OldBlocks) || p.asking == PeerAsking::NodeData) {
+			p.reset_asking();
+		}
+		self.update_targets(io.chain());
+		self.state = SyncState::FastSync;
+	}
+
+	/// Import the pivot block once both the blocks and the state are downloaded.
+	fn maybe_complete_fast_sync(&mut self, io: &mut SyncIo) {
+		let block = match self.fast_sync {
+			Some(ref mut fast_sync) if fast_sync.state.is_complete() => match fast_sync.pivot_block.take() {
+				Some(block) => block,
+				None => return,
+			},
+			_ => return,
+		};
+		let receipts = block.receipts.unwrap_or_else(|| ::rlp::EMPTY_LIST_RLP.to_vec());
+		match io.chain().import_fast_sync_pivot(block.block, receipts) {
+			Ok(_) | Err(BlockImportError::Import(ImportError::AlreadyInChain)) => {
+				if let Some(ref fast_sync) = self.fast_sync {
+					info!(target: "sync", "Fast sync complete at block #{} ({})", fast_sync.pivot_number, fast_sync.pivot_hash);
+				}
+				self.enable_fast_sync = false;
+			},
+			Err(e) => {
+				debug!(target: "sync", "Error importing fast sync pivot block: {:?}, picking a new pivot", e);
+			},
+		}
+		self.fast_sync = None;
+		self.restart(io);
+	}
+
 	/// Restart sync disregarding the block queue status. May end up re-downloading up to QUEUE_SIZE blocks
 	pub fn restart(&mut self, io: &mut SyncIo) {
 		self.update_targets(io.chain());
@@ -588,7 +789,18 @@ impl ChainSync {
 		let chain = chain.chain_info();
 		self.new_blocks = BlockDownloader::new(false, &chain.best_block_hash, chain.best_block_number);
 		self.old_blocks = None;
-		if self.download_old_blocks {
+		if let Some(ref fast_sync) = self.fast_sync {
+			if fast_sync.pivot_block.is_none() {
+				let (start_hash, start_number) = match (chain.ancient_block_hash, chain.ancient_block_number) {
+					(Some(hash), Some(number)) => (hash, number),
+					_ => (chain.best_block_hash, chain.best_block_number),
+				};
+				trace!(target: "sync", "Downloading blocks from {:?} (#{}) till fast sync pivot {:?} (#{})", start_hash, start_number, fast_sync.pivot_hash, fast_sync.pivot_number);
+				let mut downloader = BlockDownloader::with_unlimited_reorg(true, &start_hash, start_number);
+				downloader.set_target(&fast_sync.pivot_hash);
+				self.old_blocks = Some(downloader);
+			}
+		} else if self.download_old_blocks {
 			if let (Some(ancient_block_hash), Some(ancient_block_number)) = (chain.ancient_block_hash, chain.ancient_block_number) {
 
 				trace!(target: "sync", "Downloading old blocks from {:?} (#{}) till {:?} (#{:?})", ancient_block_hash, ancient_block_number, chain.first_block_hash, chain.first_block_number);
@@ -617,6 +829,7 @@ impl ChainSync {
 			asking_blocks: Vec::new(),
 			asking_hash: None,
 			ask_time: 0,
+			ask_throttled: false,
 			last_sent_transactions: HashSet::new(),
 			expired: false,
 			confirmation: if self.fork_block.is_none() { ForkConfirmation::Confirmed } else { ForkConfirmation::Unconfirmed },
@@ -673,6 +886,9 @@ impl ChainSync {
 
 	/// Called by peer once it has new block headers during sync
 	fn on_peer_block_headers(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		if self.peers.get(&peer_id).map_or(false, |p| p.asking == PeerAsking::PivotHeaders) {
+			return self.on_peer_pivot_headers(io, peer_id, r);
+		}
 		let confirmed = match self.peers.get_mut(&peer_id) {
 			Some(ref mut peer) if peer.asking == PeerAsking::ForkHeader => {
 				peer.asking = PeerAsking::Nothing;
@@ -708,7 +924,7 @@ impl ChainSync {
 		let expected_hash = self.peers.get(&peer_id).and_then(|p| p.asking_hash);
 		let allowed = self.peers.get(&peer_id).map(|p| p.is_allowed()).unwrap_or(false);
 		let block_set = self.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
-		if !self.reset_peer_asking(peer_id, PeerAsking::BlockHeaders) || expected_hash.is_none() || !allowed {
+		if !self.reset_peer_asking(io, peer_id, PeerAsking::BlockHeaders) || expected_hash.is_none() || !allowed {
 			trace!(target: "sync", "{}: Ignored unexpected headers, expected_hash = {:?}", peer_id, expected_hash);
 			self.continue_sync(io);
 			return Ok(());
@@ -745,10 +961,11 @@ impl ChainSync {
 
 		match result {
 			Err(DownloaderImportError::Useless) => {
+				io.report_peer(peer_id, PeerBehaviour::UselessResponse);
 				self.deactivate_peer(io, peer_id);
 			},
 			Err(DownloaderImportError::Invalid) => {
-				io.disable_peer(peer_id);
+				io.report_peer(peer_id, PeerBehaviour::BadBlock);
 				self.deactivate_peer(io, peer_id);
 				self.continue_sync(io);
 				return Ok(());
@@ -776,7 +993,7 @@ impl ChainSync {
 	fn on_peer_block_bodies(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
 		self.clear_peer_download(peer_id);
 		let block_set = self.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
-		if !self.reset_peer_asking(peer_id, PeerAsking::BlockBodies) {
+		if !self.reset_peer_asking(io, peer_id, PeerAsking::BlockBodies) {
 			trace!(target: "sync", "{}: Ignored unexpected bodies", peer_id);
 			self.continue_sync(io);
 			return Ok(());
@@ -808,12 +1025,13 @@ impl ChainSync {
 
 			match result {
 				Err(DownloaderImportError::Invalid) => {
-					io.disable_peer(peer_id);
+					io.report_peer(peer_id, PeerBehaviour::BadBlock);
 					self.deactivate_peer(io, peer_id);
 					self.continue_sync(io);
 					return Ok(());
 				},
 				Err(DownloaderImportError::Useless) => {
+					io.report_peer(peer_id, PeerBehaviour::UselessResponse);
 					self.deactivate_peer(io, peer_id);
 				},
 				Ok(()) => (),
@@ -830,7 +1048,7 @@ impl ChainSync {
 	fn on_peer_block_receipts(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
 		self.clear_peer_download(peer_id);
 		let block_set = self.peers.get(&peer_id).and_then(|p| p.block_set).unwrap_or(BlockSet::NewBlocks);
-		if !self.reset_peer_asking(peer_id, PeerAsking::BlockReceipts) {
+		if !self.reset_peer_asking(io, peer_id, PeerAsking::BlockReceipts) {
 			trace!(target: "sync", "{}: Ignored unexpected receipts", peer_id);
 			self.continue_sync(io);
 			return Ok(());
@@ -862,12 +1080,13 @@ impl ChainSync {
 
 			match result {
 				Err(DownloaderImportError::Invalid) => {
-					io.disable_peer(peer_id);
+					io.report_peer(peer_id, PeerBehaviour::BadBlock);
 					self.deactivate_peer(io, peer_id);
 					self.continue_sync(io);
 					return Ok(());
 				},
 				Err(DownloaderImportError::Useless) => {
+					io.report_peer(peer_id, PeerBehaviour::UselessResponse);
 					self.deactivate_peer(io, peer_id);
 				},
 				Ok(()) => (),
@@ -880,6 +1099,45 @@ impl ChainSync {
 		Ok(())
 	}
 
+	/// Called by peer once it has state trie nodes
+	fn on_peer_node_data(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
+		self.clear_peer_download(peer_id);
+		if !self.reset_peer_asking(io, peer_id, PeerAsking::NodeData) {
+			trace!(target: "sync", "{}: Ignored unexpected node data", peer_id);
+			self.continue_sync(io);
+			return Ok(());
+		}
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{} -> NodeData ({} entries)", peer_id, item_count);
+		if item_count == 0 {
+			// the peer does not have the pivot state
+			io.report_peer(peer_id, PeerBehaviour::UselessResponse);
+			self.deactivate_peer(io, peer_id);
+		} else {
+			let mut nodes = Vec::with_capacity(item_count);
+			for item in r.iter() {
+				nodes.push(item.data()?.to_vec());
+			}
+			let result = match self.fast_sync {
+				Some(ref mut fast_sync) => fast_sync.state.import_nodes(io.chain(), nodes),
+				None => Ok(0),
+			};
+			match result {
+				Ok(count) => trace!(target: "sync", "{}: Imported {} of {} state nodes", peer_id, count, item_count),
+				Err(e) => {
+					debug!(target: "sync", "Error importing state nodes: {:?}", e);
+					if let Some(ref mut fast_sync) = self.fast_sync {
+						fast_sync.state = StateDownloader::new(io.chain(), *fast_sync.state.root());
+					}
+				},
+			}
+			self.maybe_complete_fast_sync(io);
+			self.sync_peer(io, peer_id, false);
+		}
+		self.continue_sync(io);
+		Ok(())
+	}
+
 	/// Called by peer once it has new block bodies
 	fn on_peer_new_block(&mut self, io: &mut SyncIo, peer_id: PeerId, r: &UntrustedRlp) -> Result<(), PacketDecodeError> {
 		if !self.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -931,7 +1189,7 @@ impl ChainSync {
 			},
 			Err(e) => {
 				debug!(target: "sync", "Bad new block {:?} : {:?}", h, e);
-				io.disable_peer(peer_id);
+				io.report_peer(peer_id, PeerBehaviour::BadBlock);
 			}
 		};
 		if unknown {
@@ -1007,7 +1265,7 @@ impl ChainSync {
 				},
 				BlockStatus::Bad => {
 					debug!(target: "sync", "Bad new block hash {:?}", hash);
-					io.disable_peer(peer_id);
+					io.report_peer(peer_id, PeerBehaviour::BadBlock);
 					return Ok(());
 				}
 			}
@@ -1029,7 +1287,7 @@ impl ChainSync {
 			return Ok(());
 		}
 		self.clear_peer_download(peer_id);
-		if !self.reset_peer_asking(peer_id, PeerAsking::SnapshotManifest) || self.state != SyncState::SnapshotManifest {
+		if !self.reset_peer_asking(io, peer_id, PeerAsking::SnapshotManifest) || self.state != SyncState::SnapshotManifest {
 			trace!(target: "sync", "{}: Ignored unexpected/expired manifest", peer_id);
 			self.continue_sync(io);
 			return Ok(());
@@ -1073,7 +1331,7 @@ impl ChainSync {
 			return Ok(());
 		}
 		self.clear_peer_download(peer_id);
-		if !self.reset_peer_asking(peer_id, PeerAsking::SnapshotData) || (self.state != SyncState::SnapshotData && self.state != SyncState::SnapshotWaiting) {
+		if !self.reset_peer_asking(io, peer_id, PeerAsking::SnapshotData) || (self.state != SyncState::SnapshotData && self.state != SyncState::SnapshotWaiting) {
 			trace!(target: "sync", "{}: Ignored unexpected snapshot data", peer_id);
 			self.continue_sync(io);
 			return Ok(());
@@ -1113,6 +1371,7 @@ impl ChainSync {
 			}
 			Err(()) => {
 				trace!(target: "sync", "{}: Got bad snapshot chunk", peer_id);
+				io.report_peer(peer_id, PeerBehaviour::UselessResponse);
 				io.disconnect_peer(peer_id);
 				self.continue_sync(io);
 				return Ok(());
@@ -1167,7 +1426,8 @@ impl ChainSync {
 				self.sync_peer(io, p, false);
 			}
 		}
-		if (self.state != SyncState::WaitingPeers && self.state != SyncState::SnapshotWaiting && self.state != SyncState::Waiting && self.state != SyncState::Idle)
+		if (self.state != SyncState::WaitingPeers && self.state != SyncState::SnapshotWaiting && self.state != SyncState::Waiting && self.state != SyncState::Idle
+			&& self.state != SyncState::FastSyncPivot && self.state != SyncState::FastSync)
 			&& !self.peers.values().any(|p| p.asking != PeerAsking::Nothing && p.block_set != Some(BlockSet::OldBlocks) && p.can_sync()) {
 
 			self.complete_sync(io);
@@ -1217,11 +1477,25 @@ impl ChainSync {
 		let num_active_peers = self.peers.values().filter(|p| p.asking != PeerAsking::Nothing).count();
 
 		let higher_difficulty = peer_difficulty.map_or(true, |pd| pd > syncing_difficulty);
-		if force || higher_difficulty || self.old_blocks.is_some() {
+		if force || higher_difficulty || self.old_blocks.is_some() || self.fast_sync.is_some() {
 			match self.state {
 				SyncState::WaitingPeers => {
 					trace!(target: "sync", "Checking snapshot sync: {} vs {}", peer_snapshot_number, chain_info.best_block_number);
 					self.maybe_start_snapshot_sync(io);
+					self.maybe_start_fast_sync(io);
+				},
+				SyncState::FastSync => {
+					// split peers between block and state downloads
+					let node_peers = self.peers.values().filter(|p| p.asking == PeerAsking::NodeData).count();
+					let block_peers = self.peers.values().filter(|p| p.block_set == Some(BlockSet::OldBlocks)).count();
+					if node_peers <= block_peers && self.request_node_data(io, peer_id) {
+						return;
+					}
+					if let Some(request) = self.old_blocks.as_mut().and_then(|d| d.request_blocks(io, num_active_peers)) {
+						self.request_blocks(io, peer_id, request, BlockSet::OldBlocks);
+						return;
+					}
+					self.request_node_data(io, peer_id);
 				},
 				SyncState::Idle | SyncState::Blocks | SyncState::NewBlocks => {
 					if io.chain().queue_info().is_full() {
@@ -1260,7 +1534,7 @@ impl ChainSync {
 						self.request_snapshot_data(io, peer_id);
 					}
 				},
-				SyncState::SnapshotManifest | //already downloading from other peer
+				SyncState::SnapshotManifest | SyncState::FastSyncPivot | //already downloading from other peer
 					SyncState::Waiting | SyncState::SnapshotWaiting => ()
 			}
 		} else {
@@ -1324,6 +1598,11 @@ impl ChainSync {
 						self.snapshot.clear_chunk_download(&hash);
 					}
 				},
+				PeerAsking::NodeData => {
+					if let Some(ref mut fast_sync) = self.fast_sync {
+						fast_sync.state.clear_downloads(&peer.asking_blocks);
+					}
+				},
 				_ => (),
 			}
 		}
@@ -1342,7 +1621,11 @@ impl ChainSync {
 					self.restart(io);
 				} else if self.old_blocks.as_ref().map_or(false, |downloader| { downloader.is_complete() }) {
 					trace!(target: "sync", "Background block download is complete");
-					self.old_blocks = None;
+					let target_block = self.old_blocks.take().and_then(|mut downloader| downloader.take_target_block());
+					if let Some(ref mut fast_sync) = self.fast_sync {
+						fast_sync.pivot_block = target_block;
+					}
+					self.maybe_complete_fast_sync(io);
 				}
 			}
 		}
@@ -1373,6 +1656,43 @@ impl ChainSync {
 		self.send_request(sync, peer_id, PeerAsking::ForkHeader, GET_BLOCK_HEADERS_PACKET, rlp.out());
 	}
 
+	/// Request the header `FAST_SYNC_PIVOT_DISTANCE` blocks below the peer best block, along with the best block header.
+	fn request_pivot_headers(&mut self, sync: &mut SyncIo, peer_id: PeerId) {
+		let latest_hash = match self.peers.get(&peer_id) {
+			Some(peer) => peer.latest_hash,
+			None => return,
+		};
+		trace!(target: "sync", "{} <- GetPivotHeaders: from {}", peer_id, latest_hash);
+		let mut rlp = RlpStream::new_list(4);
+		rlp.append(&latest_hash);
+		rlp.append(&2u32);
+		rlp.append(&(FAST_SYNC_PIVOT_DISTANCE - 1));
+		rlp.append(&1u32);
+		self.send_request(sync, peer_id, PeerAsking::PivotHeaders, GET_BLOCK_HEADERS_PACKET, rlp.out());
+		let peer = self.peers.get_mut(&peer_id).expect("peer_id may originate either from on_packet, where it is already validated or from enumerating self.peers. qed");
+		peer.asking_hash = Some(latest_hash);
+	}
+
+	/// Request missing fast sync state nodes from a peer. Returns `false` if there is nothing to request.
+	fn request_node_data(&mut self, sync: &mut SyncIo, peer_id: PeerId) -> bool {
+		let hashes = match self.fast_sync {
+			Some(ref mut fast_sync) => fast_sync.state.needed_nodes(MAX_NODE_DATA_TO_REQUEST),
+			None => return false,
+		};
+		if hashes.is_empty() {
+			return false;
+		}
+		let mut rlp = RlpStream::new_list(hashes.len());
+		trace!(target: "sync", "{} <- GetNodeData: {} entries starting from {:?}", peer_id, hashes.len(), hashes.first());
+		for h in &hashes {
+			rlp.append(h);
+		}
+		self.send_request(sync, peer_id, PeerAsking::NodeData, GET_NODE_DATA_PACKET, rlp.out());
+		let peer = self.peers.get_mut(&peer_id).expect("peer_id may originate either from on_packet, where it is already validated or from enumerating self.peers. qed");
+		peer.asking_blocks = hashes;
+		true
+	}
+
 	/// Request snapshot manifest from a peer.
 	fn request_snapshot_manifest(&mut self, sync: &mut SyncIo, peer_id: PeerId) {
 		trace!(target: "sync", "{} <- GetSnapshotManifest", peer_id);
@@ -1415,7 +1735,7 @@ impl ChainSync {
 	}
 
 	/// Reset peer status after request is complete.
-	fn reset_peer_asking(&mut self, peer_id: PeerId, asking: PeerAsking) -> bool {
+	fn reset_peer_asking(&mut self, io: &mut SyncIo, peer_id: PeerId, asking: PeerAsking) -> bool {
 		if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
 			peer.expired = false;
 			peer.block_set = None;
@@ -1425,6 +1745,12 @@ impl ChainSync {
 				return false;
 			} else {
 				peer.asking = PeerAsking::Nothing;
+				if peer.ask_throttled {
+					trace!(target:"sync", "{}: Not reporting latency of a request delayed by upload limits", peer_id);
+				} else {
+					let latency = (time::precise_time_ns() - peer.ask_time) / 1_000_000;
+					io.report_peer(peer_id, PeerBehaviour::Latency(latency));
+				}
 				return true;
 			}
 		}
@@ -1433,19 +1759,32 @@ impl ChainSync {
 
 	/// Generic request sender
 	fn send_request(&mut self, sync: &mut SyncIo, peer_id: PeerId, asking: PeerAsking,  packet_id: PacketId, packet: Bytes) {
-		if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
-			if peer.asking != PeerAsking::Nothing {
-				warn!(target:"sync", "Asking {:?} while requesting {:?}", peer.asking, asking);
-			}
-			peer.asking = asking;
-			peer.ask_time = time::precise_time_ns();
-			let result = if packet_id >= ETH_PACKET_COUNT {
-				sync.send_protocol(WARP_SYNC_PROTOCOL_ID, peer_id, packet_id, packet)
+		let result = match self.peers.get_mut(&peer_id) {
+			Some(peer) => {
+				if peer.asking != PeerAsking::Nothing {
+					warn!(target:"sync", "Asking {:?} while requesting {:?}", peer.asking, asking);
+				}
+				peer.asking = asking;
+				peer.ask_time = time::precise_time_ns();
+				let result = if packet_id >= ETH_PACKET_COUNT {
+					sync.send_protocol(WARP_SYNC_PROTOCOL_ID, peer_id, packet_id, packet)
+				} else {
+					sync.send(peer_id, packet_id, packet)
+				};
+				peer.ask_throttled = sync.is_throttled(peer_id);
+				result
+			},
+			None => return,
+		};
+		if let Err(e) = result {
+			debug!(target:"sync", "Error sending request: {:?}", e);
+			if let network::ErrorKind::RateLimited = *e.kind() {
+				// Our own upload limit, not the peer's fault. Release the request so it can be retried.
+				self.clear_peer_download(peer_id);
+				if let Some(ref mut peer) = self.peers.get_mut(&peer_id) {
+					peer.asking = PeerAsking::Nothing;
+				}
 			} else {
-				sync.send(peer_id, packet_id, packet)
-			};
-			if let Err(e) = result {
-				debug!(target:"sync", "Error sending request: {:?}", e);
 				sync.disconnect_peer(peer_id);
 			}
 		}
@@ -1455,6 +1794,9 @@ impl ChainSync {
 	fn send_packet(&mut self, sync: &mut SyncIo, peer_id: PeerId, packet_id: PacketId, packet: Bytes) {
 		if let Err(e) = sync.send(peer_id, packet_id, packet) {
 			debug!(target:"sync", "Error sending packet: {:?}", e);
+			if let network::ErrorKind::RateLimited = *e.kind() {
+				return;
+			}
 			sync.disconnect_peer(peer_id);
 		}
 	}
@@ -1746,9 +2088,10 @@ impl ChainSync {
 				Ok(())
 			}
 		};
-		result.unwrap_or_else(|e| {
+		if let Err(e) = result {
 			debug!(target:"sync", "{} -> Malformed packet {} : {}", peer, packet_id, e);
-		})
+			io.report_peer(peer, PeerBehaviour::ProtocolViolation);
+		}
 	}
 
 	pub fn on_packet(&mut self, io: &mut SyncIo, peer: PeerId, packet_id: u8, data: &[u8]) {
@@ -1763,6 +2106,7 @@ impl ChainSync {
 			BLOCK_HEADERS_PACKET => self.on_peer_block_headers(io, peer, &rlp),
 			BLOCK_BODIES_PACKET => self.on_peer_block_bodies(io, peer, &rlp),
 			RECEIPTS_PACKET => self.on_peer_block_receipts(io, peer, &rlp),
+			NODE_DATA_PACKET => self.on_peer_node_data(io, peer, &rlp),
 			NEW_BLOCK_PACKET => self.on_peer_new_block(io, peer, &rlp),
 			NEW_BLOCK_HASHES_PACKET => self.on_peer_new_hashes(io, peer, &rlp),
 			SNAPSHOT_MANIFEST_PACKET => self.on_snapshot_manifest(io, peer, &rlp),
@@ -1772,9 +2116,10 @@ impl ChainSync {
 				Ok(())
 			}
 		};
-		result.unwrap_or_else(|e| {
+		if let Err(e) = result {
 			debug!(target:"sync", "{} -> Malformed packet {} : {}", peer, packet_id, e);
-		})
+			io.report_peer(peer, PeerBehaviour::ProtocolViolation);
+		}
 	}
 
 	pub fn maintain_peers(&mut self, io: &mut SyncIo) {
@@ -1790,9 +2135,12 @@ impl ChainSync {
 				PeerAsking::ForkHeader => elapsed > FORK_HEADER_TIMEOUT_SEC,
 				PeerAsking::SnapshotManifest => elapsed > SNAPSHOT_MANIFEST_TIMEOUT_SEC,
 				PeerAsking::SnapshotData => elapsed > SNAPSHOT_DATA_TIMEOUT_SEC,
+				PeerAsking::PivotHeaders => elapsed > HEADERS_TIMEOUT_SEC,
+				PeerAsking::NodeData => elapsed > NODE_DATA_TIMEOUT_SEC,
 			};
 			if timeout {
 				trace!(target:"sync", "Timeout {}", peer_id);
+				io.report_peer(*peer_id, PeerBehaviour::Timeout);
 				io.disconnect_peer(*peer_id);
 				aborting.push(*peer_id);
 			}
@@ -1806,6 +2154,7 @@ impl ChainSync {
 			let elapsed = (tick - ask_time) / 1_000_000_000;
 			if elapsed > STATUS_TIMEOUT_SEC {
 				trace!(target:"sync", "Status timeout {}", peer);
+				io.report_peer(*peer, PeerBehaviour::Timeout);
 				io.disconnect_peer(*peer);
 			}
 		}
@@ -2155,6 +2504,7 @@ impl ChainSync {
 	/// Maintain other peers. Send out any new blocks and transactions
 	pub fn maintain_sync(&mut self, io: &mut SyncIo) {
 		self.maybe_start_snapshot_sync(io);
+		self.maybe_start_fast_sync(io);
 		self.check_resume(io);
 	}
 
@@ -2475,6 +2825,7 @@ mod tests {
 				asking_blocks: Vec::new(),
 				asking_hash: None,
 				ask_time: 0,
+				ask_throttled: false,
 				last_sent_transactions: HashSet::new(),
 				expired: false,
 				confirmation: super::ForkConfirmation::Confirmed,
@@ -2486,6 +2837,28 @@ mod tests {
 
 	}
 
+	#[test]
+	fn does_not_report_latency_of_throttled_requests() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(H256::new(), &client);
+		insert_dummy_peer(&mut sync, 1, H256::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		io.throttled.insert(1);
+
+		for peer_id in 0..2 {
+			sync.send_request(&mut io, peer_id, PeerAsking::BlockHeaders, GET_BLOCK_HEADERS_PACKET, Bytes::new());
+			assert!(sync.reset_peer_asking(&mut io, peer_id, PeerAsking::BlockHeaders));
+		}
+
+		assert_eq!(io.reports.len(), 1);
+		match io.reports[0] {
+			(0, PeerBehaviour::Latency(_)) => {},
+			ref report => panic!("unexpected report {:?}", report),
+		}
+	}
+
 	#[test]
 	fn finds_lagging_peers() {
 		let mut client = TestBlockChainClient::new();
@@ -2515,6 +2888,22 @@ mod tests {
 		assert_eq!(107, rlp.len());
 	}
 
+	#[test]
+	fn reports_timed_out_peers() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(5), &client);
+		sync.peers.get_mut(&0).unwrap().asking = PeerAsking::BlockHeaders;
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+
+		sync.maintain_peers(&mut io);
+
+		assert_eq!(io.reports, vec![(0, PeerBehaviour::Timeout)]);
+		assert!(io.to_disconnect.contains(&0));
+	}
+
 	#[test]
 	fn sends_new_hashes_to_lagging_peer() {
 		let mut client = TestBlockChainClient::new();
@@ -2596,6 +2985,7 @@ mod tests {
 				asking_blocks: Vec::new(),
 				asking_hash: None,
 				ask_time: 0,
+				ask_throttled: false,
 				last_sent_transactions: HashSet::new(),
 				expired: false,
 				confirmation: super::ForkConfirmation::Confirmed,
@@ -57,6 +57,7 @@ mod blocks;
 mod block_sync;
 mod sync_io;
 mod snapshot;
+mod state_sync;
 mod transactions_stats;
 
 pub mod light_sync;
@@ -68,4 +69,5 @@ mod api;
 
 pub use api::*;
 pub use chain::{SyncStatus, SyncState};
-pub use network::{validate_node_url, NonReservedPeerMode, Error, ErrorKind, ConnectionFilter, ConnectionDirection};
+pub use network::{validate_node_url, NonReservedPeerMode, Error, ErrorKind, ConnectionFilter, ConnectionDirection, PeerBehaviour,
+	TrafficStats};
@@ -0,0 +1,490 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+///
+/// Fast sync state downloader
+///
+/// Fetches the state trie of the fast sync pivot block node by node with `GetNodeData`.
+/// A node is only written to the database once its whole subtree is present there,
+/// so any node found in the database is known to be complete. This lets a download
+/// that was interrupted or retargeted to a new pivot skip everything it already has.
+/// Nodes are fetched depth-first, so subtrees complete and leave memory one after
+/// another and the incomplete nodes held are bounded by the trie depth.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem;
+use heapsize::HeapSizeOf;
+use ethereum_types::H256;
+use hash::{keccak, KECCAK_NULL_RLP, KECCAK_EMPTY};
+use bytes::Bytes;
+use rlp::{UntrustedRlp, DecoderError};
+use ethcore::client::BlockChainClient;
+use ethcore::error::Error as EthcoreError;
+
+/// Trie node identifier: owning account address hash (for storage and code) and node hash.
+type NodeKey = (Option<H256>, H256);
+
+#[derive(Clone, PartialEq, Debug)]
+enum NodeKind {
+	/// State trie node with the key nibbles leading to it.
+	State(Vec<u8>),
+	/// Account storage trie node.
+	Storage,
+	/// Contract code.
+	Code,
+}
+
+/// Outstanding request for a node.
+struct NodeRequest {
+	account: Option<H256>,
+	kind: NodeKind,
+	parent: Option<NodeKey>,
+}
+
+impl HeapSizeOf for NodeRequest {
+	fn heap_size_of_children(&self) -> usize {
+		match self.kind {
+			NodeKind::State(ref path) => path.heap_size_of_children(),
+			NodeKind::Storage | NodeKind::Code => 0,
+		}
+	}
+}
+
+/// Downloaded node waiting for its children.
+struct PendingNode {
+	data: Bytes,
+	missing: usize,
+	parents: Vec<NodeKey>,
+}
+
+/// Downloads a state trie for the given root.
+pub struct StateDownloader {
+	root: H256,
+	/// Node requests by node hash.
+	waiting: HashMap<H256, Vec<NodeRequest>>,
+	/// Hashes to request, in order. Children are queued in front of their parent's siblings.
+	queue: VecDeque<H256>,
+	/// Hashes currently requested from peers.
+	in_flight: HashSet<H256>,
+	/// Downloaded nodes with missing children.
+	pending: HashMap<NodeKey, PendingNode>,
+	/// Complete nodes not yet written to the database.
+	batch: Vec<(Option<H256>, Bytes)>,
+	batch_keys: HashSet<NodeKey>,
+	/// Account address hashes found in the batch.
+	accounts: Vec<H256>,
+	/// Number of nodes written to the database.
+	done: usize,
+}
+
+impl StateDownloader {
+	/// Create a new downloader for the state trie with the given root.
+	pub fn new(chain: &BlockChainClient, root: H256) -> Self {
+		let mut downloader = StateDownloader {
+			root: root,
+			waiting: HashMap::new(),
+			queue: VecDeque::new(),
+			in_flight: HashSet::new(),
+			pending: HashMap::new(),
+			batch: Vec::new(),
+			batch_keys: HashSet::new(),
+			accounts: Vec::new(),
+			done: 0,
+		};
+		downloader.request(chain, None, root, NodeKind::State(Vec::new()), None);
+		downloader
+	}
+
+	/// State root being downloaded.
+	pub fn root(&self) -> &H256 {
+		&self.root
+	}
+
+	/// Check if the whole trie is in the database.
+	pub fn is_complete(&self) -> bool {
+		self.waiting.is_empty() && self.pending.is_empty()
+	}
+
+	/// Number of nodes written to the database so far.
+	pub fn done_count(&self) -> usize {
+		self.done
+	}
+
+	/// Number of nodes known to be missing.
+	pub fn pending_count(&self) -> usize {
+		self.waiting.len() + self.pending.len()
+	}
+
+	/// Get up to `max` node hashes to request from a peer and mark them as being downloaded.
+	pub fn needed_nodes(&mut self, max: usize) -> Vec<H256> {
+		let mut hashes = Vec::new();
+		while hashes.len() < max {
+			match self.queue.pop_front() {
+				Some(hash) => if self.waiting.contains_key(&hash) && self.in_flight.insert(hash) {
+					hashes.push(hash);
+				},
+				None => break,
+			}
+		}
+		hashes
+	}
+
+	/// Mark nodes as no longer being downloaded so they are requested again.
+	pub fn clear_downloads(&mut self, hashes: &[H256]) {
+		for hash in hashes {
+			if self.in_flight.remove(hash) && self.waiting.contains_key(hash) {
+				self.queue.push_front(*hash);
+			}
+		}
+	}
+
+	/// Mark all nodes as no longer being downloaded.
+	pub fn reset_downloads(&mut self) {
+		let hashes: Vec<_> = self.in_flight.iter().cloned().collect();
+		self.clear_downloads(&hashes);
+	}
+
+	/// Import node data received from a peer. Returns the number of requested nodes found in it.
+	pub fn import_nodes(&mut self, chain: &BlockChainClient, nodes: Vec<Bytes>) -> Result<usize, EthcoreError> {
+		let mut useful = 0;
+		for data in nodes {
+			let hash = keccak(&data);
+			let requests = match self.waiting.remove(&hash) {
+				Some(requests) => requests,
+				None => continue,
+			};
+			self.in_flight.remove(&hash);
+			useful += 1;
+			for request in requests {
+				self.insert_node(chain, request, hash, data.clone());
+			}
+		}
+		self.flush(chain)?;
+		Ok(useful)
+	}
+
+	/// Get the heap size of the downloader.
+	pub fn heap_size(&self) -> usize {
+		self.pending.values().map(|n| n.data.heap_size_of_children()).sum::<usize>()
+			+ self.batch.iter().map(|n| n.1.heap_size_of_children()).sum::<usize>()
+			+ self.waiting.heap_size_of_children()
+			+ self.queue.heap_size_of_children()
+			+ self.in_flight.heap_size_of_children()
+	}
+
+	/// Request a node unless it is already known. Returns `true` if the node is missing.
+	fn request(&mut self, chain: &BlockChainClient, account: Option<H256>, hash: H256, kind: NodeKind, parent: Option<NodeKey>) -> bool {
+		let key = (account, hash);
+		if hash == KECCAK_NULL_RLP || hash == KECCAK_EMPTY || self.batch_keys.contains(&key) {
+			return false;
+		}
+		if let Some(node) = self.pending.get_mut(&key) {
+			node.parents.extend(parent);
+			return true;
+		}
+		if chain.state_node_known(account.as_ref(), &hash) {
+			return false;
+		}
+		let request = NodeRequest { account: account, kind: kind, parent: parent };
+		let queue = &mut self.queue;
+		self.waiting.entry(hash).or_insert_with(|| { queue.push_front(hash); Vec::new() }).push(request);
+		true
+	}
+
+	/// Handle a downloaded node: request its missing children or mark it complete.
+	fn insert_node(&mut self, chain: &BlockChainClient, request: NodeRequest, hash: H256, data: Bytes) {
+		let key = (request.account, hash);
+		if let Some(node) = self.pending.get_mut(&key) {
+			node.parents.extend(request.parent);
+			return;
+		}
+		if self.batch_keys.contains(&key) {
+			self.child_complete(request.parent);
+			return;
+		}
+		let mut children = Vec::new();
+		if let Err(e) = node_children(&request.kind, request.account, &data, &mut children, &mut self.accounts) {
+			warn!(target: "sync", "Error decoding state node {}: {:?}", hash, e);
+		}
+		// queued in reverse, so the children end up at the front of the queue in order.
+		let mut missing = 0;
+		for (account, child, kind) in children.into_iter().rev() {
+			if self.request(chain, account, child, kind, Some(key)) {
+				missing += 1;
+			}
+		}
+		if missing == 0 {
+			self.commit(key, data);
+			self.child_complete(request.parent);
+		} else {
+			self.pending.insert(key, PendingNode {
+				data: data,
+				missing: missing,
+				parents: request.parent.into_iter().collect(),
+			});
+		}
+	}
+
+	/// Note that a child of `parent` is complete, completing ancestors as needed.
+	fn child_complete(&mut self, parent: Option<NodeKey>) {
+		let mut completed: Vec<NodeKey> = parent.into_iter().collect();
+		while let Some(key) = completed.pop() {
+			let is_complete = match self.pending.get_mut(&key) {
+				Some(node) => {
+					node.missing -= 1;
+					node.missing == 0
+				},
+				None => false,
+			};
+			if is_complete {
+				let node = self.pending.remove(&key).expect("Node was found in pending above; qed");
+				self.commit(key, node.data);
+				completed.extend(node.parents);
+			}
+		}
+	}
+
+	fn commit(&mut self, key: NodeKey, data: Bytes) {
+		self.batch_keys.insert(key);
+		self.batch.push((key.0, data));
+		self.done += 1;
+	}
+
+	/// Write complete nodes to the database.
+	fn flush(&mut self, chain: &BlockChainClient) -> Result<(), EthcoreError> {
+		if self.batch.is_empty() {
+			return Ok(());
+		}
+		let nodes = mem::replace(&mut self.batch, Vec::new());
+		let accounts = mem::replace(&mut self.accounts, Vec::new());
+		self.batch_keys.clear();
+		trace!(target: "sync", "Writing {} state nodes", nodes.len());
+		chain.import_state_nodes(nodes, &accounts)
+	}
+}
+
+/// Collect hash references from a trie node. State trie leaves yield the account storage root
+/// and code hash, and their address hash is added to `accounts`.
+fn node_children(kind: &NodeKind, account: Option<H256>, data: &[u8], children: &mut Vec<(Option<H256>, H256, NodeKind)>, accounts: &mut Vec<H256>) -> Result<(), DecoderError> {
+	let path = match *kind {
+		NodeKind::State(ref path) => Some(path.clone()),
+		NodeKind::Storage => None,
+		NodeKind::Code => return Ok(()),
+	};
+	walk_node(&UntrustedRlp::new(data), path, account, children, accounts)
+}
+
+fn walk_node(rlp: &UntrustedRlp, path: Option<Vec<u8>>, account: Option<H256>, children: &mut Vec<(Option<H256>, H256, NodeKind)>, accounts: &mut Vec<H256>) -> Result<(), DecoderError> {
+	if rlp.is_data() && rlp.is_empty() {
+		return Ok(());
+	}
+	match rlp.item_count()? {
+		17 => {
+			for i in 0..16 {
+				let child_path = path.as_ref().map(|p| {
+					let mut p = p.clone();
+					p.push(i as u8);
+					p
+				});
+				walk_reference(&rlp.at(i)?, child_path, account, children, accounts)?;
+			}
+			Ok(())
+		},
+		2 => {
+			let (nibbles, is_leaf) = decode_hex_prefix(rlp.at(0)?.data()?)?;
+			let path = path.map(|mut p| {
+				p.extend(nibbles);
+				p
+			});
+			if !is_leaf {
+				return walk_reference(&rlp.at(1)?, path, account, children, accounts);
+			}
+			if let Some(path) = path {
+				if path.len() != 64 {
+					return Err(DecoderError::Custom("Invalid account key length"));
+				}
+				let address_hash = nibbles_to_hash(&path);
+				let account_rlp = UntrustedRlp::new(rlp.at(1)?.data()?);
+				let storage_root: H256 = account_rlp.val_at(2)?;
+				let code_hash: H256 = account_rlp.val_at(3)?;
+				accounts.push(address_hash);
+				children.push((Some(address_hash), storage_root, NodeKind::Storage));
+				children.push((Some(address_hash), code_hash, NodeKind::Code));
+			}
+			Ok(())
+		},
+		_ => Err(DecoderError::Custom("Invalid trie node")),
+	}
+}
+
+fn walk_reference(rlp: &UntrustedRlp, path: Option<Vec<u8>>, account: Option<H256>, children: &mut Vec<(Option<H256>, H256, NodeKind)>, accounts: &mut Vec<H256>) -> Result<(), DecoderError> {
+	if rlp.is_list() {
+		// inline node
+		return walk_node(rlp, path, account, children, accounts);
+	}
+	let data = rlp.data()?;
+	match data.len() {
+		0 => Ok(()),
+		32 => {
+			let kind = match path {
+				Some(path) => NodeKind::State(path),
+				None => NodeKind::Storage,
+			};
+			children.push((account, H256::from_slice(data), kind));
+			Ok(())
+		},
+		_ => Err(DecoderError::Custom("Invalid trie node reference")),
+	}
+}
+
+/// Decode a hex-prefix encoded key into nibbles and the leaf flag.
+fn decode_hex_prefix(data: &[u8]) -> Result<(Vec<u8>, bool), DecoderError> {
+	if data.is_empty() {
+		return Err(DecoderError::Custom("Empty trie node key"));
+	}
+	let is_leaf = data[0] & 0x20 != 0;
+	let mut nibbles = Vec::with_capacity(data.len() * 2);
+	if data[0] & 0x10 != 0 {
+		nibbles.push(data[0] & 0x0f);
+	}
+	for b in &data[1..] {
+		nibbles.push(b >> 4);
+		nibbles.push(b & 0x0f);
+	}
+	Ok((nibbles, is_leaf))
+}
+
+fn nibbles_to_hash(nibbles: &[u8]) -> H256 {
+	let mut hash = [0u8; 32];
+	for (i, pair) in nibbles.chunks(2).enumerate() {
+		hash[i] = (pair[0] << 4) | pair[1];
+	}
+	H256(hash)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use rlp::RlpStream;
+	use ethcore::client::TestBlockChainClient;
+	use tests::helpers::{nibbles, leaf, account};
+
+	struct TestState {
+		root: H256,
+		branch: Bytes,
+		leaf_a: Bytes,
+		leaf_b: Bytes,
+		storage: Bytes,
+		code: Bytes,
+	}
+
+	// Two accounts under a branch node, the first with storage and code.
+	fn test_state() -> TestState {
+		let address_a = H256::from("0a00000000000000000000000000000000000000000000000000000000000001");
+		let address_b = H256::from("1b00000000000000000000000000000000000000000000000000000000000002");
+		let code = b"contract code".to_vec();
+		let storage = leaf(&nibbles(&keccak(&[1u8])), &[0x2a]);
+		let leaf_a = leaf(&nibbles(&address_a)[1..], &account(&keccak(&storage), &keccak(&code)));
+		let leaf_b = leaf(&nibbles(&address_b)[1..], &account(&KECCAK_NULL_RLP, &KECCAK_EMPTY));
+		let mut branch = RlpStream::new_list(17);
+		branch.append(&keccak(&leaf_a));
+		branch.append(&keccak(&leaf_b));
+		for _ in 2..17 {
+			branch.append_empty_data();
+		}
+		let branch = branch.out();
+		TestState {
+			root: keccak(&branch),
+			branch: branch,
+			leaf_a: leaf_a,
+			leaf_b: leaf_b,
+			storage: storage,
+			code: code,
+		}
+	}
+
+	#[test]
+	fn empty_state_is_complete() {
+		let client = TestBlockChainClient::new();
+		let mut downloader = StateDownloader::new(&client, KECCAK_NULL_RLP);
+		assert!(downloader.is_complete());
+		assert!(downloader.needed_nodes(10).is_empty());
+	}
+
+	#[test]
+	fn downloads_state_storage_and_code() {
+		let client = TestBlockChainClient::new();
+		let state = test_state();
+		let mut downloader = StateDownloader::new(&client, state.root);
+
+		assert_eq!(downloader.needed_nodes(10), vec![state.root]);
+		assert_eq!(downloader.import_nodes(&client, vec![state.branch.clone()]).unwrap(), 1);
+		assert_eq!(downloader.needed_nodes(10), vec![keccak(&state.leaf_a), keccak(&state.leaf_b)]);
+		assert_eq!(downloader.import_nodes(&client, vec![state.leaf_a.clone(), state.leaf_b.clone()]).unwrap(), 2);
+		// the account without storage is complete, the root is not
+		assert!(client.state_node_known(None, &keccak(&state.leaf_b)));
+		assert!(!client.state_node_known(None, &state.root));
+
+		assert_eq!(downloader.needed_nodes(10), vec![keccak(&state.storage), keccak(&state.code)]);
+		assert_eq!(downloader.import_nodes(&client, vec![state.storage.clone(), state.code.clone()]).unwrap(), 2);
+		assert!(downloader.is_complete());
+		assert_eq!(downloader.done_count(), 5);
+		assert!(client.state_node_known(None, &state.root));
+	}
+
+	#[test]
+	fn skips_known_subtrees() {
+		let client = TestBlockChainClient::new();
+		let state = test_state();
+		client.import_state_nodes(vec![(None, state.leaf_b.clone())], &[]).unwrap();
+		let mut downloader = StateDownloader::new(&client, state.root);
+
+		downloader.needed_nodes(10);
+		downloader.import_nodes(&client, vec![state.branch.clone()]).unwrap();
+		assert_eq!(downloader.needed_nodes(10), vec![keccak(&state.leaf_a)]);
+	}
+
+	#[test]
+	fn downloads_depth_first() {
+		let client = TestBlockChainClient::new();
+		let state = test_state();
+		let mut downloader = StateDownloader::new(&client, state.root);
+
+		downloader.needed_nodes(10);
+		downloader.import_nodes(&client, vec![state.branch.clone()]).unwrap();
+		assert_eq!(downloader.needed_nodes(1), vec![keccak(&state.leaf_a)]);
+		downloader.import_nodes(&client, vec![state.leaf_a.clone()]).unwrap();
+		// the storage and code of the first account come before its sibling.
+		assert_eq!(downloader.needed_nodes(10), vec![keccak(&state.storage), keccak(&state.code), keccak(&state.leaf_b)]);
+	}
+
+	#[test]
+	fn requeues_cleared_downloads() {
+		let client = TestBlockChainClient::new();
+		let state = test_state();
+		let mut downloader = StateDownloader::new(&client, state.root);
+
+		let hashes = downloader.needed_nodes(10);
+		assert!(downloader.needed_nodes(10).is_empty());
+		// unrequested data is ignored
+		assert_eq!(downloader.import_nodes(&client, vec![state.leaf_a.clone()]).unwrap(), 0);
+		downloader.clear_downloads(&hashes);
+		assert_eq!(downloader.needed_nodes(10), hashes);
+		downloader.reset_downloads();
+		assert_eq!(downloader.needed_nodes(10), hashes);
+		assert_eq!(downloader.pending_count(), 1);
+	}
+}
@@ -15,8 +15,15 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
+use std::collections::HashMap;
+use parking_lot::RwLock;
+use ethereum_types::H256;
+use bytes::Bytes;
+use hash::keccak;
+use rlp::RlpStream;
+use network::PeerId;
 use ethcore::client::{TestBlockChainClient, BlockChainClient, BlockId, EachBlockWith};
-use chain::{SyncState};
+use chain::{ChainSync, SyncState};
 use super::helpers::*;
 use SyncConfig;
 
@@ -268,3 +275,135 @@ fn disconnect_on_unrelated_chain() {
 	assert_eq!(net.disconnect_events, vec![(0, 0)]);
 }
 
+
+fn fast_sync_net(blocks: usize) -> TestNet<EthPeer<TestBlockChainClient>> {
+	let mut net = TestNet::new(4);
+	let mut config = SyncConfig::default();
+	config.fast_sync = true;
+	{
+		let peer = net.peer_mut(0);
+		peer.sync = RwLock::new(ChainSync::new(config, &*peer.chain));
+	}
+	for i in 1..4 {
+		net.peer(i).chain.add_blocks(blocks, EachBlockWith::Uncle);
+	}
+	net
+}
+
+#[test]
+fn fast_sync() {
+	::env_logger::init().ok();
+	let mut net = fast_sync_net(100);
+	assert_eq!(net.peer(0).sync.read().status().state, SyncState::WaitingPeers);
+	net.sync();
+	let status = net.peer(0).sync.read().status();
+	assert_eq!(status.state, SyncState::Idle);
+	assert_eq!(status.fast_sync_pivot_number, None);
+	assert_eq!(*net.peer(0).chain.blocks.read(), *net.peer(1).chain.blocks.read());
+}
+
+fn insert_node(nodes: &mut HashMap<H256, Bytes>, data: Bytes) -> H256 {
+	let hash = keccak(&data);
+	nodes.insert(hash, data);
+	hash
+}
+
+// Four accounts under two levels of branch nodes, each with code and a two slot storage trie.
+// `value` is stored in the first slot of the first account.
+fn fast_sync_state(value: u8) -> (H256, HashMap<H256, Bytes>) {
+	let mut nodes = HashMap::new();
+	let mut branches = Vec::new();
+	for i in 0..2u8 {
+		let mut leaves = Vec::new();
+		for j in 0..2u8 {
+			let mut slots = Vec::new();
+			for k in 0..2u8 {
+				let mut key = H256::zero();
+				key[0] = k << 4;
+				let slot_value = if i == 0 && j == 0 && k == 0 { value } else { 0 };
+				slots.push((k, insert_node(&mut nodes, leaf(&nibbles(&key)[1..], &[i, j, k, slot_value]))));
+			}
+			let storage_root = insert_node(&mut nodes, branch(&slots));
+			let code_hash = insert_node(&mut nodes, vec![0x60, i, 0x60, j, 0x01]);
+			let mut address_hash = H256::zero();
+			address_hash[0] = (i << 4) | j;
+			leaves.push((j, insert_node(&mut nodes, leaf(&nibbles(&address_hash)[2..], &account(&storage_root, &code_hash)))));
+		}
+		branches.push((i, insert_node(&mut nodes, branch(&leaves))));
+	}
+	(insert_node(&mut nodes, branch(&branches)), nodes)
+}
+
+#[test]
+fn fast_sync_moves_pivot_during_state_download() {
+	::env_logger::init().ok();
+	let (root_a, nodes_a) = fast_sync_state(1);
+	let (root_b, nodes_b) = fast_sync_state(2);
+	let mut net = fast_sync_net(0);
+	for i in 1..4 {
+		let chain = &net.peer(i).chain;
+		chain.state_nodes.write().extend(nodes_a.clone());
+		chain.set_state_root(root_a);
+		chain.add_blocks(100, EachBlockWith::Uncle);
+	}
+
+	net.start();
+	for _ in 0..100 {
+		if net.peer(0).sync.read().status().fast_sync_pivot_number.is_some() {
+			break;
+		}
+		net.sync_step();
+	}
+	let status = net.peer(0).sync.read().status();
+	assert_eq!(status.fast_sync_pivot_number, Some(68));
+	assert!(status.state_nodes_pending > 0);
+
+	// the network moves on to a new state before the pivot state is downloaded
+	for i in 1..4 {
+		let chain = &net.peer(i).chain;
+		chain.state_nodes.write().extend(nodes_b.clone());
+		chain.set_state_root(root_b);
+		chain.add_blocks(40, EachBlockWith::Uncle);
+		let mut hashes = RlpStream::new_list(1);
+		hashes.begin_list(2).append(&chain.chain_info().best_block_hash).append(&140u64);
+		net.peer(0).receive_message(i as PeerId, TestPacket { data: hashes.out(), packet_id: 0x01, recipient: 0 });
+	}
+	for _ in 0..100 {
+		if net.peer(0).sync.read().status().fast_sync_pivot_number != Some(68) {
+			break;
+		}
+		net.sync_step();
+	}
+	assert_eq!(net.peer(0).sync.read().status().fast_sync_pivot_number, Some(108));
+	assert!(!net.peer(0).chain.state_node_known(None, &root_a));
+
+	net.sync();
+	let status = net.peer(0).sync.read().status();
+	assert_eq!(status.state, SyncState::Idle);
+	assert_eq!(status.fast_sync_pivot_number, None);
+	assert!(net.peer(0).chain.state_node_known(None, &root_b));
+	assert_eq!(*net.peer(0).chain.blocks.read(), *net.peer(1).chain.blocks.read());
+}
+
+#[test]
+fn fast_sync_falls_back_to_full_sync_on_short_chain() {
+	::env_logger::init().ok();
+	let mut net = fast_sync_net(10);
+	net.sync();
+	assert_eq!(net.peer(0).sync.read().status().state, SyncState::Idle);
+	assert_eq!(net.peer(0).chain.chain_info().best_block_number, 10);
+}
+
+#[test]
+fn fast_sync_not_started_on_a_chain_close_to_head() {
+	::env_logger::init().ok();
+	let mut net = fast_sync_net(100);
+	net.peer(0).chain.add_blocks(10, EachBlockWith::Uncle);
+	net.start();
+	while !net.done() {
+		net.sync_step();
+		assert_eq!(net.peer(0).sync.read().status().fast_sync_pivot_number, None);
+	}
+	assert_eq!(net.peer(0).chain.chain_info().best_block_number, 100);
+	assert_eq!(*net.peer(0).chain.blocks.read(), *net.peer(1).chain.blocks.read());
+}
@@ -16,10 +16,11 @@
 
 use std::collections::{VecDeque, HashSet, HashMap};
 use std::sync::Arc;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256};
 use parking_lot::RwLock;
 use bytes::Bytes;
-use network::{self, PeerId, ProtocolId, PacketId, SessionInfo};
+use rlp::RlpStream;
+use network::{self, PeerId, ProtocolId, PacketId, SessionInfo, PeerBehaviour};
 use tests::snapshot::*;
 use ethcore::client::{TestBlockChainClient, BlockChainClient, Client as EthcoreClient, ClientConfig, ChainNotify};
 use ethcore::header::BlockNumber;
@@ -51,6 +52,8 @@ pub struct TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 	pub queue: &'p RwLock<VecDeque<TestPacket>>,
 	pub sender: Option<PeerId>,
 	pub to_disconnect: HashSet<PeerId>,
+	pub throttled: HashSet<PeerId>,
+	pub reports: Vec<(PeerId, PeerBehaviour)>,
 	pub packets: Vec<TestPacket>,
 	pub peers_info: HashMap<PeerId, String>,
 	overlay: RwLock<HashMap<BlockNumber, Bytes>>,
@@ -64,6 +67,8 @@ impl<'p, C> TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 			queue: queue,
 			sender: sender,
 			to_disconnect: HashSet::new(),
+			throttled: HashSet::new(),
+			reports: Vec::new(),
 			overlay: RwLock::new(HashMap::new()),
 			packets: Vec::new(),
 			peers_info: HashMap::new(),
@@ -86,10 +91,21 @@ impl<'p, C> SyncIo for TestIo<'p, C> where C: FlushingBlockChainClient, C: 'p {
 		self.to_disconnect.insert(peer_id);
 	}
 
+	fn report_peer(&mut self, peer_id: PeerId, behaviour: PeerBehaviour) {
+		self.reports.push((peer_id, behaviour));
+		if behaviour.is_fatal() {
+			self.disconnect_peer(peer_id);
+		}
+	}
+
 	fn is_expired(&self) -> bool {
 		false
 	}
 
+	fn is_throttled(&self, peer_id: PeerId) -> bool {
+		self.throttled.contains(&peer_id)
+	}
+
 	fn respond(&mut self, packet_id: PacketId, data: Vec<u8>) -> Result<(), network::Error> {
 		self.packets.push(TestPacket {
 			data: data,
@@ -427,3 +443,54 @@ impl ChainNotify for EthPeer<EthcoreClient> {
 		self.sync.write().propagate_consensus_packet(&mut io, message.clone());
 	}
 }
+
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Bytes {
+	let flag = if is_leaf { 0x20 } else { 0 };
+	let mut data = Vec::new();
+	let rest = if nibbles.len() % 2 == 1 {
+		data.push(flag | 0x10 | nibbles[0]);
+		&nibbles[1..]
+	} else {
+		data.push(flag);
+		nibbles
+	};
+	for pair in rest.chunks(2) {
+		data.push((pair[0] << 4) | pair[1]);
+	}
+	data
+}
+
+/// Split a hash into trie key nibbles.
+pub fn nibbles(hash: &H256) -> Vec<u8> {
+	hash.iter().flat_map(|b| vec![b >> 4, b & 0x0f]).collect()
+}
+
+/// Encode a trie leaf node.
+pub fn leaf(key: &[u8], value: &[u8]) -> Bytes {
+	let mut rlp = RlpStream::new_list(2);
+	rlp.append(&hex_prefix(key, true));
+	rlp.append(&value);
+	rlp.out()
+}
+
+/// Encode a trie branch node referencing children by hash.
+pub fn branch(children: &[(u8, H256)]) -> Bytes {
+	let mut rlp = RlpStream::new_list(17);
+	for i in 0..17u8 {
+		match children.iter().find(|&&(n, _)| n == i) {
+			Some(&(_, ref hash)) => rlp.append(hash),
+			None => rlp.append_empty_data(),
+		};
+	}
+	rlp.out()
+}
+
+/// Encode an account with a balance of 1000.
+pub fn account(storage_root: &H256, code_hash: &H256) -> Bytes {
+	let mut rlp = RlpStream::new_list(4);
+	rlp.append(&U256::zero());
+	rlp.append(&U256::from(1000));
+	rlp.append(storage_root);
+	rlp.append(code_hash);
+	rlp.out()
+}
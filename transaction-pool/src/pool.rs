@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
-use std::collections::{HashMap, BTreeSet};
+use std::collections::{hash_map, HashMap, BTreeSet};
 
 use ethereum_types::{H160, H256};
 
@@ -306,6 +306,16 @@ impl<T, S, L> Pool<T, S, L> where
 		}
 	}
 
+	/// Updates scores of all transactions from given sender.
+	///
+	/// The `event` is passed to the `Scoring` implementation,
+	/// which decides how the scores should change.
+	pub fn update_scores(&mut self, sender: &Sender, event: S::Event) {
+		self.remove_from_set(sender, |set, scoring| {
+			set.update_scores(scoring, event)
+		});
+	}
+
 	/// Removes all stalled transactions from given sender.
 	fn remove_stalled<R: Ready<T>>(&mut self, sender: &Sender, ready: &mut R) -> usize {
 		let removed_from_set = self.remove_from_set(sender, |transactions, scoring| {
@@ -345,6 +355,22 @@ impl<T, S, L> Pool<T, S, L> where
 		removed
 	}
 
+	/// Returns a transaction if it's part of the pool or `None` otherwise.
+	pub fn find(&self, hash: &H256) -> Option<Arc<T>> {
+		self.by_hash.get(hash).cloned()
+	}
+
+	/// Returns worst transaction in the queue (if any).
+	pub fn worst_transaction(&self) -> Option<Arc<T>> {
+		self.worst_transactions.iter().next_back().map(|x| x.transaction.clone())
+	}
+
+	/// Returns true if the pool is at it's capacity.
+	pub fn is_full(&self) -> bool {
+		self.by_hash.len() >= self.options.max_count
+			|| self.mem_usage >= self.options.max_mem_usage
+	}
+
 	/// Returns an iterator of pending (ready) transactions.
 	pub fn pending<R: Ready<T>>(&self, ready: R) -> PendingIterator<T, R, S, L> {
 		PendingIterator {
@@ -354,6 +380,31 @@ impl<T, S, L> Pool<T, S, L> where
 		}
 	}
 
+	/// Returns an iterator of pending (ready) transactions from given sender.
+	pub fn pending_from_sender<R: Ready<T>>(&self, ready: R, sender: &Sender) -> PendingIterator<T, R, S, L> {
+		let best_transactions = self.transactions.get(sender)
+			.and_then(|transactions| transactions.worst_and_best())
+			.map(|(_, best)| ScoreWithRef::new(best.0, best.1))
+			.map(|s| {
+				let mut set = BTreeSet::new();
+				set.insert(s);
+				set
+			})
+			.unwrap_or_default();
+
+		PendingIterator {
+			ready,
+			best_transactions,
+			pool: self,
+		}
+	}
+
+	/// Returns an iterator over all transactions in the pool.
+	/// NOTE: the order of transactions is arbitrary.
+	pub fn unordered_transactions(&self) -> hash_map::Values<H256, Arc<T>> {
+		self.by_hash.values()
+	}
+
 	/// Computes the full status of the pool (including readiness).
 	pub fn status<R: Ready<T>>(&self, mut ready: R) -> Status {
 		let mut status = Status::default();
@@ -383,6 +434,28 @@ impl<T, S, L> Pool<T, S, L> where
 			senders: self.transactions.len(),
 		}
 	}
+
+	/// Returns current pool options.
+	pub fn options(&self) -> &Options {
+		&self.options
+	}
+
+	/// Replaces pool options.
+	///
+	/// NOTE: the new limits are not enforced until the next import.
+	pub fn set_options(&mut self, options: Options) {
+		self.options = options;
+	}
+
+	/// Borrows listener instance.
+	pub fn listener(&self) -> &L {
+		&self.listener
+	}
+
+	/// Borrows listener mutably.
+	pub fn listener_mut(&mut self) -> &mut L {
+		&mut self.listener
+	}
 }
 
 /// An iterator over all pending (ready) transactions.
@@ -424,7 +497,7 @@ impl<'a, T, R, S, L> Iterator for PendingIterator<'a, T, R, S, L> where
 
 					return Some(best.transaction)
 				},
-				state => warn!("[{:?}] Ignoring {:?} transaction.", best.transaction.hash(), state),
+				state => trace!("[{:?}] Ignoring {:?} transaction.", best.transaction.hash(), state),
 			}
 		}
 
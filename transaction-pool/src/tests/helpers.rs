@@ -26,6 +26,7 @@ pub struct DummyScoring;
 
 impl Scoring<Transaction> for DummyScoring {
 	type Score = U256;
+	type Event = ();
 
 	fn compare(&self, old: &Transaction, new: &Transaction) -> cmp::Ordering {
 		old.nonce.cmp(&new.nonce)
@@ -43,7 +44,15 @@ impl Scoring<Transaction> for DummyScoring {
 		}
 	}
 
-	fn update_scores(&self, txs: &[SharedTransaction], scores: &mut [Self::Score], _change: scoring::Change) {
+	fn update_scores(&self, txs: &[SharedTransaction], scores: &mut [Self::Score], change: scoring::Change) {
+		if let scoring::Change::Event(_) = change {
+			// Bump the score of the first transaction from a sender.
+			if let Some(score) = scores.get_mut(0) {
+				*score = *score + 1.into();
+			}
+			return;
+		}
+
 		for i in 0..txs.len() {
 			scores[i] = txs[i].gas_price;
 		}
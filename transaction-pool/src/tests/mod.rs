@@ -375,6 +375,94 @@ fn should_re_insert_after_cull() {
 	});
 }
 
+#[test]
+fn should_return_worst_transaction() {
+	// given
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::default();
+	assert!(txq.worst_transaction().is_none());
+
+	// when
+	txq.import(b.tx().nonce(0).gas_price(5).new()).unwrap();
+	let tx = txq.import(b.tx().sender(1).nonce(0).gas_price(2).new()).unwrap();
+
+	// then
+	assert_eq!(txq.worst_transaction(), Some(tx));
+}
+
+#[test]
+fn should_return_is_full() {
+	// given
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::with_options(Options {
+		max_count: 2,
+		..Default::default()
+	});
+	assert!(!txq.is_full());
+
+	// when
+	txq.import(b.tx().nonce(0).gas_price(110).new()).unwrap();
+	assert!(!txq.is_full());
+
+	txq.import(b.tx().nonce(1).gas_price(100).new()).unwrap();
+
+	// then
+	assert!(txq.is_full());
+}
+
+#[test]
+fn should_find_transaction() {
+	// given
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::default();
+	let tx = txq.import(b.tx().nonce(0).new()).unwrap();
+
+	// when
+	let found = txq.find(tx.hash());
+
+	// then
+	assert_eq!(found, Some(tx));
+	assert_eq!(txq.find(&5.into()), None);
+	assert_eq!(txq.unordered_transactions().count(), 1);
+}
+
+#[test]
+fn should_construct_pending_from_sender() {
+	// given
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::default();
+
+	let tx0 = txq.import(b.tx().nonce(0).gas_price(5).new()).unwrap();
+	let tx1 = txq.import(b.tx().nonce(1).gas_price(5).new()).unwrap();
+	txq.import(b.tx().nonce(3).new()).unwrap();
+	txq.import(b.tx().sender(1).nonce(0).new()).unwrap();
+
+	// when
+	let mut pending = txq.pending_from_sender(NonceReady::default(), &0.into());
+
+	// then
+	assert_eq!(pending.next(), Some(tx0));
+	assert_eq!(pending.next(), Some(tx1));
+	assert_eq!(pending.next(), None);
+}
+
+#[test]
+fn should_update_scores_of_a_sender() {
+	// given
+	let b = TransactionBuilder::default();
+	let mut txq = TestPool::default();
+
+	let tx0 = txq.import(b.tx().nonce(0).new()).unwrap();
+	let tx1 = txq.import(b.tx().sender(1).nonce(0).new()).unwrap();
+	assert_eq!(txq.pending(NonceReady::default()).collect::<Vec<_>>(), vec![tx1.clone(), tx0.clone()]);
+
+	// when
+	txq.update_scores(&0.into(), ());
+
+	// then
+	assert_eq!(txq.pending(NonceReady::default()).collect::<Vec<_>>(), vec![tx0, tx1]);
+}
+
 mod listener {
 	use std::cell::RefCell;
 	use std::rc::Rc;
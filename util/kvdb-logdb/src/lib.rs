@@ -0,0 +1,1379 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pure-Rust log-structured `KeyValueDB` backend.
+//!
+//! All columns share a single append-only file of put and delete records. Every flush ends
+//! with a commit record, and records without one are discarded when the database is opened,
+//! so a transaction is applied to all columns or none. A sorted index of keys to value
+//! positions is rebuilt from the file on open, so a read is a single positioned read. The file
+//! is rewritten in the background once most of it is taken by superseded records.
+
+#[macro_use]
+extern crate log;
+
+extern crate elastic_array;
+extern crate interleaved_ordered;
+extern crate parking_lot;
+
+extern crate kvdb;
+extern crate rlp;
+
+#[cfg(test)]
+extern crate ethereum_types;
+#[cfg(test)]
+extern crate journaldb;
+#[cfg(test)]
+extern crate keccak_hash as keccak;
+#[cfg(test)]
+extern crate tempdir;
+
+use std::collections::{BTreeMap, HashMap, Bound};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::{mem, thread};
+
+use parking_lot::{Mutex, RwLock};
+use interleaved_ordered::{interleave_ordered, InterleaveOrdered};
+
+use elastic_array::ElasticArray32;
+use rlp::{UntrustedRlp, RlpType, Compressible};
+use kvdb::{KeyValueDB, DBTransaction, DBValue, DBOp, Result};
+
+/// Written at the start of the log file.
+const MAGIC: &'static [u8; 8] = b"logdb\0\0\x02";
+/// Size of a record header: kind, column, key length, value length and checksum.
+/// Columns are numbered from 0 for the default column.
+const HEADER_LEN: u64 = 17;
+/// Logs holding less garbage than this are never rewritten.
+const MIN_COMPACTION_GARBAGE: u64 = 16 * 1024 * 1024;
+/// Number of entries read per index lookup while iterating.
+const ITER_CHUNK: usize = 256;
+/// File holding the records of all columns.
+const LOG_FILE: &'static str = "db.log";
+
+const RECORD_DELETE: u8 = 0;
+const RECORD_INSERT: u8 = 1;
+/// Ends a batch of records; its value is the number of records in the batch.
+const RECORD_COMMIT: u8 = 2;
+
+enum KeyState {
+	Insert(DBValue),
+	InsertCompressed(DBValue),
+	Delete,
+}
+
+/// Database configuration
+#[derive(Clone)]
+pub struct DatabaseConfig {
+	/// Set number of columns
+	pub columns: Option<u32>,
+	/// Should every flush be synced to disk?
+	pub wal: bool,
+}
+
+impl DatabaseConfig {
+	/// Create new `DatabaseConfig` with default parameters and specified set of columns.
+	pub fn with_columns(columns: Option<u32>) -> Self {
+		let mut config = Self::default();
+		config.columns = columns;
+		config
+	}
+}
+
+impl Default for DatabaseConfig {
+	fn default() -> DatabaseConfig {
+		DatabaseConfig {
+			columns: None,
+			wal: true,
+		}
+	}
+}
+
+// FNV-1a over the record kind, column, key and value.
+fn checksum(kind: u8, col: u32, key: &[u8], value: &[u8]) -> u32 {
+	let mut col_bytes = Vec::with_capacity(4);
+	write_u32(&mut col_bytes, col);
+
+	let mut hash: u32 = 0x811c9dc5;
+	for byte in Some(kind).iter().chain(&col_bytes).chain(key).chain(value) {
+		hash ^= *byte as u32;
+		hash = hash.wrapping_mul(0x01000193);
+	}
+	hash
+}
+
+fn write_u32(out: &mut Vec<u8>, value: u32) {
+	for i in 0..4 {
+		out.push((value >> (8 * i)) as u8);
+	}
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+	bytes[..4].iter().enumerate().fold(0, |acc, (i, byte)| acc | (*byte as u32) << (8 * i))
+}
+
+fn encode_record(out: &mut Vec<u8>, kind: u8, col: u32, key: &[u8], value: &[u8]) {
+	out.push(kind);
+	write_u32(out, col);
+	write_u32(out, key.len() as u32);
+	write_u32(out, value.len() as u32);
+	write_u32(out, checksum(kind, col, key, value));
+	out.extend_from_slice(key);
+	out.extend_from_slice(value);
+}
+
+fn encode_commit(out: &mut Vec<u8>, records: u32) {
+	let mut count = Vec::with_capacity(4);
+	write_u32(&mut count, records);
+	encode_record(out, RECORD_COMMIT, 0, &[], &count);
+}
+
+// Read the next record. Returns `None` at the end of the file and at a partially written record.
+fn read_record<R: Read>(reader: &mut R, remaining: u64) -> io::Result<Option<(u8, u32, Vec<u8>, Vec<u8>)>> {
+	if remaining < HEADER_LEN {
+		return Ok(None);
+	}
+
+	let mut header = [0u8; HEADER_LEN as usize];
+	reader.read_exact(&mut header)?;
+	let kind = header[0];
+	let col = read_u32(&header[1..5]);
+	let key_len = read_u32(&header[5..9]) as u64;
+	let value_len = read_u32(&header[9..13]) as u64;
+	if kind > RECORD_COMMIT || HEADER_LEN + key_len + value_len > remaining {
+		return Ok(None);
+	}
+
+	let mut key = vec![0u8; key_len as usize];
+	reader.read_exact(&mut key)?;
+	let mut value = vec![0u8; value_len as usize];
+	reader.read_exact(&mut value)?;
+	if checksum(kind, col, &key, &value) != read_u32(&header[13..17]) {
+		return Ok(None);
+	}
+
+	Ok(Some((kind, col, key, value)))
+}
+
+// Make a created or renamed file durable by syncing its directory.
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> io::Result<()> {
+	match path.parent() {
+		Some(dir) => File::open(dir)?.sync_all(),
+		None => Ok(()),
+	}
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> io::Result<()> {
+	Ok(())
+}
+
+// Read at an offset without going through a shared file cursor.
+#[cfg(unix)]
+fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+	use std::os::unix::fs::FileExt;
+	file.read_at(buf, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+	use std::os::windows::fs::FileExt;
+	file.seek_read(buf, offset)
+}
+
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+	while !buf.is_empty() {
+		match read_at(file, buf, offset) {
+			Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer")),
+			Ok(n) => {
+				let rest = buf;
+				buf = &mut rest[n..];
+				offset += n as u64;
+			},
+			Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {},
+			Err(e) => return Err(e),
+		}
+	}
+	Ok(())
+}
+
+#[derive(Clone, Copy)]
+struct ValuePosition {
+	offset: u64,
+	len: u32,
+}
+
+/// Append-only file of records for all columns.
+///
+/// Every flush appends its records followed by a commit record, and a batch only takes
+/// effect once its commit record is on disk, so a transaction is never partially applied.
+struct Log {
+	path: PathBuf,
+	// Only read with positioned reads, so reads don't need to be serialized.
+	file: File,
+	// Index of every column, the default column first.
+	columns: Vec<BTreeMap<Vec<u8>, ValuePosition>>,
+	// Length of the committed part of the file.
+	len: u64,
+	// Bytes taken by superseded, deletion and commit records.
+	garbage: u64,
+	// Bumped whenever the file is replaced or records are dropped without being rewritten.
+	generation: u64,
+	// Whether a background compaction is running.
+	compacting: bool,
+}
+
+/// The live records of a log at some point, to be rewritten into a compacted file.
+struct Snapshot {
+	file: File,
+	columns: Vec<BTreeMap<Vec<u8>, ValuePosition>>,
+	len: u64,
+	generation: u64,
+}
+
+/// A compacted copy of a snapshot, not yet including the batches committed after it.
+struct Compacted {
+	path: PathBuf,
+	file: File,
+	columns: Vec<BTreeMap<Vec<u8>, ValuePosition>>,
+	len: u64,
+	snapshot_len: u64,
+	generation: u64,
+}
+
+impl Compacted {
+	// Write the live records of a snapshot to a new file as a single committed batch.
+	// Nothing is locked, so this may run while the log is being read and written.
+	fn write(snapshot: Snapshot, path: PathBuf) -> io::Result<Compacted> {
+		let mut columns = Vec::with_capacity(snapshot.columns.len());
+		let mut len = MAGIC.len() as u64;
+		let mut records = 0;
+		let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path)?;
+		{
+			let mut writer = BufWriter::new(&file);
+			writer.write_all(MAGIC)?;
+
+			let mut buffer = Vec::new();
+			let mut value = Vec::new();
+			for (column, index) in snapshot.columns.iter().enumerate() {
+				let col = column as u32;
+				let mut compacted = BTreeMap::new();
+				for (key, position) in index {
+					value.resize(position.len as usize, 0);
+					read_exact_at(&snapshot.file, &mut value, position.offset)?;
+					buffer.clear();
+					encode_record(&mut buffer, RECORD_INSERT, col, key, &value);
+					writer.write_all(&buffer)?;
+					compacted.insert(key.clone(), ValuePosition {
+						offset: len + HEADER_LEN + key.len() as u64,
+						len: position.len,
+					});
+					len += buffer.len() as u64;
+					records += 1;
+				}
+				columns.push(compacted);
+			}
+
+			buffer.clear();
+			encode_commit(&mut buffer, records);
+			writer.write_all(&buffer)?;
+			len += buffer.len() as u64;
+
+			writer.flush()?;
+		}
+		file.sync_data()?;
+
+		Ok(Compacted {
+			path: path,
+			file: file,
+			columns: columns,
+			len: len,
+			snapshot_len: snapshot.len,
+			generation: snapshot.generation,
+		})
+	}
+}
+
+impl Log {
+	// Open or create the log and rebuild the column indexes, dropping any uncommitted tail.
+	fn open(path: PathBuf, num_columns: usize) -> io::Result<Log> {
+		let mut file = OpenOptions::new().read(true).write(true).create(true).open(&path)?;
+		if file.metadata()?.len() == 0 {
+			file.write_all(MAGIC)?;
+			file.sync_all()?;
+			sync_dir(&path)?;
+		}
+		let file_len = file.metadata()?.len();
+
+		let mut log = Log {
+			file: file.try_clone()?,
+			path: path,
+			columns: (0..num_columns).map(|_| BTreeMap::new()).collect(),
+			len: MAGIC.len() as u64,
+			garbage: 0,
+			generation: 0,
+			compacting: false,
+		};
+
+		{
+			file.seek(SeekFrom::Start(0))?;
+			let mut reader = BufReader::new(&mut file);
+			let mut magic = [0u8; 8];
+			reader.read_exact(&mut magic)?;
+			if &magic != MAGIC {
+				return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} is not a database log file", log.path.display())));
+			}
+
+			// records of the current batch, applied once its commit record is read.
+			let mut batch = Vec::new();
+			let mut batch_len = 0;
+			while let Some((kind, col, key, value)) = read_record(&mut reader, file_len - log.len - batch_len)? {
+				let record_len = HEADER_LEN + key.len() as u64 + value.len() as u64;
+				if kind == RECORD_COMMIT {
+					if value.len() != 4 || read_u32(&value) as usize != batch.len() {
+						break;
+					}
+					let mut offset = log.len;
+					for (kind, column, key, value_len) in batch.drain(..) {
+						let key_len = key.len() as u64;
+						let position = match kind {
+							RECORD_INSERT => Some(ValuePosition { offset: offset + HEADER_LEN + key_len, len: value_len }),
+							_ => None,
+						};
+						offset += HEADER_LEN + key_len + value_len as u64;
+						log.index(column, key, position);
+					}
+					log.garbage += record_len;
+					log.len += batch_len + record_len;
+					batch_len = 0;
+				} else {
+					batch.push((kind, col as usize, key, value.len() as u32));
+					batch_len += record_len;
+				}
+			}
+		}
+
+		if log.len < file_len {
+			warn!("Discarding {} bytes of uncommitted records in {}", file_len - log.len, log.path.display());
+			file.set_len(log.len)?;
+			file.sync_all()?;
+		}
+
+		Ok(log)
+	}
+
+	// Point a key at its new value, or remove it, accounting for the superseded record.
+	fn index(&mut self, column: usize, key: Vec<u8>, position: Option<ValuePosition>) {
+		while self.columns.len() <= column {
+			self.columns.push(BTreeMap::new());
+		}
+		let key_len = key.len() as u64;
+		let previous = match position {
+			Some(position) => self.columns[column].insert(key, position),
+			None => {
+				self.garbage += HEADER_LEN + key_len;
+				self.columns[column].remove(&key)
+			},
+		};
+		if let Some(previous) = previous {
+			self.garbage += HEADER_LEN + key_len + previous.len as u64;
+		}
+	}
+
+	fn read(&self, position: ValuePosition) -> io::Result<DBValue> {
+		let mut value = vec![0u8; position.len as usize];
+		read_exact_at(&self.file, &mut value, position.offset)?;
+		Ok(DBValue::from_slice(&value))
+	}
+
+	// Append the changes of all columns as a single committed batch and index them.
+	// Nothing is indexed if the write fails.
+	fn write(&mut self, overlay: &[HashMap<ElasticArray32<u8>, KeyState>], sync: bool) -> io::Result<()> {
+		let mut buffer = Vec::new();
+		let mut updates = Vec::new();
+		for (column, changes) in overlay.iter().enumerate() {
+			let col = column as u32;
+			for (key, state) in changes {
+				let value_offset = self.len + buffer.len() as u64 + HEADER_LEN + key.len() as u64;
+				match *state {
+					KeyState::Delete => {
+						encode_record(&mut buffer, RECORD_DELETE, col, key, &[]);
+						updates.push((column, key.to_vec(), None));
+					},
+					KeyState::Insert(ref value) => {
+						encode_record(&mut buffer, RECORD_INSERT, col, key, value);
+						updates.push((column, key.to_vec(), Some(ValuePosition { offset: value_offset, len: value.len() as u32 })));
+					},
+					KeyState::InsertCompressed(ref value) => {
+						let compressed = UntrustedRlp::new(value).compress(RlpType::Blocks);
+						encode_record(&mut buffer, RECORD_INSERT, col, key, &compressed);
+						updates.push((column, key.to_vec(), Some(ValuePosition { offset: value_offset, len: compressed.len() as u32 })));
+					},
+				}
+			}
+		}
+		if updates.is_empty() {
+			return Ok(());
+		}
+		let commit_start = buffer.len();
+		encode_commit(&mut buffer, updates.len() as u32);
+
+		let result = self.file.seek(SeekFrom::Start(self.len))
+			.and_then(|_| self.file.write_all(&buffer))
+			.and_then(|_| if sync { self.file.sync_data() } else { Ok(()) });
+		if let Err(e) = result {
+			// drop whatever part of the batch made it to the file.
+			let _ = self.file.set_len(self.len);
+			return Err(e);
+		}
+		self.len += buffer.len() as u64;
+		self.garbage += (buffer.len() - commit_start) as u64;
+
+		for (column, key, position) in updates {
+			self.index(column, key, position);
+		}
+		Ok(())
+	}
+
+	// Whether most of the file is garbage and no compaction is running yet.
+	fn needs_compaction(&self) -> bool {
+		!self.compacting && self.garbage > MIN_COMPACTION_GARBAGE && self.garbage * 2 > self.len
+	}
+
+	fn snapshot(&self) -> io::Result<Snapshot> {
+		Ok(Snapshot {
+			// a handle of its own, so reading it never moves the cursor used for writes.
+			file: File::open(&self.path)?,
+			columns: self.columns.clone(),
+			len: self.len,
+			generation: self.generation,
+		})
+	}
+
+	// Rewrite the log with only the live records.
+	fn compact(&mut self) -> io::Result<()> {
+		let compacted = Compacted::write(self.snapshot()?, self.path.with_extension("compact"))?;
+		self.finish_compaction(compacted)
+	}
+
+	// Append the batches committed since the snapshot to the compacted file and replace the log with it.
+	// A compaction of a log that has been replaced or has dropped records since is discarded.
+	fn finish_compaction(&mut self, mut compacted: Compacted) -> io::Result<()> {
+		if compacted.generation != self.generation {
+			let _ = fs::remove_file(&compacted.path);
+			return Ok(());
+		}
+
+		let mut tail = vec![0u8; (self.len - compacted.snapshot_len) as usize];
+		read_exact_at(&self.file, &mut tail, compacted.snapshot_len)?;
+		compacted.file.seek(SeekFrom::Start(compacted.len))?;
+		compacted.file.write_all(&tail)?;
+		compacted.file.sync_all()?;
+
+		// values written before the snapshot have been moved, the ones written after are shifted.
+		let len = compacted.len + tail.len() as u64;
+		let mut live = 0;
+		let mut columns = Vec::with_capacity(self.columns.len());
+		for (column, index) in self.columns.iter().enumerate() {
+			let moved = compacted.columns.get(column);
+			let mut positions = BTreeMap::new();
+			for (key, position) in index {
+				let position = if position.offset >= compacted.snapshot_len {
+					ValuePosition { offset: position.offset - compacted.snapshot_len + compacted.len, len: position.len }
+				} else {
+					*moved.and_then(|moved| moved.get(key))
+						.expect("values written before the snapshot are in the snapshot index; qed")
+				};
+				live += HEADER_LEN + key.len() as u64 + position.len as u64;
+				positions.insert(key.clone(), position);
+			}
+			columns.push(positions);
+		}
+
+		fs::rename(&compacted.path, &self.path)?;
+		sync_dir(&self.path)?;
+		debug!("Compacted {} from {} to {} bytes", self.path.display(), self.len, len);
+
+		self.file = compacted.file;
+		self.columns = columns;
+		self.len = len;
+		self.garbage = len - MAGIC.len() as u64 - live;
+		self.generation += 1;
+		Ok(())
+	}
+}
+
+fn new_overlay(columns: usize) -> Vec<HashMap<ElasticArray32<u8>, KeyState>> {
+	(0..columns).map(|_| HashMap::new()).collect()
+}
+
+fn apply_ops(overlay: &mut [HashMap<ElasticArray32<u8>, KeyState>], ops: Vec<DBOp>) {
+	for op in ops {
+		match op {
+			DBOp::Insert { col, key, value } => {
+				let c = Database::to_overlay_column(col);
+				overlay[c].insert(key, KeyState::Insert(value));
+			},
+			DBOp::InsertCompressed { col, key, value } => {
+				let c = Database::to_overlay_column(col);
+				overlay[c].insert(key, KeyState::InsertCompressed(value));
+			},
+			DBOp::Delete { col, key } => {
+				let c = Database::to_overlay_column(col);
+				overlay[c].insert(key, KeyState::Delete);
+			},
+		}
+	}
+}
+
+/// Iterator over the flushed data of a single column.
+/// Entries are read in chunks, so a flush or compaction may happen in between.
+struct ColumnIterator<'a> {
+	db: &'a Database,
+	column: usize,
+	from: Bound<Vec<u8>>,
+	buffer: ::std::vec::IntoIter<(Box<[u8]>, Box<[u8]>)>,
+	done: bool,
+}
+
+impl<'a> ColumnIterator<'a> {
+	fn new(db: &'a Database, column: usize, from: Bound<Vec<u8>>) -> Self {
+		ColumnIterator {
+			db: db,
+			column: column,
+			from: from,
+			buffer: Vec::new().into_iter(),
+			done: false,
+		}
+	}
+}
+
+impl<'a> Iterator for ColumnIterator<'a> {
+	type Item = (Box<[u8]>, Box<[u8]>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if let Some(item) = self.buffer.next() {
+			return Some(item);
+		}
+		if self.done {
+			return None;
+		}
+
+		let db = self.db;
+		let log = db.log.read();
+		let (log, index) = match log.as_ref().and_then(|log| log.columns.get(self.column).map(|index| (log, index))) {
+			Some(found) => found,
+			None => return None,
+		};
+
+		let from = mem::replace(&mut self.from, Bound::Unbounded);
+		let mut items = Vec::with_capacity(ITER_CHUNK);
+		for (key, position) in index.range((from, Bound::Unbounded)).take(ITER_CHUNK) {
+			match log.read(*position) {
+				Ok(value) => items.push((key.clone().into_boxed_slice(), value.into_vec().into_boxed_slice())),
+				Err(e) => {
+					warn!("Failed to read {}: {}", log.path.display(), e);
+					self.done = true;
+					break;
+				},
+			}
+		}
+
+		if items.len() < ITER_CHUNK {
+			self.done = true;
+		}
+		if let Some(&(ref key, _)) = items.last() {
+			self.from = Bound::Excluded(key.to_vec());
+		}
+		self.buffer = items.into_iter();
+		self.buffer.next()
+	}
+}
+
+/// Database iterator (for flushed data only)
+pub struct DatabaseIterator<'a> {
+	iter: InterleaveOrdered<::std::vec::IntoIter<(Box<[u8]>, Box<[u8]>)>, ColumnIterator<'a>>,
+	_marker: PhantomData<&'a Database>,
+}
+
+impl<'a> Iterator for DatabaseIterator<'a> {
+	type Item = (Box<[u8]>, Box<[u8]>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.iter.next()
+	}
+}
+
+/// Key-Value database.
+pub struct Database {
+	// Shared with the background compaction.
+	log: Arc<RwLock<Option<Log>>>,
+	config: DatabaseConfig,
+	path: PathBuf,
+	// Dirty values added with `write_buffered`. Cleaned on `flush`.
+	// Always locked after `log`.
+	overlay: RwLock<Vec<HashMap<ElasticArray32<u8>, KeyState>>>,
+	// The running or last finished background compaction.
+	compaction: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl Database {
+	/// Open database with default settings.
+	pub fn open_default(path: &str) -> Result<Database> {
+		Database::open(&DatabaseConfig::default(), path)
+	}
+
+	/// Open database directory. Creates if it does not exist.
+	pub fn open(config: &DatabaseConfig, path: &str) -> Result<Database> {
+		let path = PathBuf::from(path);
+		fs::create_dir_all(&path)?;
+
+		let num_cols = config.columns.unwrap_or(0);
+		let log = Log::open(path.join(LOG_FILE), num_cols as usize + 1)?;
+
+		Ok(Database {
+			overlay: RwLock::new(new_overlay(log.columns.len())),
+			log: Arc::new(RwLock::new(Some(log))),
+			config: config.clone(),
+			path: path,
+			compaction: Mutex::new(None),
+		})
+	}
+
+	/// Helper to create new transaction for this database.
+	pub fn transaction(&self) -> DBTransaction {
+		DBTransaction::new()
+	}
+
+	fn to_overlay_column(col: Option<u32>) -> usize {
+		col.map_or(0, |c| (c + 1) as usize)
+	}
+
+	// Start rewriting the log in the background once most of it is garbage.
+	// Reads and writes go on meanwhile, the batches committed in between are carried over at the end.
+	fn maybe_compact(&self, log: &mut Log) {
+		if !log.needs_compaction() {
+			return;
+		}
+		let snapshot = match log.snapshot() {
+			Ok(snapshot) => snapshot,
+			Err(e) => {
+				warn!("Failed to compact {}: {}", log.path.display(), e);
+				return;
+			},
+		};
+		let path = log.path.with_extension("compacting");
+		let shared = self.log.clone();
+		let spawned = thread::Builder::new().name("logdb compaction".into()).spawn(move || {
+			let compacted = Compacted::write(snapshot, path.clone());
+			match *shared.write() {
+				Some(ref mut log) => {
+					log.compacting = false;
+					// the batches are already committed, a failed compaction only leaves the garbage in place.
+					if let Err(e) = compacted.and_then(|compacted| log.finish_compaction(compacted)) {
+						warn!("Failed to compact {}: {}", log.path.display(), e);
+					}
+				},
+				None => {
+					let _ = fs::remove_file(&path);
+				},
+			}
+		});
+		match spawned {
+			Ok(handle) => {
+				log.compacting = true;
+				// the previous compaction is done, it cleared the flag before returning.
+				if let Some(previous) = mem::replace(&mut *self.compaction.lock(), Some(handle)) {
+					let _ = previous.join();
+				}
+			},
+			Err(e) => warn!("Failed to compact {}: {}", log.path.display(), e),
+		}
+	}
+
+	/// Commit transaction to database.
+	pub fn write_buffered(&self, tr: DBTransaction) {
+		apply_ops(&mut self.overlay.write(), tr.ops);
+	}
+
+	/// Commit buffered changes to database.
+	/// The changes of all columns are committed together, and stay buffered if the write fails.
+	pub fn flush(&self) -> Result<()> {
+		match *self.log.write() {
+			Some(ref mut log) => {
+				let mut overlay = self.overlay.write();
+				log.write(&overlay, self.config.wal)?;
+				*overlay = new_overlay(log.columns.len());
+				self.maybe_compact(log);
+				Ok(())
+			},
+			None => Err("Database is closed".into()),
+		}
+	}
+
+	/// Commit transaction to database.
+	/// The transaction is written on its own and is not applied at all if the write fails.
+	pub fn write(&self, tr: DBTransaction) -> Result<()> {
+		match *self.log.write() {
+			Some(ref mut log) => {
+				let mut changes = new_overlay(log.columns.len());
+				apply_ops(&mut changes, tr.ops);
+				log.write(&changes, self.config.wal)?;
+
+				// remove any buffered operation for the written keys
+				let mut overlay = self.overlay.write();
+				for (column, changes) in changes.into_iter().enumerate() {
+					for key in changes.keys() {
+						overlay[column].remove(key);
+					}
+				}
+				self.maybe_compact(log);
+				Ok(())
+			},
+			None => Err("Database is closed".into()),
+		}
+	}
+
+	/// Get value by key.
+	pub fn get(&self, col: Option<u32>, key: &[u8]) -> Result<Option<DBValue>> {
+		match *self.log.read() {
+			Some(ref log) => {
+				let c = Self::to_overlay_column(col);
+				match self.overlay.read()[c].get(key) {
+					Some(&KeyState::Insert(ref value)) | Some(&KeyState::InsertCompressed(ref value)) => Ok(Some(value.clone())),
+					Some(&KeyState::Delete) => Ok(None),
+					None => match log.columns[c].get(key) {
+						Some(position) => Ok(Some(log.read(*position)?)),
+						None => Ok(None),
+					},
+				}
+			},
+			None => Ok(None),
+		}
+	}
+
+	/// Get value by partial key. Prefix size should match configured prefix size. Only searches flushed values.
+	pub fn get_by_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<Box<[u8]>> {
+		self.iter_from_prefix(col, prefix).and_then(|mut iter| {
+			match iter.next() {
+				Some((k, v)) => if k.starts_with(prefix) { Some(v) } else { None },
+				_ => None
+			}
+		})
+	}
+
+	/// Get database iterator for flushed data.
+	pub fn iter(&self, col: Option<u32>) -> Option<DatabaseIterator> {
+		match *self.log.read() {
+			Some(_) => {
+				let overlay = &self.overlay.read()[Self::to_overlay_column(col)];
+				let mut overlay_data = overlay.iter()
+					.filter_map(|(k, v)| match *v {
+						KeyState::Insert(ref value) |
+						KeyState::InsertCompressed(ref value) =>
+							Some((k.clone().into_vec().into_boxed_slice(), value.clone().into_vec().into_boxed_slice())),
+						KeyState::Delete => None,
+					}).collect::<Vec<_>>();
+				overlay_data.sort();
+
+				Some(DatabaseIterator {
+					iter: interleave_ordered(overlay_data, ColumnIterator::new(self, Self::to_overlay_column(col), Bound::Unbounded)),
+					_marker: PhantomData,
+				})
+			},
+			None => None,
+		}
+	}
+
+	fn iter_from_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<DatabaseIterator> {
+		match *self.log.read() {
+			Some(_) => Some(DatabaseIterator {
+				iter: interleave_ordered(Vec::new(), ColumnIterator::new(self, Self::to_overlay_column(col), Bound::Included(prefix.to_vec()))),
+				_marker: PhantomData,
+			}),
+			None => None,
+		}
+	}
+
+	/// Close the database
+	fn close(&self) {
+		*self.log.write() = None;
+		self.overlay.write().clear();
+		// a running compaction finds the log closed and discards its copy.
+		if let Some(compaction) = self.compaction.lock().take() {
+			let _ = compaction.join();
+		}
+	}
+
+	/// Restore the database from a copy at given path.
+	pub fn restore(&self, new_db: &str) -> Result<()> {
+		self.close();
+
+		let mut backup_db = self.path.clone();
+		backup_db.pop();
+		backup_db.push("backup_db");
+
+		let existed = match fs::rename(&self.path, &backup_db) {
+			Ok(_) => true,
+			Err(e) => if let io::ErrorKind::NotFound = e.kind() {
+				false
+			} else {
+				return Err(e.into());
+			}
+		};
+
+		match fs::rename(&new_db, &self.path) {
+			Ok(_) => {
+				// clean up the backup.
+				if existed {
+					fs::remove_dir_all(&backup_db)?;
+				}
+			}
+			Err(e) => {
+				// restore the backup.
+				if existed {
+					fs::rename(&backup_db, &self.path)?;
+				}
+				return Err(e.into())
+			}
+		}
+
+		// reopen the database and steal handles into self
+		let db = Self::open(&self.config, &self.path.to_string_lossy())?;
+		*self.log.write() = mem::replace(&mut *db.log.write(), None);
+		*self.overlay.write() = mem::replace(&mut *db.overlay.write(), Vec::new());
+		Ok(())
+	}
+
+	/// The number of non-default columns.
+	pub fn num_columns(&self) -> u32 {
+		self.log.read().as_ref()
+			.map(|log| log.columns.len() as u32 - 1)
+			.unwrap_or(0)
+	}
+
+	/// Drop the last column and remove its records from the log.
+	pub fn drop_column(&self) -> Result<()> {
+		match *self.log.write() {
+			Some(ref mut log) => {
+				if log.columns.len() > 1 {
+					log.columns.pop();
+					self.overlay.write().pop();
+					log.compact()?;
+				}
+				Ok(())
+			},
+			None => Ok(()),
+		}
+	}
+
+	/// Add a column.
+	pub fn add_column(&self) -> Result<()> {
+		match *self.log.write() {
+			Some(ref mut log) => {
+				log.columns.push(BTreeMap::new());
+				self.overlay.write().push(HashMap::new());
+				Ok(())
+			},
+			None => Ok(()),
+		}
+	}
+}
+
+// duplicate declaration of methods here to avoid trait import in certain existing cases
+// at time of addition.
+impl KeyValueDB for Database {
+	fn get(&self, col: Option<u32>, key: &[u8]) -> Result<Option<DBValue>> {
+		Database::get(self, col, key)
+	}
+
+	fn get_by_prefix(&self, col: Option<u32>, prefix: &[u8]) -> Option<Box<[u8]>> {
+		Database::get_by_prefix(self, col, prefix)
+	}
+
+	fn write_buffered(&self, transaction: DBTransaction) {
+		Database::write_buffered(self, transaction)
+	}
+
+	fn write(&self, transaction: DBTransaction) -> Result<()> {
+		Database::write(self, transaction)
+	}
+
+	fn flush(&self) -> Result<()> {
+		Database::flush(self)
+	}
+
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		let unboxed = Database::iter(self, col);
+		Box::new(unboxed.into_iter().flat_map(|inner| inner))
+	}
+
+	fn iter_from_prefix<'a>(&'a self, col: Option<u32>, prefix: &'a [u8])
+		-> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a>
+	{
+		let unboxed = Database::iter_from_prefix(self, col, prefix);
+		Box::new(unboxed.into_iter().flat_map(|inner| inner))
+	}
+
+	fn restore(&self, new_db: &str) -> Result<()> {
+		Database::restore(self, new_db)
+	}
+}
+
+impl Drop for Database {
+	fn drop(&mut self) {
+		// write all buffered changes if we can.
+		let _ = self.flush();
+		self.close();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+	use std::sync::Arc;
+	use tempdir::TempDir;
+	use ethereum_types::H256;
+	use journaldb::{self, Algorithm};
+	use keccak::keccak;
+	use super::*;
+
+	fn test_db(config: &DatabaseConfig) {
+		let tempdir = TempDir::new("").unwrap();
+		let db = Database::open(config, tempdir.path().to_str().unwrap()).unwrap();
+		let key1 = H256::from_str("02c69be41d0b7e40352fc85be1cd65eb03d40ef8427a0ca4596b1ead9a00e9fc").unwrap();
+		let key2 = H256::from_str("03c69be41d0b7e40352fc85be1cd65eb03d40ef8427a0ca4596b1ead9a00e9fc").unwrap();
+		let key3 = H256::from_str("01c69be41d0b7e40352fc85be1cd65eb03d40ef8427a0ca4596b1ead9a00e9fc").unwrap();
+
+		let mut batch = db.transaction();
+		batch.put(None, &key1, b"cat");
+		batch.put(None, &key2, b"dog");
+		db.write(batch).unwrap();
+
+		assert_eq!(&*db.get(None, &key1).unwrap().unwrap(), b"cat");
+
+		let contents: Vec<_> = db.iter(None).into_iter().flat_map(|inner| inner).collect();
+		assert_eq!(contents.len(), 2);
+		assert_eq!(&*contents[0].0, &*key1);
+		assert_eq!(&*contents[0].1, b"cat");
+		assert_eq!(&*contents[1].0, &*key2);
+		assert_eq!(&*contents[1].1, b"dog");
+
+		let mut batch = db.transaction();
+		batch.delete(None, &key1);
+		db.write(batch).unwrap();
+
+		assert!(db.get(None, &key1).unwrap().is_none());
+
+		let mut batch = db.transaction();
+		batch.put(None, &key1, b"cat");
+		db.write(batch).unwrap();
+
+		let mut transaction = db.transaction();
+		transaction.put(None, &key3, b"elephant");
+		transaction.delete(None, &key1);
+		db.write(transaction).unwrap();
+		assert!(db.get(None, &key1).unwrap().is_none());
+		assert_eq!(&*db.get(None, &key3).unwrap().unwrap(), b"elephant");
+
+		assert_eq!(&*db.get_by_prefix(None, &key3).unwrap(), b"elephant");
+		assert_eq!(&*db.get_by_prefix(None, &key2).unwrap(), b"dog");
+
+		let mut transaction = db.transaction();
+		transaction.put(None, &key1, b"horse");
+		transaction.delete(None, &key3);
+		db.write_buffered(transaction);
+		assert!(db.get(None, &key3).unwrap().is_none());
+		assert_eq!(&*db.get(None, &key1).unwrap().unwrap(), b"horse");
+
+		db.flush().unwrap();
+		assert!(db.get(None, &key3).unwrap().is_none());
+		assert_eq!(&*db.get(None, &key1).unwrap().unwrap(), b"horse");
+	}
+
+	#[test]
+	fn kvdb() {
+		let tempdir = TempDir::new("").unwrap();
+		let _ = Database::open_default(tempdir.path().to_str().unwrap()).unwrap();
+		test_db(&DatabaseConfig::default());
+		test_db(&DatabaseConfig { columns: None, wal: false });
+	}
+
+	#[test]
+	fn persists_across_reopen() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().to_str().unwrap();
+		let config = DatabaseConfig::with_columns(Some(2));
+
+		{
+			let db = Database::open(&config, path).unwrap();
+			let mut batch = db.transaction();
+			batch.put(None, b"foo", b"bar");
+			batch.put(Some(1), b"foo", b"baz");
+			batch.put(Some(1), b"gone", b"soon");
+			db.write(batch).unwrap();
+
+			let mut batch = db.transaction();
+			batch.delete(Some(1), b"gone");
+			// left buffered, written on drop.
+			db.write_buffered(batch);
+		}
+
+		let db = Database::open(&config, path).unwrap();
+		assert_eq!(&*db.get(None, b"foo").unwrap().unwrap(), b"bar");
+		assert_eq!(&*db.get(Some(1), b"foo").unwrap().unwrap(), b"baz");
+		assert!(db.get(Some(0), b"foo").unwrap().is_none());
+		assert!(db.get(Some(1), b"gone").unwrap().is_none());
+	}
+
+	#[test]
+	fn iter_merges_buffered_and_starts_from_prefix() {
+		let tempdir = TempDir::new("").unwrap();
+		let db = Database::open_default(tempdir.path().to_str().unwrap()).unwrap();
+
+		let mut batch = db.transaction();
+		for i in 0..(ITER_CHUNK as u32 * 2 + 3) {
+			batch.put(None, &[(i >> 8) as u8, i as u8], &[1]);
+		}
+		db.write(batch).unwrap();
+
+		let mut batch = db.transaction();
+		batch.put(None, &[0xff], &[2]);
+		db.write_buffered(batch);
+
+		let keys: Vec<_> = db.iter(None).unwrap().map(|(k, _)| k.into_vec()).collect();
+		assert_eq!(keys.len(), ITER_CHUNK * 2 + 4);
+		assert!(keys.windows(2).all(|w| w[0] < w[1]));
+		assert_eq!(keys.last().unwrap(), &vec![0xff]);
+
+		let from: Vec<_> = db.iter_from_prefix(None, &[2]).unwrap().map(|(k, _)| k.into_vec()).collect();
+		assert_eq!(from, vec![vec![2, 0], vec![2, 1], vec![2, 2]]);
+	}
+
+	#[test]
+	fn discards_partially_written_records() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().to_str().unwrap();
+
+		{
+			let db = Database::open_default(path).unwrap();
+			let mut batch = db.transaction();
+			batch.put(None, b"foo", b"bar");
+			db.write(batch).unwrap();
+		}
+
+		{
+			let mut file = OpenOptions::new().append(true).open(tempdir.path().join(LOG_FILE)).unwrap();
+			let mut record = Vec::new();
+			encode_record(&mut record, RECORD_INSERT, 0, b"torn", b"write");
+			file.write_all(&record[..record.len() - 1]).unwrap();
+		}
+
+		let db = Database::open_default(path).unwrap();
+		assert_eq!(&*db.get(None, b"foo").unwrap().unwrap(), b"bar");
+		assert!(db.get(None, b"torn").unwrap().is_none());
+
+		let mut batch = db.transaction();
+		batch.put(None, b"after", b"recovery");
+		db.write(batch).unwrap();
+		drop(db);
+
+		let db = Database::open_default(path).unwrap();
+		assert_eq!(&*db.get(None, b"after").unwrap().unwrap(), b"recovery");
+	}
+
+	#[test]
+	fn discards_uncommitted_transaction_in_all_columns() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().to_str().unwrap();
+		let config = DatabaseConfig::with_columns(Some(2));
+
+		{
+			let db = Database::open(&config, path).unwrap();
+			let mut batch = db.transaction();
+			batch.put(Some(0), b"foo", b"bar");
+			db.write(batch).unwrap();
+		}
+
+		{
+			// complete records of two columns, but the crash happened before the commit record.
+			let mut file = OpenOptions::new().append(true).open(tempdir.path().join(LOG_FILE)).unwrap();
+			let mut records = Vec::new();
+			encode_record(&mut records, RECORD_INSERT, 1, b"foo", b"new");
+			encode_record(&mut records, RECORD_DELETE, 2, b"foo", &[]);
+			file.write_all(&records).unwrap();
+		}
+
+		let db = Database::open(&config, path).unwrap();
+		assert_eq!(&*db.get(Some(0), b"foo").unwrap().unwrap(), b"bar");
+		assert!(db.get(Some(1), b"foo").unwrap().is_none());
+	}
+
+	#[test]
+	fn failed_flush_keeps_buffered_changes() {
+		let tempdir = TempDir::new("").unwrap();
+		let db = Database::open_default(tempdir.path().to_str().unwrap()).unwrap();
+
+		let mut batch = db.transaction();
+		batch.put(None, b"foo", b"bar");
+		db.write_buffered(batch);
+
+		let log_path = tempdir.path().join(LOG_FILE);
+		let writable = {
+			let mut log = db.log.write();
+			let log = log.as_mut().unwrap();
+			mem::replace(&mut log.file, File::open(&log_path).unwrap())
+		};
+		assert!(db.flush().is_err());
+		assert_eq!(&*db.get(None, b"foo").unwrap().unwrap(), b"bar");
+
+		db.log.write().as_mut().unwrap().file = writable;
+		db.flush().unwrap();
+		drop(db);
+
+		let db = Database::open_default(tempdir.path().to_str().unwrap()).unwrap();
+		assert_eq!(&*db.get(None, b"foo").unwrap().unwrap(), b"bar");
+	}
+
+	#[test]
+	fn failed_write_is_not_applied() {
+		let tempdir = TempDir::new("").unwrap();
+		let db = Database::open_default(tempdir.path().to_str().unwrap()).unwrap();
+
+		let mut batch = db.transaction();
+		batch.put(None, b"foo", b"bar");
+		db.write_buffered(batch);
+
+		let log_path = tempdir.path().join(LOG_FILE);
+		let writable = {
+			let mut log = db.log.write();
+			let log = log.as_mut().unwrap();
+			mem::replace(&mut log.file, File::open(&log_path).unwrap())
+		};
+		let mut batch = db.transaction();
+		batch.put(None, b"foo", b"baz");
+		batch.put(None, b"qux", b"quux");
+		assert!(db.write(batch).is_err());
+		assert_eq!(&*db.get(None, b"foo").unwrap().unwrap(), b"bar");
+		assert!(db.get(None, b"qux").unwrap().is_none());
+
+		db.log.write().as_mut().unwrap().file = writable;
+		db.flush().unwrap();
+		drop(db);
+
+		let db = Database::open_default(tempdir.path().to_str().unwrap()).unwrap();
+		assert_eq!(&*db.get(None, b"foo").unwrap().unwrap(), b"bar");
+		assert!(db.get(None, b"qux").unwrap().is_none());
+	}
+
+	#[test]
+	fn compaction_keeps_live_records() {
+		let tempdir = TempDir::new("").unwrap();
+		let log_path = tempdir.path().join(LOG_FILE);
+		let mut log = Log::open(log_path.clone(), 2).unwrap();
+
+		for i in 0..10u8 {
+			let mut overlay = new_overlay(2);
+			overlay[0].insert(ElasticArray32::from_slice(&[i % 2]), KeyState::Insert(DBValue::from_slice(&[i; 100])));
+			overlay[0].insert(ElasticArray32::from_slice(&[0xff]), KeyState::Delete);
+			overlay[1].insert(ElasticArray32::from_slice(&[0]), KeyState::Insert(DBValue::from_slice(&[i; 10])));
+			log.write(&overlay, false).unwrap();
+		}
+		assert!(log.garbage > HEADER_LEN + 4);
+
+		log.compact().unwrap();
+		assert_eq!(log.garbage, HEADER_LEN + 4);
+		assert_eq!(&*log.read(log.columns[0][&vec![0]]).unwrap(), &[8; 100][..]);
+		assert_eq!(&*log.read(log.columns[0][&vec![1]]).unwrap(), &[9; 100][..]);
+		assert_eq!(&*log.read(log.columns[1][&vec![0]]).unwrap(), &[9; 10][..]);
+		drop(log);
+
+		let log = Log::open(log_path, 2).unwrap();
+		assert_eq!(log.columns[0].len(), 2);
+		assert_eq!(log.columns[1].len(), 1);
+		assert_eq!(log.garbage, HEADER_LEN + 4);
+		assert_eq!(&*log.read(log.columns[0][&vec![1]]).unwrap(), &[9; 100][..]);
+	}
+
+	#[test]
+	fn compaction_keeps_batches_committed_meanwhile() {
+		let tempdir = TempDir::new("").unwrap();
+		let log_path = tempdir.path().join(LOG_FILE);
+		let mut log = Log::open(log_path.clone(), 1).unwrap();
+
+		for i in 0..10u8 {
+			let mut overlay = new_overlay(1);
+			overlay[0].insert(ElasticArray32::from_slice(&[i % 3]), KeyState::Insert(DBValue::from_slice(&[i; 100])));
+			log.write(&overlay, false).unwrap();
+		}
+		let compacted = Compacted::write(log.snapshot().unwrap(), log_path.with_extension("compacting")).unwrap();
+
+		let mut overlay = new_overlay(1);
+		overlay[0].insert(ElasticArray32::from_slice(&[0]), KeyState::Insert(DBValue::from_slice(&[10; 50])));
+		overlay[0].insert(ElasticArray32::from_slice(&[1]), KeyState::Delete);
+		overlay[0].insert(ElasticArray32::from_slice(&[3]), KeyState::Insert(DBValue::from_slice(&[11; 10])));
+		log.write(&overlay, false).unwrap();
+
+		log.finish_compaction(compacted).unwrap();
+		assert_eq!(log.generation, 1);
+		assert_eq!(log.columns[0].len(), 3);
+		assert_eq!(&*log.read(log.columns[0][&vec![0]]).unwrap(), &[10; 50][..]);
+		assert_eq!(&*log.read(log.columns[0][&vec![2]]).unwrap(), &[8; 100][..]);
+		assert_eq!(&*log.read(log.columns[0][&vec![3]]).unwrap(), &[11; 10][..]);
+		let garbage = log.garbage;
+		drop(log);
+
+		let log = Log::open(log_path, 1).unwrap();
+		assert_eq!(log.columns[0].len(), 3);
+		assert_eq!(log.garbage, garbage);
+		assert_eq!(&*log.read(log.columns[0][&vec![0]]).unwrap(), &[10; 50][..]);
+		assert_eq!(&*log.read(log.columns[0][&vec![2]]).unwrap(), &[8; 100][..]);
+		assert_eq!(&*log.read(log.columns[0][&vec![3]]).unwrap(), &[11; 10][..]);
+	}
+
+	#[test]
+	fn add_columns() {
+		let config = DatabaseConfig::default();
+		let config_5 = DatabaseConfig::with_columns(Some(5));
+
+		let tempdir = TempDir::new("").unwrap();
+
+		// open empty, add 5.
+		{
+			let db = Database::open(&config, tempdir.path().to_str().unwrap()).unwrap();
+			assert_eq!(db.num_columns(), 0);
+
+			for i in 0..5 {
+				db.add_column().unwrap();
+				assert_eq!(db.num_columns(), i + 1);
+			}
+		}
+
+		// reopen as 5.
+		{
+			let db = Database::open(&config_5, tempdir.path().to_str().unwrap()).unwrap();
+			assert_eq!(db.num_columns(), 5);
+		}
+	}
+
+	#[test]
+	fn drop_columns() {
+		let config = DatabaseConfig::default();
+		let config_5 = DatabaseConfig::with_columns(Some(5));
+
+		let tempdir = TempDir::new("").unwrap();
+
+		// open 5, remove all.
+		{
+			let db = Database::open(&config_5, tempdir.path().to_str().unwrap()).unwrap();
+			assert_eq!(db.num_columns(), 5);
+
+			let mut batch = db.transaction();
+			batch.put(Some(4), b"foo", b"bar");
+			db.write(batch).unwrap();
+
+			for i in (0..5).rev() {
+				db.drop_column().unwrap();
+				assert_eq!(db.num_columns(), i);
+			}
+		}
+
+		// reopen as 0.
+		{
+			let db = Database::open(&config, tempdir.path().to_str().unwrap()).unwrap();
+			assert_eq!(db.num_columns(), 0);
+		}
+
+		// records of dropped columns are gone.
+		{
+			let db = Database::open(&config_5, tempdir.path().to_str().unwrap()).unwrap();
+			assert!(db.get(Some(4), b"foo").unwrap().is_none());
+		}
+	}
+
+	#[test]
+	fn restore() {
+		let tempdir = TempDir::new("").unwrap();
+		let db_path = tempdir.path().join("db");
+		let new_path = tempdir.path().join("new");
+
+		{
+			let new_db = Database::open_default(new_path.to_str().unwrap()).unwrap();
+			let mut batch = new_db.transaction();
+			batch.put(None, b"restored", b"yes");
+			new_db.write(batch).unwrap();
+		}
+
+		let db = Database::open_default(db_path.to_str().unwrap()).unwrap();
+		let mut batch = db.transaction();
+		batch.put(None, b"old", b"value");
+		db.write(batch).unwrap();
+
+		db.restore(new_path.to_str().unwrap()).unwrap();
+		assert!(db.get(None, b"old").unwrap().is_none());
+		assert_eq!(&*db.get(None, b"restored").unwrap().unwrap(), b"yes");
+		assert!(!new_path.exists());
+	}
+
+	#[test]
+	fn journaldb_reopen() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().to_str().unwrap();
+
+		for &algorithm in &[Algorithm::Archive, Algorithm::EarlyMerge, Algorithm::OverlayRecent, Algorithm::RefCounted] {
+			let (foo, bar) = {
+				let backing = Arc::new(Database::open_default(path).unwrap());
+				let mut jdb = journaldb::new(backing.clone(), algorithm, None);
+				let foo = jdb.insert(b"foo");
+				let bar = jdb.insert(b"bar");
+				let mut batch = backing.transaction();
+				jdb.journal_under(&mut batch, 0, &keccak(b"0")).unwrap();
+				backing.write(batch).unwrap();
+				(foo, bar)
+			};
+
+			{
+				let backing = Arc::new(Database::open_default(path).unwrap());
+				let mut jdb = journaldb::new(backing.clone(), algorithm, None);
+				assert!(jdb.contains(&foo));
+				assert!(jdb.contains(&bar));
+				jdb.remove(&foo);
+				let mut batch = backing.transaction();
+				jdb.journal_under(&mut batch, 1, &keccak(b"1")).unwrap();
+				jdb.mark_canonical(&mut batch, 0, &keccak(b"0")).unwrap();
+				backing.write(batch).unwrap();
+			}
+
+			{
+				let backing = Arc::new(Database::open_default(path).unwrap());
+				let mut jdb = journaldb::new(backing.clone(), algorithm, None);
+				let mut batch = backing.transaction();
+				jdb.journal_under(&mut batch, 2, &keccak(b"2")).unwrap();
+				jdb.mark_canonical(&mut batch, 1, &keccak(b"1")).unwrap();
+				backing.write(batch).unwrap();
+				assert!(jdb.contains(&bar));
+				assert_eq!(jdb.contains(&foo), !jdb.is_pruned());
+			}
+
+			::std::fs::remove_dir_all(path).unwrap();
+		}
+	}
+}
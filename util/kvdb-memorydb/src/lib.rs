@@ -14,20 +14,45 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+//! In-memory `KeyValueDB` implementation.
+//!
+//! Keys are kept sorted per column, so iteration matches the order of the
+//! on-disk backend. The database can optionally be capped in size and
+//! persisted to a single file which is loaded on open and written back on drop.
+
 extern crate parking_lot;
 extern crate kvdb;
 extern crate rlp;
 
+#[macro_use]
+extern crate log;
+
+#[cfg(test)]
+extern crate tempdir;
+
 use std::collections::{BTreeMap, HashMap};
-use parking_lot::RwLock;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use parking_lot::{Mutex, RwLock};
 use kvdb::{DBValue, DBTransaction, KeyValueDB, DBOp, Result};
-use rlp::{RlpType, UntrustedRlp, Compressible};
+use rlp::{RlpType, RlpStream, UntrustedRlp, Compressible};
 
 /// A key-value database fulfilling the `KeyValueDB` trait, living in memory.
-/// This is generally intended for tests and is not particularly optimized.
+/// This is generally intended for tests and ephemeral nodes.
 #[derive(Default)]
 pub struct InMemory {
 	columns: RwLock<HashMap<Option<u32>, BTreeMap<Vec<u8>, DBValue>>>,
+	/// Bytes of keys and values currently held.
+	mem_used: AtomicUsize,
+	/// Maximum number of bytes of keys and values, if capped.
+	mem_limit: Option<usize>,
+	/// Error of a rejected buffered write, reported on the next flush.
+	/// Use `write` to have a transaction rejected immediately.
+	overflow: Mutex<Option<String>>,
+	/// File the contents are written to when the database is dropped.
+	dump_path: Option<PathBuf>,
 }
 
 /// Create an in-memory database with the given number of columns.
@@ -41,7 +66,168 @@ pub fn create(num_cols: u32) -> InMemory {
 	}
 
 	InMemory {
-		columns: RwLock::new(cols)
+		columns: RwLock::new(cols),
+		mem_used: AtomicUsize::new(0),
+		mem_limit: None,
+		overflow: Mutex::new(None),
+		dump_path: None,
+	}
+}
+
+/// Create an in-memory database with the given number of columns, holding at most
+/// `limit` bytes of keys and values.
+pub fn with_limit(num_cols: u32, limit: usize) -> InMemory {
+	let mut db = create(num_cols);
+	db.mem_limit = Some(limit);
+	db
+}
+
+/// Open an in-memory database backed by a dump file.
+///
+/// The contents of the file are loaded if it exists, and the whole database is
+/// written back to it when the returned instance is dropped.
+pub fn open(num_cols: u32, limit: Option<usize>, path: &Path) -> Result<InMemory> {
+	let mut db = if path.exists() {
+		InMemory::load(num_cols, path)?
+	} else {
+		create(num_cols)
+	};
+
+	if let Some(limit) = limit {
+		let used = db.mem_used();
+		if used > limit {
+			return Err(format!("Database dump {} holds {} bytes, more than the limit of {} bytes", path.display(), used, limit).into());
+		}
+	}
+
+	db.mem_limit = limit;
+	db.dump_path = Some(path.to_owned());
+	Ok(db)
+}
+
+fn entry_size(key: &[u8], value: &[u8]) -> usize {
+	key.len() + value.len()
+}
+
+impl InMemory {
+	/// Number of bytes of keys and values currently held.
+	pub fn mem_used(&self) -> usize {
+		self.mem_used.load(Ordering::SeqCst)
+	}
+
+	/// Maximum number of bytes of keys and values, if capped.
+	pub fn mem_limit(&self) -> Option<usize> {
+		self.mem_limit
+	}
+
+	/// Apply a transaction, leaving the database untouched if it would exceed the limit.
+	fn apply(&self, transaction: DBTransaction) -> ::std::result::Result<(), String> {
+		let mut columns = self.columns.write();
+
+		// resolve the operations first so the whole transaction can be checked against the limit.
+		let ops: Vec<_> = transaction.ops.into_iter().map(|op| match op {
+			DBOp::Insert { col, key, value } => (col, key.into_vec(), Some(value)),
+			DBOp::InsertCompressed { col, key, value } => {
+				let compressed = UntrustedRlp::new(&value).compress(RlpType::Blocks);
+				let mut value = DBValue::new();
+				value.append_slice(&compressed);
+				(col, key.into_vec(), Some(value))
+			},
+			DBOp::Delete { col, key } => (col, key.into_vec(), None),
+		}).collect();
+
+		// size of every touched entry as of the previous operation in this transaction.
+		let mut used = self.mem_used() as isize;
+		{
+			let mut sizes: HashMap<(Option<u32>, &[u8]), usize> = HashMap::new();
+			for &(col, ref key, ref value) in &ops {
+				let map = match columns.get(&col) {
+					Some(map) => map,
+					None => continue,
+				};
+				let old = match sizes.get(&(col, key.as_slice())) {
+					Some(size) => *size,
+					None => map.get(key).map_or(0, |v| entry_size(key, v)),
+				};
+				let new = value.as_ref().map_or(0, |v| entry_size(key, v));
+				used += new as isize - old as isize;
+				sizes.insert((col, key.as_slice()), new);
+			}
+		}
+		let used = used as usize;
+
+		if let Some(limit) = self.mem_limit {
+			if used > limit {
+				return Err(format!("In-memory database limit exceeded: transaction needs {} bytes, limit is {} bytes", used, limit));
+			}
+		}
+
+		for (col, key, value) in ops {
+			if let Some(col) = columns.get_mut(&col) {
+				match value {
+					Some(value) => { col.insert(key, value); },
+					None => { col.remove(&key); },
+				}
+			}
+		}
+		self.mem_used.store(used, Ordering::SeqCst);
+		Ok(())
+	}
+
+	/// Write all columns to the given file, replacing it atomically.
+	pub fn dump(&self, path: &Path) -> Result<()> {
+		let columns = self.columns.read();
+		let mut cols: Vec<_> = columns.keys().cloned().collect();
+		cols.sort();
+
+		let mut stream = RlpStream::new_list(cols.len());
+		for col in cols {
+			let map = &columns[&col];
+			stream.begin_list(2);
+			stream.append(&col);
+			stream.begin_list(map.len());
+			for (key, value) in map {
+				stream.begin_list(2);
+				stream.append(&key.as_slice());
+				stream.append(&&**value);
+			}
+		}
+
+		let tmp_path = path.with_extension("tmp");
+		{
+			let mut file = File::create(&tmp_path)?;
+			file.write_all(&stream.out())?;
+			file.sync_all()?;
+		}
+		fs::rename(&tmp_path, path)?;
+		Ok(())
+	}
+
+	/// Load a database previously written with `dump`.
+	/// Fails if the dump contains a column not indexable by 0..`num_cols`.
+	pub fn load(num_cols: u32, path: &Path) -> Result<InMemory> {
+		let mut bytes = Vec::new();
+		File::open(path)?.read_to_end(&mut bytes)?;
+
+		let db = create(num_cols);
+		let mut used = 0;
+		{
+			let mut columns = db.columns.write();
+			let rlp = UntrustedRlp::new(&bytes);
+			for column in rlp.iter() {
+				let col: Option<u32> = column.val_at(0).map_err(|e| format!("Invalid database dump: {:?}", e))?;
+				let map = columns.get_mut(&col).ok_or_else(|| format!("No such column family: {:?}", col))?;
+				for item in column.at(1).map_err(|e| format!("Invalid database dump: {:?}", e))?.iter() {
+					let key: Vec<u8> = item.val_at(0).map_err(|e| format!("Invalid database dump: {:?}", e))?;
+					let value = item.at(1).and_then(|v| v.data()).map_err(|e| format!("Invalid database dump: {:?}", e))?;
+					used += entry_size(&key, value);
+					map.insert(key, DBValue::from_slice(value));
+				}
+			}
+		}
+
+		db.mem_used.store(used, Ordering::SeqCst);
+		Ok(db)
 	}
 }
 
@@ -59,41 +245,37 @@ impl KeyValueDB for InMemory {
 		match columns.get(&col) {
 			None => None,
 			Some(map) =>
-				map.iter()
-					.find(|&(ref k ,_)| k.starts_with(prefix))
-					.map(|(_, v)| v.to_vec().into_boxed_slice())
+				map.range(prefix.to_vec()..)
+					.next()
+					.and_then(|(k, v)| if k.starts_with(prefix) { Some(v.to_vec().into_boxed_slice()) } else { None })
 		}
 	}
 
 	fn write_buffered(&self, transaction: DBTransaction) {
-		let mut columns = self.columns.write();
-		let ops = transaction.ops;
-		for op in ops {
-			match op {
-				DBOp::Insert { col, key, value } => {
-					if let Some(col) = columns.get_mut(&col) {
-						col.insert(key.into_vec(), value);
-					}
-				},
-				DBOp::InsertCompressed { col, key, value } => {
-					if let Some(col) = columns.get_mut(&col) {
-						let compressed = UntrustedRlp::new(&value).compress(RlpType::Blocks);
-						let mut value = DBValue::new();
-						value.append_slice(&compressed);
-						col.insert(key.into_vec(), value);
-					}
-				},
-				DBOp::Delete { col, key } => {
-					if let Some(col) = columns.get_mut(&col) {
-						col.remove(&*key);
-					}
-				},
-			}
+		// buffered writes cannot fail, a rejected one is reported on the next flush.
+		if let Err(err) = self.apply(transaction) {
+			warn!("Rejected buffered write: {}", err);
+			*self.overflow.lock() = Some(err);
 		}
 	}
 
+	fn write(&self, transaction: DBTransaction) -> Result<()> {
+		self.apply(transaction).map_err(Into::into)
+	}
+
 	fn flush(&self) -> Result<()> {
-		Ok(())
+		match self.overflow.lock().take() {
+			Some(err) => Err(err.into()),
+			None => Ok(()),
+		}
+	}
+
+	fn memory_footprint(&self) -> Option<usize> {
+		Some(self.mem_used())
+	}
+
+	fn is_capped(&self) -> bool {
+		self.mem_limit.is_some()
 	}
 
 	fn iter<'a>(&'a self, col: Option<u32>) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
@@ -112,10 +294,10 @@ impl KeyValueDB for InMemory {
 	{
 		match self.columns.read().get(&col) {
 			Some(map) => Box::new(
-				map.clone()
+				map.range(prefix.to_vec()..)
+					.map(|(k, v)| (k.clone().into_boxed_slice(), v.to_vec().into_boxed_slice()))
+					.collect::<Vec<_>>()
 					.into_iter()
-					.skip_while(move |&(ref k, _)| !k.starts_with(prefix))
-					.map(|(k, v)| (k.into_boxed_slice(), v.into_vec().into_boxed_slice()))
 			),
 			None => Box::new(None.into_iter()),
 		}
@@ -125,3 +307,105 @@ impl KeyValueDB for InMemory {
 		Err("Attempted to restore in-memory database".into())
 	}
 }
+
+impl Drop for InMemory {
+	fn drop(&mut self) {
+		if let Some(ref path) = self.dump_path {
+			match self.dump(path) {
+				Ok(()) => info!("Wrote in-memory database ({} bytes) to {}", self.mem_used(), path.display()),
+				Err(e) => warn!("Failed to write in-memory database to {}: {}", path.display(), e),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use kvdb::KeyValueDB;
+	use tempdir::TempDir;
+	use super::{create, with_limit, open, InMemory};
+
+	#[test]
+	fn iter_is_sorted() {
+		let db = create(1);
+		let mut tx = db.transaction();
+		tx.put(Some(0), b"c", b"3");
+		tx.put(Some(0), b"a", b"1");
+		tx.put(Some(0), b"b", b"2");
+		db.write(tx).unwrap();
+
+		let keys: Vec<_> = db.iter(Some(0)).map(|(k, _)| k.into_vec()).collect();
+		assert_eq!(keys, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+	}
+
+	#[test]
+	fn iter_from_prefix_starts_at_prefix() {
+		let db = create(0);
+		let mut tx = db.transaction();
+		tx.put(None, b"aa", b"1");
+		tx.put(None, b"ba", b"2");
+		tx.put(None, b"bb", b"3");
+		tx.put(None, b"ca", b"4");
+		db.write(tx).unwrap();
+
+		let keys: Vec<_> = db.iter_from_prefix(None, b"b").map(|(k, _)| k.into_vec()).collect();
+		assert_eq!(keys, vec![b"ba".to_vec(), b"bb".to_vec(), b"ca".to_vec()]);
+		assert_eq!(&*db.get_by_prefix(None, b"b").unwrap(), b"2");
+		assert!(db.get_by_prefix(None, b"d").is_none());
+	}
+
+	#[test]
+	fn tracks_memory_and_enforces_limit() {
+		let db = with_limit(0, 10);
+		let mut tx = db.transaction();
+		tx.put(None, b"key", b"value");
+		db.write(tx).unwrap();
+		assert_eq!(db.mem_used(), 8);
+
+		let mut tx = db.transaction();
+		tx.put(None, b"other", b"value");
+		assert!(db.write(tx).is_err());
+		assert_eq!(db.mem_used(), 8);
+		assert!(db.get(None, b"other").unwrap().is_none());
+		assert!(db.flush().is_ok());
+
+		// a rejected buffered write is reported on flush.
+		let mut tx = db.transaction();
+		tx.put(None, b"other", b"value");
+		db.write_buffered(tx);
+		assert!(db.get(None, b"other").unwrap().is_none());
+		assert!(db.flush().is_err());
+		assert!(db.flush().is_ok());
+
+		// replacing within a transaction only counts the final value.
+		let mut tx = db.transaction();
+		tx.delete(None, b"key");
+		tx.put(None, b"k", b"v");
+		tx.put(None, b"k", b"value");
+		db.write(tx).unwrap();
+		assert_eq!(db.mem_used(), 6);
+	}
+
+	#[test]
+	fn dump_and_load() {
+		let tempdir = TempDir::new("").unwrap();
+		let path = tempdir.path().join("db.rlp");
+
+		{
+			let db = open(2, None, &path).unwrap();
+			let mut tx = db.transaction();
+			tx.put(None, b"a", b"1");
+			tx.put(Some(1), b"b", b"2");
+			db.write(tx).unwrap();
+		}
+
+		let db = InMemory::load(2, &path).unwrap();
+		assert_eq!(&*db.get(None, b"a").unwrap().unwrap(), b"1");
+		assert_eq!(&*db.get(Some(1), b"b").unwrap().unwrap(), b"2");
+		assert!(db.get(Some(0), b"b").unwrap().is_none());
+		assert_eq!(db.mem_used(), 4);
+
+		assert!(InMemory::load(1, &path).is_err());
+		assert!(open(2, Some(3), &path).is_err());
+	}
+}
@@ -194,4 +194,11 @@ pub trait KeyValueDB: Sync + Send {
 
 	/// Attempt to replace this database with a new one located at the given path.
 	fn restore(&self, new_db: &str) -> Result<()>;
+
+	/// Number of bytes of keys and values held, if the whole database lives in memory.
+	fn memory_footprint(&self) -> Option<usize> { None }
+
+	/// Whether writes can be rejected because the database is full.
+	/// Such writes should go through `write`, so the rejection is seen at once.
+	fn is_capped(&self) -> bool { false }
 }
@@ -25,6 +25,8 @@ extern crate error_chain;
 
 extern crate kvdb;
 extern crate kvdb_rocksdb;
+#[cfg(feature = "logdb")]
+extern crate kvdb_logdb;
 
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
@@ -32,7 +34,7 @@ use std::sync::Arc;
 use std::{fs, io};
 
 use kvdb::DBTransaction;
-use kvdb_rocksdb::{CompactionProfile, Database, DatabaseConfig};
+use kvdb_rocksdb::{CompactionProfile, DatabaseConfig};
 
 error_chain! {
 	links {
@@ -56,6 +58,22 @@ error_chain! {
 	}
 }
 
+/// Database engine the migrated database is stored in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Engine {
+	/// RocksDB.
+	RocksDb,
+	/// Log-structured database from `kvdb-logdb`.
+	#[cfg(feature = "logdb")]
+	LogDb,
+}
+
+impl Default for Engine {
+	fn default() -> Self {
+		Engine::RocksDb
+	}
+}
+
 /// Migration config.
 #[derive(Clone)]
 pub struct Config {
@@ -63,6 +81,8 @@ pub struct Config {
 	pub batch_size: usize,
 	/// Database compaction profile.
 	pub compaction_profile: CompactionProfile,
+	/// Database engine.
+	pub engine: Engine,
 }
 
 impl Default for Config {
@@ -70,6 +90,78 @@ impl Default for Config {
 		Config {
 			batch_size: 1024,
 			compaction_profile: Default::default(),
+			engine: Default::default(),
+		}
+	}
+}
+
+/// Database being migrated, stored in one of the supported engines.
+pub enum Database {
+	/// RocksDB database.
+	RocksDb(kvdb_rocksdb::Database),
+	/// Log-structured database.
+	#[cfg(feature = "logdb")]
+	LogDb(kvdb_logdb::Database),
+}
+
+impl Database {
+	/// Open database with the given engine.
+	pub fn open(engine: Engine, config: &DatabaseConfig, path: &str) -> Result<Database> {
+		match engine {
+			Engine::RocksDb => Ok(Database::RocksDb(kvdb_rocksdb::Database::open(config, path)?)),
+			#[cfg(feature = "logdb")]
+			Engine::LogDb => {
+				let config = kvdb_logdb::DatabaseConfig {
+					columns: config.columns,
+					wal: config.wal,
+				};
+				Ok(Database::LogDb(kvdb_logdb::Database::open(&config, path)?))
+			},
+		}
+	}
+
+	/// Get database iterator for flushed data.
+	pub fn iter<'a>(&'a self, col: Option<u32>) -> Option<Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a>> {
+		match *self {
+			Database::RocksDb(ref db) => db.iter(col).map(|iter| Box::new(iter) as Box<_>),
+			#[cfg(feature = "logdb")]
+			Database::LogDb(ref db) => db.iter(col).map(|iter| Box::new(iter) as Box<_>),
+		}
+	}
+
+	/// Commit transaction to database.
+	pub fn write(&self, tr: DBTransaction) -> kvdb::Result<()> {
+		match *self {
+			Database::RocksDb(ref db) => db.write(tr),
+			#[cfg(feature = "logdb")]
+			Database::LogDb(ref db) => db.write(tr),
+		}
+	}
+
+	/// The number of non-default column families.
+	pub fn num_columns(&self) -> u32 {
+		match *self {
+			Database::RocksDb(ref db) => db.num_columns(),
+			#[cfg(feature = "logdb")]
+			Database::LogDb(ref db) => db.num_columns(),
+		}
+	}
+
+	/// Add a new column family to the DB.
+	pub fn add_column(&self) -> kvdb::Result<()> {
+		match *self {
+			Database::RocksDb(ref db) => db.add_column(),
+			#[cfg(feature = "logdb")]
+			Database::LogDb(ref db) => db.add_column(),
+		}
+	}
+
+	/// Drop the last column family from the DB.
+	pub fn drop_column(&self) -> kvdb::Result<()> {
+		match *self {
+			Database::RocksDb(ref db) => db.drop_column(),
+			#[cfg(feature = "logdb")]
+			Database::LogDb(ref db) => db.drop_column(),
 		}
 	}
 }
@@ -281,7 +373,7 @@ impl Manager {
 
 		// start with the old db.
 		let old_path_str = old_path.to_str().ok_or(ErrorKind::MigrationImpossible)?;
-		let mut cur_db = Arc::new(Database::open(&db_config, old_path_str)?);
+		let mut cur_db = Arc::new(Database::open(config.engine, &db_config, old_path_str)?);
 
 		for migration in migrations {
 			trace!(target: "migration", "starting migration to version {}", migration.version());
@@ -295,7 +387,7 @@ impl Manager {
 
 				// open the target temporary database.
 				let temp_path_str = temp_path.to_str().ok_or(ErrorKind::MigrationImpossible)?;
-				let mut new_db = Database::open(&db_config, temp_path_str)?;
+				let mut new_db = Database::open(config.engine, &db_config, temp_path_str)?;
 
 				match current_columns {
 					// migrate only default column
@@ -318,11 +410,11 @@ impl Manager {
 				// we can do this in-place.
 				let goal_columns = migration.columns().unwrap_or(0);
 				while cur_db.num_columns() < goal_columns {
-					cur_db.add_column().map_err(kvdb::Error::from)?;
+					cur_db.add_column()?;
 				}
 
 				while cur_db.num_columns() > goal_columns {
-					cur_db.drop_column().map_err(kvdb::Error::from)?;
+					cur_db.drop_column()?;
 				}
 			}
 		}
@@ -22,6 +22,8 @@
 extern crate macros;
 extern crate tempdir;
 extern crate kvdb_rocksdb;
+#[cfg(feature = "logdb")]
+extern crate kvdb_logdb;
 extern crate migration;
 
 use std::collections::BTreeMap;
@@ -112,7 +114,7 @@ impl Migration for AddsColumn {
 
 	fn version(&self) -> u32 { 1 }
 
-	fn migrate(&mut self, source: Arc<Database>, config: &Config, dest: &mut Database, col: Option<u32>) -> Result<(), Error> {
+	fn migrate(&mut self, source: Arc<migration::Database>, config: &Config, dest: &mut migration::Database, col: Option<u32>) -> Result<(), Error> {
 		let mut batch = Batch::new(config, col);
 
 		for (key, value) in source.iter(col).into_iter().flat_map(|inner| inner) {
@@ -270,3 +272,34 @@ fn change_columns() {
 	let db = Database::open(&config, new_path.to_str().unwrap()).unwrap();
 	assert_eq!(db.num_columns(), 4);
 }
+
+#[test]
+#[cfg(feature = "logdb")]
+fn logdb_migrations() {
+	use kvdb_logdb::{Database, DatabaseConfig};
+	use migration::Engine;
+
+	let tempdir = TempDir::new("").unwrap();
+	let db_path = db_path(tempdir.path());
+	{
+		let db = Database::open_default(db_path.to_str().unwrap()).unwrap();
+		let mut transaction = db.transaction();
+		transaction.put(None, &[], &[]);
+		transaction.put(None, &[1], &[1]);
+		db.write(transaction).unwrap();
+	}
+
+	let mut manager = Manager::new(Config { engine: Engine::LogDb, ..Config::default() });
+	manager.add_migration(Migration0).unwrap();
+	manager.add_migration(ChangeColumns {
+		pre_columns: None,
+		post_columns: Some(2),
+		version: 2,
+	}).unwrap();
+	let end_path = manager.execute(&db_path, 0).unwrap();
+
+	let db = Database::open(&DatabaseConfig::with_columns(Some(2)), end_path.to_str().unwrap()).unwrap();
+	assert_eq!(db.num_columns(), 2);
+	assert_eq!(&*db.get(None, &[0x11]).unwrap().unwrap(), &[0x22]);
+	assert_eq!(&*db.get(None, &[1, 0x11]).unwrap().unwrap(), &[1, 0x22]);
+}
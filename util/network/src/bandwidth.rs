@@ -0,0 +1,169 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Upload rate limiting.
+
+use std::cmp::min;
+use std::collections::HashMap;
+use std::time::Instant;
+use parking_lot::Mutex;
+use host::{ProtocolId, PacketId};
+
+/// Token bucket limiting the average rate of a byte stream.
+/// Allows bursts of up to one second worth of data.
+#[derive(Debug)]
+pub struct RateLimiter {
+	/// Bytes per second.
+	rate: u64,
+	/// Bytes that may be sent right now. Negative when a packet exceeded the allowance.
+	allowance: i64,
+	last_refill: Instant,
+}
+
+impl RateLimiter {
+	/// Create a new limiter for the given rate in bytes per second.
+	pub fn new(rate: u64) -> RateLimiter {
+		// the allowance is signed, larger rates are not limiting anyway.
+		let rate = min(rate, i64::max_value() as u64);
+		RateLimiter {
+			rate: rate,
+			allowance: rate as i64,
+			last_refill: Instant::now(),
+		}
+	}
+
+	fn refill(&mut self, now: Instant) {
+		let elapsed = now.duration_since(self.last_refill);
+		let elapsed_ms = elapsed.as_secs() * 1000 + (elapsed.subsec_nanos() / 1_000_000) as u64;
+		let refill = self.rate.saturating_mul(elapsed_ms) / 1000;
+		if refill > 0 {
+			self.allowance = min(self.allowance.saturating_add(refill as i64), self.rate as i64);
+			self.last_refill = now;
+		}
+	}
+
+	fn is_exhausted(&mut self, now: Instant) -> bool {
+		self.refill(now);
+		self.allowance < 0
+	}
+
+	fn consume(&mut self, size: usize) {
+		self.allowance = self.allowance.saturating_sub(size as i64);
+	}
+}
+
+/// Upload limits shared by all sessions.
+#[derive(Debug, Default)]
+pub struct UploadLimits {
+	/// Limit for all subprotocol traffic.
+	total: Option<Mutex<RateLimiter>>,
+	/// Limits for individual subprotocol packets.
+	packets: HashMap<(ProtocolId, PacketId), Mutex<RateLimiter>>,
+}
+
+impl UploadLimits {
+	/// Create limits from rates in bytes per second. A rate of 0 means no limit.
+	pub fn new(total: Option<u64>, packets: &HashMap<(ProtocolId, PacketId), u64>) -> UploadLimits {
+		UploadLimits {
+			total: total.and_then(|rate| match rate {
+				0 => None,
+				rate => Some(Mutex::new(RateLimiter::new(rate))),
+			}),
+			packets: packets.iter()
+				.filter(|&(_, rate)| *rate > 0)
+				.map(|(p, rate)| (*p, Mutex::new(RateLimiter::new(*rate))))
+				.collect(),
+		}
+	}
+
+	/// Returns `true` if any limit is configured.
+	pub fn is_enabled(&self) -> bool {
+		self.total.is_some() || !self.packets.is_empty()
+	}
+
+	/// Account for a packet about to be sent. Returns `false` if a subprotocol packet should be
+	/// delayed because a limit has been exceeded. Base protocol packets are always allowed.
+	pub fn allow(&self, protocol: Option<ProtocolId>, packet_id: PacketId, size: usize) -> bool {
+		self.allow_at(protocol, packet_id, size, Instant::now())
+	}
+
+	fn allow_at(&self, protocol: Option<ProtocolId>, packet_id: PacketId, size: usize, now: Instant) -> bool {
+		let mut total = self.total.as_ref().map(|l| l.lock());
+		let mut limit = protocol.and_then(|p| self.packets.get(&(p, packet_id))).map(|l| l.lock());
+		if protocol.is_some() {
+			let exhausted = total.as_mut().map_or(false, |l| l.is_exhausted(now))
+				|| limit.as_mut().map_or(false, |l| l.is_exhausted(now));
+			if exhausted {
+				return false;
+			}
+		}
+		if let Some(ref mut total) = total {
+			total.consume(size);
+		}
+		if let Some(ref mut limit) = limit {
+			limit.consume(size);
+		}
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	#[test]
+	fn limits_packet_rate() {
+		let mut rates = HashMap::new();
+		rates.insert((*b"par", 0x14), 1000);
+		let limits = UploadLimits::new(None, &rates);
+		let start = Instant::now();
+
+		// a burst larger than the allowance goes through, then packets are delayed until refilled.
+		assert!(limits.allow_at(Some(*b"par"), 0x14, 1500, start));
+		assert!(!limits.allow_at(Some(*b"par"), 0x14, 10, start));
+		// other packets of the same protocol are not limited.
+		assert!(limits.allow_at(Some(*b"par"), 0x12, 10_000, start));
+		assert!(limits.allow_at(Some(*b"eth"), 0x14, 10_000, start));
+		assert!(!limits.allow_at(Some(*b"par"), 0x14, 10, start + Duration::from_millis(400)));
+		assert!(limits.allow_at(Some(*b"par"), 0x14, 10, start + Duration::from_millis(600)));
+	}
+
+	#[test]
+	fn limits_total_rate() {
+		let limits = UploadLimits::new(Some(100), &HashMap::new());
+		let start = Instant::now();
+
+		assert!(limits.allow_at(Some(*b"eth"), 0, 200, start));
+		assert!(!limits.allow_at(Some(*b"eth"), 0, 1, start));
+		// base protocol packets are never delayed.
+		assert!(limits.allow_at(None, 0, 10, start));
+		assert!(!limits.allow_at(Some(*b"pip"), 0, 1, start + Duration::from_millis(1000)));
+		assert!(limits.allow_at(Some(*b"pip"), 0, 1, start + Duration::from_millis(2000)));
+	}
+
+	#[test]
+	fn zero_and_huge_rates_do_not_limit() {
+		let mut rates = HashMap::new();
+		rates.insert((*b"par", 0x14), 0);
+		let limits = UploadLimits::new(Some(u64::max_value()), &rates);
+		let start = Instant::now();
+
+		for _ in 0..10 {
+			assert!(limits.allow_at(Some(*b"par"), 0x14, 1_000_000, start));
+		}
+	}
+}
@@ -27,9 +27,11 @@ use time;
 use ethereum_types::{H256, H520};
 use rlp::*;
 use node_table::*;
+use node_record::NodeRecord;
 use error::{Error, ErrorKind};
 use io::{StreamToken, IoContext};
 use ethkey::{Secret, KeyPair, sign, recover};
+use host::ProtocolId;
 use IpFilter;
 
 use PROTOCOL_VERSION;
@@ -46,9 +48,18 @@ const PACKET_PING: u8 = 1;
 const PACKET_PONG: u8 = 2;
 const PACKET_FIND_NODE: u8 = 3;
 const PACKET_NEIGHBOURS: u8 = 4;
+const PACKET_ENR_REQUEST: u8 = 5;
+const PACKET_ENR_RESPONSE: u8 = 6;
+// Topic packets are a Parity extension, kept clear of the ids assigned to discovery v4 packets.
+const PACKET_TOPIC_REGISTER: u8 = 0x10;
+const PACKET_TOPIC_QUERY: u8 = 0x11;
+const PACKET_TOPIC_NODES: u8 = 0x12;
 
 const PING_TIMEOUT_MS: u64 = 300;
 const MAX_NODES_PING: usize = 32; // Max nodes to add/ping at once
+const TOPIC_AD_EXPIRY_SEC: u64 = 300; // Lifetime of a topic advertisement, refreshed by the advertiser.
+const MAX_TOPIC_ADS: usize = 64; // Max advertisements stored per topic
+const MAX_TOPIC_NODES: usize = 64; // Max nodes kept per queried topic
 
 #[derive(Clone, Debug)]
 pub struct NodeEntry {
@@ -100,11 +111,32 @@ pub struct Discovery {
 	check_timestamps: bool,
 	adding_nodes: Vec<NodeEntry>,
 	ip_filter: IpFilter,
+	/// Topics advertised by this node.
+	topics: HashSet<ProtocolId>,
+	/// Topics this node is looking for peers of.
+	topic_queries: HashSet<ProtocolId>,
+	/// Advertisements stored on behalf of other nodes, with their expiry time.
+	topic_ads: HashMap<ProtocolId, HashMap<NodeId, (NodeEntry, u64)>>,
+	/// Nodes found advertising a queried topic.
+	topic_nodes: HashMap<ProtocolId, HashMap<NodeId, NodeEntry>>,
+	/// Nodes a topic query was sent to since the last refresh.
+	topic_queried: HashMap<ProtocolId, HashSet<NodeId>>,
+	/// Local node record served to other nodes.
+	record: Option<NodeRecord>,
+	/// Hashes of node record requests sent since the last refresh.
+	enr_requests: HashMap<NodeId, H256>,
+	/// Sequence numbers of the node records received from other nodes.
+	enr_seqs: HashMap<NodeId, u64>,
+	/// Hashes of pings waiting for a pong, with the payload hash echoed by legacy nodes and the time they were sent.
+	in_flight_pings: HashMap<H256, (H256, u64)>,
+	/// Nodes which answered our ping, proving their endpoint.
+	bonded: HashSet<NodeId>,
 }
 
 pub struct TableUpdates {
 	pub added: HashMap<NodeId, NodeEntry>,
 	pub removed: HashSet<NodeId>,
+	pub records: Vec<NodeRecord>,
 }
 
 impl Discovery {
@@ -125,6 +157,16 @@ impl Discovery {
 			check_timestamps: true,
 			adding_nodes: Vec::new(),
 			ip_filter: ip_filter,
+			topics: HashSet::new(),
+			topic_queries: HashSet::new(),
+			topic_ads: HashMap::new(),
+			topic_nodes: HashMap::new(),
+			topic_queried: HashMap::new(),
+			record: None,
+			enr_requests: HashMap::new(),
+			enr_seqs: HashMap::new(),
+			in_flight_pings: HashMap::new(),
+			bonded: HashSet::new(),
 		}
 	}
 
@@ -152,6 +194,29 @@ impl Discovery {
 		}
 	}
 
+	/// Advertise a topic to the nodes closest to it. Advertisements are renewed on every refresh.
+	pub fn register_topic(&mut self, topic: ProtocolId) {
+		if self.topics.insert(topic) {
+			self.send_topic_registration(&topic);
+		}
+	}
+
+	/// Look for nodes advertising a topic. The query is repeated on every refresh.
+	pub fn query_topic(&mut self, topic: ProtocolId) {
+		self.topic_queries.insert(topic);
+		self.send_topic_query(&topic);
+	}
+
+	/// Nodes found advertising a topic.
+	pub fn topic_nodes(&self, topic: &ProtocolId) -> Vec<NodeEntry> {
+		self.topic_nodes.get(topic).map_or_else(Vec::new, |nodes| nodes.values().cloned().collect())
+	}
+
+	/// Set the local node record served on request.
+	pub fn set_record(&mut self, record: NodeRecord) {
+		self.record = Some(record);
+	}
+
 	fn update_node(&mut self, e: NodeEntry) {
 		trace!(target: "discovery", "Inserting {:?}", &e);
 		let id_hash = keccak(e.id);
@@ -230,6 +295,37 @@ impl Discovery {
 		self.discovery_round += 1;
 	}
 
+	/// Kademlia target of a topic. Advertisements are stored at the nodes closest to it.
+	fn topic_target(topic: &ProtocolId) -> NodeId {
+		let mut target = NodeId::new();
+		target[0..32].copy_from_slice(&keccak(&topic[..]));
+		target
+	}
+
+	fn send_topic_registration(&mut self, topic: &ProtocolId) {
+		let nearest = Discovery::nearest_node_entries(&Discovery::topic_target(topic), &self.node_buckets);
+		let mut rlp = RlpStream::new_list(2);
+		self.public_endpoint.to_rlp_list(&mut rlp);
+		rlp.append(&&topic[..]);
+		let payload = rlp.drain();
+		for n in nearest.iter().take(ALPHA) {
+			self.send_packet(PACKET_TOPIC_REGISTER, &n.endpoint.udp_address(), &payload);
+			trace!(target: "discovery", "Sent TopicRegister to {:?}", &n.endpoint);
+		}
+	}
+
+	fn send_topic_query(&mut self, topic: &ProtocolId) {
+		let nearest = Discovery::nearest_node_entries(&Discovery::topic_target(topic), &self.node_buckets);
+		let mut rlp = RlpStream::new_list(1);
+		rlp.append(&&topic[..]);
+		let payload = rlp.drain();
+		for n in nearest.iter().take(ALPHA) {
+			self.send_packet(PACKET_TOPIC_QUERY, &n.endpoint.udp_address(), &payload);
+			self.topic_queried.entry(*topic).or_insert_with(HashSet::new).insert(n.id.clone());
+			trace!(target: "discovery", "Sent TopicQuery to {:?}", &n.endpoint);
+		}
+	}
+
 	fn distance(a: &H256, b: &H256) -> u32 {
 		let d = *a ^ *b;
 		let mut ret:u32 = 0;
@@ -249,27 +345,61 @@ impl Discovery {
 		self.public_endpoint.to_rlp_list(&mut rlp);
 		node.to_rlp_list(&mut rlp);
 		trace!(target: "discovery", "Sent Ping to {:?}", &node);
-		self.send_packet(PACKET_PING, &node.udp_address(), &rlp.drain());
+		let enr_seq = self.enr_seq();
+		if let Some((hash, payload_hash)) = self.send_packet_with_enr_seq(PACKET_PING, &node.udp_address(), &rlp.drain(), enr_seq) {
+			self.in_flight_pings.insert(hash, (payload_hash, time::precise_time_ns()));
+		}
+	}
+
+	/// Sequence number of the local node record, if any.
+	fn enr_seq(&self) -> Option<u64> {
+		self.record.as_ref().map(|r| r.seq())
 	}
 
-	fn send_packet(&mut self, packet_id: u8, address: &SocketAddr, payload: &[u8]) {
-		let mut rlp = RlpStream::new();
-		rlp.append_raw(&[packet_id], 1);
+	/// Request the record of a node, unless the one we have is up to date.
+	fn request_record(&mut self, node: &NodeId, seq: u64, address: &SocketAddr) {
+		if self.enr_requests.contains_key(node) || self.enr_seqs.get(node).map_or(false, |known| *known >= seq) {
+			return;
+		}
+		trace!(target: "discovery", "Sent ENRRequest to {:?}", address);
+		if let Some(hash) = self.send_packet(PACKET_ENR_REQUEST, address, &RlpStream::new_list(0).drain()) {
+			self.enr_requests.insert(node.clone(), hash);
+		}
+	}
+
+	/// Send a signed packet. Returns the hash of the packet sent.
+	fn send_packet(&mut self, packet_id: u8, address: &SocketAddr, payload: &[u8]) -> Option<H256> {
+		self.send_packet_with_enr_seq(packet_id, address, payload, None).map(|(hash, _)| hash)
+	}
+
+	/// Send a signed packet, with the local node record sequence number following the expiration (EIP-868).
+	/// Returns the hash of the packet sent and the hash of its payload.
+	fn send_packet_with_enr_seq(&mut self, packet_id: u8, address: &SocketAddr, payload: &[u8], enr_seq: Option<u64>) -> Option<(H256, H256)> {
 		let source = Rlp::new(payload);
-		rlp.begin_list(source.item_count() + 1);
+		let mut rlp = RlpStream::new_list(source.item_count() + 1 + enr_seq.map_or(0, |_| 1));
 		for i in 0 .. source.item_count() {
 			rlp.append_raw(source.at(i).as_raw(), 1);
 		}
 		let timestamp = time::get_time().sec as u32 + 60;
 		rlp.append(&timestamp);
+		if let Some(seq) = enr_seq {
+			rlp.append(&seq);
+		}
+		self.send_signed_packet(packet_id, address, &rlp.drain())
+	}
 
-		let bytes = rlp.drain();
-		let hash = keccak(bytes.as_ref());
+	/// Send a signed packet with the payload as given, without an expiration.
+	/// Returns the hash of the packet sent and the hash of its payload.
+	fn send_signed_packet(&mut self, packet_id: u8, address: &SocketAddr, payload: &[u8]) -> Option<(H256, H256)> {
+		let mut bytes = Bytes::with_capacity(payload.len() + 1);
+		bytes.push(packet_id);
+		bytes.extend_from_slice(payload);
+		let hash = keccak(&bytes);
 		let signature = match sign(&self.secret, &hash) {
 			Ok(s) => s,
 			Err(_) => {
 				warn!("Error signing UDP packet");
-				return;
+				return None;
 			}
 		};
 		let mut packet = Bytes::with_capacity(bytes.len() + 32 + 65);
@@ -279,6 +409,7 @@ impl Discovery {
 		let signed_hash = keccak(&packet[32..]);
 		packet[0..32].clone_from_slice(&signed_hash);
 		self.send_to(packet, address.clone());
+		Some((signed_hash, keccak(payload)))
 	}
 
 	fn nearest_node_entries(target: &NodeId, buckets: &[NodeBucket]) -> Vec<NodeEntry> {
@@ -379,10 +510,15 @@ impl Discovery {
 		let packet_id = signed[0];
 		let rlp = UntrustedRlp::new(&signed[1..]);
 		match packet_id {
-			PACKET_PING => self.on_ping(&rlp, &node_id, &from),
+			PACKET_PING => self.on_ping(&rlp, &node_id, &hash_signed, &from),
 			PACKET_PONG => self.on_pong(&rlp, &node_id, &from),
 			PACKET_FIND_NODE => self.on_find_node(&rlp, &node_id, &from),
 			PACKET_NEIGHBOURS => self.on_neighbours(&rlp, &node_id, &from),
+			PACKET_TOPIC_REGISTER => self.on_topic_register(&rlp, &node_id, &from),
+			PACKET_TOPIC_QUERY => self.on_topic_query(&rlp, &node_id, &from),
+			PACKET_TOPIC_NODES => self.on_topic_nodes(&rlp, &node_id, &from),
+			PACKET_ENR_REQUEST => self.on_enr_request(&rlp, &node_id, &hash_signed, &from),
+			PACKET_ENR_RESPONSE => self.on_enr_response(&rlp, &node_id, &from),
 			_ => {
 				debug!("Unknown UDP packet: {}", packet_id);
 				Ok(None)
@@ -402,43 +538,72 @@ impl Discovery {
 		entry.endpoint.is_allowed(&self.ip_filter) && entry.id != self.id
 	}
 
-	fn on_ping(&mut self, rlp: &UntrustedRlp, node: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
+	fn on_ping(&mut self, rlp: &UntrustedRlp, node: &NodeId, hash: &H256, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
 		trace!(target: "discovery", "Got Ping from {:?}", &from);
 		let source = NodeEndpoint::from_rlp(&rlp.at(1)?)?;
 		let dest = NodeEndpoint::from_rlp(&rlp.at(2)?)?;
 		let timestamp: u64 = rlp.val_at(3)?;
 		self.check_timestamp(timestamp)?;
+		// anything else following the expiration is ignored (EIP-8).
+		let enr_seq: Option<u64> = rlp.val_at(4).ok();
 		let mut added_map = HashMap::new();
 		let entry = NodeEntry { id: node.clone(), endpoint: source.clone() };
-		if !entry.endpoint.is_valid() {
+		let accepted = if !entry.endpoint.is_valid() {
 			debug!(target: "discovery", "Got bad address: {:?}", entry);
+			false
 		} else if !self.is_allowed(&entry) {
 			debug!(target: "discovery", "Address not allowed: {:?}", entry);
+			false
 		} else {
 			self.update_node(entry.clone());
 			added_map.insert(node.clone(), entry);
-		}
-		let hash = keccak(rlp.as_raw());
+			true
+		};
 		let mut response = RlpStream::new_list(2);
 		dest.to_rlp_list(&mut response);
-		response.append(&hash);
-		self.send_packet(PACKET_PONG, from, &response.drain());
+		response.append(hash);
+		let local_seq = self.enr_seq();
+		self.send_packet_with_enr_seq(PACKET_PONG, from, &response.drain(), local_seq);
 
-		Ok(Some(TableUpdates { added: added_map, removed: HashSet::new() }))
+		if accepted {
+			if !self.bonded.contains(node) {
+				self.ping(&source);
+			}
+			// the pong goes out first, so the node has bonded with us by the time the request arrives.
+			if let Some(seq) = enr_seq {
+				self.request_record(node, seq, from);
+			}
+		}
+
+		Ok(Some(TableUpdates { added: added_map, removed: HashSet::new(), records: Vec::new() }))
 	}
 
 	fn on_pong(&mut self, rlp: &UntrustedRlp, node: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
 		trace!(target: "discovery", "Got Pong from {:?}", &from);
-		// TODO: validate pong packet
 		let dest = NodeEndpoint::from_rlp(&rlp.at(0)?)?;
+		let ping_hash: H256 = rlp.val_at(1)?;
 		let timestamp: u64 = rlp.val_at(2)?;
 		self.check_timestamp(timestamp)?;
+		// legacy nodes echo the hash of the ping payload rather than the hash of the packet.
+		let ping = self.in_flight_pings.iter()
+			.find(|&(hash, &(payload_hash, _))| *hash == ping_hash || payload_hash == ping_hash)
+			.map(|(hash, _)| hash.clone());
+		match ping {
+			Some(hash) => {
+				self.in_flight_pings.remove(&hash);
+			},
+			None => {
+				debug!(target: "discovery", "Unexpected Pong from {:?}", &from);
+				return Ok(None);
+			},
+		}
 		let mut entry = NodeEntry { id: node.clone(), endpoint: dest };
 		if !entry.endpoint.is_valid() {
 			debug!(target: "discovery", "Bad address: {:?}", entry);
 			entry.endpoint.address = from.clone();
 		}
 		self.clear_ping(node);
+		self.bonded.insert(node.clone());
 		let mut added_map = HashMap::new();
 		added_map.insert(node.clone(), entry);
 		Ok(None)
@@ -500,7 +665,162 @@ impl Discovery {
 			self.ping(&entry.endpoint);
 			self.update_node(entry);
 		}
-		Ok(Some(TableUpdates { added: added, removed: HashSet::new() }))
+		Ok(Some(TableUpdates { added: added, removed: HashSet::new(), records: Vec::new() }))
+	}
+
+	fn on_topic_register(&mut self, rlp: &UntrustedRlp, node: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
+		trace!(target: "discovery", "Got TopicRegister from {:?}", &from);
+		let mut endpoint = NodeEndpoint::from_rlp(&rlp.at(0)?)?;
+		let topic = Discovery::topic_from_rlp(&rlp.at(1)?)?;
+		let timestamp: u64 = rlp.val_at(2)?;
+		self.check_timestamp(timestamp)?;
+		if !endpoint.is_valid() {
+			debug!(target: "discovery", "Bad address: {:?}", endpoint);
+			endpoint.address = from.clone();
+		}
+		let entry = NodeEntry { id: node.clone(), endpoint: endpoint };
+		if !self.is_allowed(&entry) {
+			debug!(target: "discovery", "Address not allowed: {:?}", entry);
+			return Ok(None);
+		}
+		let expiry = time::get_time().sec as u64 + TOPIC_AD_EXPIRY_SEC;
+		let ads = self.topic_ads.entry(topic).or_insert_with(HashMap::new);
+		if ads.len() >= MAX_TOPIC_ADS && !ads.contains_key(node) {
+			debug!(target: "discovery", "Too many advertisements for topic {:?}", topic);
+			return Ok(None);
+		}
+		ads.insert(node.clone(), (entry, expiry));
+		Ok(None)
+	}
+
+	fn on_topic_query(&mut self, rlp: &UntrustedRlp, node: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
+		trace!(target: "discovery", "Got TopicQuery from {:?}", &from);
+		let topic = Discovery::topic_from_rlp(&rlp.at(0)?)?;
+		let timestamp: u64 = rlp.val_at(1)?;
+		self.check_timestamp(timestamp)?;
+		let mut nodes: Vec<NodeEntry> = self.topic_ads.get(&topic)
+			.map_or_else(Vec::new, |ads| ads.values().map(|&(ref entry, _)| entry.clone()).collect());
+		if self.topics.contains(&topic) {
+			nodes.push(NodeEntry { id: self.id.clone(), endpoint: self.public_endpoint.clone() });
+		}
+		nodes.retain(|n| &n.id != node);
+		if nodes.is_empty() {
+			return Ok(None);
+		}
+		let mut packets = Discovery::prepare_topic_nodes_packets(&topic, &nodes);
+		for p in packets.drain(..) {
+			self.send_packet(PACKET_TOPIC_NODES, from, &p);
+		}
+		trace!(target: "discovery", "Sent {} TopicNodes to {:?}", nodes.len(), &from);
+		Ok(None)
+	}
+
+	fn prepare_topic_nodes_packets(topic: &ProtocolId, nodes: &[NodeEntry]) -> Vec<Bytes> {
+		let limit = (MAX_DATAGRAM_SIZE - 109 - 4) / 90;
+		let chunks = nodes.chunks(limit);
+		let packets = chunks.map(|c| {
+			let mut rlp = RlpStream::new_list(2);
+			rlp.append(&&topic[..]);
+			rlp.begin_list(c.len());
+			for n in 0 .. c.len() {
+				rlp.begin_list(4);
+				c[n].endpoint.to_rlp(&mut rlp);
+				rlp.append(&c[n].id);
+			}
+			rlp.out()
+		});
+		packets.collect()
+	}
+
+	fn on_topic_nodes(&mut self, rlp: &UntrustedRlp, node: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
+		let topic = Discovery::topic_from_rlp(&rlp.at(0)?)?;
+		if !self.topic_queried.get(&topic).map_or(false, |queried| queried.contains(node)) {
+			debug!(target: "discovery", "Unexpected TopicNodes for {:?} from {:?}", topic, &from);
+			return Ok(None);
+		}
+		let mut added = HashMap::new();
+		trace!(target: "discovery", "Got {} TopicNodes from {:?}", rlp.at(1)?.item_count()?, &from);
+		for r in rlp.at(1)?.iter() {
+			let endpoint = NodeEndpoint::from_rlp(&r)?;
+			if !endpoint.is_valid() {
+				debug!(target: "discovery", "Bad address: {:?}", endpoint);
+				continue;
+			}
+			let node_id: NodeId = r.val_at(3)?;
+			let entry = NodeEntry { id: node_id.clone(), endpoint: endpoint };
+			if !self.is_allowed(&entry) {
+				debug!(target: "discovery", "Address not allowed: {:?}", entry);
+				continue;
+			}
+			{
+				let nodes = self.topic_nodes.entry(topic).or_insert_with(HashMap::new);
+				if nodes.len() >= MAX_TOPIC_NODES && !nodes.contains_key(&node_id) {
+					debug!(target: "discovery", "Too many nodes for topic {:?}", topic);
+					break;
+				}
+				nodes.insert(node_id.clone(), entry.clone());
+			}
+			added.insert(node_id, entry.clone());
+			self.ping(&entry.endpoint);
+			self.update_node(entry);
+		}
+		Ok(Some(TableUpdates { added: added, removed: HashSet::new(), records: Vec::new() }))
+	}
+
+	fn on_enr_request(&mut self, rlp: &UntrustedRlp, node: &NodeId, hash: &H256, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
+		trace!(target: "discovery", "Got ENRRequest from {:?}", &from);
+		let timestamp: u64 = rlp.val_at(0)?;
+		self.check_timestamp(timestamp)?;
+		if !self.bonded.contains(node) {
+			debug!(target: "discovery", "ENRRequest from unbonded node {:?}", &from);
+			return Ok(None);
+		}
+		let payload = match self.record {
+			Some(ref record) => {
+				let mut response = RlpStream::new_list(2);
+				response.append(hash);
+				response.append_raw(record.as_raw(), 1);
+				response.drain()
+			},
+			None => return Ok(None),
+		};
+		// the response carries no expiration (EIP-868).
+		self.send_signed_packet(PACKET_ENR_RESPONSE, from, &payload);
+		Ok(None)
+	}
+
+	fn on_enr_response(&mut self, rlp: &UntrustedRlp, node: &NodeId, from: &SocketAddr) -> Result<Option<TableUpdates>, Error> {
+		trace!(target: "discovery", "Got ENRResponse from {:?}", &from);
+		let hash: H256 = rlp.val_at(0)?;
+		if self.enr_requests.get(node) != Some(&hash) {
+			debug!(target: "discovery", "Unexpected ENRResponse from {:?}", &from);
+			return Ok(None);
+		}
+		let record = NodeRecord::from_rlp(rlp.at(1)?.as_raw())?;
+		if record.id() != node {
+			debug!(target: "discovery", "Node record from {:?} has a different id", &from);
+			return Err(ErrorKind::InvalidNodeRecord.into());
+		}
+		self.enr_seqs.insert(node.clone(), record.seq());
+		Ok(Some(TableUpdates { added: HashMap::new(), removed: HashSet::new(), records: vec![record] }))
+	}
+
+	fn topic_from_rlp(rlp: &UntrustedRlp) -> Result<ProtocolId, Error> {
+		let data = rlp.data()?;
+		if data.len() != 3 {
+			return Err(ErrorKind::BadProtocol.into());
+		}
+		let mut topic = ProtocolId::default();
+		topic.copy_from_slice(data);
+		Ok(topic)
+	}
+
+	fn expire_topic_ads(&mut self) {
+		let now = time::get_time().sec as u64;
+		for ads in self.topic_ads.values_mut() {
+			ads.retain(|_, &mut (_, expiry)| expiry > now);
+		}
+		self.topic_ads.retain(|_, ads| !ads.is_empty());
 	}
 
 	fn check_expired(&mut self, force: bool) -> HashSet<NodeId> {
@@ -525,14 +845,32 @@ impl Discovery {
 
 	pub fn round(&mut self) -> Option<TableUpdates> {
 		let removed = self.check_expired(false);
+		for nodes in self.topic_nodes.values_mut() {
+			nodes.retain(|id, _| !removed.contains(id));
+		}
+		self.bonded.retain(|id| !removed.contains(id));
+		self.enr_seqs.retain(|id, _| !removed.contains(id));
+		let now = time::precise_time_ns();
+		self.in_flight_pings.retain(|_, &mut (_, sent)| now - sent < PING_TIMEOUT_MS * 1000_0000);
+		self.expire_topic_ads();
 		self.discover();
 		if !removed.is_empty() {
-			Some(TableUpdates { added: HashMap::new(), removed: removed })
+			Some(TableUpdates { added: HashMap::new(), removed: removed, records: Vec::new() })
 		} else { None }
 	}
 
 	pub fn refresh(&mut self) {
 		self.start();
+		self.enr_requests.clear();
+		self.topic_queried.clear();
+		let topics: Vec<_> = self.topics.iter().cloned().collect();
+		for topic in topics {
+			self.send_topic_registration(&topic);
+		}
+		let queries: Vec<_> = self.topic_queries.iter().cloned().collect();
+		for topic in queries {
+			self.send_topic_query(&topic);
+		}
 	}
 
 	pub fn register_socket<Host:Handler>(&self, event_loop: &mut EventLoop<Host>) -> Result<(), Error> {
@@ -613,6 +951,221 @@ mod tests {
 		assert_eq!(Discovery::nearest_node_entries(&NodeId::new(), &discovery2.node_buckets).len(), 3)
 	}
 
+	fn exchange_udp_packets(nodes: &mut [Discovery]) {
+		for _ in 0 .. 10 {
+			for d in nodes.iter_mut() {
+				while let Some(datagramm) = d.send_queue.pop_front() {
+					d.udp_socket.send_to(&datagramm.payload, &datagramm.address).unwrap();
+				}
+			}
+			::std::thread::sleep(::std::time::Duration::from_millis(10));
+			for d in nodes.iter_mut() {
+				let mut buf = [0u8; MAX_DATAGRAM_SIZE];
+				while let Ok(Some((len, from))) = d.udp_socket.recv_from(&mut buf) {
+					d.on_packet(&buf[0..len], from).ok();
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn topics() {
+		let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+		let endpoints: Vec<_> = (0..4u16).map(|i| NodeEndpoint {
+			address: SocketAddr::from_str(&format!("127.0.0.1:{}", 40450 + i)).unwrap(),
+			udp_port: 40450 + i,
+		}).collect();
+		let mut nodes: Vec<_> = keys.iter().zip(endpoints.iter())
+			.map(|(key, ep)| Discovery::new(key, ep.address.clone(), ep.clone(), 0, IpFilter::default()))
+			.collect();
+
+		// bootstrap everyone from the first node.
+		for i in 1..4 {
+			nodes[i].add_node(NodeEntry { id: keys[0].public().clone(), endpoint: endpoints[0].clone() });
+		}
+		for _ in 0..5 {
+			for d in nodes.iter_mut() {
+				d.refresh();
+				d.round();
+			}
+			exchange_udp_packets(&mut nodes);
+		}
+		for d in &nodes {
+			assert_eq!(Discovery::nearest_node_entries(&NodeId::new(), &d.node_buckets).len(), 3);
+		}
+
+		nodes[1].register_topic(*b"tst");
+		nodes[2].register_topic(*b"tst");
+		nodes[3].register_topic(*b"foo");
+		exchange_udp_packets(&mut nodes);
+
+		nodes[3].query_topic(*b"tst");
+		nodes[3].query_topic(*b"bar");
+		nodes[1].query_topic(*b"foo");
+		exchange_udp_packets(&mut nodes);
+
+		let found: HashSet<_> = nodes[3].topic_nodes(b"tst").into_iter().map(|n| n.id).collect();
+		let expected: HashSet<_> = vec![keys[1].public().clone(), keys[2].public().clone()].into_iter().collect();
+		assert_eq!(found, expected);
+		assert!(nodes[3].topic_nodes(b"bar").is_empty());
+
+		let found: Vec<_> = nodes[1].topic_nodes(b"foo").into_iter().map(|n| n.id).collect();
+		assert_eq!(found, vec![keys[3].public().clone()]);
+
+		// topics nobody asked for are not collected.
+		assert!(nodes[0].topic_nodes(b"tst").is_empty());
+	}
+
+	#[test]
+	fn topic_nodes_only_from_queried_nodes() {
+		let key1 = Random.generate().unwrap();
+		let key2 = Random.generate().unwrap();
+		let ep1 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40454").unwrap(), udp_port: 40454 };
+		let ep2 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40455").unwrap(), udp_port: 40455 };
+		let mut discovery1 = Discovery::new(&key1, ep1.address.clone(), ep1.clone(), 0, IpFilter::default());
+		let mut discovery2 = Discovery::new(&key2, ep2.address.clone(), ep2.clone(), 0, IpFilter::default());
+
+		let advertised: Vec<_> = (0..(MAX_TOPIC_NODES as u16 + 10)).map(|i| NodeEntry {
+			id: Random.generate().unwrap().public().clone(),
+			endpoint: NodeEndpoint { address: SocketAddr::from_str(&format!("127.0.0.1:{}", 41000 + i)).unwrap(), udp_port: 41000 + i },
+		}).collect();
+		let send_topic_nodes = |from: &mut Discovery, to: &mut Discovery| {
+			for packet in Discovery::prepare_topic_nodes_packets(b"tst", &advertised) {
+				from.send_packet(PACKET_TOPIC_NODES, &ep1.address, &packet);
+			}
+			while let Some(datagramm) = from.send_queue.pop_front() {
+				to.on_packet(&datagramm.payload, ep2.address.clone()).unwrap();
+			}
+		};
+
+		// the topic is queried, but not at discovery2.
+		discovery1.query_topic(*b"tst");
+		send_topic_nodes(&mut discovery2, &mut discovery1);
+		assert!(discovery1.topic_nodes(b"tst").is_empty());
+
+		discovery1.add_node(NodeEntry { id: key2.public().clone(), endpoint: ep2.clone() });
+		discovery1.query_topic(*b"tst");
+		send_topic_nodes(&mut discovery2, &mut discovery1);
+		assert_eq!(discovery1.topic_nodes(b"tst").len(), MAX_TOPIC_NODES);
+	}
+
+	#[test]
+	fn node_records() {
+		let key1 = Random.generate().unwrap();
+		let key2 = Random.generate().unwrap();
+		let ep1 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40460").unwrap(), udp_port: 40460 };
+		let ep2 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40461").unwrap(), udp_port: 40461 };
+		let mut discovery1 = Discovery::new(&key1, ep1.address.clone(), ep1.clone(), 0, IpFilter::default());
+		let mut discovery2 = Discovery::new(&key2, ep2.address.clone(), ep2.clone(), 0, IpFilter::default());
+		let record = NodeRecord::new(&key1, 1, NodeRecord::endpoint_pairs(&ep1)).unwrap();
+		discovery1.set_record(record.clone());
+
+		let exchange = |discovery1: &mut Discovery, discovery2: &mut Discovery| {
+			let mut records = Vec::new();
+			for _ in 0 .. 4 {
+				while let Some(datagramm) = discovery2.send_queue.pop_front() {
+					discovery1.on_packet(&datagramm.payload, ep2.address.clone()).unwrap();
+				}
+				while let Some(datagramm) = discovery1.send_queue.pop_front() {
+					if let Some(updates) = discovery2.on_packet(&datagramm.payload, ep1.address.clone()).unwrap() {
+						records.extend(updates.records);
+					}
+				}
+			}
+			records
+		};
+
+		// records are only served once the requesting node answered a ping.
+		discovery2.request_record(key1.public(), 1, &ep1.address);
+		assert!(exchange(&mut discovery1, &mut discovery2).is_empty());
+		discovery2.refresh();
+
+		discovery2.add_node(NodeEntry { id: key1.public().clone(), endpoint: ep1.clone() });
+		assert_eq!(exchange(&mut discovery1, &mut discovery2), vec![record]);
+
+		// an unchanged sequence number doesn't trigger another request.
+		discovery2.refresh();
+		discovery1.ping(&ep2);
+		assert!(exchange(&mut discovery1, &mut discovery2).is_empty());
+
+		let record = NodeRecord::new(&key1, 2, NodeRecord::endpoint_pairs(&ep1)).unwrap();
+		discovery1.set_record(record.clone());
+		discovery1.ping(&ep2);
+		assert_eq!(exchange(&mut discovery1, &mut discovery2), vec![record]);
+	}
+
+	// Encode a packet the way geth does: hash || signature || packet-type || packet-data.
+	fn geth_packet(key: &KeyPair, packet_id: u8, data: &[u8]) -> Bytes {
+		let mut signed = vec![packet_id];
+		signed.extend_from_slice(data);
+		let signature = sign(key.secret(), &keccak(&signed)).unwrap();
+		let mut packet = signature.to_vec();
+		packet.extend(signed);
+		let mut hashed = keccak(&packet).to_vec();
+		hashed.extend(packet);
+		hashed
+	}
+
+	#[test]
+	fn enr_packets_match_eip868() {
+		let key1 = Random.generate().unwrap();
+		let key2 = Random.generate().unwrap();
+		let ep1 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40464").unwrap(), udp_port: 40464 };
+		let ep2 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40465").unwrap(), udp_port: 40465 };
+		let mut discovery1 = Discovery::new(&key1, ep1.address.clone(), ep1.clone(), 0, IpFilter::default());
+		let mut discovery2 = Discovery::new(&key2, ep2.address.clone(), ep2.clone(), 0, IpFilter::default());
+		let record = NodeRecord::new(&key1, 1, NodeRecord::endpoint_pairs(&ep1)).unwrap();
+		discovery1.set_record(record.clone());
+		discovery1.bonded.insert(key2.public().clone());
+
+		// ENRRequest 0x05: [expiration], answered with ENRResponse 0x06: [request-hash, ENR].
+		let mut request = RlpStream::new_list(1);
+		request.append(&(time::get_time().sec as u64 + 60));
+		let request = geth_packet(&key2, 0x05, &request.drain());
+		discovery1.on_packet(&request, ep2.address.clone()).unwrap();
+		let response = discovery1.send_queue.pop_front().unwrap().payload;
+		assert_eq!(response[32 + 65], 0x06);
+		let data = UntrustedRlp::new(&response[(32 + 65 + 1)..]);
+		assert_eq!(data.item_count().unwrap(), 2);
+		assert_eq!(data.val_at::<H256>(0).unwrap(), H256::from_slice(&request[0..32]));
+		assert_eq!(data.at(1).unwrap().as_raw(), record.as_raw());
+
+		discovery2.request_record(key1.public(), 1, &ep1.address);
+		let request = discovery2.send_queue.pop_front().unwrap().payload;
+		assert_eq!(request[32 + 65], 0x05);
+		assert_eq!(UntrustedRlp::new(&request[(32 + 65 + 1)..]).item_count().unwrap(), 1);
+		let mut response = RlpStream::new_list(2);
+		response.append(&H256::from_slice(&request[0..32]));
+		response.append_raw(record.as_raw(), 1);
+		let response = geth_packet(&key1, 0x06, &response.drain());
+		let updates = discovery2.on_packet(&response, ep1.address.clone()).unwrap().unwrap();
+		assert_eq!(updates.records, vec![record]);
+	}
+
+	#[test]
+	fn accepts_pong_echoing_ping_payload_hash() {
+		let key1 = Random.generate().unwrap();
+		let key2 = Random.generate().unwrap();
+		let ep1 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40462").unwrap(), udp_port: 40462 };
+		let ep2 = NodeEndpoint { address: SocketAddr::from_str("127.0.0.1:40463").unwrap(), udp_port: 40463 };
+		let mut discovery1 = Discovery::new(&key1, ep1.address.clone(), ep1.clone(), 0, IpFilter::default());
+		let mut discovery2 = Discovery::new(&key2, ep2.address.clone(), ep2.clone(), 0, IpFilter::default());
+
+		discovery1.ping(&ep2);
+		let ping = discovery1.send_queue.pop_front().unwrap();
+		let payload_hash = keccak(&ping.payload[(32 + 65 + 1)..]);
+
+		let mut response = RlpStream::new_list(2);
+		ep2.to_rlp_list(&mut response);
+		response.append(&payload_hash);
+		discovery2.send_packet(PACKET_PONG, &ep1.address, &response.drain());
+		let pong = discovery2.send_queue.pop_front().unwrap();
+		discovery1.on_packet(&pong.payload, ep2.address.clone()).unwrap();
+
+		assert!(discovery1.bonded.contains(key2.public()));
+		assert!(discovery1.in_flight_pings.is_empty());
+	}
+
 	#[test]
 	fn removes_expired() {
 		let key = Random.generate().unwrap();
@@ -131,11 +131,23 @@ error_chain! {
 			display("Invalid node id"),
 		}
 
+		#[doc = "Invalid node record"]
+		InvalidNodeRecord {
+			description("Invalid node record"),
+			display("Invalid node record"),
+		}
+
 		#[doc = "Packet size is over the protocol limit"]
 		OversizedPacket {
 			description("Packet is too large"),
 			display("Packet is too large"),
 		}
+
+		#[doc = "Packet was dropped because too many packets are delayed by upload rate limits"]
+		RateLimited {
+			description("Upload rate limit exceeded"),
+			display("Upload rate limit exceeded"),
+		}
 	}
 }
 
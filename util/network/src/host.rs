@@ -20,7 +20,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::ops::*;
-use std::cmp::min;
+use std::cmp::{min, max};
 use std::path::{Path, PathBuf};
 use std::io::{Read, Write, self};
 use std::fs;
@@ -35,7 +35,10 @@ use session::{Session, SessionInfo, SessionData};
 use io::*;
 use {NetworkProtocolHandler, NonReservedPeerMode, PROTOCOL_VERSION, IpFilter};
 use node_table::*;
+use node_record::{self, NodeRecord};
+use reputation::PeerBehaviour;
 use stats::NetworkStats;
+use bandwidth::UploadLimits;
 use discovery::{Discovery, TableUpdates, NodeEntry};
 use ip_utils::{map_external_address, select_public_address};
 use path::restrict_permissions_owner;
@@ -57,6 +60,7 @@ const DISCOVERY: StreamToken = SYS_TIMER + 3;
 const DISCOVERY_REFRESH: TimerToken = SYS_TIMER + 4;
 const DISCOVERY_ROUND: TimerToken = SYS_TIMER + 5;
 const NODE_TABLE: TimerToken = SYS_TIMER + 6;
+const THROTTLE: TimerToken = SYS_TIMER + 7;
 const FIRST_SESSION: StreamToken = 0;
 const LAST_SESSION: StreamToken = FIRST_SESSION + MAX_SESSIONS - 1;
 const USER_TIMER: TimerToken = LAST_SESSION + 256;
@@ -71,6 +75,8 @@ const DISCOVERY_REFRESH_TIMEOUT: u64 = 60_000;
 const DISCOVERY_ROUND_TIMEOUT: u64 = 300;
 // for NODE_TABLE TimerToken
 const NODE_TABLE_TIMEOUT: u64 = 300_000;
+// for flushing packets delayed by upload limits
+const THROTTLE_TIMEOUT: u64 = 100;
 
 #[derive(Debug, PartialEq, Clone)]
 /// Network service configuration
@@ -109,6 +115,12 @@ pub struct NetworkConfiguration {
 	pub ip_filter: IpFilter,
 	/// Client identifier
 	pub client_version: String,
+	/// Chain id advertised in the local node record
+	pub chain_id: Option<u64>,
+	/// Maximum upload rate for all subprotocol traffic in bytes per second
+	pub max_upload_rate: Option<u64>,
+	/// Maximum upload rates for individual subprotocol packets in bytes per second
+	pub packet_upload_rates: HashMap<(ProtocolId, PacketId), u64>,
 }
 
 impl Default for NetworkConfiguration {
@@ -138,6 +150,9 @@ impl NetworkConfiguration {
 			reserved_nodes: Vec::new(),
 			non_reserved_mode: NonReservedPeerMode::Accept,
 			client_version: "Parity-network".into(),
+			chain_id: None,
+			max_upload_rate: None,
+			packet_upload_rates: HashMap::new(),
 		}
 	}
 
@@ -191,8 +206,12 @@ pub enum NetworkIoMessage {
 	Disconnect(PeerId),
 	/// Disconnect and temporary disable peer.
 	DisablePeer(PeerId),
+	/// Update peer reputation. Peers that end up banned are disconnected.
+	ReportPeer(PeerId, PeerBehaviour),
 	/// Network has been started with the host as the given enode.
 	NetworkStarted(String),
+	/// Look for nodes advertising the given protocol through discovery.
+	QueryTopic(ProtocolId),
 }
 
 /// Local (temporary) peer session ID.
@@ -288,11 +307,28 @@ impl<'s> NetworkContext<'s> {
 			.unwrap_or_else(|e| warn!("Error sending network IO message: {:?}", e));
 	}
 
+	/// Report peer behaviour. The peer is disconnected if its reputation drops too low.
+	pub fn report_peer(&self, peer: PeerId, behaviour: PeerBehaviour) {
+		self.io.message(NetworkIoMessage::ReportPeer(peer, behaviour))
+			.unwrap_or_else(|e| warn!("Error sending network IO message: {:?}", e));
+	}
+
 	/// Check if the session is still active.
 	pub fn is_expired(&self) -> bool {
 		self.session.as_ref().map_or(false, |s| s.lock().expired())
 	}
 
+	/// Check if packets to the peer are delayed by upload limits.
+	pub fn is_throttled(&self, peer: PeerId) -> bool {
+		self.resolve_session(peer).map_or(false, |s| s.lock().is_throttled())
+	}
+
+	/// Ask discovery for nodes advertising the given protocol. Nodes found are preferred when connecting.
+	pub fn query_topic(&self, topic: ProtocolId) {
+		self.io.message(NetworkIoMessage::QueryTopic(topic))
+			.unwrap_or_else(|e| warn!("Error sending network IO message: {:?}", e));
+	}
+
 	/// Register a new IO timer. 'IoHandler::timeout' will be called with the token.
 	pub fn register_timer(&self, token: TimerToken, ms: u64) -> Result<(), Error> {
 		self.io.message(NetworkIoMessage::AddTimer {
@@ -339,6 +375,8 @@ pub struct HostInfo {
 	pub local_endpoint: NodeEndpoint,
 	/// Public address + discovery port
 	pub public_endpoint: Option<NodeEndpoint>,
+	/// Signed local node record
+	pub record: Option<NodeRecord>,
 }
 
 impl HostInfo {
@@ -382,7 +420,9 @@ pub struct Host {
 	timers: RwLock<HashMap<TimerToken, ProtocolTimer>>,
 	timer_counter: RwLock<usize>,
 	stats: Arc<NetworkStats>,
+	limits: Arc<UploadLimits>,
 	reserved_nodes: RwLock<HashSet<NodeId>>,
+	topic_queries: RwLock<HashSet<ProtocolId>>,
 	num_sessions: AtomicUsize,
 	stopping: AtomicBool,
 	filter: Option<Arc<ConnectionFilter>>,
@@ -420,6 +460,7 @@ impl Host {
 		let boot_nodes = config.boot_nodes.clone();
 		let reserved_nodes = config.reserved_nodes.clone();
 		config.max_handshakes = min(config.max_handshakes, MAX_HANDSHAKES as u32);
+		let limits = Arc::new(UploadLimits::new(config.max_upload_rate, &config.packet_upload_rates));
 
 		let mut host = Host {
 			info: RwLock::new(HostInfo {
@@ -429,6 +470,7 @@ impl Host {
 				protocol_version: PROTOCOL_VERSION,
 				capabilities: Vec::new(),
 				public_endpoint: None,
+				record: None,
 				local_endpoint: local_endpoint,
 			}),
 			discovery: Mutex::new(None),
@@ -439,7 +481,9 @@ impl Host {
 			timers: RwLock::new(HashMap::new()),
 			timer_counter: RwLock::new(USER_TIMER),
 			stats: stats,
+			limits: limits,
 			reserved_nodes: RwLock::new(HashSet::new()),
+			topic_queries: RwLock::new(HashSet::new()),
 			num_sessions: AtomicUsize::new(0),
 			stopping: AtomicBool::new(false),
 			filter: filter,
@@ -527,6 +571,17 @@ impl Host {
 		info.public_endpoint.as_ref().map(|e| format!("{}", Node::new(info.id().clone(), e.clone())))
 	}
 
+	pub fn external_record(&self) -> Option<String> {
+		self.info.read().record.as_ref().map(|r| r.to_string())
+	}
+
+	/// Reputation score of a connected peer.
+	pub fn peer_reputation(&self, peer: PeerId) -> Option<i32> {
+		let session = self.sessions.read().get(peer).cloned();
+		let id = session.and_then(|s| s.lock().id().cloned());
+		id.and_then(|id| self.nodes.read().reputation(&id))
+	}
+
 	pub fn local_url(&self) -> String {
 		let info = self.info.read();
 		format!("{}", Node::new(info.id().clone(), info.local_endpoint.clone()))
@@ -562,6 +617,34 @@ impl Host {
 		peers
 	}
 
+	/// Sign a new local node record from the public endpoint and registered capabilities.
+	fn update_record(&self) -> Option<NodeRecord> {
+		let mut info = self.info.write();
+		let mut pairs = match info.public_endpoint {
+			Some(ref endpoint) => NodeRecord::endpoint_pairs(endpoint),
+			None => return None,
+		};
+		let capabilities: Vec<_> = info.capabilities.iter().map(|c| (c.protocol, c.version)).collect();
+		if !capabilities.is_empty() {
+			pairs.insert(node_record::KEY_CAPABILITIES.into(), NodeRecord::capabilities_value(&capabilities));
+		}
+		if let Some(chain_id) = info.config.chain_id {
+			pairs.insert(node_record::KEY_CHAIN_ID.into(), encode(&chain_id).into_vec());
+		}
+		// Sequence numbers must increase across restarts, so base them on the clock.
+		let seq = max(info.record.as_ref().map_or(0, |r| r.seq() + 1), ::time::get_time().sec as u64);
+		match NodeRecord::new(&info.keys, seq, pairs) {
+			Ok(record) => {
+				info.record = Some(record.clone());
+				Some(record)
+			},
+			Err(e) => {
+				warn!(target: "network", "Error creating node record: {:?}", e);
+				None
+			}
+		}
+	}
+
 	fn init_public_interface(&self, io: &IoContext<NetworkIoMessage>) -> Result<(), Error> {
 		if self.info.read().public_endpoint.is_some() {
 			return Ok(());
@@ -589,6 +672,7 @@ impl Host {
 		};
 
 		self.info.write().public_endpoint = Some(public_endpoint.clone());
+		let record = self.update_record();
 
 		if let Some(url) = self.external_url() {
 			io.message(NetworkIoMessage::NetworkStarted(url)).unwrap_or_else(|e| warn!("Error sending IO notification: {:?}", e));
@@ -607,12 +691,24 @@ impl Host {
 		if let Some(mut discovery) = discovery {
 			discovery.init_node_list(self.nodes.read().unordered_entries());
 			discovery.add_node_list(self.nodes.read().unordered_entries());
+			for capability in &self.info.read().capabilities {
+				discovery.register_topic(capability.protocol);
+			}
+			if let Some(record) = record {
+				discovery.set_record(record);
+			}
+			for topic in self.topic_queries.read().iter() {
+				discovery.query_topic(*topic);
+			}
 			*self.discovery.lock() = Some(discovery);
 			io.register_stream(DISCOVERY)?;
 			io.register_timer(DISCOVERY_REFRESH, DISCOVERY_REFRESH_TIMEOUT)?;
 			io.register_timer(DISCOVERY_ROUND, DISCOVERY_ROUND_TIMEOUT)?;
 		}
 		io.register_timer(NODE_TABLE, NODE_TABLE_TIMEOUT)?;
+		if self.limits.is_enabled() {
+			io.register_timer(THROTTLE, THROTTLE_TIMEOUT)?;
+		}
 		io.register_stream(TCP_ACCEPT)?;
 		Ok(())
 	}
@@ -639,6 +735,20 @@ impl Host {
 		self.sessions.read().count().saturating_sub(self.session_count())
 	}
 
+	fn send_throttled(&self, io: &IoContext<NetworkIoMessage>) {
+		let mut to_kill = Vec::new();
+		for e in self.sessions.read().iter() {
+			let mut s = e.lock();
+			if let Err(err) = s.send_throttled(io) {
+				debug!(target: "network", "Error sending delayed packets to {}: {:?}", s.token(), err);
+				to_kill.push(s.token());
+			}
+		}
+		for p in to_kill {
+			self.kill_connection(p, io, true);
+		}
+	}
+
 	fn keep_alive(&self, io: &IoContext<NetworkIoMessage>) {
 		let mut to_kill = Vec::new();
 		for e in self.sessions.write().iter_mut() {
@@ -665,6 +775,14 @@ impl Host {
 			(config.min_peers, config.non_reserved_mode == NonReservedPeerMode::Deny, config.max_handshakes as usize, config.ip_filter.clone(), info.id().clone())
 		};
 
+		let topic_nodes: HashSet<NodeId> = match *self.discovery.lock() {
+			Some(ref discovery) if !pin => self.topic_queries.read().iter()
+				.flat_map(|topic| discovery.topic_nodes(topic))
+				.map(|entry| entry.id)
+				.collect(),
+			_ => HashSet::new(),
+		};
+
 		let session_count = self.session_count();
 		let reserved_nodes = self.reserved_nodes.read();
 		if session_count >= min_peers as usize + reserved_nodes.len() {
@@ -686,7 +804,12 @@ impl Host {
 		// iterate over all nodes, reserved ones coming first.
 		// if we are pinned to only reserved nodes, ignore all others.
 		let nodes = reserved_nodes.iter().cloned().chain(if !pin {
-			self.nodes.read().nodes(allow_ips)
+			// nodes advertising the protocols we are looking for come right after reserved ones,
+			// as long as the node table allows connecting to them.
+			let (mut nodes, others): (Vec<_>, Vec<_>) = self.nodes.read().nodes(allow_ips).into_iter()
+				.partition(|id| topic_nodes.contains(id));
+			nodes.extend(others);
+			nodes
 		} else {
 			Vec::new()
 		});
@@ -719,7 +842,6 @@ impl Host {
 			let address = {
 				let mut nodes = self.nodes.write();
 				if let Some(node) = nodes.get_mut(id) {
-					node.attempts += 1;
 					node.endpoint.address
 				}
 				else {
@@ -749,7 +871,7 @@ impl Host {
 		let mut sessions = self.sessions.write();
 
 		let token = sessions.insert_with_opt(|token| {
-			match Session::new(io, socket, token, id, &nonce, self.stats.clone(), &self.info.read()) {
+			match Session::new(io, socket, token, id, &nonce, self.stats.clone(), self.limits.clone(), &self.info.read()) {
 				Ok(s) => Some(Arc::new(Mutex::new(s))),
 				Err(e) => {
 					debug!(target: "network", "Session create error: {:?}", e);
@@ -821,7 +943,7 @@ impl Host {
 							if let ErrorKind::Disconnect(DisconnectReason::IncompatibleProtocol) = *e.kind() {
 								if let Some(id) = s.id() {
 									if !self.reserved_nodes.read().contains(id) {
-										self.nodes.write().mark_as_useless(id);
+										self.nodes.write().report(id, PeerBehaviour::Incompatible);
 									}
 								}
 							}
@@ -864,6 +986,13 @@ impl Host {
 								break;
 							}
 
+							if self.nodes.read().is_banned(&id) && !self.reserved_nodes.read().contains(&id) {
+								trace!(target: "network", "Connection from banned node {:?}", id);
+								s.disconnect(io, DisconnectReason::UselessPeer);
+								kill = true;
+								break;
+							}
+
 							ready_id = Some(id);
 
 							// Add it to the node table
@@ -882,6 +1011,7 @@ impl Host {
 									}
 								}
 							}
+							self.nodes.write().report(&id, PeerBehaviour::Connected);
 							for (p, _) in self.handlers.read().iter() {
 								if s.have_capability(*p) {
 									ready_data.push(*p);
@@ -973,7 +1103,7 @@ impl Host {
 		}
 		if let Some(id) = failure_id {
 			if remote {
-				self.nodes.write().note_failure(&id);
+				self.nodes.write().report(&id, PeerBehaviour::ConnectionFailure);
 			}
 		}
 		for p in to_disconnect {
@@ -1087,9 +1217,10 @@ impl IoHandler<NetworkIoMessage> for Host {
 			},
 			NODE_TABLE => {
 				trace!(target: "network", "Refreshing node table");
-				self.nodes.write().clear_useless();
+				self.nodes.write().decay_reputation();
 				self.nodes.write().save();
 			},
+			THROTTLE => self.send_throttled(io),
 			_ => match self.timers.read().get(&token).cloned() {
 				Some(timer) => match self.handlers.read().get(&timer.protocol).cloned() {
 					None => { warn!(target: "network", "No handler found for protocol: {:?}", timer.protocol) },
@@ -1121,9 +1252,21 @@ impl IoHandler<NetworkIoMessage> for Host {
 					&*self.info.read(),
 				);
 				self.handlers.write().insert(*protocol, h);
-				let mut info = self.info.write();
-				for v in versions {
-					info.capabilities.push(CapabilityInfo { protocol: *protocol, version: *v, packet_count: *packet_count });
+				{
+					let mut info = self.info.write();
+					for v in versions {
+						info.capabilities.push(CapabilityInfo { protocol: *protocol, version: *v, packet_count: *packet_count });
+					}
+				}
+				let record = self.update_record();
+				let has_discovery = self.discovery.lock().as_mut().map(|d| {
+					if let Some(record) = record {
+						d.set_record(record);
+					}
+					d.register_topic(*protocol);
+				}).is_some();
+				if has_discovery {
+					io.update_registration(DISCOVERY).unwrap_or_else(|e| debug!("Error updating discovery registration: {:?}", e));
 				}
 			},
 			NetworkIoMessage::AddTimer {
@@ -1154,12 +1297,33 @@ impl IoHandler<NetworkIoMessage> for Host {
 				if let Some(session) = session {
 					session.lock().disconnect(io, DisconnectReason::DisconnectRequested);
 					if let Some(id) = session.lock().id() {
-						self.nodes.write().mark_as_useless(id)
+						self.nodes.write().ban(id)
 					}
 				}
 				trace!(target: "network", "Disabling peer {}", peer);
 				self.kill_connection(*peer, io, false);
 			},
+			NetworkIoMessage::ReportPeer(ref peer, ref behaviour) => {
+				let session = { self.sessions.read().get(*peer).cloned() };
+				if let Some(session) = session {
+					let id = session.lock().id().cloned();
+					if let Some(id) = id {
+						trace!(target: "network", "Peer {} reported: {:?}", peer, behaviour);
+						let banned = self.nodes.write().report(&id, *behaviour);
+						if banned && !self.reserved_nodes.read().contains(&id) {
+							trace!(target: "network", "Banning peer {}", peer);
+							session.lock().disconnect(io, DisconnectReason::UselessPeer);
+							self.kill_connection(*peer, io, false);
+						}
+					}
+				}
+			},
+			NetworkIoMessage::QueryTopic(ref topic) => {
+				self.topic_queries.write().insert(*topic);
+				if self.discovery.lock().as_mut().map(|d| d.query_topic(*topic)).is_some() {
+					io.update_registration(DISCOVERY).unwrap_or_else(|e| debug!("Error updating discovery registration: {:?}", e));
+				}
+			},
 			NetworkIoMessage::InitPublicInterface =>
 				self.init_public_interface(io).unwrap_or_else(|e| warn!("Error initializing public interface: {:?}", e)),
 			_ => {}	// ignore others.
@@ -102,19 +102,24 @@ mod discovery;
 mod service;
 mod error;
 mod node_table;
+mod node_record;
+mod reputation;
 mod stats;
+mod bandwidth;
 mod ip_utils;
 mod connection_filter;
 
 pub use host::{HostInfo, PeerId, PacketId, ProtocolId, NetworkContext, NetworkIoMessage, NetworkConfiguration};
 pub use service::NetworkService;
 pub use error::{Error, ErrorKind};
-pub use stats::NetworkStats;
+pub use stats::{NetworkStats, TrafficStats};
 pub use session::SessionInfo;
 pub use connection_filter::{ConnectionFilter, ConnectionDirection};
 
 pub use io::TimerToken;
 pub use node_table::{validate_node_url, NodeId};
+pub use node_record::NodeRecord;
+pub use reputation::PeerBehaviour;
 use ipnetwork::{IpNetwork, IpNetworkError};
 use std::str::FromStr;
 
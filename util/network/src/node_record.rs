@@ -0,0 +1,379 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ethereum Node Records (EIP-778).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use ethcore_bytes::Bytes;
+use ethereum_types::H256;
+use ethkey::{KeyPair, Signature, sign, recover};
+use ethkey::math::{public_to_compressed, public_from_compressed};
+use hash::keccak;
+use rlp::{self, RlpStream, UntrustedRlp, Decodable};
+use error::{Error, ErrorKind};
+use host::ProtocolId;
+use node_table::{NodeId, NodeEndpoint};
+
+/// Maximum size of an encoded record.
+pub const MAX_RECORD_SIZE: usize = 300;
+/// Prefix of the textual record form.
+const RECORD_PREFIX: &str = "enr:";
+/// The only identity scheme supported.
+const ID_SCHEME: &[u8] = b"v4";
+
+/// Record key of the identity scheme.
+pub const KEY_ID: &str = "id";
+/// Record key of the compressed secp256k1 public key.
+pub const KEY_SECP256K1: &str = "secp256k1";
+/// Record key of the IPv4 address.
+pub const KEY_IP: &str = "ip";
+/// Record key of the IPv6 address.
+pub const KEY_IP6: &str = "ip6";
+/// Record key of the TCP port.
+pub const KEY_TCP: &str = "tcp";
+/// Record key of the UDP port.
+pub const KEY_UDP: &str = "udp";
+/// Record key of the chain id.
+pub const KEY_CHAIN_ID: &str = "chainid";
+/// Record key of the list of supported protocols and their versions.
+pub const KEY_CAPABILITIES: &str = "caps";
+
+/// Signed and versioned node information record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeRecord {
+	seq: u64,
+	/// Keys mapped to RLP encoded values.
+	pairs: BTreeMap<String, Bytes>,
+	signature: Bytes,
+	id: NodeId,
+	raw: Bytes,
+}
+
+impl NodeRecord {
+	/// Create a record signed with the given key. `pairs` maps keys to RLP encoded values;
+	/// identity scheme entries are added automatically.
+	pub fn new(key: &KeyPair, seq: u64, mut pairs: BTreeMap<String, Bytes>) -> Result<NodeRecord, Error> {
+		pairs.insert(KEY_ID.into(), rlp::encode(&ID_SCHEME).into_vec());
+		pairs.insert(KEY_SECP256K1.into(), rlp::encode(&&public_to_compressed(key.public())?[..]).into_vec());
+
+		let signature = sign(key.secret(), &NodeRecord::content_hash(seq, &pairs))?;
+		let signature = signature[0..64].to_vec();
+		let raw = NodeRecord::encode(&signature, seq, &pairs);
+		if raw.len() > MAX_RECORD_SIZE {
+			return Err(ErrorKind::InvalidNodeRecord.into());
+		}
+
+		Ok(NodeRecord {
+			seq: seq,
+			pairs: pairs,
+			signature: signature,
+			id: key.public().clone(),
+			raw: raw,
+		})
+	}
+
+	/// Record entries describing a node endpoint.
+	pub fn endpoint_pairs(endpoint: &NodeEndpoint) -> BTreeMap<String, Bytes> {
+		let mut pairs = BTreeMap::new();
+		match endpoint.address {
+			SocketAddr::V4(a) => pairs.insert(KEY_IP.into(), rlp::encode(&&a.ip().octets()[..]).into_vec()),
+			SocketAddr::V6(a) => pairs.insert(KEY_IP6.into(), rlp::encode(&&a.ip().octets()[..]).into_vec()),
+		};
+		pairs.insert(KEY_TCP.into(), rlp::encode(&endpoint.address.port()).into_vec());
+		pairs.insert(KEY_UDP.into(), rlp::encode(&endpoint.udp_port).into_vec());
+		pairs
+	}
+
+	/// Record entry listing supported protocols.
+	pub fn capabilities_value(capabilities: &[(ProtocolId, u8)]) -> Bytes {
+		let mut s = RlpStream::new_list(capabilities.len());
+		for &(ref protocol, version) in capabilities {
+			s.begin_list(2).append(&&protocol[..]).append(&version);
+		}
+		s.out()
+	}
+
+	/// Decode and verify an RLP encoded record.
+	pub fn from_rlp(raw: &[u8]) -> Result<NodeRecord, Error> {
+		if raw.len() > MAX_RECORD_SIZE {
+			return Err(ErrorKind::InvalidNodeRecord.into());
+		}
+
+		let rlp = UntrustedRlp::new(raw);
+		let count = rlp.item_count()?;
+		if count < 2 || count % 2 != 0 {
+			return Err(ErrorKind::InvalidNodeRecord.into());
+		}
+		let signature: Vec<u8> = rlp.val_at(0)?;
+		let seq: u64 = rlp.val_at(1)?;
+
+		let mut pairs = BTreeMap::new();
+		let mut index = 2;
+		while index < count {
+			let key = String::from_utf8(rlp.val_at(index)?).map_err(|_| ErrorKind::InvalidNodeRecord)?;
+			// keys must be sorted and unique.
+			if pairs.keys().next_back().map_or(false, |last: &String| *last >= key) {
+				return Err(ErrorKind::InvalidNodeRecord.into());
+			}
+			pairs.insert(key, rlp.at(index + 1)?.as_raw().to_vec());
+			index += 2;
+		}
+
+		let scheme: Vec<u8> = NodeRecord::value(&pairs, KEY_ID).ok_or(ErrorKind::InvalidNodeRecord)?;
+		if scheme != ID_SCHEME || signature.len() != 64 {
+			return Err(ErrorKind::InvalidNodeRecord.into());
+		}
+		let public: Vec<u8> = NodeRecord::value(&pairs, KEY_SECP256K1).ok_or(ErrorKind::InvalidNodeRecord)?;
+		let id = public_from_compressed(&public)?;
+
+		// the signature carries no recovery id, so try both.
+		let hash = NodeRecord::content_hash(seq, &pairs);
+		let r = H256::from_slice(&signature[0..32]);
+		let s = H256::from_slice(&signature[32..64]);
+		let valid = (0..2).any(|v| recover(&Signature::from_rsv(&r, &s, v), &hash).ok() == Some(id));
+		if !valid {
+			return Err(ErrorKind::InvalidNodeRecord.into());
+		}
+
+		Ok(NodeRecord {
+			seq: seq,
+			pairs: pairs,
+			signature: signature,
+			id: id,
+			raw: raw.to_vec(),
+		})
+	}
+
+	/// Public key of the node.
+	pub fn id(&self) -> &NodeId {
+		&self.id
+	}
+
+	/// Sequence number, increased whenever the record changes.
+	pub fn seq(&self) -> u64 {
+		self.seq
+	}
+
+	/// All record entries, with RLP encoded values.
+	pub fn pairs(&self) -> &BTreeMap<String, Bytes> {
+		&self.pairs
+	}
+
+	/// Decode the value of an entry.
+	pub fn get<T>(&self, key: &str) -> Option<T> where T: Decodable {
+		NodeRecord::value(&self.pairs, key)
+	}
+
+	/// RLP encoded record.
+	pub fn as_raw(&self) -> &[u8] {
+		&self.raw
+	}
+
+	/// Node endpoint described by the record, if any.
+	pub fn endpoint(&self) -> Option<NodeEndpoint> {
+		let tcp_port: Option<u16> = self.get(KEY_TCP);
+		let udp_port: Option<u16> = self.get(KEY_UDP);
+		let (tcp_port, udp_port) = match (tcp_port, udp_port) {
+			(Some(tcp), Some(udp)) => (tcp, udp),
+			(Some(port), None) | (None, Some(port)) => (port, port),
+			(None, None) => return None,
+		};
+
+		let address = if let Some(ip) = self.get::<Vec<u8>>(KEY_IP) {
+			if ip.len() != 4 {
+				return None;
+			}
+			SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(ip[0], ip[1], ip[2], ip[3]), tcp_port))
+		} else if let Some(ip) = self.get::<Vec<u8>>(KEY_IP6) {
+			if ip.len() != 16 {
+				return None;
+			}
+			let mut octets = [0u8; 16];
+			octets.copy_from_slice(&ip);
+			SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::from(octets), tcp_port, 0, 0))
+		} else {
+			return None;
+		};
+
+		Some(NodeEndpoint { address: address, udp_port: udp_port })
+	}
+
+	/// Chain id advertised by the node.
+	pub fn chain_id(&self) -> Option<u64> {
+		self.get(KEY_CHAIN_ID)
+	}
+
+	/// Protocols supported by the node.
+	pub fn capabilities(&self) -> Vec<(ProtocolId, u8)> {
+		let value = match self.pairs.get(KEY_CAPABILITIES) {
+			Some(value) => value,
+			None => return Vec::new(),
+		};
+		UntrustedRlp::new(value).iter().filter_map(|cap| {
+			let name: Vec<u8> = cap.val_at(0).ok()?;
+			let version: u8 = cap.val_at(1).ok()?;
+			if name.len() != 3 {
+				return None;
+			}
+			let mut protocol = ProtocolId::default();
+			protocol.copy_from_slice(&name);
+			Some((protocol, version))
+		}).collect()
+	}
+
+	fn value<T>(pairs: &BTreeMap<String, Bytes>, key: &str) -> Option<T> where T: Decodable {
+		pairs.get(key).and_then(|value| UntrustedRlp::new(value).as_val().ok())
+	}
+
+	fn content_hash(seq: u64, pairs: &BTreeMap<String, Bytes>) -> H256 {
+		let mut s = RlpStream::new_list(1 + pairs.len() * 2);
+		s.append(&seq);
+		for (key, value) in pairs {
+			s.append(key).append_raw(value, 1);
+		}
+		keccak(s.as_raw())
+	}
+
+	fn encode(signature: &[u8], seq: u64, pairs: &BTreeMap<String, Bytes>) -> Bytes {
+		let mut s = RlpStream::new_list(2 + pairs.len() * 2);
+		s.append(&signature).append(&seq);
+		for (key, value) in pairs {
+			s.append(key).append_raw(value, 1);
+		}
+		s.out()
+	}
+}
+
+impl fmt::Display for NodeRecord {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}{}", RECORD_PREFIX, base64::encode(&self.raw))
+	}
+}
+
+impl FromStr for NodeRecord {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<NodeRecord, Error> {
+		if !s.starts_with(RECORD_PREFIX) {
+			return Err(ErrorKind::InvalidNodeRecord.into());
+		}
+		let raw = base64::decode(&s[RECORD_PREFIX.len()..]).ok_or(ErrorKind::InvalidNodeRecord)?;
+		NodeRecord::from_rlp(&raw)
+	}
+}
+
+/// URL-safe base64 without padding, as used by the textual record form.
+mod base64 {
+	const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+	pub fn encode(data: &[u8]) -> String {
+		let mut out = String::with_capacity((data.len() * 4 + 2) / 3);
+		for chunk in data.chunks(3) {
+			let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+			let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+			for i in 0..(chunk.len() + 1) {
+				out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+			}
+		}
+		out
+	}
+
+	pub fn decode(s: &str) -> Option<Vec<u8>> {
+		let mut out = Vec::with_capacity(s.len() * 3 / 4);
+		let mut acc = 0u32;
+		let mut bits = 0;
+		for c in s.bytes() {
+			let v = ALPHABET.iter().position(|&a| a == c)? as u32;
+			acc = acc << 6 | v;
+			bits += 6;
+			if bits >= 8 {
+				bits -= 8;
+				out.push((acc >> bits) as u8);
+				acc &= (1 << bits) - 1;
+			}
+		}
+		// a single leftover character can't encode a whole byte.
+		if bits >= 6 || acc != 0 {
+			return None;
+		}
+		Some(out)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethkey::Secret;
+
+	// example record from EIP-778.
+	const EXAMPLE_RECORD: &str = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8";
+	const EXAMPLE_SECRET: &str = "b71c71a67e1177ad4e901695e1b4b9ee17ae16c6668d313eac2f96dbcda3f291";
+
+	#[test]
+	fn decodes_example_record() {
+		let key = KeyPair::from_secret(Secret::from_str(EXAMPLE_SECRET).unwrap()).unwrap();
+		let record = NodeRecord::from_str(EXAMPLE_RECORD).unwrap();
+
+		assert_eq!(record.id(), key.public());
+		assert_eq!(record.seq(), 1);
+		let endpoint = record.endpoint().unwrap();
+		assert_eq!(endpoint.address, SocketAddr::from_str("127.0.0.1:30303").unwrap());
+		assert_eq!(endpoint.udp_port, 30303);
+		assert_eq!(record.to_string(), EXAMPLE_RECORD);
+	}
+
+	#[test]
+	fn signs_example_record() {
+		let key = KeyPair::from_secret(Secret::from_str(EXAMPLE_SECRET).unwrap()).unwrap();
+		let mut pairs = BTreeMap::new();
+		pairs.insert(KEY_IP.to_owned(), rlp::encode(&&[127u8, 0, 0, 1][..]).into_vec());
+		pairs.insert(KEY_UDP.to_owned(), rlp::encode(&30303u16).into_vec());
+
+		let record = NodeRecord::new(&key, 1, pairs).unwrap();
+		assert_eq!(record.to_string(), EXAMPLE_RECORD);
+	}
+
+	#[test]
+	fn roundtrip() {
+		let key = KeyPair::from_secret(Secret::from_str(EXAMPLE_SECRET).unwrap()).unwrap();
+		let endpoint = NodeEndpoint::from_str("10.0.0.1:30303").unwrap();
+		let mut pairs = NodeRecord::endpoint_pairs(&endpoint);
+		pairs.insert(KEY_CHAIN_ID.to_owned(), rlp::encode(&42u64).into_vec());
+		pairs.insert(KEY_CAPABILITIES.to_owned(), NodeRecord::capabilities_value(&[(*b"eth", 63), (*b"pip", 1)]));
+
+		let record = NodeRecord::new(&key, 7, pairs).unwrap();
+		let decoded = NodeRecord::from_str(&record.to_string()).unwrap();
+		assert_eq!(decoded, record);
+		assert_eq!(decoded.seq(), 7);
+		assert_eq!(decoded.chain_id(), Some(42));
+		assert_eq!(decoded.capabilities(), vec![(*b"eth", 63), (*b"pip", 1)]);
+		assert_eq!(decoded.endpoint().unwrap().address, endpoint.address);
+	}
+
+	#[test]
+	fn rejects_tampered_record() {
+		let record = NodeRecord::from_str(EXAMPLE_RECORD).unwrap();
+		let mut raw = record.as_raw().to_vec();
+		// change the udp port.
+		let last = raw.len() - 1;
+		raw[last] ^= 1;
+		assert!(NodeRecord::from_rlp(&raw).is_err());
+		assert!(NodeRecord::from_str("enode://1234").is_err());
+	}
+}
@@ -26,6 +26,8 @@ use rlp::*;
 use error::{Error, ErrorKind};
 use {AllowIP, IpFilter};
 use discovery::{TableUpdates, NodeEntry};
+use node_record::NodeRecord;
+use reputation::{PeerBehaviour, Reputation};
 use ip_utils::*;
 use serde_json;
 
@@ -138,30 +140,34 @@ pub struct Node {
 	pub id: NodeId,
 	pub endpoint: NodeEndpoint,
 	pub peer_type: PeerType,
-	pub attempts: u32,
-	pub failures: u32,
+	pub reputation: Reputation,
+	/// Latest known node record.
+	pub record: Option<NodeRecord>,
 }
 
-const DEFAULT_FAILURE_PERCENTAGE: usize = 50;
-
 impl Node {
 	pub fn new(id: NodeId, endpoint: NodeEndpoint) -> Node {
 		Node {
 			id: id,
 			endpoint: endpoint,
 			peer_type: PeerType::Optional,
-			attempts: 0,
-			failures: 0,
+			reputation: Reputation::default(),
+			record: None,
 		}
 	}
 
-	/// Returns the node's failure percentage (0..100) in buckets of 5%. If there are 0 connection attempts for this
-	/// node the default failure percentage is returned (50%).
-	pub fn failure_percentage(&self) -> usize {
-		if self.attempts == 0 {
-			DEFAULT_FAILURE_PERCENTAGE
-		} else {
-			(self.failures * 100 / self.attempts / 5 * 5) as usize
+	/// Create a node from its record. Fails if the record doesn't describe an endpoint.
+	pub fn from_record(record: NodeRecord) -> Result<Node, Error> {
+		let endpoint = record.endpoint().ok_or(ErrorKind::InvalidNodeRecord)?;
+		let mut node = Node::new(record.id().clone(), endpoint);
+		node.record = Some(record);
+		Ok(node)
+	}
+
+	/// Replace the node record if the given one is newer.
+	pub fn update_record(&mut self, record: NodeRecord) {
+		if record.id() == &self.id && self.record.as_ref().map_or(true, |r| r.seq() < record.seq()) {
+			self.record = Some(record);
 		}
 	}
 }
@@ -180,6 +186,10 @@ impl Display for Node {
 impl FromStr for Node {
 	type Err = Error;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.starts_with("enr:") {
+			return Node::from_record(s.parse()?);
+		}
+
 		let (id, endpoint) = if s.len() > 136 && &s[0..8] == "enode://" && &s[136..137] == "@" {
 			(s[8..136].parse().map_err(|_| ErrorKind::InvalidNodeId)?, NodeEndpoint::from_str(&s[137..])?)
 		}
@@ -191,8 +201,8 @@ impl FromStr for Node {
 			id: id,
 			endpoint: endpoint,
 			peer_type: PeerType::Optional,
-			attempts: 0,
-			failures: 0,
+			reputation: Reputation::default(),
+			record: None,
 		})
 	}
 }
@@ -216,7 +226,6 @@ const NODES_FILE: &str = "nodes.json";
 /// Node table backed by disk file.
 pub struct NodeTable {
 	nodes: HashMap<NodeId, Node>,
-	useless_nodes: HashSet<NodeId>,
 	path: Option<String>,
 }
 
@@ -225,35 +234,37 @@ impl NodeTable {
 		NodeTable {
 			path: path.clone(),
 			nodes: NodeTable::load(path),
-			useless_nodes: HashSet::new(),
 		}
 	}
 
 	/// Add a node to table
 	pub fn add_node(&mut self, mut node: Node) {
-		// preserve attempts and failure counter
-		let (attempts, failures) =
-			self.nodes.get(&node.id).map_or((0, 0), |n| (n.attempts, n.failures));
+		// preserve reputation
+		let (reputation, record) =
+			self.nodes.get(&node.id).map_or((Reputation::default(), None), |n| (n.reputation, n.record.clone()));
 
-		node.attempts = attempts;
-		node.failures = failures;
+		node.reputation = reputation;
+		if let Some(record) = record {
+			node.update_record(record);
+		}
 
 		self.nodes.insert(node.id.clone(), node);
 	}
 
-	/// Returns node ids sorted by failure percentage, for nodes with the same failure percentage the absolute number of
-	/// failures is considered.
+	/// Returns nodes sorted by reputation, best first.
+	fn ordered_nodes(&self) -> Vec<&Node> {
+		let mut refs: Vec<&Node> = self.nodes.values().collect();
+		refs.sort_by(|a, b| b.reputation.score().cmp(&a.reputation.score()));
+		refs
+	}
+
+	/// Returns ids of nodes that are not banned, best reputation first.
 	pub fn nodes(&self, filter: IpFilter) -> Vec<NodeId> {
-		let mut refs: Vec<&Node> = self.nodes.values()
-			.filter(|n| !self.useless_nodes.contains(&n.id))
+		self.ordered_nodes().into_iter()
+			.filter(|n| !n.reputation.is_banned())
 			.filter(|n| n.endpoint.is_allowed(&filter))
-			.collect();
-		refs.sort_by(|a, b| {
-			a.failure_percentage().cmp(&b.failure_percentage())
-				.then_with(|| a.failures.cmp(&b.failures))
-				.then_with(|| b.attempts.cmp(&a.attempts)) // we use reverse ordering for number of attempts
-		});
-		refs.into_iter().map(|n| n.id).collect()
+			.map(|n| n.id)
+			.collect()
 	}
 
 	/// Unordered list of all entries
@@ -280,6 +291,11 @@ impl NodeTable {
 			let entry = self.nodes.entry(node.id.clone()).or_insert_with(|| Node::new(node.id.clone(), node.endpoint.clone()));
 			entry.endpoint = node.endpoint;
 		}
+		for record in update.records {
+			if let Some(node) = self.nodes.get_mut(record.id()) {
+				node.update_record(record);
+			}
+		}
 		for r in update.removed {
 			if !reserved.contains(&r) {
 				self.nodes.remove(&r);
@@ -287,21 +303,39 @@ impl NodeTable {
 		}
 	}
 
-	/// Increase failure counte for a node
-	pub fn note_failure(&mut self, id: &NodeId) {
+	/// Update node reputation. Returns `true` if the node is banned as a result.
+	pub fn report(&mut self, id: &NodeId, behaviour: PeerBehaviour) -> bool {
+		match self.nodes.get_mut(id) {
+			Some(node) => {
+				node.reputation.report(behaviour);
+				node.reputation.is_banned()
+			},
+			None => false,
+		}
+	}
+
+	/// Ban a node for a while, regardless of its reputation.
+	pub fn ban(&mut self, id: &NodeId) {
 		if let Some(node) = self.nodes.get_mut(id) {
-			node.failures += 1;
+			node.reputation.ban();
 		}
 	}
 
-	/// Mark as useless, no further attempts to connect until next call to `clear_useless`.
-	pub fn mark_as_useless(&mut self, id: &NodeId) {
-		self.useless_nodes.insert(id.clone());
+	/// Check if a node is banned.
+	pub fn is_banned(&self, id: &NodeId) -> bool {
+		self.nodes.get(id).map_or(false, |n| n.reputation.is_banned())
 	}
 
-	/// Atempt to connect to useless nodes again.
-	pub fn clear_useless(&mut self) {
-		self.useless_nodes.clear();
+	/// Reputation score of a node.
+	pub fn reputation(&self, id: &NodeId) -> Option<i32> {
+		self.nodes.get(id).map(|n| n.reputation.score())
+	}
+
+	/// Move all reputations closer to neutral. Bans are not affected.
+	pub fn decay_reputation(&mut self) {
+		for node in self.nodes.values_mut() {
+			node.reputation.decay();
+		}
 	}
 
 	/// Save the nodes.json file.
@@ -315,11 +349,8 @@ impl NodeTable {
 			return;
 		}
 		path.push(NODES_FILE);
-		let node_ids = self.nodes(IpFilter::default());
-		let nodes = node_ids.into_iter()
-			.map(|id| self.nodes.get(&id).expect("self.nodes() only returns node IDs from self.nodes"))
+		let nodes = self.ordered_nodes().into_iter()
 			.take(MAX_NODES)
-			.map(|node| node.clone())
 			.map(Into::into)
 			.collect();
 		let table = json::NodeTable { nodes };
@@ -390,16 +421,22 @@ mod json {
 	#[derive(Serialize, Deserialize)]
 	pub struct Node {
 		pub url: String,
-		pub attempts: u32,
-		pub failures: u32,
+		#[serde(default)]
+		pub reputation: i32,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		pub banned_until: Option<u64>,
+		#[serde(default, skip_serializing_if = "Option::is_none")]
+		pub enr: Option<String>,
 	}
 
 	impl Node {
 		pub fn into_node(self) -> Option<super::Node> {
 			match super::Node::from_str(&self.url) {
 				Ok(mut node) => {
-					node.attempts = self.attempts;
-					node.failures = self.failures;
+					node.reputation = Reputation::restore(self.reputation, self.banned_until);
+					if let Some(record) = self.enr.and_then(|enr| enr.parse().ok()) {
+						node.update_record(record);
+					}
 					Some(node)
 				},
 				_ => None,
@@ -411,8 +448,9 @@ mod json {
 		fn from(node: &'a super::Node) -> Self {
 			Node {
 				url: format!("{}", node),
-				attempts: node.attempts,
-				failures: node.failures,
+				reputation: node.reputation.score(),
+				banned_until: node.reputation.banned_until(),
+				enr: node.record.as_ref().map(|record| record.to_string()),
 			}
 		}
 	}
@@ -455,7 +493,18 @@ mod tests {
 	}
 
 	#[test]
-	fn table_failure_percentage_order() {
+	fn node_parse_record() {
+		let url = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8";
+		assert!(validate_node_url(url).is_none());
+		let node = Node::from_str(url).unwrap();
+		assert_eq!(node.endpoint.address, SocketAddr::from_str("127.0.0.1:30303").unwrap());
+		assert_eq!(node.endpoint.udp_port, 30303);
+		assert_eq!(node.record.as_ref().map(|r| r.id()), Some(&node.id));
+		assert!(validate_node_url("enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZX").is_some());
+	}
+
+	#[test]
+	fn table_reputation_order() {
 		let node1 = Node::from_str("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
 		let node2 = Node::from_str("enode://b979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
 		let node3 = Node::from_str("enode://c979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
@@ -471,26 +520,35 @@ mod tests {
 		table.add_node(node3);
 		table.add_node(node4);
 
-		// node 1 - failure percentage 100%
-		table.get_mut(&id1).unwrap().attempts = 2;
-		table.note_failure(&id1);
-		table.note_failure(&id1);
+		// node1 - timed out twice
+		assert!(!table.report(&id1, PeerBehaviour::Timeout));
+		assert!(!table.report(&id1, PeerBehaviour::Timeout));
 
-		// node2 - failure percentage 33%
-		table.get_mut(&id2).unwrap().attempts = 3;
-		table.note_failure(&id2);
+		// node2 - connected, then failed
+		table.report(&id2, PeerBehaviour::Connected);
+		table.report(&id2, PeerBehaviour::ConnectionFailure);
 
-		// node3 - failure percentage 0%
-		table.get_mut(&id3).unwrap().attempts = 1;
+		// node3 - connected and responsive
+		table.report(&id3, PeerBehaviour::Connected);
+		table.report(&id3, PeerBehaviour::Latency(100));
 
-		// node4 - failure percentage 50% (default when no attempts)
+		// node4 - sent a bad block
+		assert!(table.report(&id4, PeerBehaviour::BadBlock));
 
 		let r = table.nodes(IpFilter::default());
 
+		assert_eq!(r.len(), 3);
 		assert_eq!(r[0][..], id3[..]);
 		assert_eq!(r[1][..], id2[..]);
-		assert_eq!(r[2][..], id4[..]);
-		assert_eq!(r[3][..], id1[..]);
+		assert_eq!(r[2][..], id1[..]);
+
+		// decay doesn't lift bans
+		for _ in 0..200 {
+			table.decay_reputation();
+		}
+		assert_eq!(table.reputation(&id4), Some(0));
+		assert!(table.is_banned(&id4));
+		assert_eq!(table.nodes(IpFilter::default()).len(), 3);
 	}
 
 	#[test]
@@ -505,16 +563,36 @@ mod tests {
 			table.add_node(node1);
 			table.add_node(node2);
 
-			table.get_mut(&id1).unwrap().attempts = 1;
-			table.get_mut(&id2).unwrap().attempts = 1;
-			table.note_failure(&id2);
+			table.report(&id1, PeerBehaviour::Connected);
+			table.report(&id2, PeerBehaviour::Timeout);
+			table.report(&id2, PeerBehaviour::BadBlock);
 		}
 
 		{
 			let table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
 			let r = table.nodes(IpFilter::default());
-			assert_eq!(r[0][..], id1[..]);
-			assert_eq!(r[1][..], id2[..]);
+			assert_eq!(r, vec![id1]);
+			assert!(table.is_banned(&id2));
+		}
+	}
+
+	#[test]
+	fn table_save_load_record() {
+		let tempdir = TempDir::new("").unwrap();
+		let node = Node::from_str("enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8").unwrap();
+		let id = node.id.clone();
+		let record = node.record.clone();
+		{
+			let mut table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			table.add_node(node);
+			// re-adding the node without a record keeps the known one.
+			let endpoint = table.get_mut(&id).unwrap().endpoint.clone();
+			table.add_node(Node::new(id.clone(), endpoint));
+		}
+
+		{
+			let mut table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			assert_eq!(table.get_mut(&id).unwrap().record, record);
 		}
 	}
 
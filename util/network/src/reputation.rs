@@ -0,0 +1,216 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Peer reputation tracking.
+
+use std::cmp::{min, max};
+use time;
+
+/// Lowest possible score.
+const MIN_SCORE: i32 = -1000;
+/// Highest possible score.
+const MAX_SCORE: i32 = 1000;
+/// Nodes at or below this score are banned.
+const BAN_THRESHOLD: i32 = -500;
+/// How long a ban lasts, in seconds.
+const BAN_DURATION_SEC: u64 = 60 * 60;
+/// Responses faster than this are rewarded.
+const FAST_RESPONSE_MS: u64 = 1000;
+/// Responses slower than this are penalized.
+const SLOW_RESPONSE_MS: u64 = 5000;
+
+/// Peer behaviour affecting its reputation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerBehaviour {
+	/// A session with the peer has been established.
+	Connected,
+	/// The peer answered a request after the given number of milliseconds.
+	Latency(u64),
+	/// Connection to the peer failed or was dropped by the peer.
+	ConnectionFailure,
+	/// The peer sent data we can't make any use of.
+	UselessResponse,
+	/// The peer didn't answer a request in time.
+	Timeout,
+	/// The peer sent a malformed or unexpected packet.
+	ProtocolViolation,
+	/// The peer sent an invalid block.
+	BadBlock,
+	/// The peer is running an incompatible protocol or chain.
+	Incompatible,
+}
+
+impl PeerBehaviour {
+	/// Score change caused by the behaviour.
+	pub fn score(&self) -> i32 {
+		match *self {
+			PeerBehaviour::Connected => 10,
+			PeerBehaviour::Latency(ms) if ms < FAST_RESPONSE_MS => 5,
+			PeerBehaviour::Latency(ms) if ms < SLOW_RESPONSE_MS => 0,
+			PeerBehaviour::Latency(_) => -25,
+			PeerBehaviour::ConnectionFailure => -20,
+			PeerBehaviour::UselessResponse => -50,
+			PeerBehaviour::Timeout => -100,
+			PeerBehaviour::ProtocolViolation => -200,
+			PeerBehaviour::BadBlock => -500,
+			PeerBehaviour::Incompatible => MIN_SCORE,
+		}
+	}
+
+	/// Whether the behaviour gets the peer banned regardless of its reputation.
+	pub fn is_fatal(&self) -> bool {
+		match *self {
+			PeerBehaviour::BadBlock | PeerBehaviour::Incompatible => true,
+			_ => false,
+		}
+	}
+}
+
+fn now() -> u64 {
+	time::get_time().sec as u64
+}
+
+/// Node reputation. The score starts neutral and decays back towards neutral over time,
+/// bans expire on their own after `BAN_DURATION_SEC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reputation {
+	score: i32,
+	/// Unix time the ban is lifted at.
+	banned_until: Option<u64>,
+}
+
+impl Reputation {
+	/// Restore a persisted reputation.
+	pub fn restore(score: i32, banned_until: Option<u64>) -> Reputation {
+		Reputation {
+			score: max(MIN_SCORE, min(MAX_SCORE, score)),
+			banned_until: banned_until,
+		}
+	}
+
+	/// Current score.
+	pub fn score(&self) -> i32 {
+		self.score
+	}
+
+	/// Unix time the current ban is lifted at.
+	pub fn banned_until(&self) -> Option<u64> {
+		self.banned_until
+	}
+
+	/// Apply the score change of a behaviour. Fatal behaviour or a score at or below
+	/// the threshold bans the node.
+	pub fn report(&mut self, behaviour: PeerBehaviour) {
+		self.score = max(MIN_SCORE, min(MAX_SCORE, self.score.saturating_add(behaviour.score())));
+		if behaviour.is_fatal() || self.score <= BAN_THRESHOLD {
+			self.ban();
+		}
+	}
+
+	/// Ban the node for `BAN_DURATION_SEC`.
+	pub fn ban(&mut self) {
+		self.ban_at(now());
+	}
+
+	fn ban_at(&mut self, now: u64) {
+		self.banned_until = Some(max(self.banned_until.unwrap_or(0), now + BAN_DURATION_SEC));
+	}
+
+	/// Move the score 5% closer to neutral and forget expired bans.
+	pub fn decay(&mut self) {
+		self.score = self.score * 19 / 20;
+		if !self.is_banned() {
+			self.banned_until = None;
+		}
+	}
+
+	/// Whether the node should not be connected to.
+	pub fn is_banned(&self) -> bool {
+		self.is_banned_at(now())
+	}
+
+	fn is_banned_at(&self, now: u64) -> bool {
+		self.banned_until.map_or(false, |until| until > now)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reputation_bounds() {
+		let mut reputation = Reputation::default();
+		reputation.report(PeerBehaviour::Incompatible);
+		reputation.report(PeerBehaviour::BadBlock);
+		assert_eq!(reputation.score(), MIN_SCORE);
+		assert_eq!(Reputation::restore(5000, None).score(), MAX_SCORE);
+	}
+
+	#[test]
+	fn bad_block_bans() {
+		let mut reputation = Reputation::restore(MAX_SCORE, None);
+		reputation.report(PeerBehaviour::Latency(100));
+		assert!(!reputation.is_banned());
+		reputation.report(PeerBehaviour::BadBlock);
+		assert!(reputation.is_banned());
+	}
+
+	#[test]
+	fn misbehaviour_accumulates() {
+		let mut reputation = Reputation::default();
+		reputation.report(PeerBehaviour::ProtocolViolation);
+		reputation.report(PeerBehaviour::Timeout);
+		reputation.report(PeerBehaviour::Latency(10_000));
+		assert!(!reputation.is_banned());
+		reputation.report(PeerBehaviour::ProtocolViolation);
+		assert!(reputation.is_banned());
+	}
+
+	#[test]
+	fn decays_towards_neutral() {
+		let mut bad = Reputation::restore(-100, None);
+		bad.decay();
+		assert_eq!(bad.score(), -95);
+		for _ in 0..200 {
+			bad.decay();
+		}
+		assert_eq!(bad.score(), 0);
+
+		let mut good = Reputation::restore(100, None);
+		good.decay();
+		assert_eq!(good.score(), 95);
+	}
+
+	#[test]
+	fn bans_expire_independently_of_decay() {
+		let mut reputation = Reputation::default();
+		reputation.report(PeerBehaviour::BadBlock);
+		for _ in 0..200 {
+			reputation.decay();
+		}
+		assert_eq!(reputation.score(), 0);
+		assert!(reputation.is_banned());
+
+		let banned_until = reputation.banned_until().unwrap();
+		assert!(reputation.is_banned_at(banned_until - 1));
+		assert!(!reputation.is_banned_at(banned_until));
+
+		// a ban is never shortened by a later one.
+		reputation.ban_at(0);
+		assert_eq!(reputation.banned_until(), Some(banned_until));
+	}
+}
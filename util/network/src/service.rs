@@ -107,6 +107,18 @@ impl NetworkService {
 		host.as_ref().and_then(|h| h.external_url())
 	}
 
+	/// Returns the signed local node record if available.
+	pub fn external_record(&self) -> Option<String> {
+		let host = self.host.read();
+		host.as_ref().and_then(|h| h.external_record())
+	}
+
+	/// Returns reputation score of a connected peer.
+	pub fn peer_reputation(&self, peer: PeerId) -> Option<i32> {
+		let host = self.host.read();
+		host.as_ref().and_then(|h| h.peer_reputation(peer))
+	}
+
 	/// Returns external url if available.
 	pub fn local_url(&self) -> Option<String> {
 		let host = self.host.read();
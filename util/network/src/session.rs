@@ -18,7 +18,7 @@ use std::{str, io};
 use std::net::SocketAddr;
 use std::cmp::Ordering;
 use std::sync::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use mio::*;
 use mio::deprecated::{Handler, EventLoop};
@@ -32,6 +32,7 @@ use error::{Error, ErrorKind, DisconnectReason};
 use host::*;
 use node_table::NodeId;
 use stats::NetworkStats;
+use bandwidth::UploadLimits;
 use time;
 use snappy;
 
@@ -40,6 +41,8 @@ const PING_TIMEOUT_SEC: u64 = 60;
 const PING_INTERVAL_SEC: u64 = 120;
 const MIN_PROTOCOL_VERSION: u32 = 4;
 const MIN_COMPRESSION_PROTOCOL_VERSION: u32 = 5;
+// Maximum number of packets delayed by upload limits before sending fails.
+const MAX_THROTTLED_PACKETS: usize = 256;
 
 #[derive(Debug, Clone)]
 enum ProtocolState {
@@ -49,6 +52,13 @@ enum ProtocolState {
 	Connected,
 }
 
+// Encoded subprotocol packet delayed by an upload limit.
+struct ThrottledPacket {
+	protocol: ProtocolId,
+	packet_id: PacketId,
+	packet: Vec<u8>,
+}
+
 /// Peer session over encrypted connection.
 /// When created waits for Hello packet exchange and signals ready state.
 /// Sends and receives protocol packets and handles basic packes such as ping/pong and disconnect.
@@ -65,6 +75,10 @@ pub struct Session {
 	// Protocol states -- accumulates pending packets until signaled as ready.
 	protocol_states: HashMap<ProtocolId, ProtocolState>,
 	compression: bool,
+	stats: Arc<NetworkStats>,
+	limits: Arc<UploadLimits>,
+	// Packets waiting for the upload limits to allow them, in send order.
+	throttled: VecDeque<ThrottledPacket>,
 }
 
 enum State {
@@ -111,6 +125,10 @@ pub struct SessionInfo {
 	pub remote_address: String,
 	/// Local endpoint address of the session
 	pub local_address: String,
+	/// Total bytes received from the peer
+	pub ingress: u64,
+	/// Total bytes sent to the peer
+	pub egress: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -178,10 +196,10 @@ impl Session {
 	/// Create a new session out of comepleted handshake. This clones the handshake connection object
 	/// and leaves the handhsake in limbo to be deregistered from the event loop.
 	pub fn new<Message>(io: &IoContext<Message>, socket: TcpStream, token: StreamToken, id: Option<&NodeId>,
-		nonce: &H256, stats: Arc<NetworkStats>, host: &HostInfo) -> Result<Session, Error>
+		nonce: &H256, stats: Arc<NetworkStats>, limits: Arc<UploadLimits>, host: &HostInfo) -> Result<Session, Error>
 		where Message: Send + Clone + Sync + 'static {
 		let originated = id.is_some();
-		let mut handshake = Handshake::new(token, id, socket, nonce, stats).expect("Can't create handshake");
+		let mut handshake = Handshake::new(token, id, socket, nonce, stats.clone()).expect("Can't create handshake");
 		let local_addr = handshake.connection.local_addr_str();
 		handshake.start(io, host, originated)?;
 		Ok(Session {
@@ -197,12 +215,17 @@ impl Session {
 				originated: originated,
 				remote_address: "Handshake".to_owned(),
 				local_address: local_addr,
+				ingress: 0,
+				egress: 0,
 			},
 			ping_time_ns: 0,
 			pong_time_ns: None,
 			expired: false,
 			protocol_states: HashMap::new(),
 			compression: false,
+			stats: stats,
+			limits: limits,
+			throttled: VecDeque::new(),
 		})
 	}
 
@@ -366,7 +389,61 @@ impl Session {
 			payload = &compressed[0..len];
 		}
 		rlp.append_raw(payload, 1);
-		self.send(io, &rlp.drain())
+		let packet = rlp.drain();
+		if let Some(protocol) = protocol {
+			// keep the order of packets of the same kind
+			let queued = self.throttled.iter().any(|p| p.protocol == protocol && p.packet_id == packet_id);
+			if queued || !self.limits.allow(Some(protocol), packet_id, packet.len()) {
+				if self.throttled.len() >= MAX_THROTTLED_PACKETS {
+					debug!(target: "network", "Too many delayed packets, dropping packet {} to {}", packet_id, self.token());
+					bail!(ErrorKind::RateLimited);
+				}
+				trace!(target: "network", "Upload limit exceeded, delaying packet {} to {}", packet_id, self.token());
+				self.throttled.push_back(ThrottledPacket { protocol: protocol, packet_id: packet_id, packet: packet.to_vec() });
+				return Ok(());
+			}
+		} else {
+			// base protocol packets are never delayed, only accounted for
+			self.limits.allow(None, packet_id, packet.len());
+		}
+		self.send_counted(io, protocol, &packet)
+	}
+
+	/// Check if any packets are waiting for the upload limits.
+	pub fn is_throttled(&self) -> bool {
+		!self.throttled.is_empty()
+	}
+
+	/// Send packets delayed by upload limits as far as the limits allow.
+	pub fn send_throttled<Message>(&mut self, io: &IoContext<Message>) -> Result<(), Error> where Message: Send + Sync + Clone {
+		if self.throttled.is_empty() || self.expired() {
+			return Ok(());
+		}
+		let mut blocked = HashSet::new();
+		let mut remaining = VecDeque::new();
+		while let Some(p) = self.throttled.pop_front() {
+			let key = (p.protocol, p.packet_id);
+			if blocked.contains(&key) || !self.limits.allow(Some(p.protocol), p.packet_id, p.packet.len()) {
+				blocked.insert(key);
+				remaining.push_back(p);
+				continue;
+			}
+			if let Err(e) = self.send_counted(io, Some(p.protocol), &p.packet) {
+				remaining.extend(self.throttled.drain(..));
+				self.throttled = remaining;
+				return Err(e);
+			}
+		}
+		self.throttled = remaining;
+		Ok(())
+	}
+
+	fn send_counted<Message>(&mut self, io: &IoContext<Message>, protocol: Option<ProtocolId>, packet: &[u8]) -> Result<(), Error> where Message: Send + Sync + Clone {
+		self.info.egress += packet.len() as u64;
+		if let Some(protocol) = protocol {
+			self.stats.inc_protocol_send(protocol, packet.len());
+		}
+		self.send(io, packet)
 	}
 
 	/// Keep this session alive. Returns false if ping timeout happened
@@ -411,6 +488,7 @@ impl Session {
 		if packet.data.len() < 2 {
 			return Err(ErrorKind::BadProtocol.into());
 		}
+		self.info.ingress += packet.data.len() as u64;
 		let packet_id = packet.data[0];
 		if packet_id != PACKET_HELLO && packet_id != PACKET_DISCONNECT && !self.had_hello {
 			return Err(ErrorKind::BadProtocol.into());
@@ -463,6 +541,7 @@ impl Session {
 				// map to protocol
 				let protocol = self.info.capabilities[i].protocol;
 				let protocol_packet_id = packet_id - self.info.capabilities[i].id_offset;
+				self.stats.inc_protocol_recv(protocol, packet.data.len());
 
 				match *self.protocol_states.entry(protocol).or_insert_with(|| ProtocolState::Pending(Vec::new())) {
 					ProtocolState::Connected => {
@@ -16,6 +16,18 @@
 
 //! Network Statistics
 use std::sync::atomic::*;
+use std::collections::HashMap;
+use parking_lot::RwLock;
+use host::ProtocolId;
+
+/// Bytes transferred in each direction.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrafficStats {
+	/// Bytes received
+	pub ingress: u64,
+	/// Bytes sent
+	pub egress: u64,
+}
 
 /// Network statistics structure
 #[derive(Default, Debug)]
@@ -26,6 +38,8 @@ pub struct NetworkStats {
 	send: AtomicUsize,
 	/// Total number of sessions created
 	sessions: AtomicUsize,
+	/// Bytes transferred by each subprotocol
+	protocols: RwLock<HashMap<ProtocolId, TrafficStats>>,
 }
 
 impl NetworkStats {
@@ -41,6 +55,16 @@ impl NetworkStats {
 		self.send.fetch_add(size, Ordering::Relaxed);
 	}
 
+	/// Increase bytes received for a subprotocol.
+	pub fn inc_protocol_recv(&self, protocol: ProtocolId, size: usize) {
+		self.protocols.write().entry(protocol).or_insert_with(Default::default).ingress += size as u64;
+	}
+
+	/// Increase bytes sent for a subprotocol.
+	pub fn inc_protocol_send(&self, protocol: ProtocolId, size: usize) {
+		self.protocols.write().entry(protocol).or_insert_with(Default::default).egress += size as u64;
+	}
+
 	/// Increase number of sessions.
 	#[inline]
 	pub fn inc_sessions(&self) {
@@ -65,12 +89,18 @@ impl NetworkStats {
 		self.sessions.load(Ordering::Relaxed)
 	}
 
+	/// Get bytes transferred by each subprotocol.
+	pub fn protocols(&self) -> HashMap<ProtocolId, TrafficStats> {
+		self.protocols.read().clone()
+	}
+
 	/// Create a new empty instance.
 	pub fn new() -> NetworkStats {
 		NetworkStats {
 			recv: AtomicUsize::new(0),
 			send: AtomicUsize::new(0),
 			sessions: AtomicUsize::new(0),
+			protocols: RwLock::new(HashMap::new()),
 		}
 	}
 }